@@ -0,0 +1,6 @@
+-- AlterTable
+ALTER TABLE "Transaction" ADD COLUMN "transfer_direction" TEXT CHECK ("transfer_direction" IN ('out','in'));
+ALTER TABLE "Transaction" ADD COLUMN "linked_transaction_id" TEXT;
+
+-- CreateIndex
+CREATE INDEX "Transaction_linked_transaction_id_idx" ON "Transaction"("linked_transaction_id");
@@ -0,0 +1,5 @@
+-- AlterTable
+ALTER TABLE "Account" ADD COLUMN "opening_balance_cents" INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE "Account" ADD COLUMN "sort_order" INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE "Account" ADD COLUMN "status" TEXT NOT NULL DEFAULT 'active' CHECK ("status" IN ('active','archived','closed'));
+ALTER TABLE "Account" ADD COLUMN "closed_at" DATETIME;
@@ -0,0 +1,21 @@
+-- CreateTable
+CREATE TABLE "TransactionSplit" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "transaction_id" TEXT NOT NULL,
+    "user_id" TEXT NOT NULL,
+    "category_id" TEXT,
+    "goal_id" TEXT,
+    "amount_cents" INTEGER NOT NULL,
+    "notes" TEXT,
+    "sort_order" INTEGER NOT NULL DEFAULT 0,
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT "TransactionSplit_transaction_id_fkey" FOREIGN KEY ("transaction_id") REFERENCES "Transaction" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+    CONSTRAINT "TransactionSplit_category_id_fkey" FOREIGN KEY ("category_id") REFERENCES "Category" ("id") ON DELETE SET NULL ON UPDATE CASCADE,
+    CONSTRAINT "TransactionSplit_goal_id_fkey" FOREIGN KEY ("goal_id") REFERENCES "Goal" ("id") ON DELETE SET NULL ON UPDATE CASCADE
+);
+
+-- CreateIndex
+CREATE INDEX "TransactionSplit_transaction_id_idx" ON "TransactionSplit"("transaction_id");
+
+-- CreateIndex
+CREATE INDEX "TransactionSplit_category_id_idx" ON "TransactionSplit"("category_id");
@@ -0,0 +1,42 @@
+-- CreateTable
+CREATE TABLE "RecurringTransaction" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "user_id" TEXT NOT NULL,
+    "account_id" TEXT NOT NULL,
+    "category_id" TEXT,
+    "goal_id" TEXT,
+    "transfer_account_id" TEXT,
+    "type" TEXT NOT NULL,
+    "amount_cents" INTEGER NOT NULL,
+    "currency" TEXT NOT NULL,
+    "notes" TEXT,
+    "tags" TEXT,
+    "rrule" TEXT NOT NULL,
+    "start_date" TEXT NOT NULL,
+    "end_date" TEXT,
+    "next_occurrence" TEXT,
+    "last_posted_on" TEXT,
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" DATETIME NOT NULL,
+    CONSTRAINT "RecurringTransaction_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+    CONSTRAINT "RecurringTransaction_account_id_fkey" FOREIGN KEY ("account_id") REFERENCES "Account" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+    CONSTRAINT "RecurringTransaction_transfer_account_id_fkey" FOREIGN KEY ("transfer_account_id") REFERENCES "Account" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+    CONSTRAINT "RecurringTransaction_category_id_fkey" FOREIGN KEY ("category_id") REFERENCES "Category" ("id") ON DELETE SET NULL ON UPDATE CASCADE,
+    CONSTRAINT "RecurringTransaction_goal_id_fkey" FOREIGN KEY ("goal_id") REFERENCES "Goal" ("id") ON DELETE SET NULL ON UPDATE CASCADE
+);
+
+-- CreateTable
+CREATE TABLE "RecurringSkip" (
+    "recurring_id" TEXT NOT NULL,
+    "occurrence_date" TEXT NOT NULL,
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY ("recurring_id", "occurrence_date"),
+    CONSTRAINT "RecurringSkip_recurring_id_fkey" FOREIGN KEY ("recurring_id") REFERENCES "RecurringTransaction" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- CreateIndex
+CREATE INDEX "RecurringTransaction_user_id_next_occurrence_idx" ON "RecurringTransaction"("user_id", "next_occurrence");
+
+-- CreateIndex
+CREATE INDEX "Transaction_recurrence_id_idx" ON "Transaction"("recurrence_id");
@@ -0,0 +1,17 @@
+-- CreateTable
+CREATE TABLE "ExchangeRate" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "base_currency" TEXT NOT NULL,
+    "quote_currency" TEXT NOT NULL,
+    "rate" REAL NOT NULL,
+    "rate_date" TEXT NOT NULL,
+    "source" TEXT NOT NULL DEFAULT 'manual',
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" DATETIME NOT NULL
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "ExchangeRate_base_currency_quote_currency_rate_date_key" ON "ExchangeRate"("base_currency", "quote_currency", "rate_date");
+
+-- CreateIndex
+CREATE INDEX "ExchangeRate_quote_currency_rate_date_idx" ON "ExchangeRate"("quote_currency", "rate_date");
@@ -0,0 +1,30 @@
+-- Full-text index over transaction notes, payee and tags (not modelled in schema.prisma)
+CREATE VIRTUAL TABLE "TransactionSearch" USING fts5(
+    transaction_id UNINDEXED,
+    notes,
+    payee,
+    tags,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- CreateTrigger
+CREATE TRIGGER "Transaction_search_insert" AFTER INSERT ON "Transaction" BEGIN
+    INSERT INTO "TransactionSearch" (transaction_id, notes, payee, tags)
+    VALUES (new.id, new.notes, NULL, REPLACE(new.tags, ',', ' '));
+END;
+
+-- CreateTrigger
+CREATE TRIGGER "Transaction_search_update" AFTER UPDATE OF notes, tags ON "Transaction" BEGIN
+    UPDATE "TransactionSearch"
+    SET notes = new.notes, tags = REPLACE(new.tags, ',', ' ')
+    WHERE transaction_id = new.id;
+END;
+
+-- CreateTrigger
+CREATE TRIGGER "Transaction_search_delete" AFTER DELETE ON "Transaction" BEGIN
+    DELETE FROM "TransactionSearch" WHERE transaction_id = old.id;
+END;
+
+-- Backfill
+INSERT INTO "TransactionSearch" (transaction_id, notes, payee, tags)
+SELECT id, notes, NULL, REPLACE(tags, ',', ' ') FROM "Transaction";
@@ -0,0 +1,71 @@
+-- CreateTable
+CREATE TABLE "Payee" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "user_id" TEXT NOT NULL,
+    "name" TEXT NOT NULL,
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" DATETIME NOT NULL,
+    CONSTRAINT "Payee_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- CreateTable
+CREATE TABLE "TransactionRule" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "user_id" TEXT NOT NULL,
+    "name" TEXT NOT NULL,
+    "priority" INTEGER NOT NULL DEFAULT 0,
+    "enabled" BOOLEAN NOT NULL DEFAULT true,
+    "payee_contains" TEXT,
+    "notes_pattern" TEXT,
+    "min_amount_cents" INTEGER,
+    "max_amount_cents" INTEGER,
+    "account_id" TEXT,
+    "category_id" TEXT,
+    "tags" TEXT,
+    "goal_id" TEXT,
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" DATETIME NOT NULL,
+    CONSTRAINT "TransactionRule_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+    CONSTRAINT "TransactionRule_account_id_fkey" FOREIGN KEY ("account_id") REFERENCES "Account" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+    CONSTRAINT "TransactionRule_category_id_fkey" FOREIGN KEY ("category_id") REFERENCES "Category" ("id") ON DELETE SET NULL ON UPDATE CASCADE,
+    CONSTRAINT "TransactionRule_goal_id_fkey" FOREIGN KEY ("goal_id") REFERENCES "Goal" ("id") ON DELETE SET NULL ON UPDATE CASCADE
+);
+
+-- AlterTable
+ALTER TABLE "Transaction" ADD COLUMN "payee_id" TEXT REFERENCES "Payee" ("id") ON DELETE SET NULL ON UPDATE CASCADE;
+
+-- CreateIndex (payee names are unique per user regardless of case; not expressible in schema.prisma)
+CREATE UNIQUE INDEX "Payee_user_id_name_key" ON "Payee"("user_id", "name" COLLATE NOCASE);
+
+-- CreateIndex
+CREATE INDEX "TransactionRule_user_id_priority_idx" ON "TransactionRule"("user_id", "priority");
+
+-- CreateIndex
+CREATE INDEX "Transaction_payee_id_idx" ON "Transaction"("payee_id");
+
+-- Index payee names in full-text search
+DROP TRIGGER "Transaction_search_insert";
+CREATE TRIGGER "Transaction_search_insert" AFTER INSERT ON "Transaction" BEGIN
+    INSERT INTO "TransactionSearch" (transaction_id, notes, payee, tags)
+    VALUES (
+        new.id,
+        new.notes,
+        (SELECT name FROM "Payee" WHERE id = new.payee_id),
+        REPLACE(new.tags, ',', ' ')
+    );
+END;
+
+DROP TRIGGER "Transaction_search_update";
+CREATE TRIGGER "Transaction_search_update" AFTER UPDATE OF notes, tags, payee_id ON "Transaction" BEGIN
+    UPDATE "TransactionSearch"
+    SET notes = new.notes,
+        payee = (SELECT name FROM "Payee" WHERE id = new.payee_id),
+        tags = REPLACE(new.tags, ',', ' ')
+    WHERE transaction_id = new.id;
+END;
+
+CREATE TRIGGER "Payee_search_rename" AFTER UPDATE OF name ON "Payee" BEGIN
+    UPDATE "TransactionSearch"
+    SET payee = new.name
+    WHERE transaction_id IN (SELECT id FROM "Transaction" WHERE payee_id = new.id);
+END;
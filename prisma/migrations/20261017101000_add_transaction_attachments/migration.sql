@@ -0,0 +1,18 @@
+-- CreateTable
+CREATE TABLE "TransactionAttachment" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "user_id" TEXT NOT NULL,
+    "transaction_id" TEXT NOT NULL,
+    "content_hash" TEXT NOT NULL,
+    "file_name" TEXT NOT NULL,
+    "mime_type" TEXT NOT NULL,
+    "size_bytes" INTEGER NOT NULL,
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT "TransactionAttachment_transaction_id_fkey" FOREIGN KEY ("transaction_id") REFERENCES "Transaction" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "TransactionAttachment_transaction_id_content_hash_key" ON "TransactionAttachment"("transaction_id", "content_hash");
+
+-- CreateIndex
+CREATE INDEX "TransactionAttachment_content_hash_idx" ON "TransactionAttachment"("content_hash");
@@ -0,0 +1,28 @@
+-- CreateTable
+CREATE TABLE "Reconciliation" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "user_id" TEXT NOT NULL,
+    "account_id" TEXT NOT NULL,
+    "statement_date" TEXT NOT NULL,
+    "statement_balance_cents" INTEGER NOT NULL,
+    "starting_balance_cents" INTEGER NOT NULL DEFAULT 0,
+    "cleared_balance_cents" INTEGER,
+    "status" TEXT NOT NULL DEFAULT 'in_progress' CHECK ("status" IN ('in_progress','completed')),
+    "completed_at" DATETIME,
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" DATETIME NOT NULL,
+    CONSTRAINT "Reconciliation_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+    CONSTRAINT "Reconciliation_account_id_fkey" FOREIGN KEY ("account_id") REFERENCES "Account" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- AlterTable
+ALTER TABLE "Transaction" ADD COLUMN "reconciliation_id" TEXT REFERENCES "Reconciliation" ("id") ON DELETE SET NULL ON UPDATE CASCADE;
+
+-- CreateIndex
+CREATE INDEX "Reconciliation_account_id_statement_date_idx" ON "Reconciliation"("account_id", "statement_date");
+
+-- CreateIndex (one open session per account; partial indexes are not expressible in schema.prisma)
+CREATE UNIQUE INDEX "Reconciliation_account_id_open_key" ON "Reconciliation"("account_id") WHERE "status" = 'in_progress';
+
+-- CreateIndex
+CREATE INDEX "Transaction_reconciliation_id_idx" ON "Transaction"("reconciliation_id");
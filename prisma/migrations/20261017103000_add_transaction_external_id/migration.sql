@@ -0,0 +1,5 @@
+-- AlterTable
+ALTER TABLE "Transaction" ADD COLUMN "external_id" TEXT;
+
+-- CreateIndex
+CREATE INDEX "Transaction_account_id_external_id_idx" ON "Transaction"("account_id", "external_id");
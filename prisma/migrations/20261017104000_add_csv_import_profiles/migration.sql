@@ -0,0 +1,29 @@
+-- CreateTable
+CREATE TABLE "CsvImportProfile" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "user_id" TEXT NOT NULL,
+    "name" TEXT NOT NULL,
+    "account_id" TEXT,
+    "delimiter" TEXT NOT NULL DEFAULT ',',
+    "encoding" TEXT NOT NULL DEFAULT 'utf-8',
+    "header_row_offset" INTEGER NOT NULL DEFAULT 0,
+    "has_header" BOOLEAN NOT NULL DEFAULT true,
+    "date_column" TEXT NOT NULL,
+    "date_format" TEXT NOT NULL DEFAULT '%Y-%m-%d',
+    "amount_column" TEXT,
+    "debit_column" TEXT,
+    "credit_column" TEXT,
+    "sign_convention" TEXT NOT NULL DEFAULT 'negative_is_expense',
+    "decimal_separator" TEXT NOT NULL DEFAULT '.',
+    "payee_column" TEXT,
+    "notes_column" TEXT,
+    "category_column" TEXT,
+    "external_id_column" TEXT,
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" DATETIME NOT NULL,
+    CONSTRAINT "CsvImportProfile_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+    CONSTRAINT "CsvImportProfile_account_id_fkey" FOREIGN KEY ("account_id") REFERENCES "Account" ("id") ON DELETE SET NULL ON UPDATE CASCADE
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "CsvImportProfile_user_id_name_key" ON "CsvImportProfile"("user_id", "name");
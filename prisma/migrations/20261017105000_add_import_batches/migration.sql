@@ -0,0 +1,26 @@
+-- CreateTable
+CREATE TABLE "ImportBatch" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "user_id" TEXT NOT NULL,
+    "source" TEXT NOT NULL DEFAULT 'manual',
+    "file_name" TEXT,
+    "file_hash" TEXT,
+    "profile_id" TEXT,
+    "item_count" INTEGER NOT NULL DEFAULT 0,
+    "created_count" INTEGER NOT NULL DEFAULT 0,
+    "merged_count" INTEGER NOT NULL DEFAULT 0,
+    "skipped_count" INTEGER NOT NULL DEFAULT 0,
+    "rolled_back_at" DATETIME,
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" DATETIME NOT NULL,
+    CONSTRAINT "ImportBatch_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- AlterTable
+ALTER TABLE "Transaction" ADD COLUMN "import_batch_id" TEXT REFERENCES "ImportBatch" ("id") ON DELETE SET NULL ON UPDATE CASCADE;
+
+-- CreateIndex
+CREATE INDEX "ImportBatch_user_id_created_at_idx" ON "ImportBatch"("user_id", "created_at");
+
+-- CreateIndex
+CREATE INDEX "Transaction_import_batch_id_idx" ON "Transaction"("import_batch_id");
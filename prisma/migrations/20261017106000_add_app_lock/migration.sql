@@ -0,0 +1,4 @@
+-- AlterTable
+ALTER TABLE "User" ADD COLUMN "app_lock_hash" TEXT;
+ALTER TABLE "User" ADD COLUMN "app_lock_idle_minutes" INTEGER NOT NULL DEFAULT 10;
+ALTER TABLE "User" ADD COLUMN "app_lock_on_sleep" BOOLEAN NOT NULL DEFAULT true;
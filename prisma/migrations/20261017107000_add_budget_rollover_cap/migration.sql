@@ -0,0 +1,2 @@
+-- AlterTable
+ALTER TABLE "Budget" ADD COLUMN "rollover_cap_cents" INTEGER;
@@ -0,0 +1,16 @@
+-- AlterTable
+ALTER TABLE "Budget" ADD COLUMN "recurring" BOOLEAN NOT NULL DEFAULT false;
+
+-- CreateTable
+CREATE TABLE "BudgetPeriodHistory" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "budget_id" TEXT NOT NULL,
+    "period_start" DATETIME NOT NULL,
+    "period_end" DATETIME NOT NULL,
+    "amount_cents" INTEGER NOT NULL,
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT "BudgetPeriodHistory_budget_id_fkey" FOREIGN KEY ("budget_id") REFERENCES "Budget" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "BudgetPeriodHistory_budget_id_period_start_key" ON "BudgetPeriodHistory"("budget_id", "period_start");
@@ -0,0 +1,15 @@
+-- CreateTable
+CREATE TABLE "BudgetAlert" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "budget_id" TEXT NOT NULL,
+    "period_start" DATETIME NOT NULL,
+    "kind" TEXT NOT NULL,
+    "spent_cents" INTEGER NOT NULL,
+    "available_cents" INTEGER NOT NULL,
+    "progress_percent" REAL NOT NULL,
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT "BudgetAlert_budget_id_fkey" FOREIGN KEY ("budget_id") REFERENCES "Budget" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "BudgetAlert_budget_id_period_start_kind_key" ON "BudgetAlert"("budget_id", "period_start", "kind");
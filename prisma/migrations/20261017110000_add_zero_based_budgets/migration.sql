@@ -0,0 +1,30 @@
+-- RedefineTables
+-- SQLite ignores the foreign_keys pragma inside a transaction, so it is switched off first
+PRAGMA foreign_keys=OFF;
+BEGIN;
+CREATE TABLE "new_Budget" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "user_id" TEXT NOT NULL,
+    "name" TEXT NOT NULL,
+    "period" TEXT NOT NULL CHECK ("period" IN ('weekly','monthly','quarterly','yearly')),
+    "type" TEXT NOT NULL CHECK ("type" IN ('envelope','overall','zero_based')),
+    "category_id" TEXT,
+    "amount_cents" INTEGER NOT NULL,
+    "start_date" DATETIME NOT NULL,
+    "end_date" DATETIME NOT NULL,
+    "rollover" BOOLEAN NOT NULL DEFAULT false,
+    "alert_threshold" REAL NOT NULL DEFAULT 0.8,
+    "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "rollover_cap_cents" INTEGER,
+    "recurring" BOOLEAN NOT NULL DEFAULT false,
+    CONSTRAINT "Budget_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+    CONSTRAINT "Budget_category_id_fkey" FOREIGN KEY ("category_id") REFERENCES "Category" ("id") ON DELETE SET NULL ON UPDATE CASCADE
+);
+INSERT INTO "new_Budget" ("id", "user_id", "name", "period", "type", "category_id", "amount_cents", "start_date", "end_date", "rollover", "alert_threshold", "created_at", "rollover_cap_cents", "recurring")
+SELECT "id", "user_id", "name", "period", "type", "category_id", "amount_cents", "start_date", "end_date", "rollover", "alert_threshold", "created_at", "rollover_cap_cents", "recurring" FROM "Budget";
+DROP TABLE "Budget";
+ALTER TABLE "new_Budget" RENAME TO "Budget";
+CREATE INDEX "Budget_user_id_period_start_date_idx" ON "Budget"("user_id", "period", "start_date");
+CREATE INDEX "Budget_user_id_type_start_date_idx" ON "Budget"("user_id", "type", "start_date");
+COMMIT;
+PRAGMA foreign_keys=ON;
@@ -0,0 +1,82 @@
+use std::time::{Duration, SystemTime};
+
+use tauri::{ipc::Invoke, AppHandle, Emitter, Manager, Runtime};
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::state::AppState;
+
+/// Commands the window may still call while the app is locked.
+const ALLOWED_WHILE_LOCKED: &[&str] = &["ping", "get_app_lock_status", "unlock_app"];
+
+const LOCKED_ERROR: &str = "The app is locked";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+/// A check arriving this much later than scheduled means the machine was suspended: timers
+/// stop while asleep but the wall clock keeps going.
+const SLEEP_GAP: Duration = Duration::from_secs(60);
+
+/// Wraps the generated command handler so a locked app refuses everything but the
+/// commands in [`ALLOWED_WHILE_LOCKED`], whatever the UI shows.
+pub fn guard<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        let locked = invoke
+            .message
+            .webview()
+            .try_state::<AppState>()
+            .is_some_and(|state| state.is_locked());
+        if !locked || ALLOWED_WHILE_LOCKED.contains(&command.as_str()) {
+            return handler(invoke);
+        }
+
+        warn!(command = %command, "Refused a command while the app is locked");
+        invoke.resolver.reject(LOCKED_ERROR);
+        true
+    }
+}
+
+/// Locks the app (when a PIN is set) and tells the window to show the lock screen.
+pub fn lock(app_handle: &AppHandle, reason: &str) -> bool {
+    let locked = app_handle.state::<AppState>().lock();
+    if locked {
+        info!(reason, "Locked the app");
+        if let Err(err) = app_handle.emit("app:locked", reason) {
+            warn!(error = %err, "Failed to emit app:locked event");
+        }
+    }
+    locked
+}
+
+/// Locks the app after the configured idle time and when the machine wakes from sleep.
+pub async fn watch(app_handle: AppHandle) {
+    let mut checks = interval(CHECK_INTERVAL);
+    checks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut last_check = SystemTime::now();
+
+    loop {
+        checks.tick().await;
+
+        let now = SystemTime::now();
+        let woke_up = now
+            .duration_since(last_check)
+            .is_ok_and(|gap| gap > CHECK_INTERVAL + SLEEP_GAP);
+        last_check = now;
+
+        let state = app_handle.state::<AppState>();
+        let settings = state.app_lock_settings();
+        if !settings.pin_set || state.is_locked() {
+            continue;
+        }
+
+        if woke_up && settings.lock_on_sleep {
+            lock(&app_handle, "sleep");
+        } else if settings.idle_minutes > 0
+            && state.idle_for() >= Duration::from_secs(u64::from(settings.idle_minutes) * 60)
+        {
+            lock(&app_handle, "idle");
+        }
+    }
+}
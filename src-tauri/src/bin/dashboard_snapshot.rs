@@ -1,11 +1,13 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
 use personal_finance_desktop::services::dashboard::DashboardService;
-use personal_finance_desktop::services::SqliteDashboardService;
+use personal_finance_desktop::services::{SqliteBudgetService, SqliteDashboardService};
 
 fn main() {
     let path = PathBuf::from(r"C:\Users\user\AppData\Roaming\com.example.personalfinance\Personal Finance Desktop\FinanceApp\storage\app.db");
-    let svc = SqliteDashboardService::new(path, None, None).expect("dashboard service");
+    let budgets = SqliteBudgetService::new(path.clone(), None, None).expect("budget service");
+    let svc = SqliteDashboardService::new(path, None, None, Arc::new(budgets))
+        .expect("dashboard service");
     match svc.snapshot() {
         Ok(snapshot) => {
             println!("currency {}", snapshot.currency);
@@ -12,6 +12,10 @@ fn main() -> Result<()> {
     println!("Inspecting DB at: {}", db_path.display());
 
     let conn = Connection::open(&db_path)?;
+    // The app database is SQLCipher-encrypted; pass the key from secrets.json
+    if let Ok(key) = env::var("DB_KEY") {
+        conn.pragma_update(None, "key", key)?;
+    }
     for table in ["User", "Account", "Transaction", "Budget", "Goal", "Reminder"] {
         match conn.query_row::<i64, _, _>(
             &format!("SELECT COUNT(*) FROM \"{}\"", table),
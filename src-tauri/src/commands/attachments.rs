@@ -0,0 +1,78 @@
+use serde::Deserialize;
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::{
+    services::{AttachFilesInput, AttachmentContentDto, AttachmentDto, AttachmentGcResult},
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListAttachmentsPayload {
+    pub transaction_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentIdPayload {
+    pub id: String,
+}
+
+#[tauri::command]
+pub async fn attach_files(
+    state: State<'_, AppState>,
+    payload: AttachFilesInput,
+) -> Result<Vec<AttachmentDto>, String> {
+    let service = state.services().attachment();
+    spawn_blocking(move || service.attach_files(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn list_attachments(
+    state: State<'_, AppState>,
+    payload: ListAttachmentsPayload,
+) -> Result<Vec<AttachmentDto>, String> {
+    let service = state.services().attachment();
+    spawn_blocking(move || service.list_attachments(&payload.transaction_id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn read_attachment(
+    state: State<'_, AppState>,
+    payload: AttachmentIdPayload,
+) -> Result<AttachmentContentDto, String> {
+    let service = state.services().attachment();
+    spawn_blocking(move || service.read_attachment(&payload.id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_attachment(
+    state: State<'_, AppState>,
+    payload: AttachmentIdPayload,
+) -> Result<(), String> {
+    let service = state.services().attachment();
+    spawn_blocking(move || service.remove_attachment(&payload.id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn collect_attachment_garbage(
+    state: State<'_, AppState>,
+) -> Result<AttachmentGcResult, String> {
+    let service = state.services().attachment();
+    spawn_blocking(move || service.collect_garbage())
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::{
+    services::{self, BackupSummary},
+    state::AppState,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBackupPayload {
+    /// Directory to write the archive to; defaults to the exports directory.
+    pub dest_dir: Option<String>,
+}
+
+/// Archives the (still encrypted) database together with the attachment files.
+#[tauri::command]
+pub async fn create_backup(
+    state: State<'_, AppState>,
+    payload: Option<CreateBackupPayload>,
+) -> Result<BackupSummary, String> {
+    let paths = state.paths().clone();
+    let key = state.secrets()?.sqlcipher_key().to_string();
+    let dest_dir = payload
+        .and_then(|payload| payload.dest_dir)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| paths.exports_dir().to_path_buf());
+
+    spawn_blocking(move || {
+        services::create_backup(
+            paths.db_path(),
+            Some(&key),
+            paths.attachments_dir(),
+            &dest_dir,
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(|err| err.to_string())
+}
@@ -2,15 +2,20 @@ use tauri::{async_runtime::spawn_blocking, State};
 
 use crate::{
     services::{
-        BudgetDto, BudgetEntryDto, CreateBudgetInput, RecordSnapshotInput, UpdateBudgetInput,
+        AssignToEnvelopeInput, BudgetAlertDto, BudgetDto, BudgetEntryDto, BudgetPeriodSummaryDto,
+        CreateBudgetInput, ListBudgetSnapshotsInput, MoveEnvelopeFundsInput, RecordSnapshotInput,
+        UpdateBudgetInput, ZeroBasedMonthDto,
     },
     state::AppState,
 };
 
 #[tauri::command]
-pub async fn list_budgets(state: State<'_, AppState>) -> Result<Vec<BudgetDto>, String> {
+pub async fn list_budgets(
+    state: State<'_, AppState>,
+    as_of: Option<String>,
+) -> Result<Vec<BudgetDto>, String> {
     let service = state.services().budget();
-    spawn_blocking(move || service.list_budgets())
+    spawn_blocking(move || service.list_budgets(as_of.as_deref()))
         .await
         .map_err(|err| err.to_string())?
         .map_err(|err| err.to_string())
@@ -31,10 +36,12 @@ pub async fn create_budget(
     payload: CreateBudgetInput,
 ) -> Result<BudgetDto, String> {
     let service = state.services().budget();
-    spawn_blocking(move || service.create_budget(payload))
+    let budget = spawn_blocking(move || service.create_budget(payload))
         .await
         .map_err(|err| err.to_string())?
-        .map_err(|err| err.to_string())
+        .map_err(|err| err.to_string())?;
+    state.request_budget_check();
+    Ok(budget)
 }
 
 #[tauri::command]
@@ -43,10 +50,12 @@ pub async fn update_budget(
     payload: UpdateBudgetInput,
 ) -> Result<BudgetDto, String> {
     let service = state.services().budget();
-    spawn_blocking(move || service.update_budget(payload))
+    let budget = spawn_blocking(move || service.update_budget(payload))
         .await
         .map_err(|err| err.to_string())?
-        .map_err(|err| err.to_string())
+        .map_err(|err| err.to_string())?;
+    state.request_budget_check();
+    Ok(budget)
 }
 
 #[tauri::command]
@@ -69,3 +78,82 @@ pub async fn record_snapshot(
         .map_err(|err| err.to_string())?
         .map_err(|err| err.to_string())
 }
+
+#[tauri::command]
+pub async fn list_budget_snapshots(
+    state: State<'_, AppState>,
+    payload: ListBudgetSnapshotsInput,
+) -> Result<Vec<BudgetEntryDto>, String> {
+    let service = state.services().budget();
+    spawn_blocking(move || service.list_budget_snapshots(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn get_budget_period_summary(
+    state: State<'_, AppState>,
+    id: String,
+    as_of: Option<String>,
+) -> Result<BudgetPeriodSummaryDto, String> {
+    let service = state.services().budget();
+    spawn_blocking(move || service.get_period_summary(&id, as_of.as_deref()))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn get_zero_based_month(
+    state: State<'_, AppState>,
+    month: Option<String>,
+) -> Result<ZeroBasedMonthDto, String> {
+    let service = state.services().budget();
+    spawn_blocking(move || service.get_zero_based_month(month.as_deref()))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn assign_to_envelope(
+    state: State<'_, AppState>,
+    payload: AssignToEnvelopeInput,
+) -> Result<ZeroBasedMonthDto, String> {
+    let service = state.services().budget();
+    let month = spawn_blocking(move || service.assign_to_envelope(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+    state.request_budget_check();
+    Ok(month)
+}
+
+#[tauri::command]
+pub async fn move_envelope_funds(
+    state: State<'_, AppState>,
+    payload: MoveEnvelopeFundsInput,
+) -> Result<ZeroBasedMonthDto, String> {
+    let service = state.services().budget();
+    let month = spawn_blocking(move || service.move_envelope_funds(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+    state.request_budget_check();
+    Ok(month)
+}
+
+const DEFAULT_ALERT_LIMIT: u32 = 50;
+
+#[tauri::command]
+pub async fn list_budget_alerts(
+    state: State<'_, AppState>,
+    limit: Option<u32>,
+) -> Result<Vec<BudgetAlertDto>, String> {
+    let service = state.services().budget();
+    spawn_blocking(move || service.list_budget_alerts(limit.unwrap_or(DEFAULT_ALERT_LIMIT)))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
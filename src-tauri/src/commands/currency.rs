@@ -0,0 +1,92 @@
+use std::fs;
+
+use serde::Deserialize;
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::{
+    services::{
+        ConversionDto, ConvertAmountInput, ExchangeRateDto, ExchangeRateQuery,
+        ImportExchangeRatesInput, ImportExchangeRatesResult, RateFileFormat,
+        UpsertExchangeRateInput,
+    },
+    state::AppState,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportExchangeRatesPayload {
+    pub file_path: String,
+    /// Inferred from the file extension when omitted.
+    #[serde(default)]
+    pub format: Option<RateFileFormat>,
+}
+
+#[tauri::command]
+pub async fn list_exchange_rates(
+    state: State<'_, AppState>,
+    query: Option<ExchangeRateQuery>,
+) -> Result<Vec<ExchangeRateDto>, String> {
+    let service = state.services().currency();
+    spawn_blocking(move || service.list_exchange_rates(query.unwrap_or_default()))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn upsert_exchange_rate(
+    state: State<'_, AppState>,
+    payload: UpsertExchangeRateInput,
+) -> Result<ExchangeRateDto, String> {
+    let service = state.services().currency();
+    spawn_blocking(move || service.upsert_exchange_rate(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_exchange_rate(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    let service = state.services().currency();
+    spawn_blocking(move || service.delete_exchange_rate(&id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+/// Imports a rate file (CSV or ECB XML) from disk.
+#[tauri::command]
+pub async fn import_exchange_rates(
+    state: State<'_, AppState>,
+    payload: ImportExchangeRatesPayload,
+) -> Result<ImportExchangeRatesResult, String> {
+    let service = state.services().currency();
+    spawn_blocking(move || {
+        let format = payload
+            .format
+            .or_else(|| RateFileFormat::from_path(&payload.file_path))
+            .ok_or_else(|| {
+                "Unsupported rate file. Only CSV and ECB XML are supported.".to_string()
+            })?;
+        let contents = fs::read_to_string(&payload.file_path)
+            .map_err(|err| format!("Failed to read file: {err}"))?;
+
+        service
+            .import_exchange_rates(ImportExchangeRatesInput { format, contents })
+            .map_err(|err| err.to_string())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+pub async fn convert_amount(
+    state: State<'_, AppState>,
+    payload: ConvertAmountInput,
+) -> Result<ConversionDto, String> {
+    let service = state.services().currency();
+    spawn_blocking(move || service.convert_amount(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
@@ -1,12 +1,11 @@
 use std::fs;
 use std::io::Write;
 
-use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tauri::{async_runtime::spawn_blocking, State};
 
 use crate::{
-    services::{MonthlyReportDto, SpendingByCategoryDto},
+    services::{export_crypto, MonthlyReportDto, SpendingByCategoryDto},
     state::AppState,
 };
 
@@ -95,35 +94,25 @@ pub async fn export_report_json(
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
+/// Writes the report encrypted with a key derived from `passphrase`; see
+/// `services::export_crypto` for the file layout.
 #[tauri::command]
 pub async fn export_report_encrypted_json(
     state: State<'_, AppState>,
     month: String,
     report: MonthlyReportDto,
+    passphrase: String,
 ) -> Result<ExportResult, String> {
     let exports_dir = state.paths().exports_dir().to_path_buf();
     let file_name = format!("report_{}_encrypted.json", month);
     let file_path = exports_dir.join(&file_name);
 
     spawn_blocking(move || {
-        // For now, we'll use base64 encoding as a simple encryption placeholder
-        // In production, use proper encryption (AES-GCM) with user key
-        let json = serde_json::to_string(&report)
+        let json = serde_json::to_vec(&report)
             .map_err(|e| format!("Failed to serialize report: {}", e))?;
 
-        use base64::{engine::general_purpose, Engine as _};
-        let encoded = general_purpose::STANDARD.encode(json.as_bytes());
-
-        let encrypted_payload = serde_json::json!({
-            "version": "1.0",
-            "encrypted": true,
-            "timestamp": Utc::now().to_rfc3339(),
-            "data": encoded,
-            "checksum": calculate_checksum(&encoded),
-        });
-
-        let encrypted_json = serde_json::to_string_pretty(&encrypted_payload)
-            .map_err(|e| format!("Failed to serialize encrypted payload: {}", e))?;
+        let encrypted_json = export_crypto::seal(&json, &passphrase)
+            .map_err(|e| format!("Failed to encrypt report: {}", e))?;
 
         fs::write(&file_path, encrypted_json)
             .map_err(|e| format!("Failed to write encrypted JSON file: {}", e))?;
@@ -176,12 +165,3 @@ fn escape_csv_field(field: &str) -> String {
         field.to_string()
     }
 }
-
-fn calculate_checksum(data: &str) -> String {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-
-    let mut hasher = DefaultHasher::new();
-    data.hash(&mut hasher);
-    format!("{:x}", hasher.finish())
-}
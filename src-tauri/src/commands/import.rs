@@ -1,9 +1,17 @@
 use std::fs;
 
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tauri::{async_runtime::spawn_blocking, State};
 
-use crate::state::AppState;
+use crate::{
+    services::{
+        export_crypto, import::decode_statement_file, CsvCommitResultDto, CsvImportInput,
+        CsvImportProfileDto, CsvImportProfileInput, CsvPreviewDto, ExportQifInput, ImportOfxInput,
+        ImportQifInput, OfxImportResult, QifImportResult, UpdateCsvImportProfileInput,
+    },
+    state::AppState,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -18,12 +26,41 @@ pub struct ReadImportFilePayload {
     pub file_path: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QifExportFileResult {
+    pub file_path: String,
+    pub file_name: String,
+    pub account_count: usize,
+    pub transaction_count: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvImportProfileIdPayload {
+    pub id: String,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DecryptEncryptedJsonPayload {
     pub file_path: String,
+    /// Not needed for files in the legacy format.
+    #[serde(default)]
+    pub passphrase: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptedJsonResult {
+    pub contents: String,
+    /// Set when the file used the old format, which was not actually encrypted.
+    pub warning: Option<String>,
+}
+
+const LEGACY_EXPORT_WARNING: &str = "This file uses the old export format, which was not \
+    encrypted. Export it again to protect it with a passphrase.";
+
 #[tauri::command]
 pub async fn read_import_file(
     _state: State<'_, AppState>,
@@ -32,17 +69,33 @@ pub async fn read_import_file(
     let file_path = payload.file_path.clone();
 
     spawn_blocking(move || {
+        let lower = file_path.to_lowercase();
+
+        // OFX downloads are often Windows-1252, so they are decoded per their header
+        if lower.ends_with(".ofx") || lower.ends_with(".qfx") {
+            let bytes = fs::read(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+            return Ok(ImportFileResult {
+                contents: decode_statement_file(&bytes),
+                format: "ofx".to_string(),
+            });
+        }
+
         // Read file contents
         let contents =
             fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
 
         // Determine format from file extension
-        let format = if file_path.ends_with(".csv") {
+        let format = if lower.ends_with(".csv") {
             "csv"
-        } else if file_path.ends_with(".json") {
+        } else if lower.ends_with(".json") {
             "json"
+        } else if lower.ends_with(".qif") {
+            "qif"
         } else {
-            return Err("Unsupported file format. Only CSV and JSON are supported.".to_string());
+            return Err(
+                "Unsupported file format. Only CSV, JSON, OFX/QFX and QIF are supported."
+                    .to_string(),
+            );
         };
 
         Ok(ImportFileResult {
@@ -55,64 +108,154 @@ pub async fn read_import_file(
 }
 
 #[tauri::command]
-pub async fn decrypt_encrypted_json(
-    _state: State<'_, AppState>,
-    payload: DecryptEncryptedJsonPayload,
-) -> Result<String, String> {
-    let file_path = payload.file_path.clone();
+pub async fn import_ofx_file(
+    state: State<'_, AppState>,
+    payload: ImportOfxInput,
+) -> Result<OfxImportResult, String> {
+    let service = state.services().import();
+    let result = spawn_blocking(move || service.import_ofx(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+    state.request_budget_check();
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn import_qif_file(
+    state: State<'_, AppState>,
+    payload: ImportQifInput,
+) -> Result<QifImportResult, String> {
+    let service = state.services().import();
+    let result = spawn_blocking(move || service.import_qif(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+    state.request_budget_check();
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn export_qif_file(
+    state: State<'_, AppState>,
+    payload: ExportQifInput,
+) -> Result<QifExportFileResult, String> {
+    let service = state.services().import();
+    let exports_dir = state.paths().exports_dir().to_path_buf();
+    let file_name = format!("transactions_{}.qif", Utc::now().format("%Y%m%d_%H%M%S"));
+    let file_path = exports_dir.join(&file_name);
 
     spawn_blocking(move || {
-        // Read encrypted JSON file
-        let contents = fs::read_to_string(&file_path)
-            .map_err(|e| format!("Failed to read encrypted JSON file: {}", e))?;
+        let export = service.export_qif(payload).map_err(|err| err.to_string())?;
+        fs::write(&file_path, export.contents)
+            .map_err(|e| format!("Failed to write QIF file: {}", e))?;
 
-        // Parse encrypted JSON wrapper
-        let encrypted_wrapper: serde_json::Value = serde_json::from_str(&contents)
-            .map_err(|e| format!("Failed to parse encrypted JSON: {}", e))?;
+        Ok(QifExportFileResult {
+            file_path: file_path.to_string_lossy().to_string(),
+            file_name,
+            account_count: export.account_count,
+            transaction_count: export.transaction_count,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
 
-        // Verify encrypted flag
-        let is_encrypted = encrypted_wrapper
-            .get("encrypted")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
+#[tauri::command]
+pub async fn list_csv_import_profiles(
+    state: State<'_, AppState>,
+) -> Result<Vec<CsvImportProfileDto>, String> {
+    let service = state.services().import();
+    spawn_blocking(move || service.list_csv_profiles())
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
 
-        if !is_encrypted {
-            return Err("File is not marked as encrypted".to_string());
-        }
+#[tauri::command]
+pub async fn create_csv_import_profile(
+    state: State<'_, AppState>,
+    payload: CsvImportProfileInput,
+) -> Result<CsvImportProfileDto, String> {
+    let service = state.services().import();
+    spawn_blocking(move || service.create_csv_profile(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
 
-        // Decode base64 data
-        let encoded_data = encrypted_wrapper
-            .get("data")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| "Missing encrypted data field".to_string())?;
-
-        // Verify checksum
-        let expected_checksum = encrypted_wrapper
-            .get("checksum")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| "Missing checksum field".to_string())?;
-
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let mut hasher = DefaultHasher::new();
-        encoded_data.hash(&mut hasher);
-        let calculated_checksum = format!("{:x}", hasher.finish());
-
-        if calculated_checksum != expected_checksum {
-            return Err(
-                "Checksum verification failed - file may be corrupted or tampered".to_string(),
-            );
-        }
+#[tauri::command]
+pub async fn update_csv_import_profile(
+    state: State<'_, AppState>,
+    payload: UpdateCsvImportProfileInput,
+) -> Result<CsvImportProfileDto, String> {
+    let service = state.services().import();
+    spawn_blocking(move || service.update_csv_profile(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_csv_import_profile(
+    state: State<'_, AppState>,
+    payload: CsvImportProfileIdPayload,
+) -> Result<(), String> {
+    let service = state.services().import();
+    spawn_blocking(move || service.delete_csv_profile(&payload.id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn preview_csv_import(
+    state: State<'_, AppState>,
+    payload: CsvImportInput,
+) -> Result<CsvPreviewDto, String> {
+    let service = state.services().import();
+    spawn_blocking(move || service.preview_csv(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
 
-        // Decode base64
-        use base64::{engine::general_purpose, Engine as _};
-        let decoded_bytes = general_purpose::STANDARD
-            .decode(encoded_data)
-            .map_err(|e| format!("Failed to decode base64 data: {}", e))?;
+#[tauri::command]
+pub async fn commit_csv_import(
+    state: State<'_, AppState>,
+    payload: CsvImportInput,
+) -> Result<CsvCommitResultDto, String> {
+    let service = state.services().import();
+    let result = spawn_blocking(move || service.commit_csv(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+    state.request_budget_check();
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn decrypt_encrypted_json(
+    _state: State<'_, AppState>,
+    payload: DecryptEncryptedJsonPayload,
+) -> Result<DecryptedJsonResult, String> {
+    spawn_blocking(move || {
+        let contents = fs::read_to_string(&payload.file_path)
+            .map_err(|e| format!("Failed to read encrypted JSON file: {}", e))?;
+
+        let opened = export_crypto::open(&contents, payload.passphrase.as_deref())
+            .map_err(|e| format!("Failed to decrypt file: {}", e))?;
+        let warning = opened.legacy.then(|| {
+            tracing::warn!(
+                file = %payload.file_path,
+                "Read an encrypted export in the legacy unencrypted format"
+            );
+            LEGACY_EXPORT_WARNING.to_string()
+        });
 
-        // Return decrypted JSON string
-        String::from_utf8(decoded_bytes)
-            .map_err(|e| format!("Failed to convert decrypted data to string: {}", e))
+        let contents = String::from_utf8(opened.plaintext)
+            .map_err(|e| format!("Failed to convert decrypted data to string: {}", e))?;
+        Ok(DecryptedJsonResult { contents, warning })
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
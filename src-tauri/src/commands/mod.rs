@@ -1,22 +1,32 @@
+mod attachments;
+mod backup;
 mod budgets;
+mod currency;
 mod dashboard;
 mod export;
 mod goals;
 mod import;
+mod recurring;
 mod reminders;
 mod reports;
+mod security;
 mod settings;
 mod sync;
 mod transactions;
 mod planning;
 
+pub use attachments::*;
+pub use backup::*;
 pub use budgets::*;
+pub use currency::*;
 pub use dashboard::*;
 pub use export::*;
 pub use goals::*;
 pub use import::*;
+pub use recurring::*;
 pub use reminders::*;
 pub use reports::*;
+pub use security::*;
 pub use settings::*;
 pub use sync::*;
 pub use transactions::*;
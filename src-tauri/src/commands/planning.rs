@@ -18,7 +18,7 @@ pub async fn create_monthly_plan(
     input: CreateMonthlyPlanInput,
 ) -> Result<crate::services::planning::MonthlyPlanDto, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -33,7 +33,7 @@ pub async fn list_monthly_plans(
     state: State<'_, AppState>,
 ) -> Result<Vec<crate::services::planning::MonthlyPlanDto>, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -49,7 +49,7 @@ pub async fn add_planned_income(
     input: AddPlannedIncomeInput,
 ) -> Result<crate::services::planning::PlannedIncomeDto, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -65,7 +65,7 @@ pub async fn list_planned_incomes(
     plan_id: String,
 ) -> Result<Vec<crate::services::planning::PlannedIncomeDto>, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -81,7 +81,7 @@ pub async fn update_planned_income(
     input: UpdatePlannedIncomeInput,
 ) -> Result<crate::services::planning::PlannedIncomeDto, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -97,7 +97,7 @@ pub async fn delete_planned_income(
     input: DeletePlannedIncomeInput,
 ) -> Result<(), String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -113,7 +113,7 @@ pub async fn add_planned_expense(
     input: AddPlannedExpenseInput,
 ) -> Result<crate::services::planning::PlannedExpenseDto, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -129,7 +129,7 @@ pub async fn list_planned_expenses(
     plan_id: String,
 ) -> Result<Vec<crate::services::planning::PlannedExpenseDto>, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -145,7 +145,7 @@ pub async fn update_planned_expense(
     input: UpdatePlannedExpenseInput,
 ) -> Result<crate::services::planning::PlannedExpenseDto, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -161,7 +161,7 @@ pub async fn delete_planned_expense(
     input: DeletePlannedExpenseInput,
 ) -> Result<(), String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -177,7 +177,7 @@ pub async fn add_planned_saving(
     input: AddPlannedSavingInput,
 ) -> Result<crate::services::planning::PlannedSavingDto, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -193,7 +193,7 @@ pub async fn list_planned_savings(
     plan_id: String,
 ) -> Result<Vec<crate::services::planning::PlannedSavingDto>, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -209,7 +209,7 @@ pub async fn update_planned_saving(
     input: UpdatePlannedSavingInput,
 ) -> Result<crate::services::planning::PlannedSavingDto, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -225,7 +225,7 @@ pub async fn delete_planned_saving(
     input: DeletePlannedSavingInput,
 ) -> Result<(), String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -241,7 +241,7 @@ pub async fn add_debt_account(
     input: AddDebtAccountInput,
 ) -> Result<crate::services::planning::DebtAccountDto, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -256,7 +256,7 @@ pub async fn list_debt_accounts(
     state: State<'_, AppState>,
 ) -> Result<Vec<crate::services::planning::DebtAccountDto>, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -272,7 +272,7 @@ pub async fn update_debt_account(
     input: UpdateDebtAccountInput,
 ) -> Result<crate::services::planning::DebtAccountDto, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -288,7 +288,7 @@ pub async fn delete_debt_account(
     input: DeleteDebtAccountInput,
 ) -> Result<(), String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -304,7 +304,7 @@ pub async fn generate_debt_schedule(
     input: GenerateDebtScheduleInput,
 ) -> Result<Vec<crate::services::planning::DebtScheduleDto>, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -320,7 +320,7 @@ pub async fn list_debt_schedule(
     debt_id: String,
 ) -> Result<Vec<crate::services::planning::DebtScheduleDto>, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -336,7 +336,7 @@ pub async fn confirm_debt_payment(
     input: ConfirmDebtPaymentInput,
 ) -> Result<crate::services::planning::DebtScheduleDto, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -352,7 +352,7 @@ pub async fn plan_vs_actual(
     plan_id: String,
 ) -> Result<crate::services::planning::PlanActualSummary, String> {
     let db_path = state.paths().db_path().to_path_buf();
-    let key = state.secrets().sqlcipher_key().to_string();
+    let key = state.secrets()?.sqlcipher_key().to_string();
     spawn_blocking(move || {
         let svc = PlanningService::new(db_path, Some(key), "seed-user".to_string())
             .map_err(|e| e.to_string())?;
@@ -0,0 +1,84 @@
+use tauri::{async_runtime::spawn_blocking, State};
+
+use crate::{
+    services::{
+        CreateRecurringTransactionInput, RecurringTransactionDto, SkipOccurrenceInput,
+        TransactionDto, UpdateRecurringTransactionInput,
+    },
+    state::AppState,
+};
+
+#[tauri::command]
+pub async fn list_recurring_transactions(
+    state: State<'_, AppState>,
+) -> Result<Vec<RecurringTransactionDto>, String> {
+    let service = state.services().recurring();
+    spawn_blocking(move || service.list_recurring())
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn create_recurring_transaction(
+    state: State<'_, AppState>,
+    payload: CreateRecurringTransactionInput,
+) -> Result<RecurringTransactionDto, String> {
+    let service = state.services().recurring();
+    spawn_blocking(move || service.create_recurring(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn update_recurring_transaction(
+    state: State<'_, AppState>,
+    payload: UpdateRecurringTransactionInput,
+) -> Result<RecurringTransactionDto, String> {
+    let service = state.services().recurring();
+    spawn_blocking(move || service.update_recurring(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_recurring_transaction(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), String> {
+    let service = state.services().recurring();
+    spawn_blocking(move || service.delete_recurring(&id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn skip_recurring_occurrence(
+    state: State<'_, AppState>,
+    payload: SkipOccurrenceInput,
+) -> Result<RecurringTransactionDto, String> {
+    let service = state.services().recurring();
+    spawn_blocking(move || service.skip_occurrence(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+/// Posts anything due now instead of waiting for the next scheduler tick.
+#[tauri::command]
+pub async fn post_due_recurring_transactions(
+    state: State<'_, AppState>,
+) -> Result<Vec<TransactionDto>, String> {
+    let service = state.services().recurring();
+    // The UTC date, like the scheduler, so both post the same occurrences
+    let today = chrono::Utc::now().date_naive();
+    let posted = spawn_blocking(move || service.post_due(today))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+    state.request_budget_check();
+    Ok(posted)
+}
@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime::spawn_blocking, AppHandle, Manager, State};
+
+use crate::{
+    app_lock,
+    secrets::KeySource,
+    services::{AppLockSettingsDto, SetAppLockPinInput, UpdateAppLockSettingsInput},
+    session,
+    state::AppState,
+};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionStatus {
+    /// Waiting for the master passphrase; every data command fails until unlocked.
+    pub locked: bool,
+    pub passphrase_protected: bool,
+    /// The key comes from `PF_APP_DB_KEY` and cannot be rotated or wrapped by the app.
+    pub key_from_environment: bool,
+    /// The platform keyring and the encrypted file hold different keys; says which is used.
+    pub secret_store_conflict: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlockPayload {
+    pub passphrase: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetMasterPassphrasePayload {
+    /// Required when a passphrase is already set.
+    pub current_passphrase: Option<String>,
+    /// `None` or empty removes the passphrase and stores the key unwrapped again.
+    pub new_passphrase: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateEncryptionKeyPayload {
+    /// Required when a master passphrase is set.
+    pub passphrase: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLockStatus {
+    /// Every command but `ping`, `get_app_lock_status` and `unlock_app` is refused.
+    pub locked: bool,
+    #[serde(flatten)]
+    pub settings: AppLockSettingsDto,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlockAppPayload {
+    pub pin: String,
+}
+
+fn app_lock_status(state: &AppState) -> AppLockStatus {
+    AppLockStatus {
+        locked: state.is_locked(),
+        settings: state.app_lock_settings(),
+    }
+}
+
+#[tauri::command]
+pub fn get_encryption_status(state: State<AppState>) -> EncryptionStatus {
+    match state.secrets() {
+        Ok(secrets) => EncryptionStatus {
+            locked: false,
+            passphrase_protected: secrets.is_passphrase_protected(),
+            key_from_environment: secrets.source() == KeySource::Environment,
+            secret_store_conflict: state.secret_store_conflict().map(str::to_string),
+        },
+        // Only a wrapped key keeps the app sealed after startup
+        Err(_) => EncryptionStatus {
+            locked: true,
+            passphrase_protected: true,
+            key_from_environment: false,
+            secret_store_conflict: state.secret_store_conflict().map(str::to_string),
+        },
+    }
+}
+
+#[tauri::command]
+pub async fn unlock_with_passphrase(app: AppHandle, payload: UnlockPayload) -> Result<(), String> {
+    spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        if !state.is_sealed() {
+            return Ok(());
+        }
+        session::unlock(&state, &payload.passphrase)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+/// Sets, changes or removes the master passphrase; returns whether one is set afterwards.
+#[tauri::command]
+pub async fn set_master_passphrase(
+    app: AppHandle,
+    payload: SetMasterPassphrasePayload,
+) -> Result<bool, String> {
+    spawn_blocking(move || {
+        session::set_passphrase(
+            &app.state::<AppState>(),
+            payload.current_passphrase.as_deref(),
+            payload.new_passphrase.as_deref(),
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+/// Re-keys the database and attachments with a new random key.
+#[tauri::command]
+pub async fn rotate_encryption_key(
+    app: AppHandle,
+    payload: Option<RotateEncryptionKeyPayload>,
+) -> Result<(), String> {
+    let passphrase = payload.unwrap_or_default().passphrase;
+    spawn_blocking(move || {
+        session::rotate_key(&app.state::<AppState>(), passphrase.as_deref()).map_err(|err| {
+            tracing::error!(error = %err, "Failed to rotate the encryption key");
+            err
+        })
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+pub fn get_app_lock_status(state: State<AppState>) -> AppLockStatus {
+    app_lock_status(&state)
+}
+
+#[tauri::command]
+pub async fn unlock_app(app: AppHandle, payload: UnlockAppPayload) -> Result<(), String> {
+    spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        if !state.is_locked() {
+            return Ok(());
+        }
+        state.start_pin_check()?;
+        let checked = state
+            .services()
+            .settings()
+            .verify_app_lock_pin(&payload.pin)
+            .map_err(|err| err.to_string());
+        state.finish_pin_check(matches!(checked, Ok(true)));
+        if checked? {
+            Ok(())
+        } else {
+            Err("Incorrect PIN".to_string())
+        }
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+pub fn lock_app(app: AppHandle) -> Result<(), String> {
+    if app_lock::lock(&app, "manual") || app.state::<AppState>().is_locked() {
+        Ok(())
+    } else {
+        Err("Set a PIN before locking the app".to_string())
+    }
+}
+
+/// Called by the window on user input; resets the idle timer.
+#[tauri::command]
+pub fn report_activity(state: State<AppState>) {
+    state.record_activity();
+}
+
+#[tauri::command]
+pub async fn set_app_lock_pin(
+    state: State<'_, AppState>,
+    input: SetAppLockPinInput,
+) -> Result<AppLockStatus, String> {
+    let service = state.services().settings();
+    let settings = spawn_blocking(move || service.set_app_lock_pin(input))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+    state.set_app_lock_settings(settings);
+    state.record_activity();
+    Ok(app_lock_status(&state))
+}
+
+#[tauri::command]
+pub async fn update_app_lock_settings(
+    state: State<'_, AppState>,
+    input: UpdateAppLockSettingsInput,
+) -> Result<AppLockStatus, String> {
+    let service = state.services().settings();
+    let settings = spawn_blocking(move || service.update_app_lock_settings(input))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+    state.set_app_lock_settings(settings);
+    state.record_activity();
+    Ok(app_lock_status(&state))
+}
@@ -3,8 +3,15 @@ use tauri::{async_runtime::spawn_blocking, State};
 
 use crate::{
     services::{
-        AccountDto, CategoryDto, CreateTransactionInput, ImportTransactionsInput, TransactionDto,
-        TransactionQuery, UpdateTransactionInput,
+        AccountDto, ApplyRulesInput, ApplyRulesResult, ArchiveCategoryInput, CategoryDto,
+        CreateAccountInput, CreateCategoryInput, CreatePayeeInput, CreateTransactionInput,
+        DeleteCategoryInput, ImportBatchDto, ImportTransactionsInput, ImportTransactionsResult,
+        MarkReconciliationInput, MergeCategoriesInput, PayeeDto, ReconciliationDetailDto,
+        ReconciliationDto, ReorderAccountsInput, StartReconciliationInput, SuspectedDuplicateDto,
+        TransactionDto, TransactionPage, TransactionQuery, TransactionRuleDto,
+        TransactionRuleInput, UpdateAccountInput, UpdateAccountStatusInput, UpdateCategoryInput,
+        UpdatePayeeInput, UpdateReconciliationInput, UpdateTransactionInput,
+        UpdateTransactionRuleInput,
     },
     state::AppState,
 };
@@ -14,16 +21,58 @@ use crate::{
 pub struct ListAccountsPayload {
     #[serde(default = "default_include_balances")]
     pub include_balances: bool,
+    #[serde(default)]
+    pub include_archived: bool,
 }
 
 fn default_include_balances() -> bool {
     true
 }
 
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCategoriesPayload {
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
 #[derive(Debug, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteTransactionPayload {
     pub id: String,
+    /// Required to delete a transaction locked by a completed reconciliation.
+    #[serde(default)]
+    pub override_reconciled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportBatchIdPayload {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletePayeePayload {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteTransactionRulePayload {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListReconciliationsPayload {
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationIdPayload {
+    pub id: String,
 }
 
 #[tauri::command]
@@ -31,20 +80,132 @@ pub async fn list_accounts(
     state: State<'_, AppState>,
     payload: Option<ListAccountsPayload>,
 ) -> Result<Vec<AccountDto>, String> {
-    let include = payload
-        .map(|p| p.include_balances)
-        .unwrap_or_else(default_include_balances);
+    let (include_balances, include_archived) = payload
+        .map(|p| (p.include_balances, p.include_archived))
+        .unwrap_or_else(|| (default_include_balances(), false));
+    let service = state.services().transaction();
+    spawn_blocking(move || service.list_accounts(include_balances, include_archived))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn create_account(
+    state: State<'_, AppState>,
+    payload: CreateAccountInput,
+) -> Result<AccountDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.create_account(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn update_account(
+    state: State<'_, AppState>,
+    payload: UpdateAccountInput,
+) -> Result<AccountDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.update_account(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn update_account_status(
+    state: State<'_, AppState>,
+    payload: UpdateAccountStatusInput,
+) -> Result<AccountDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.update_account_status(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn reorder_accounts(
+    state: State<'_, AppState>,
+    payload: ReorderAccountsInput,
+) -> Result<(), String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.reorder_accounts(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn list_categories(
+    state: State<'_, AppState>,
+    payload: Option<ListCategoriesPayload>,
+) -> Result<Vec<CategoryDto>, String> {
+    let include_archived = payload.unwrap_or_default().include_archived;
+    let service = state.services().transaction();
+    spawn_blocking(move || service.list_categories(include_archived))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn create_category(
+    state: State<'_, AppState>,
+    payload: CreateCategoryInput,
+) -> Result<CategoryDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.create_category(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn update_category(
+    state: State<'_, AppState>,
+    payload: UpdateCategoryInput,
+) -> Result<CategoryDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.update_category(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn archive_category(
+    state: State<'_, AppState>,
+    payload: ArchiveCategoryInput,
+) -> Result<CategoryDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.archive_category(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn merge_categories(
+    state: State<'_, AppState>,
+    payload: MergeCategoriesInput,
+) -> Result<CategoryDto, String> {
     let service = state.services().transaction();
-    spawn_blocking(move || service.list_accounts(include))
+    spawn_blocking(move || service.merge_categories(payload))
         .await
         .map_err(|err| err.to_string())?
         .map_err(|err| err.to_string())
 }
 
 #[tauri::command]
-pub async fn list_categories(state: State<'_, AppState>) -> Result<Vec<CategoryDto>, String> {
+pub async fn delete_category(
+    state: State<'_, AppState>,
+    payload: DeleteCategoryInput,
+) -> Result<(), String> {
     let service = state.services().transaction();
-    spawn_blocking(move || service.list_categories())
+    spawn_blocking(move || service.delete_category(payload))
         .await
         .map_err(|err| err.to_string())?
         .map_err(|err| err.to_string())
@@ -63,16 +224,32 @@ pub async fn list_transactions(
         .map_err(|err| err.to_string())
 }
 
+/// Paged search returning the total match count alongside the page.
+#[tauri::command]
+pub async fn search_transactions(
+    state: State<'_, AppState>,
+    payload: Option<TransactionQuery>,
+) -> Result<TransactionPage, String> {
+    let query = payload.unwrap_or_default();
+    let service = state.services().transaction();
+    spawn_blocking(move || service.search_transactions(query))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 pub async fn create_transaction(
     state: State<'_, AppState>,
     payload: CreateTransactionInput,
 ) -> Result<TransactionDto, String> {
     let service = state.services().transaction();
-    spawn_blocking(move || service.create_transaction(payload))
+    let transaction = spawn_blocking(move || service.create_transaction(payload))
         .await
         .map_err(|err| err.to_string())?
-        .map_err(|err| err.to_string())
+        .map_err(|err| err.to_string())?;
+    state.request_budget_check();
+    Ok(transaction)
 }
 
 #[tauri::command]
@@ -81,10 +258,12 @@ pub async fn update_transaction(
     payload: UpdateTransactionInput,
 ) -> Result<TransactionDto, String> {
     let service = state.services().transaction();
-    spawn_blocking(move || service.update_transaction(payload))
+    let transaction = spawn_blocking(move || service.update_transaction(payload))
         .await
         .map_err(|err| err.to_string())?
-        .map_err(|err| err.to_string())
+        .map_err(|err| err.to_string())?;
+    state.request_budget_check();
+    Ok(transaction)
 }
 
 #[tauri::command]
@@ -93,7 +272,30 @@ pub async fn delete_transaction(
     payload: DeleteTransactionPayload,
 ) -> Result<(), String> {
     let service = state.services().transaction();
-    spawn_blocking(move || service.delete_transaction(&payload.id))
+    let attachments = state.services().attachment();
+    spawn_blocking(move || {
+        service
+            .delete_transaction(&payload.id, payload.override_reconciled)
+            .map_err(|err| err.to_string())?;
+        // Attachment rows go with the transaction; drop files nothing refers to any more
+        if let Err(err) = attachments.collect_garbage() {
+            tracing::warn!(error = %err, "Failed to collect orphaned attachments");
+        }
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+    state.request_budget_check();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn find_import_duplicates(
+    state: State<'_, AppState>,
+    payload: ImportTransactionsInput,
+) -> Result<Vec<SuspectedDuplicateDto>, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.find_import_duplicates(&payload.items))
         .await
         .map_err(|err| err.to_string())?
         .map_err(|err| err.to_string())
@@ -103,9 +305,237 @@ pub async fn delete_transaction(
 pub async fn import_transactions(
     state: State<'_, AppState>,
     payload: ImportTransactionsInput,
-) -> Result<Vec<TransactionDto>, String> {
+) -> Result<ImportTransactionsResult, String> {
+    let service = state.services().transaction();
+    let result = spawn_blocking(move || service.import_transactions(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+    state.request_budget_check();
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn list_import_batches(
+    state: State<'_, AppState>,
+) -> Result<Vec<ImportBatchDto>, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.list_import_batches())
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn rollback_import_batch(
+    state: State<'_, AppState>,
+    payload: ImportBatchIdPayload,
+) -> Result<ImportBatchDto, String> {
+    let service = state.services().transaction();
+    let attachments = state.services().attachment();
+    let batch = spawn_blocking(move || {
+        let batch = service
+            .rollback_import_batch(&payload.id)
+            .map_err(|err| err.to_string())?;
+        if let Err(err) = attachments.collect_garbage() {
+            tracing::warn!(error = %err, "Failed to collect orphaned attachments");
+        }
+        Ok::<_, String>(batch)
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+    state.request_budget_check();
+    Ok(batch)
+}
+
+#[tauri::command]
+pub async fn list_payees(state: State<'_, AppState>) -> Result<Vec<PayeeDto>, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.list_payees())
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn create_payee(
+    state: State<'_, AppState>,
+    payload: CreatePayeeInput,
+) -> Result<PayeeDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.create_payee(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn update_payee(
+    state: State<'_, AppState>,
+    payload: UpdatePayeeInput,
+) -> Result<PayeeDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.update_payee(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_payee(
+    state: State<'_, AppState>,
+    payload: DeletePayeePayload,
+) -> Result<(), String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.delete_payee(&payload.id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn list_transaction_rules(
+    state: State<'_, AppState>,
+) -> Result<Vec<TransactionRuleDto>, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.list_rules())
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn create_transaction_rule(
+    state: State<'_, AppState>,
+    payload: TransactionRuleInput,
+) -> Result<TransactionRuleDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.create_rule(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn update_transaction_rule(
+    state: State<'_, AppState>,
+    payload: UpdateTransactionRuleInput,
+) -> Result<TransactionRuleDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.update_rule(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_transaction_rule(
+    state: State<'_, AppState>,
+    payload: DeleteTransactionRulePayload,
+) -> Result<(), String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.delete_rule(&payload.id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+/// Re-runs rules over stored transactions; `dryRun` only reports what would change.
+#[tauri::command]
+pub async fn apply_transaction_rules(
+    state: State<'_, AppState>,
+    payload: Option<ApplyRulesInput>,
+) -> Result<ApplyRulesResult, String> {
+    let input = payload.unwrap_or_default();
+    let service = state.services().transaction();
+    let result = spawn_blocking(move || service.apply_rules(input))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+    state.request_budget_check();
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn list_reconciliations(
+    state: State<'_, AppState>,
+    payload: Option<ListReconciliationsPayload>,
+) -> Result<Vec<ReconciliationDto>, String> {
+    let account_id = payload.unwrap_or_default().account_id;
+    let service = state.services().transaction();
+    spawn_blocking(move || service.list_reconciliations(account_id.as_deref()))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn get_reconciliation(
+    state: State<'_, AppState>,
+    payload: ReconciliationIdPayload,
+) -> Result<ReconciliationDetailDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.get_reconciliation(&payload.id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn start_reconciliation(
+    state: State<'_, AppState>,
+    payload: StartReconciliationInput,
+) -> Result<ReconciliationDetailDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.start_reconciliation(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn update_reconciliation(
+    state: State<'_, AppState>,
+    payload: UpdateReconciliationInput,
+) -> Result<ReconciliationDetailDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.update_reconciliation(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn mark_reconciliation_transactions(
+    state: State<'_, AppState>,
+    payload: MarkReconciliationInput,
+) -> Result<ReconciliationDetailDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.mark_reconciliation_transactions(payload))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn finish_reconciliation(
+    state: State<'_, AppState>,
+    payload: ReconciliationIdPayload,
+) -> Result<ReconciliationDto, String> {
+    let service = state.services().transaction();
+    spawn_blocking(move || service.finish_reconciliation(&payload.id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn cancel_reconciliation(
+    state: State<'_, AppState>,
+    payload: ReconciliationIdPayload,
+) -> Result<(), String> {
     let service = state.services().transaction();
-    spawn_blocking(move || service.import_transactions(payload.items))
+    spawn_blocking(move || service.cancel_reconciliation(&payload.id))
         .await
         .map_err(|err| err.to_string())?
         .map_err(|err| err.to_string())
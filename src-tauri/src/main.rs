@@ -1,20 +1,19 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod app_lock;
 mod commands;
 mod logging;
 mod scheduler;
+mod secret_store;
 mod secrets;
 mod services;
+mod session;
 mod state;
 
 use std::{env, io};
 
 use scheduler::ReminderScheduler;
-use services::{
-    ServiceRegistry, SqliteBudgetService, SqliteDashboardService, SqliteGoalService,
-    SqliteReminderService, SqliteReportService, SqliteSettingsService, SqliteSyncService,
-    SqliteTransactionService,
-};
+use secrets::LoadedSecrets;
 use state::PathState;
 use tauri::Manager;
 
@@ -28,65 +27,36 @@ fn main() {
             logging::init_logging(&app_handle, paths.logs_dir())?;
             println!("Using database at {}", paths.db_path().display());
 
-            let service_name = app.config().identifier.clone();
-            let secrets = secrets::load_or_create(&service_name, paths.secrets_file())
-                .map_err(|err| tauri::Error::Io(io::Error::other(err.to_string())))?;
+            let (secret_store, secret_store_conflict) =
+                secret_store::open(&app.config().identifier, paths.secret_store_file());
+            tracing::info!(store = secret_store.name(), "Opened the secret store");
+            let loaded = secrets::load_or_create(
+                secret_store.as_ref(),
+                paths.secrets_file(),
+                paths.db_path(),
+            )
+            .map_err(|err| tauri::Error::Io(io::Error::other(err.to_string())))?;
 
-            let database_url = paths.database_url(secrets.sqlcipher_key())?;
-            env::set_var("DATABASE_URL", &database_url);
             env::set_var(
                 "PF_APP_DB_PATH",
                 paths.db_path().to_string_lossy().to_string(),
             );
 
-            let transaction_service = SqliteTransactionService::new(paths.db_path().to_path_buf(), None, None)
-            .map_err(|err| tauri::Error::Io(io::Error::other(err.to_string())))?;
-
-            let dashboard_service =
-                SqliteDashboardService::new(paths.db_path().to_path_buf(), None, None)
-            .map_err(|err| tauri::Error::Io(io::Error::other(err.to_string())))?;
-
-            let budget_service = SqliteBudgetService::new(paths.db_path().to_path_buf(), None, None)
-            .map_err(|err| tauri::Error::Io(io::Error::other(err.to_string())))?;
-
-            let goal_service = SqliteGoalService::new(paths.db_path().to_path_buf(), None, None)
-            .map_err(|err| tauri::Error::Io(io::Error::other(err.to_string())))?;
-
-            let reminder_service =
-                SqliteReminderService::new(paths.db_path().to_path_buf(), None, None)
-            .map_err(|err| tauri::Error::Io(io::Error::other(err.to_string())))?;
-
-            // Create a second instance for scheduler (lightweight, only stores path and key)
-            let reminder_service_for_scheduler =
-                SqliteReminderService::new(paths.db_path().to_path_buf(), None, None)
-            .map_err(|err| tauri::Error::Io(io::Error::other(err.to_string())))?;
-
-            let report_service =
-                SqliteReportService::new(paths.db_path().to_path_buf(), None, None)
-            .map_err(|err| tauri::Error::Io(io::Error::other(err.to_string())))?;
-
-            let settings_service =
-                SqliteSettingsService::new(paths.db_path().to_path_buf(), None, None)
-            .map_err(|err| tauri::Error::Io(io::Error::other(err.to_string())))?;
-
-            let sync_service = SqliteSyncService::new(
-                paths.db_path().to_path_buf(),
-                None,
-                std::env::var("PF_SYNC_ENDPOINT").ok(),
-            )
-            .map_err(|err| tauri::Error::Io(io::Error::other(err.to_string())))?;
-
-            let services = ServiceRegistry::builder()
-                .with_transaction(transaction_service)
-                .with_dashboard(dashboard_service)
-                .with_budget(budget_service)
-                .with_goal(goal_service)
-                .with_reminder(reminder_service)
-                .with_report(report_service)
-                .with_settings(settings_service)
-                .with_sync(sync_service)
-                .build();
-            let app_state = state::AppState::new(paths, secrets, services, database_url);
+            // Shown by the window through get_encryption_status
+            let app_state = state::AppState::new(paths, secret_store, secret_store_conflict);
+            match loaded {
+                LoadedSecrets::Ready(secrets) => {
+                    session::open(&app_state, secrets, None)
+                        .map_err(|err| tauri::Error::Io(io::Error::other(err)))?;
+                    // Start locked when a PIN is set. Unlocking with the master passphrase
+                    // already proves the user is present, so that path skips the PIN.
+                    app_state.lock();
+                }
+                // Services stay unconfigured until unlock_with_passphrase succeeds
+                LoadedSecrets::PassphraseRequired => {
+                    tracing::info!("Waiting for the master passphrase to open the database")
+                }
+            }
             app.manage(app_state);
 
             let app_name = app
@@ -97,25 +67,82 @@ fn main() {
 
             tracing::info!(app = %app_name, "Tauri shell initialized");
 
-            // Start reminder scheduler
-            let app_handle_clone = app.handle().clone();
-            let reminder_service_arc = std::sync::Arc::new(reminder_service_for_scheduler);
-            let scheduler = ReminderScheduler::new(reminder_service_arc, app_handle_clone);
+            // Start reminder scheduler (also posts due recurring transactions, raises budget
+            // alerts and records budget snapshots)
+            let scheduler = ReminderScheduler::new(app.handle().clone());
             tauri::async_runtime::spawn(async move {
                 scheduler.start_polling().await;
             });
 
+            tauri::async_runtime::spawn(app_lock::watch(app.handle().clone()));
+
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(app_lock::guard(tauri::generate_handler![
             commands::ping,
+            commands::get_app_lock_status,
+            commands::unlock_app,
+            commands::lock_app,
+            commands::report_activity,
+            commands::set_app_lock_pin,
+            commands::update_app_lock_settings,
+            commands::get_encryption_status,
+            commands::unlock_with_passphrase,
+            commands::set_master_passphrase,
+            commands::rotate_encryption_key,
             commands::list_accounts,
+            commands::create_account,
+            commands::update_account,
+            commands::update_account_status,
+            commands::reorder_accounts,
             commands::list_categories,
+            commands::create_category,
+            commands::update_category,
+            commands::archive_category,
+            commands::merge_categories,
+            commands::delete_category,
             commands::list_transactions,
+            commands::search_transactions,
             commands::create_transaction,
             commands::update_transaction,
             commands::delete_transaction,
+            commands::attach_files,
+            commands::list_attachments,
+            commands::read_attachment,
+            commands::remove_attachment,
+            commands::collect_attachment_garbage,
+            commands::create_backup,
+            commands::find_import_duplicates,
             commands::import_transactions,
+            commands::list_import_batches,
+            commands::rollback_import_batch,
+            commands::list_payees,
+            commands::create_payee,
+            commands::update_payee,
+            commands::delete_payee,
+            commands::list_transaction_rules,
+            commands::create_transaction_rule,
+            commands::update_transaction_rule,
+            commands::delete_transaction_rule,
+            commands::apply_transaction_rules,
+            commands::list_reconciliations,
+            commands::get_reconciliation,
+            commands::start_reconciliation,
+            commands::update_reconciliation,
+            commands::mark_reconciliation_transactions,
+            commands::finish_reconciliation,
+            commands::cancel_reconciliation,
+            commands::list_recurring_transactions,
+            commands::create_recurring_transaction,
+            commands::update_recurring_transaction,
+            commands::delete_recurring_transaction,
+            commands::skip_recurring_occurrence,
+            commands::post_due_recurring_transactions,
+            commands::list_exchange_rates,
+            commands::upsert_exchange_rate,
+            commands::delete_exchange_rate,
+            commands::import_exchange_rates,
+            commands::convert_amount,
             commands::get_dashboard_snapshot,
             commands::list_budgets,
             commands::get_budget,
@@ -123,6 +150,12 @@ fn main() {
             commands::update_budget,
             commands::delete_budget,
             commands::record_snapshot,
+            commands::list_budget_snapshots,
+            commands::get_budget_period_summary,
+            commands::get_zero_based_month,
+            commands::assign_to_envelope,
+            commands::move_envelope_funds,
+            commands::list_budget_alerts,
             commands::list_goals,
             commands::get_goal,
             commands::create_goal,
@@ -150,6 +183,15 @@ fn main() {
             commands::update_user_settings,
             commands::update_category_order,
             commands::read_import_file,
+            commands::import_ofx_file,
+            commands::import_qif_file,
+            commands::export_qif_file,
+            commands::list_csv_import_profiles,
+            commands::create_csv_import_profile,
+            commands::update_csv_import_profile,
+            commands::delete_csv_import_profile,
+            commands::preview_csv_import,
+            commands::commit_csv_import,
             commands::decrypt_encrypted_json,
             commands::sync_upload,
             commands::sync_download,
@@ -175,7 +217,7 @@ fn main() {
             commands::list_debt_schedule,
             commands::confirm_debt_payment,
             commands::plan_vs_actual
-        ])
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -1,25 +1,30 @@
-use std::sync::Arc;
 use std::time::Duration;
 
-use tauri::{AppHandle, Emitter};
+use chrono::NaiveDate;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
 use tokio::time::interval;
 use tracing::{error, info, warn};
 
-use crate::services::reminders::{ReminderDto, ReminderService};
+use crate::{
+    services::{
+        reminders::{ReminderDto, ReminderService},
+        BudgetAlertDto, BudgetAlertKind, BudgetService, RecurringService, SettingsService,
+    },
+    state::AppState,
+};
 
 const POLL_INTERVAL_SECONDS: u64 = 60;
 
+/// Polls the services currently installed on [`AppState`], so it keeps working across a
+/// key rotation and does nothing while the database is locked.
 pub struct ReminderScheduler {
-    reminder_service: Arc<dyn ReminderService>,
     app_handle: AppHandle,
 }
 
 impl ReminderScheduler {
-    pub fn new(reminder_service: Arc<dyn ReminderService>, app_handle: AppHandle) -> Self {
-        Self {
-            reminder_service,
-            app_handle,
-        }
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
     }
 
     pub async fn start_polling(&self) {
@@ -30,18 +35,163 @@ impl ReminderScheduler {
             POLL_INTERVAL_SECONDS
         );
 
+        let mut snapshot_day = None;
         loop {
-            poll_interval.tick().await;
+            let state = self.app_handle.state::<AppState>();
+            let tick = tokio::select! {
+                _ = poll_interval.tick() => true,
+                // Transaction writes ask for a budget check without waiting for the tick
+                _ = state.budget_check_requested() => false,
+            };
+
+            if state.is_sealed() {
+                continue;
+            }
+            let services = state.services();
+            // One UTC date for every job below, matching the UTC-midnight budget periods,
+            // so they agree on which day it is
+            let today = chrono::Utc::now().date_naive();
+
+            if tick {
+                if let Err(err) = self.process_due_reminders(&*services.reminder()).await {
+                    error!(error = %err, "Error processing due reminders");
+                }
+
+                // Also post due recurring transactions on every tick
+                if let Err(err) = self
+                    .process_due_recurring(&*services.recurring(), today)
+                    .await
+                {
+                    error!(error = %err, "Error posting recurring transactions");
+                }
+            }
+
+            // After posting, so recurring spending is counted
+            if let Err(err) = self
+                .process_budget_alerts(
+                    &*services.budget(),
+                    &*services.settings(),
+                    today,
+                    state.is_locked(),
+                )
+                .await
+            {
+                error!(error = %err, "Error evaluating budget alerts");
+            }
+
+            // Once a day, and after every write so today's snapshot keeps up with it
+            if !tick || snapshot_day != Some(today) {
+                match services.budget().record_daily_snapshots(today) {
+                    Ok(written) => {
+                        info!(count = written, "Recorded budget snapshots");
+                        snapshot_day = Some(today);
+                    }
+                    Err(err) => error!(error = %err, "Error recording budget snapshots"),
+                }
+            }
+        }
+    }
+
+    /// Alerts are recorded as they are raised, so a failed event or notification is logged
+    /// rather than retried. While the app lock is on, one notification says only that
+    /// budgets need attention, keeping names and amounts off the lock screen.
+    async fn process_budget_alerts(
+        &self,
+        budget_service: &dyn BudgetService,
+        settings_service: &dyn SettingsService,
+        today: NaiveDate,
+        locked: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let alerts = budget_service
+            .evaluate_alerts(today)
+            .map_err(|e| format!("Failed to evaluate budget alerts: {}", e))?;
+
+        if alerts.is_empty() {
+            return Ok(());
+        }
 
-            if let Err(err) = self.process_due_reminders().await {
-                error!(error = %err, "Error processing due reminders");
+        let currency = settings_service
+            .get_user_settings()
+            .map_err(|e| format!("Failed to load the default currency: {}", e))?
+            .default_currency;
+
+        for alert in &alerts {
+            info!(
+                budget_id = %alert.budget_id,
+                kind = %alert.kind.as_str(),
+                "Budget alert raised"
+            );
+
+            if let Err(err) = self.app_handle.emit("budget:alert", alert) {
+                error!(
+                    budget_id = %alert.budget_id,
+                    error = %err,
+                    "Failed to emit budget:alert event"
+                );
             }
+
+            if !locked {
+                let (title, body) = budget_alert_text(alert, &currency);
+                self.show_budget_notification(title, body);
+            }
+        }
+
+        if locked {
+            self.show_budget_notification(
+                "Budget alert".to_string(),
+                "Unlock the app to see which budgets need attention.".to_string(),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn show_budget_notification(&self, title: String, body: String) {
+        if let Err(err) = self
+            .app_handle
+            .notification()
+            .builder()
+            .title(title)
+            .body(body)
+            .show()
+        {
+            warn!(error = %err, "Failed to show budget notification");
+        }
+    }
+
+    /// The first tick fires immediately, so occurrences missed while the app was closed
+    /// are caught up at startup.
+    async fn process_due_recurring(
+        &self,
+        recurring_service: &dyn RecurringService,
+        today: NaiveDate,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let posted = recurring_service
+            .post_due(today)
+            .map_err(|e| format!("Failed to post recurring transactions: {}", e))?;
+
+        if posted.is_empty() {
+            return Ok(());
         }
+
+        info!(
+            count = posted.len(),
+            "Posted {} recurring transaction(s)",
+            posted.len()
+        );
+
+        self.app_handle
+            .emit("recurring:posted", &posted)
+            .map_err(|e| format!("Failed to emit recurring:posted event: {}", e))?;
+
+        Ok(())
     }
 
-    async fn process_due_reminders(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let due_reminders = self
-            .reminder_service
+    async fn process_due_reminders(
+        &self,
+        reminder_service: &dyn ReminderService,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let due_reminders = reminder_service
             .get_due_reminders()
             .map_err(|e| format!("Failed to get due reminders: {}", e))?;
 
@@ -56,7 +206,7 @@ impl ReminderScheduler {
         );
 
         for reminder in due_reminders {
-            if let Err(err) = self.trigger_notification(&reminder).await {
+            if let Err(err) = self.trigger_notification(reminder_service, &reminder).await {
                 warn!(
                     reminder_id = %reminder.id,
                     error = %err,
@@ -70,6 +220,7 @@ impl ReminderScheduler {
 
     async fn trigger_notification(
         &self,
+        reminder_service: &dyn ReminderService,
         reminder: &ReminderDto,
     ) -> Result<(), Box<dyn std::error::Error>> {
         info!(
@@ -85,7 +236,7 @@ impl ReminderScheduler {
             .map_err(|e| format!("Failed to emit notification:prepared event: {}", e))?;
 
         // Mark reminder as sent and update last_triggered_at
-        self.reminder_service
+        reminder_service
             .mark_reminder_sent(&reminder.id)
             .map_err(|e| format!("Failed to mark reminder as sent: {}", e))?;
 
@@ -98,6 +249,23 @@ impl ReminderScheduler {
     }
 }
 
+fn budget_alert_text(alert: &BudgetAlertDto, currency: &str) -> (String, String) {
+    let amount = |cents: i64| format!("{:.2} {currency}", cents as f64 / 100.0);
+    let title = match alert.kind {
+        BudgetAlertKind::Threshold => format!(
+            "{} is at {:.0}% of its budget",
+            alert.budget_name, alert.progress_percent
+        ),
+        BudgetAlertKind::Over => format!("{} is over budget", alert.budget_name),
+    };
+    let body = format!(
+        "Spent {} of {} this period.",
+        amount(alert.spent_cents),
+        amount(alert.available_cents)
+    );
+    (title, body)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,134 @@
+use std::{ffi::OsStr, os::windows::prelude::OsStrExt};
+
+use windows::{
+    core::{PCWSTR, PWSTR},
+    Win32::{
+        Foundation::ERROR_NOT_FOUND,
+        Security::Credentials::{
+            CredDeleteW, CredFree, CredReadW, CredWriteW, CREDENTIALW, CRED_PERSIST_ENTERPRISE,
+            CRED_TYPE_GENERIC,
+        },
+    },
+};
+
+use super::{SecretStore, SecretStoreError, SecretStoreResult, DB_KEY_ACCOUNT};
+
+/// Generic credentials in the Windows Credential Manager, one per account, with target
+/// names `<service>/<account>`.
+pub struct CredentialManagerStore {
+    service: String,
+}
+
+impl CredentialManagerStore {
+    pub fn new(service: &str) -> Self {
+        Self {
+            service: service.to_string(),
+        }
+    }
+
+    fn target(&self, account: &str) -> String {
+        format!("{}/{account}", self.service)
+    }
+
+    /// Earlier versions kept the database key in a credential named after the service
+    /// alone; move it to its account target.
+    pub fn adopt_legacy_credential(&self) -> SecretStoreResult<()> {
+        let Some(secret) = read_credential(&self.service)? else {
+            return Ok(());
+        };
+        if self.get(DB_KEY_ACCOUNT)?.is_none() {
+            self.set(DB_KEY_ACCOUNT, &secret)?;
+        }
+        delete_credential(&self.service)
+    }
+}
+
+impl SecretStore for CredentialManagerStore {
+    fn name(&self) -> &'static str {
+        "Windows Credential Manager"
+    }
+
+    fn get(&self, account: &str) -> SecretStoreResult<Option<String>> {
+        read_credential(&self.target(account))
+    }
+
+    fn set(&self, account: &str, secret: &str) -> SecretStoreResult<()> {
+        write_credential(&self.target(account), secret)
+    }
+
+    fn delete(&self, account: &str) -> SecretStoreResult<()> {
+        delete_credential(&self.target(account))
+    }
+}
+
+fn wide(value: &str) -> Vec<u16> {
+    let mut encoded: Vec<u16> = OsStr::new(value).encode_wide().collect();
+    encoded.push(0);
+    encoded
+}
+
+fn credential_error(code: u32) -> SecretStoreError {
+    SecretStoreError::Backend(format!("credential manager error code: {code}"))
+}
+
+fn read_credential(target: &str) -> SecretStoreResult<Option<String>> {
+    let encoded = wide(target);
+    let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+    if let Err(err) = unsafe {
+        CredReadW(
+            PCWSTR(encoded.as_ptr()),
+            CRED_TYPE_GENERIC,
+            0,
+            &mut credential,
+        )
+    } {
+        let code = err.code().0 as u32;
+        if code == ERROR_NOT_FOUND.0 {
+            return Ok(None);
+        }
+        return Err(credential_error(code));
+    }
+
+    let cred = unsafe { credential.as_ref() }.ok_or_else(|| credential_error(0))?;
+    let buffer = unsafe {
+        std::slice::from_raw_parts(cred.CredentialBlob, cred.CredentialBlobSize as usize)
+    };
+    let secret = String::from_utf8(buffer.to_vec());
+
+    unsafe { CredFree(credential as *mut _) };
+
+    secret
+        .map(Some)
+        .map_err(|_| SecretStoreError::Backend(format!("{target} is not valid UTF-8")))
+}
+
+fn write_credential(target: &str, secret: &str) -> SecretStoreResult<()> {
+    let mut encoded = wide(target);
+    let mut blob = secret.as_bytes().to_vec();
+
+    let credential = CREDENTIALW {
+        Type: CRED_TYPE_GENERIC,
+        TargetName: PWSTR(encoded.as_mut_ptr()),
+        CredentialBlobSize: blob.len() as u32,
+        CredentialBlob: blob.as_mut_ptr(),
+        Persist: CRED_PERSIST_ENTERPRISE,
+        AttributeCount: 0,
+        Comment: PWSTR::null(),
+        TargetAlias: PWSTR::null(),
+        UserName: PWSTR::null(),
+        ..Default::default()
+    };
+
+    unsafe { CredWriteW(&credential, 0) }.map_err(|err| credential_error(err.code().0 as u32))
+}
+
+fn delete_credential(target: &str) -> SecretStoreResult<()> {
+    let encoded = wide(target);
+    if let Err(err) = unsafe { CredDeleteW(PCWSTR(encoded.as_ptr()), CRED_TYPE_GENERIC, 0) } {
+        let code = err.code().0 as u32;
+        if code != ERROR_NOT_FOUND.0 {
+            return Err(credential_error(code));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{SecretStore, SecretStoreError, SecretStoreResult};
+
+const FORMAT: &str = "pfd-secret-store";
+const NONCE_LEN: usize = 12;
+
+/// Fallback for machines without a reachable keyring. Each secret is sealed with
+/// AES-256-GCM under a key derived from the machine id and the service name, so the file
+/// is useless when copied to another machine. Anyone who can read the machine id can
+/// derive the same key, though; a master passphrase is the real protection here.
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    key: [u8; 32],
+    lock: Mutex<()>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoreFile {
+    format: String,
+    entries: BTreeMap<String, Entry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    nonce: String,
+    ciphertext: String,
+}
+
+impl EncryptedFileStore {
+    pub fn new(path: PathBuf, service: &str) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(FORMAT.as_bytes());
+        hasher.update(machine_id().as_bytes());
+        hasher.update(service.as_bytes());
+        Self {
+            path,
+            key: hasher.finalize().into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new_from_slice(&self.key).expect("key is 32 bytes")
+    }
+
+    fn read(&self) -> SecretStoreResult<BTreeMap<String, Entry>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let file: StoreFile = serde_json::from_str(&contents)?;
+        if file.format != FORMAT {
+            return Err(SecretStoreError::Backend(format!(
+                "{} is not a secret store file",
+                self.path.display()
+            )));
+        }
+        Ok(file.entries)
+    }
+
+    /// Writes through a temporary file, readable by the current user only.
+    fn write(&self, entries: BTreeMap<String, Entry>) -> SecretStoreResult<()> {
+        if entries.is_empty() {
+            return match fs::remove_file(&self.path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+                _ => Ok(()),
+            };
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let serialized = serde_json::to_string_pretty(&StoreFile {
+            format: FORMAT.to_string(),
+            entries,
+        })?;
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serialized)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600))?;
+        }
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+impl SecretStore for EncryptedFileStore {
+    fn name(&self) -> &'static str {
+        "encrypted file"
+    }
+
+    fn get(&self, account: &str) -> SecretStoreResult<Option<String>> {
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(entry) = self.read()?.remove(account) else {
+            return Ok(None);
+        };
+
+        let unreadable = || {
+            SecretStoreError::Backend(format!(
+                "{account} in {} cannot be decrypted on this machine",
+                self.path.display()
+            ))
+        };
+        let nonce = STANDARD.decode(&entry.nonce).map_err(|_| unreadable())?;
+        let ciphertext = STANDARD
+            .decode(&entry.ciphertext)
+            .map_err(|_| unreadable())?;
+        if nonce.len() != NONCE_LEN {
+            return Err(unreadable());
+        }
+        let plaintext = self
+            .cipher()
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: account.as_bytes(),
+                },
+            )
+            .map_err(|_| unreadable())?;
+        String::from_utf8(plaintext)
+            .map(Some)
+            .map_err(|_| unreadable())
+    }
+
+    fn set(&self, account: &str, secret: &str) -> SecretStoreResult<()> {
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: secret.as_bytes(),
+                    aad: account.as_bytes(),
+                },
+            )
+            .map_err(|_| SecretStoreError::Backend("Failed to encrypt secret".into()))?;
+
+        let mut entries = self.read()?;
+        entries.insert(
+            account.to_string(),
+            Entry {
+                nonce: STANDARD.encode(nonce),
+                ciphertext: STANDARD.encode(ciphertext),
+            },
+        );
+        self.write(entries)
+    }
+
+    fn delete(&self, account: &str) -> SecretStoreResult<()> {
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut entries = self.read()?;
+        if entries.remove(account).is_some() {
+            self.write(entries)?;
+        }
+        Ok(())
+    }
+}
+
+/// The systemd/D-Bus machine id where there is one. Elsewhere the platform keyring is
+/// used instead and this store only matters as a migration source.
+fn machine_id() -> String {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .find(|id| !id.is_empty())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stores_secrets_encrypted_and_removes_empty_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret-store.json");
+        let store = EncryptedFileStore::new(path.clone(), "test");
+
+        assert_eq!(store.get("key").unwrap(), None);
+        store.set("key", "hunter22").unwrap();
+        store.set("other", "second").unwrap();
+        assert_eq!(store.get("key").unwrap().as_deref(), Some("hunter22"));
+        assert!(!fs::read_to_string(&path).unwrap().contains("hunter22"));
+
+        // Another service name derives another key
+        let other = EncryptedFileStore::new(path.clone(), "other service");
+        assert!(matches!(
+            other.get("key"),
+            Err(SecretStoreError::Backend(_))
+        ));
+
+        store.delete("key").unwrap();
+        store.delete("other").unwrap();
+        store.delete("missing").unwrap();
+        assert!(!path.exists());
+    }
+}
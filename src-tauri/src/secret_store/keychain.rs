@@ -0,0 +1,53 @@
+use security_framework::{
+    base::Error,
+    passwords::{delete_generic_password, get_generic_password, set_generic_password},
+};
+
+use super::{SecretStore, SecretStoreError, SecretStoreResult};
+
+/// `errSecItemNotFound`
+const ITEM_NOT_FOUND: i32 = -25300;
+
+/// Generic passwords in the user's login keychain, keyed by service and account.
+pub struct KeychainStore {
+    service: String,
+}
+
+impl KeychainStore {
+    pub fn new(service: &str) -> Self {
+        Self {
+            service: service.to_string(),
+        }
+    }
+}
+
+impl SecretStore for KeychainStore {
+    fn name(&self) -> &'static str {
+        "macOS Keychain"
+    }
+
+    fn get(&self, account: &str) -> SecretStoreResult<Option<String>> {
+        match get_generic_password(&self.service, account) {
+            Ok(bytes) => String::from_utf8(bytes)
+                .map(Some)
+                .map_err(|_| SecretStoreError::Backend(format!("{account} is not valid UTF-8"))),
+            Err(err) if err.code() == ITEM_NOT_FOUND => Ok(None),
+            Err(err) => Err(backend(err)),
+        }
+    }
+
+    fn set(&self, account: &str, secret: &str) -> SecretStoreResult<()> {
+        set_generic_password(&self.service, account, secret.as_bytes()).map_err(backend)
+    }
+
+    fn delete(&self, account: &str) -> SecretStoreResult<()> {
+        match delete_generic_password(&self.service, account) {
+            Err(err) if err.code() != ITEM_NOT_FOUND => Err(backend(err)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn backend(err: Error) -> SecretStoreError {
+    SecretStoreError::Backend(err.to_string())
+}
@@ -0,0 +1,160 @@
+#[cfg(target_os = "windows")]
+mod credential_manager;
+mod file;
+#[cfg(target_os = "macos")]
+mod keychain;
+#[cfg(target_os = "linux")]
+mod secret_service;
+
+use std::path::Path;
+
+use thiserror::Error;
+
+pub use file::EncryptedFileStore;
+
+/// Account holding the SQLCipher key.
+pub const DB_KEY_ACCOUNT: &str = "sqlcipher-key";
+/// Account holding the key a rotation is moving to.
+pub const PENDING_KEY_ACCOUNT: &str = "sqlcipher-key-pending";
+
+#[derive(Debug, Error)]
+pub enum SecretStoreError {
+    #[error("secret store unavailable: {0}")]
+    Unavailable(String),
+    #[error("secret store error: {0}")]
+    Backend(String),
+    #[error("secret stores disagree: {0}")]
+    Conflict(String),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("serde error: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+pub type SecretStoreResult<T> = Result<T, SecretStoreError>;
+
+/// Named secrets for one application, kept by the OS keyring or the encrypted file.
+pub trait SecretStore: Send + Sync {
+    /// Short backend name for logs and diagnostics.
+    fn name(&self) -> &'static str;
+    fn get(&self, account: &str) -> SecretStoreResult<Option<String>>;
+    fn set(&self, account: &str, secret: &str) -> SecretStoreResult<()>;
+    /// Succeeds when nothing is stored under `account`.
+    fn delete(&self, account: &str) -> SecretStoreResult<()>;
+}
+
+/// The keyring of the current platform, or `None` when it cannot be reached (no Secret
+/// Service on the session bus, for example).
+pub fn platform_store(service: &str) -> Option<Box<dyn SecretStore>> {
+    #[cfg(target_os = "linux")]
+    {
+        match secret_service::SecretServiceStore::connect(service) {
+            Ok(store) => Some(Box::new(store)),
+            Err(err) => {
+                tracing::warn!(error = %err, "Secret Service is not available");
+                None
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        Some(Box::new(keychain::KeychainStore::new(service)))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let store = credential_manager::CredentialManagerStore::new(service);
+        if let Err(err) = store.adopt_legacy_credential() {
+            tracing::warn!(error = %err, "Failed to move the old credential");
+        }
+        Some(Box::new(store))
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        let _ = service;
+        None
+    }
+}
+
+/// Picks the platform keyring when available, moving anything the encrypted file fallback
+/// at `fallback_path` holds into it; otherwise uses the fallback. The message is for the
+/// user when both stores hold a different key, in which case the fallback's is used.
+pub fn open(service: &str, fallback_path: &Path) -> (Box<dyn SecretStore>, Option<String>) {
+    let fallback = EncryptedFileStore::new(fallback_path.to_path_buf(), service);
+    let Some(platform) = platform_store(service) else {
+        tracing::warn!("Keeping secrets in the encrypted file fallback");
+        return (Box::new(fallback), None);
+    };
+
+    match migrate(&fallback, platform.as_ref()) {
+        Ok(_) => (platform, None),
+        // The fallback keeps the secrets, so nothing is lost; the next start retries
+        Err(SecretStoreError::Conflict(conflict)) => {
+            tracing::error!(%conflict, "Secret stores hold different keys");
+            let message = format!(
+                "{conflict}. The database was opened with the one in the {}. Remove the \
+                 wrong copy so the key can move into the {}.",
+                fallback.name(),
+                platform.name()
+            );
+            (Box::new(fallback), Some(message))
+        }
+        Err(err) => {
+            tracing::error!(error = %err, "Failed to move secrets into the platform store");
+            (Box::new(fallback), None)
+        }
+    }
+}
+
+/// Copies every known account from `from` to `to`, verifying each copy before deleting
+/// the original. Never overwrites `to`: an account it already holds is only dropped from
+/// `from` when both copies match, and left in both stores and reported otherwise.
+pub fn migrate(from: &dyn SecretStore, to: &dyn SecretStore) -> SecretStoreResult<usize> {
+    let mut moved = 0;
+    let mut conflicts = Vec::new();
+    for account in [DB_KEY_ACCOUNT, PENDING_KEY_ACCOUNT] {
+        let Some(secret) = from.get(account)? else {
+            continue;
+        };
+        match to.get(account)? {
+            Some(existing) if existing == secret => {}
+            Some(_) => {
+                conflicts.push(account_label(account));
+                continue;
+            }
+            None => {
+                to.set(account, &secret)?;
+                if to.get(account)?.as_deref() != Some(secret.as_str()) {
+                    return Err(SecretStoreError::Backend(format!(
+                        "{} did not keep {account}",
+                        to.name()
+                    )));
+                }
+                moved += 1;
+            }
+        }
+        from.delete(account)?;
+    }
+    if moved > 0 {
+        tracing::info!(from = from.name(), to = to.name(), moved, "Moved secrets");
+    }
+    if !conflicts.is_empty() {
+        return Err(SecretStoreError::Conflict(format!(
+            "The {} and the {} hold different copies of {}; both were kept",
+            from.name(),
+            to.name(),
+            conflicts.join(" and ")
+        )));
+    }
+    Ok(moved)
+}
+
+fn account_label(account: &str) -> &str {
+    match account {
+        DB_KEY_ACCOUNT => "the database key",
+        PENDING_KEY_ACCOUNT => "the key a rotation is moving to",
+        other => other,
+    }
+}
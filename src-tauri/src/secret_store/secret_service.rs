@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use zbus::{
+    blocking::{proxy::Builder as ProxyBuilder, Connection, Proxy},
+    proxy::CacheProperties,
+    zvariant::{OwnedObjectPath, OwnedValue, Type, Value},
+};
+
+use super::{SecretStore, SecretStoreError, SecretStoreResult};
+
+const BUS_NAME: &str = "org.freedesktop.secrets";
+const SERVICE_PATH: &str = "/org/freedesktop/secrets";
+const SERVICE_IFACE: &str = "org.freedesktop.Secret.Service";
+const COLLECTION_IFACE: &str = "org.freedesktop.Secret.Collection";
+const ITEM_IFACE: &str = "org.freedesktop.Secret.Item";
+const PROMPT_IFACE: &str = "org.freedesktop.Secret.Prompt";
+/// Returned in place of a prompt or collection path when there is none.
+const NO_OBJECT: &str = "/";
+
+/// The `(oayays)` secret struct of the Secret Service API.
+#[derive(Debug, Serialize, Deserialize, Type)]
+struct Secret {
+    session: OwnedObjectPath,
+    parameters: Vec<u8>,
+    value: Vec<u8>,
+    content_type: String,
+}
+
+/// Keeps secrets in the freedesktop Secret Service (GNOME Keyring, KWallet, KeePassXC) on
+/// the session bus, as items with `service` and `account` attributes in the default
+/// collection. Uses a `plain` session; the transport is the local session bus.
+pub struct SecretServiceStore {
+    conn: Connection,
+    session: OwnedObjectPath,
+    service: String,
+}
+
+impl SecretServiceStore {
+    pub fn connect(service: &str) -> SecretStoreResult<Self> {
+        let conn = Connection::session().map_err(unavailable)?;
+        Self::with_connection(conn, service)
+    }
+
+    fn with_connection(conn: Connection, service: &str) -> SecretStoreResult<Self> {
+        let proxy = proxy(&conn, SERVICE_PATH, SERVICE_IFACE)?;
+        let (_, session): (OwnedValue, OwnedObjectPath) = proxy
+            .call("OpenSession", &("plain", Value::from("")))
+            .map_err(unavailable)?;
+        Ok(Self {
+            conn,
+            session,
+            service: service.to_string(),
+        })
+    }
+
+    fn proxy(&self, path: &str, interface: &'static str) -> SecretStoreResult<Proxy<'static>> {
+        proxy(&self.conn, path, interface)
+    }
+
+    fn attributes<'a>(&'a self, account: &'a str) -> HashMap<&'a str, &'a str> {
+        HashMap::from([("service", self.service.as_str()), ("account", account)])
+    }
+
+    /// Path of the item stored for `account`, unlocked.
+    fn find(&self, account: &str) -> SecretStoreResult<Option<OwnedObjectPath>> {
+        let (unlocked, locked): (Vec<OwnedObjectPath>, Vec<OwnedObjectPath>) = self
+            .proxy(SERVICE_PATH, SERVICE_IFACE)?
+            .call("SearchItems", &(self.attributes(account),))
+            .map_err(backend)?;
+        if let Some(item) = unlocked.into_iter().next() {
+            return Ok(Some(item));
+        }
+        let Some(item) = locked.into_iter().next() else {
+            return Ok(None);
+        };
+        self.unlock(&item)?;
+        Ok(Some(item))
+    }
+
+    fn unlock(&self, object: &OwnedObjectPath) -> SecretStoreResult<()> {
+        let (_, prompt): (Vec<OwnedObjectPath>, OwnedObjectPath) = self
+            .proxy(SERVICE_PATH, SERVICE_IFACE)?
+            .call("Unlock", &(vec![object],))
+            .map_err(backend)?;
+        self.prompt(&prompt)
+    }
+
+    /// Shows the keyring's own unlock or confirmation dialog and waits for it.
+    fn prompt(&self, prompt: &OwnedObjectPath) -> SecretStoreResult<()> {
+        if prompt.as_str() == NO_OBJECT {
+            return Ok(());
+        }
+        let proxy = self.proxy(prompt.as_str(), PROMPT_IFACE)?;
+        let mut completed = proxy.receive_signal("Completed").map_err(backend)?;
+        proxy.call::<_, _, ()>("Prompt", &("",)).map_err(backend)?;
+        let signal = completed
+            .next()
+            .ok_or_else(|| SecretStoreError::Backend("The keyring prompt vanished".into()))?;
+        let (dismissed, _): (bool, OwnedValue) = signal.body().deserialize().map_err(backend)?;
+        if dismissed {
+            return Err(SecretStoreError::Backend(
+                "The keyring prompt was dismissed".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl SecretStore for SecretServiceStore {
+    fn name(&self) -> &'static str {
+        "Secret Service"
+    }
+
+    fn get(&self, account: &str) -> SecretStoreResult<Option<String>> {
+        let Some(item) = self.find(account)? else {
+            return Ok(None);
+        };
+        let secret: Secret = self
+            .proxy(item.as_str(), ITEM_IFACE)?
+            .call("GetSecret", &(&self.session,))
+            .map_err(backend)?;
+        String::from_utf8(secret.value)
+            .map(Some)
+            .map_err(|_| SecretStoreError::Backend(format!("{account} is not valid UTF-8")))
+    }
+
+    fn set(&self, account: &str, secret: &str) -> SecretStoreResult<()> {
+        let collection: OwnedObjectPath = self
+            .proxy(SERVICE_PATH, SERVICE_IFACE)?
+            .call("ReadAlias", &("default",))
+            .map_err(backend)?;
+        if collection.as_str() == NO_OBJECT {
+            return Err(SecretStoreError::Unavailable(
+                "the keyring has no default collection".into(),
+            ));
+        }
+        self.unlock(&collection)?;
+
+        let properties = HashMap::from([
+            (
+                "org.freedesktop.Secret.Item.Label",
+                Value::from(format!("{} ({account})", self.service)),
+            ),
+            (
+                "org.freedesktop.Secret.Item.Attributes",
+                Value::from(self.attributes(account)),
+            ),
+        ]);
+        let secret = Secret {
+            session: self.session.clone(),
+            parameters: Vec::new(),
+            value: secret.as_bytes().to_vec(),
+            content_type: "text/plain".into(),
+        };
+        let (_, prompt): (OwnedObjectPath, OwnedObjectPath) = self
+            .proxy(collection.as_str(), COLLECTION_IFACE)?
+            .call("CreateItem", &(properties, secret, true))
+            .map_err(backend)?;
+        self.prompt(&prompt)
+    }
+
+    fn delete(&self, account: &str) -> SecretStoreResult<()> {
+        let Some(item) = self.find(account)? else {
+            return Ok(());
+        };
+        let prompt: OwnedObjectPath = self
+            .proxy(item.as_str(), ITEM_IFACE)?
+            .call("Delete", &())
+            .map_err(backend)?;
+        self.prompt(&prompt)
+    }
+}
+
+fn proxy(
+    conn: &Connection,
+    path: &str,
+    interface: &'static str,
+) -> SecretStoreResult<Proxy<'static>> {
+    ProxyBuilder::new(conn)
+        .destination(BUS_NAME)
+        .and_then(|builder| builder.path(path.to_string()))
+        .and_then(|builder| builder.interface(interface))
+        .map(|builder| builder.cache_properties(CacheProperties::No))
+        .and_then(|builder| builder.build())
+        .map_err(backend)
+}
+
+fn unavailable(err: zbus::Error) -> SecretStoreError {
+    SecretStoreError::Unavailable(err.to_string())
+}
+
+fn backend(err: zbus::Error) -> SecretStoreError {
+    SecretStoreError::Backend(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        os::unix::net::UnixStream,
+        sync::{Arc, Mutex},
+    };
+
+    use zbus::{blocking::connection::Builder, fdo, interface, zvariant::ObjectPath, Guid};
+
+    use super::*;
+    use crate::secret_store::{migrate, EncryptedFileStore, DB_KEY_ACCOUNT};
+
+    const COLLECTION_PATH: &str = "/org/freedesktop/secrets/collection/login";
+
+    /// Items of the stand-in keyring: path, attributes and secret; `None` once deleted.
+    type Items = Arc<Mutex<Vec<(String, HashMap<String, String>, Option<Vec<u8>>)>>>;
+
+    /// Just enough of the Secret Service API for [`SecretServiceStore`], without prompts.
+    struct StandInService {
+        items: Items,
+    }
+
+    #[interface(name = "org.freedesktop.Secret.Service")]
+    impl StandInService {
+        fn open_session(
+            &self,
+            algorithm: &str,
+            _input: Value<'_>,
+        ) -> fdo::Result<(OwnedValue, OwnedObjectPath)> {
+            if algorithm != "plain" {
+                return Err(fdo::Error::NotSupported(algorithm.to_string()));
+            }
+            Ok((
+                OwnedValue::from(0u32),
+                ObjectPath::from_static_str_unchecked("/org/freedesktop/secrets/session/1").into(),
+            ))
+        }
+
+        fn search_items(
+            &self,
+            attributes: HashMap<String, String>,
+        ) -> (Vec<OwnedObjectPath>, Vec<OwnedObjectPath>) {
+            let items = self.items.lock().unwrap();
+            let found = items
+                .iter()
+                .filter(|(_, attrs, secret)| secret.is_some() && *attrs == attributes)
+                .map(|(path, _, _)| ObjectPath::try_from(path.clone()).unwrap().into())
+                .collect();
+            (found, Vec::new())
+        }
+
+        fn unlock(&self, objects: Vec<OwnedObjectPath>) -> (Vec<OwnedObjectPath>, OwnedObjectPath) {
+            (
+                objects,
+                ObjectPath::from_static_str_unchecked(NO_OBJECT).into(),
+            )
+        }
+
+        fn read_alias(&self, name: &str) -> OwnedObjectPath {
+            let path = if name == "default" {
+                COLLECTION_PATH
+            } else {
+                NO_OBJECT
+            };
+            ObjectPath::try_from(path).unwrap().into()
+        }
+    }
+
+    struct StandInCollection {
+        items: Items,
+    }
+
+    #[interface(name = "org.freedesktop.Secret.Collection")]
+    impl StandInCollection {
+        async fn create_item(
+            &self,
+            properties: HashMap<String, OwnedValue>,
+            secret: Secret,
+            replace: bool,
+            #[zbus(object_server)] server: &zbus::ObjectServer,
+        ) -> fdo::Result<(OwnedObjectPath, OwnedObjectPath)> {
+            let attributes: HashMap<String, String> = properties
+                .get("org.freedesktop.Secret.Item.Attributes")
+                .and_then(|value| value.try_clone().ok())
+                .and_then(|value| value.try_into().ok())
+                .ok_or_else(|| fdo::Error::InvalidArgs("missing attributes".into()))?;
+
+            let (path, is_new) = {
+                let mut items = self.items.lock().unwrap();
+                let existing = items
+                    .iter_mut()
+                    .find(|(_, attrs, value)| replace && value.is_some() && *attrs == attributes);
+                match existing {
+                    Some((path, _, value)) => {
+                        *value = Some(secret.value);
+                        (path.clone(), false)
+                    }
+                    None => {
+                        let path = format!("{COLLECTION_PATH}/{}", items.len() + 1);
+                        items.push((path.clone(), attributes, Some(secret.value)));
+                        (path, true)
+                    }
+                }
+            };
+            if is_new {
+                let item = StandInItem {
+                    items: self.items.clone(),
+                    path: path.clone(),
+                };
+                server.at(path.clone(), item).await?;
+            }
+            Ok((
+                ObjectPath::try_from(path).unwrap().into(),
+                ObjectPath::from_static_str_unchecked(NO_OBJECT).into(),
+            ))
+        }
+    }
+
+    struct StandInItem {
+        items: Items,
+        path: String,
+    }
+
+    impl StandInItem {
+        fn with_entry<T>(
+            &self,
+            f: impl FnOnce(&mut Option<Vec<u8>>) -> fdo::Result<T>,
+        ) -> fdo::Result<T> {
+            let mut items = self.items.lock().unwrap();
+            let (_, _, secret) = items
+                .iter_mut()
+                .find(|(path, _, _)| *path == self.path)
+                .ok_or_else(|| fdo::Error::UnknownObject(self.path.clone()))?;
+            f(secret)
+        }
+    }
+
+    #[interface(name = "org.freedesktop.Secret.Item")]
+    impl StandInItem {
+        fn get_secret(&self, session: OwnedObjectPath) -> fdo::Result<Secret> {
+            self.with_entry(|secret| {
+                let value = secret
+                    .clone()
+                    .ok_or_else(|| fdo::Error::UnknownObject("deleted".into()))?;
+                Ok(Secret {
+                    session,
+                    parameters: Vec::new(),
+                    value,
+                    content_type: "text/plain".into(),
+                })
+            })
+        }
+
+        fn delete(&self) -> fdo::Result<OwnedObjectPath> {
+            self.with_entry(|secret| {
+                *secret = None;
+                Ok(ObjectPath::from_static_str_unchecked(NO_OBJECT).into())
+            })
+        }
+    }
+
+    /// Serves the stand-in over a socket pair, so no session bus is needed.
+    fn connect_to_stand_in() -> (Connection, Connection) {
+        let (server_socket, client_socket) = UnixStream::pair().unwrap();
+        let items = Items::default();
+        let guid = Guid::generate();
+        let server = std::thread::spawn(move || {
+            Builder::unix_stream(server_socket)
+                .server(guid)
+                .unwrap()
+                .p2p()
+                .serve_at(
+                    SERVICE_PATH,
+                    StandInService {
+                        items: items.clone(),
+                    },
+                )
+                .unwrap()
+                .serve_at(COLLECTION_PATH, StandInCollection { items })
+                .unwrap()
+                .build()
+                .unwrap()
+        });
+        let client = Builder::unix_stream(client_socket).p2p().build().unwrap();
+        (server.join().unwrap(), client)
+    }
+
+    #[test]
+    fn stores_replaces_and_deletes_items() {
+        let (_server, client) = connect_to_stand_in();
+        let store = SecretServiceStore::with_connection(client, "test.app").unwrap();
+
+        assert_eq!(store.get("key").unwrap(), None);
+        store.set("key", "first").unwrap();
+        store.set("key", "second").unwrap();
+        store.set("pending", "next").unwrap();
+        assert_eq!(store.get("key").unwrap().as_deref(), Some("second"));
+        assert_eq!(store.get("pending").unwrap().as_deref(), Some("next"));
+
+        store.delete("key").unwrap();
+        store.delete("key").unwrap();
+        assert_eq!(store.get("key").unwrap(), None);
+        assert_eq!(store.get("pending").unwrap().as_deref(), Some("next"));
+    }
+
+    #[test]
+    fn migrates_fallback_file_into_secret_service() {
+        let dir = tempfile::tempdir().unwrap();
+        let fallback = EncryptedFileStore::new(dir.path().join("store.json"), "t");
+        fallback.set(DB_KEY_ACCOUNT, "db key").unwrap();
+
+        let (_server, client) = connect_to_stand_in();
+        let store = SecretServiceStore::with_connection(client, "t").unwrap();
+        assert_eq!(migrate(&fallback, &store).unwrap(), 1);
+
+        assert_eq!(fallback.get(DB_KEY_ACCOUNT).unwrap(), None);
+        assert_eq!(
+            store.get(DB_KEY_ACCOUNT).unwrap().as_deref(),
+            Some("db key")
+        );
+    }
+}
@@ -1,71 +1,260 @@
-use std::{fs, path::Path};
+use std::{fs, io, path::Path};
 
 use base64::{engine::general_purpose::STANDARD_NO_PAD, Engine};
 use rand::{rngs::OsRng, RngCore};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::{
+    secret_store::{SecretStore, SecretStoreError, DB_KEY_ACCOUNT, PENDING_KEY_ACCOUNT},
+    services::{
+        export_crypto::{self, ExportCryptoError},
+        sqlcipher,
+    },
+};
+
 const ENV_KEY: &str = "PF_APP_DB_KEY";
 
 #[derive(Debug, Clone)]
 pub struct AppSecrets {
     db_key: String,
+    /// Key a rotation is moving to; set until the database and attachments use it.
+    pending_key: Option<String>,
+    source: KeySource,
+}
+
+/// Where the database key was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySource {
+    Environment,
+    Stored,
+    Passphrase,
 }
 
 impl AppSecrets {
     pub fn new(db_key: String) -> Self {
-        Self { db_key }
+        Self {
+            db_key,
+            pending_key: None,
+            source: KeySource::Stored,
+        }
     }
 
     pub fn sqlcipher_key(&self) -> &str {
         &self.db_key
     }
+
+    pub fn pending_key(&self) -> Option<&str> {
+        self.pending_key.as_deref()
+    }
+
+    pub fn source(&self) -> KeySource {
+        self.source
+    }
+
+    pub fn is_passphrase_protected(&self) -> bool {
+        self.source == KeySource::Passphrase
+    }
+
+    /// Starts a rotation to a freshly generated key.
+    pub fn with_new_pending_key(mut self) -> Self {
+        self.pending_key = Some(generate_key());
+        self
+    }
+
+    /// Finishes a rotation: the pending key becomes the database key.
+    pub fn promote_pending_key(mut self) -> Self {
+        if let Some(key) = self.pending_key.take() {
+            self.db_key = key;
+        }
+        self
+    }
+}
+
+/// Outcome of reading the stored secrets at startup.
+pub enum LoadedSecrets {
+    Ready(AppSecrets),
+    /// The key is wrapped with the master passphrase; call [`unlock`] with it.
+    PassphraseRequired,
 }
 
 #[derive(Debug, Error)]
 pub enum SecretError {
-    #[error("utf8 error: {0}")]
-    Utf8(#[from] std::string::FromUtf8Error),
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
     #[error("serde error: {0}")]
     Serde(#[from] serde_json::Error),
-    #[cfg(target_os = "windows")]
-    #[error("credential manager error code: {0}")]
-    CredentialManager(u32),
+    #[error("incorrect passphrase")]
+    WrongPassphrase,
+    #[error("{0}")]
+    Unsupported(String),
+    #[error("database key not found: {0}")]
+    MissingKey(String),
+    #[error("passphrase error: {0}")]
+    Wrap(String),
+    #[error(transparent)]
+    Store(#[from] SecretStoreError),
 }
 
-#[derive(Serialize, Deserialize)]
+/// `config/secrets.json`. Only exists while a master passphrase is set and then holds
+/// `wrapped`: an [`export_crypto`] envelope around the serialized [`KeySet`]. Older
+/// versions wrote the plaintext keys here; [`load_or_create`] moves them into the
+/// [`SecretStore`].
+#[derive(Default, Serialize, Deserialize)]
 struct SecretFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sqlcipher_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pending_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wrapped: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeySet {
     sqlcipher_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pending_key: Option<String>,
 }
 
-pub fn load_or_create(service_name: &str, fallback_file: &Path) -> Result<AppSecrets, SecretError> {
+/// Reads the keys for the database at `db_path`, generating a database key only while
+/// there is no encrypted database that an earlier key could belong to.
+pub fn load_or_create(
+    store: &dyn SecretStore,
+    secrets_file: &Path,
+    db_path: &Path,
+) -> Result<LoadedSecrets, SecretError> {
     if let Some(value) = std::env::var_os(ENV_KEY) {
         if let Ok(secret) = value.into_string() {
-            return Ok(AppSecrets::new(secret));
+            let mut secrets = AppSecrets::new(secret);
+            secrets.source = KeySource::Environment;
+            return Ok(LoadedSecrets::Ready(secrets));
+        }
+    }
+
+    match read_secret_file(secrets_file)? {
+        Some(SecretFile {
+            wrapped: Some(_), ..
+        }) => return Ok(LoadedSecrets::PassphraseRequired),
+        Some(SecretFile {
+            sqlcipher_key: Some(secret),
+            pending_key,
+            ..
+        }) => {
+            // Older versions kept the key here in plaintext; it is newer than anything in
+            // the store, so it replaces what the store holds
+            let mut secrets = AppSecrets::new(secret);
+            secrets.pending_key = pending_key;
+            save(store, secrets_file, &secrets, None)?;
+            tracing::info!(
+                store = store.name(),
+                "Moved the database key out of secrets.json"
+            );
+            return Ok(LoadedSecrets::Ready(secrets));
         }
+        _ => {}
     }
 
-    #[cfg(target_os = "windows")]
-    if let Ok(Some(secret)) = read_windows_credential(service_name) {
-        return Ok(AppSecrets::new(secret));
+    // A store that cannot be read must not lead to a new key: the database would be lost
+    if let Some(secret) = store.get(DB_KEY_ACCOUNT)? {
+        let mut secrets = AppSecrets::new(secret);
+        secrets.pending_key = store.get(PENDING_KEY_ACCOUNT)?;
+        return Ok(LoadedSecrets::Ready(secrets));
     }
 
-    if let Some(secret) = read_secret_file(fallback_file)? {
-        return Ok(AppSecrets::new(secret));
+    // Same for a store that lost the key, or a fallback used while the keyring is away
+    if is_encrypted_database(db_path)? {
+        return Err(SecretError::MissingKey(format!(
+            "{} is encrypted but {} holds no key for it",
+            db_path.display(),
+            store.name()
+        )));
     }
 
-    let generated = generate_key();
+    let secrets = AppSecrets::new(generate_key());
+    save(store, secrets_file, &secrets, None)?;
+    Ok(LoadedSecrets::Ready(secrets))
+}
 
-    #[cfg(target_os = "windows")]
-    if write_windows_credential(service_name, &generated).is_err() {
-        tracing::warn!("Failed to write to Windows Credential Manager, using fallback file");
+/// Unwraps the keys in `secrets_file` with the master passphrase.
+pub fn unlock(secrets_file: &Path, passphrase: &str) -> Result<AppSecrets, SecretError> {
+    let wrapped = read_secret_file(secrets_file)?
+        .and_then(|file| file.wrapped)
+        .ok_or_else(|| SecretError::Unsupported("No master passphrase is set".into()))?;
+    let opened =
+        export_crypto::open(&wrapped.to_string(), Some(passphrase)).map_err(|err| match err {
+            ExportCryptoError::Decryption => SecretError::WrongPassphrase,
+            other => SecretError::Wrap(other.to_string()),
+        })?;
+    let keys: KeySet = serde_json::from_slice(&opened.plaintext)?;
+    Ok(AppSecrets {
+        db_key: keys.sqlcipher_key,
+        pending_key: keys.pending_key,
+        source: KeySource::Passphrase,
+    })
+}
+
+/// Persists `secrets`: wrapped with `passphrase` in `secrets_file` when one is given,
+/// otherwise in `store`. A key lives in exactly one of the two, and the new copy is
+/// written before the old one is removed. Returns the secrets as they will be loaded
+/// next time.
+pub fn save(
+    store: &dyn SecretStore,
+    secrets_file: &Path,
+    secrets: &AppSecrets,
+    passphrase: Option<&str>,
+) -> Result<AppSecrets, SecretError> {
+    if secrets.source == KeySource::Environment {
+        return Err(SecretError::Unsupported(format!(
+            "The database key comes from {ENV_KEY}; change it there instead"
+        )));
     }
 
-    write_secret_file(fallback_file, &generated)?;
+    let mut stored = secrets.clone();
+    match passphrase {
+        Some(passphrase) => {
+            let keys = serde_json::to_vec(&KeySet {
+                sqlcipher_key: secrets.db_key.clone(),
+                pending_key: secrets.pending_key.clone(),
+            })?;
+            let sealed = export_crypto::seal(&keys, passphrase)
+                .map_err(|err| SecretError::Wrap(err.to_string()))?;
+            write_secret_file(
+                secrets_file,
+                &SecretFile {
+                    wrapped: Some(serde_json::from_str(&sealed)?),
+                    ..SecretFile::default()
+                },
+            )?;
+            store.delete(PENDING_KEY_ACCOUNT)?;
+            store.delete(DB_KEY_ACCOUNT)?;
+            stored.source = KeySource::Passphrase;
+        }
+        None => {
+            // The database key first: a crash before the pending key is cleared only
+            // repeats an already finished rotation
+            store.set(DB_KEY_ACCOUNT, &secrets.db_key)?;
+            match &secrets.pending_key {
+                Some(pending) => store.set(PENDING_KEY_ACCOUNT, pending)?,
+                None => store.delete(PENDING_KEY_ACCOUNT)?,
+            }
+            remove_secret_file(secrets_file)?;
+            stored.source = KeySource::Stored;
+        }
+    }
 
-    Ok(AppSecrets::new(generated))
+    Ok(stored)
+}
+
+/// Whether `db_path` holds a database a generated key could not open. Plaintext databases
+/// are encrypted with whatever key is loaded, so they do not count.
+fn is_encrypted_database(db_path: &Path) -> io::Result<bool> {
+    match fs::metadata(db_path) {
+        Ok(metadata) if metadata.len() > 0 => Ok(!sqlcipher::is_plaintext(db_path)?),
+        Ok(_) => Ok(false),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
 }
 
 fn generate_key() -> String {
@@ -74,103 +263,147 @@ fn generate_key() -> String {
     STANDARD_NO_PAD.encode(bytes)
 }
 
-fn read_secret_file(path: &Path) -> Result<Option<String>, SecretError> {
+fn read_secret_file(path: &Path) -> Result<Option<SecretFile>, SecretError> {
     if !path.exists() {
         return Ok(None);
     }
 
     let contents = fs::read_to_string(path)?;
-    let parsed: SecretFile = serde_json::from_str(&contents)?;
-    Ok(Some(parsed.sqlcipher_key))
+    Ok(Some(serde_json::from_str(&contents)?))
 }
 
-fn write_secret_file(path: &Path, secret: &str) -> Result<(), SecretError> {
+/// Writes through a temporary file so a crash mid-write never loses the key.
+fn write_secret_file(path: &Path, payload: &SecretFile) -> Result<(), SecretError> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
-    let payload = SecretFile {
-        sqlcipher_key: secret.to_string(),
-    };
-    let serialized = serde_json::to_string_pretty(&payload)?;
-    fs::write(path, serialized)?;
+    let serialized = serde_json::to_string_pretty(payload)?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serialized)?;
+    fs::rename(&tmp, path)?;
     Ok(())
 }
 
-#[cfg(target_os = "windows")]
-fn read_windows_credential(service_name: &str) -> Result<Option<String>, SecretError> {
-    use std::ffi::OsStr;
-    use std::os::windows::prelude::OsStrExt;
-
-    use windows::core::PCWSTR;
-    use windows::Win32::Foundation::ERROR_NOT_FOUND;
-    use windows::Win32::Security::Credentials::{
-        CredFree, CredReadW, CREDENTIALW, CRED_TYPE_GENERIC,
-    };
-
-    let mut encoded: Vec<u16> = OsStr::new(service_name).encode_wide().collect();
-    encoded.push(0);
-
-    let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
-    match unsafe {
-        CredReadW(
-            PCWSTR(encoded.as_ptr()),
-            CRED_TYPE_GENERIC,
-            0,
-            &mut credential,
-        )
-    } {
-        Ok(_) => {}
-        Err(err) => {
-            let code = err.code().0 as u32;
-            if code == ERROR_NOT_FOUND.0 {
-                return Ok(None);
-            }
-            return Err(SecretError::CredentialManager(code));
-        }
+fn remove_secret_file(path: &Path) -> Result<(), SecretError> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
     }
-
-    let cred = unsafe { credential.as_ref() }.ok_or(SecretError::CredentialManager(0))?;
-    let buffer = unsafe {
-        std::slice::from_raw_parts(cred.CredentialBlob, cred.CredentialBlobSize as usize)
-    };
-    let secret = String::from_utf8(buffer.to_vec())?;
-
-    unsafe { CredFree(credential as *mut _) };
-
-    Ok(Some(secret))
 }
 
-#[cfg(target_os = "windows")]
-fn write_windows_credential(service_name: &str, secret: &str) -> Result<(), SecretError> {
-    use std::ffi::OsStr;
-    use std::os::windows::prelude::OsStrExt;
-
-    use windows::core::PWSTR;
-    use windows::Win32::Security::Credentials::{
-        CredWriteW, CREDENTIALW, CRED_PERSIST_ENTERPRISE, CRED_TYPE_GENERIC,
-    };
-
-    let mut encoded: Vec<u16> = OsStr::new(service_name).encode_wide().collect();
-    encoded.push(0);
-
-    let mut blob = secret.as_bytes().to_vec();
-
-    let credential = CREDENTIALW {
-        Type: CRED_TYPE_GENERIC,
-        TargetName: PWSTR(encoded.as_mut_ptr()),
-        CredentialBlobSize: blob.len() as u32,
-        CredentialBlob: blob.as_mut_ptr(),
-        Persist: CRED_PERSIST_ENTERPRISE,
-        AttributeCount: 0,
-        Comment: PWSTR::null(),
-        TargetAlias: PWSTR::null(),
-        UserName: PWSTR::null(),
-        ..Default::default()
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret_store::{self, EncryptedFileStore, SecretStoreError};
+
+    #[test]
+    fn wraps_keys_with_passphrase_and_unwraps_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("secrets.json");
+        let db = dir.path().join("app.db");
+        let store = EncryptedFileStore::new(dir.path().join("secret-store.json"), "test");
+
+        let LoadedSecrets::Ready(secrets) = load_or_create(&store, &file, &db).unwrap() else {
+            panic!("a new key is generated without a passphrase");
+        };
+        assert!(!file.exists());
+        let rotating = secrets.clone().with_new_pending_key();
+        let stored = save(&store, &file, &rotating, Some("correct horse")).unwrap();
+        assert!(stored.is_passphrase_protected());
+        assert_eq!(store.get(DB_KEY_ACCOUNT).unwrap(), None);
+
+        let contents = fs::read_to_string(&file).unwrap();
+        assert!(!contents.contains(secrets.sqlcipher_key()));
+        assert!(matches!(
+            load_or_create(&store, &file, &db).unwrap(),
+            LoadedSecrets::PassphraseRequired
+        ));
+        assert!(matches!(
+            unlock(&file, "wrong horse"),
+            Err(SecretError::WrongPassphrase)
+        ));
+
+        let unlocked = unlock(&file, "correct horse").unwrap();
+        assert_eq!(unlocked.sqlcipher_key(), secrets.sqlcipher_key());
+        assert_eq!(unlocked.pending_key(), rotating.pending_key());
+
+        save(&store, &file, &unlocked.promote_pending_key(), None).unwrap();
+        assert!(!file.exists());
+        let LoadedSecrets::Ready(reloaded) = load_or_create(&store, &file, &db).unwrap() else {
+            panic!("the passphrase was removed");
+        };
+        assert_eq!(Some(reloaded.sqlcipher_key()), rotating.pending_key());
+        assert_eq!(reloaded.pending_key(), None);
+    }
 
-    if let Err(err) = unsafe { CredWriteW(&credential, 0) } {
-        return Err(SecretError::CredentialManager(err.code().0 as u32));
+    #[test]
+    fn moves_plaintext_keys_into_the_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("secrets.json");
+        let db = dir.path().join("app.db");
+        let store = EncryptedFileStore::new(dir.path().join("secret-store.json"), "test");
+        store.set(DB_KEY_ACCOUNT, "stale").unwrap();
+        fs::write(
+            &file,
+            r#"{ "sqlcipher_key": "legacy", "pending_key": "next" }"#,
+        )
+        .unwrap();
+
+        let LoadedSecrets::Ready(secrets) = load_or_create(&store, &file, &db).unwrap() else {
+            panic!("plaintext keys need no passphrase");
+        };
+        assert_eq!(secrets.sqlcipher_key(), "legacy");
+        assert_eq!(secrets.pending_key(), Some("next"));
+        assert!(!file.exists());
+        assert_eq!(
+            store.get(DB_KEY_ACCOUNT).unwrap().as_deref(),
+            Some("legacy")
+        );
+        assert_eq!(
+            store.get(PENDING_KEY_ACCOUNT).unwrap().as_deref(),
+            Some("next")
+        );
     }
 
-    Ok(())
+    #[test]
+    fn keeps_the_keyring_key_when_it_was_unavailable_at_startup() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("secrets.json");
+        let db = dir.path().join("app.db");
+        // SQLCipher databases have no plaintext header
+        fs::write(&db, [0x5a; 64]).unwrap();
+        let keyring = EncryptedFileStore::new(dir.path().join("keyring.json"), "test");
+        keyring.set(DB_KEY_ACCOUNT, "real").unwrap();
+        let fallback = EncryptedFileStore::new(dir.path().join("secret-store.json"), "test");
+
+        // Keyring unavailable: the fallback has no key and must not invent one
+        assert!(matches!(
+            load_or_create(&fallback, &file, &db),
+            Err(SecretError::MissingKey(_))
+        ));
+        assert_eq!(fallback.get(DB_KEY_ACCOUNT).unwrap(), None);
+
+        // Keyring back: nothing to move, and the original key is still there
+        assert_eq!(secret_store::migrate(&fallback, &keyring).unwrap(), 0);
+        let LoadedSecrets::Ready(secrets) = load_or_create(&keyring, &file, &db).unwrap() else {
+            panic!("the keyring holds the key");
+        };
+        assert_eq!(secrets.sqlcipher_key(), "real");
+
+        // A key an older version generated into the fallback is kept, never copied over
+        fallback.set(DB_KEY_ACCOUNT, "generated").unwrap();
+        let Err(SecretStoreError::Conflict(conflict)) = secret_store::migrate(&fallback, &keyring)
+        else {
+            panic!("the stores disagree");
+        };
+        assert!(conflict.contains("different copies of the database key"));
+        assert_eq!(
+            keyring.get(DB_KEY_ACCOUNT).unwrap().as_deref(),
+            Some("real")
+        );
+        assert_eq!(
+            fallback.get(DB_KEY_ACCOUNT).unwrap().as_deref(),
+            Some("generated")
+        );
+    }
 }
@@ -0,0 +1,124 @@
+mod sqlite;
+mod store;
+
+pub use sqlite::SqliteAttachmentService;
+pub use store::{stored_blobs, BlobStore};
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::ServiceDescriptor;
+
+/// Largest file accepted as an attachment.
+pub const MAX_ATTACHMENT_BYTES: u64 = 25 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentDto {
+    pub id: String,
+    pub transaction_id: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub content_hash: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachFilesInput {
+    pub transaction_id: String,
+    /// Files to copy into attachment storage; the originals are left untouched.
+    pub file_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentContentDto {
+    pub id: String,
+    pub file_name: String,
+    pub mime_type: String,
+    /// Decrypted file contents, base64-encoded for use in a `data:` URL.
+    pub data_base64: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentGcResult {
+    pub removed_files: usize,
+    pub freed_bytes: u64,
+}
+
+#[derive(Debug, Error)]
+pub enum AttachmentServiceError {
+    #[error("database error: {0}")]
+    Database(String),
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("validation failed: {0}")]
+    Validation(String),
+    #[error("storage error: {0}")]
+    Storage(String),
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+pub type AttachmentResult<T> = Result<T, AttachmentServiceError>;
+
+impl From<rusqlite::Error> for AttachmentServiceError {
+    fn from(err: rusqlite::Error) -> Self {
+        AttachmentServiceError::Database(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for AttachmentServiceError {
+    fn from(err: std::io::Error) -> Self {
+        AttachmentServiceError::Storage(err.to_string())
+    }
+}
+
+pub trait AttachmentService: Send + Sync {
+    fn descriptor(&self) -> ServiceDescriptor;
+    fn attach_files(&self, input: AttachFilesInput) -> AttachmentResult<Vec<AttachmentDto>>;
+    fn list_attachments(&self, transaction_id: &str) -> AttachmentResult<Vec<AttachmentDto>>;
+    fn read_attachment(&self, id: &str) -> AttachmentResult<AttachmentContentDto>;
+    fn remove_attachment(&self, id: &str) -> AttachmentResult<()>;
+    /// Deletes stored files no attachment row refers to any more, e.g. after the
+    /// transactions they belonged to were deleted.
+    fn collect_garbage(&self) -> AttachmentResult<AttachmentGcResult>;
+}
+
+pub(crate) fn ensure_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS "TransactionAttachment" (
+            "id" TEXT NOT NULL PRIMARY KEY,
+            "user_id" TEXT NOT NULL,
+            "transaction_id" TEXT NOT NULL,
+            "content_hash" TEXT NOT NULL,
+            "file_name" TEXT NOT NULL,
+            "mime_type" TEXT NOT NULL,
+            "size_bytes" INTEGER NOT NULL,
+            "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            CONSTRAINT "TransactionAttachment_transaction_id_fkey" FOREIGN KEY ("transaction_id") REFERENCES "Transaction" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS "TransactionAttachment_transaction_id_content_hash_key" ON "TransactionAttachment"("transaction_id", "content_hash");
+        CREATE INDEX IF NOT EXISTS "TransactionAttachment_content_hash_idx" ON "TransactionAttachment"("content_hash");
+        "#,
+    )
+}
+
+/// MIME type for the supported attachment formats, by file extension.
+pub fn mime_type_for(file_name: &str) -> Option<&'static str> {
+    let extension = file_name.rsplit_once('.')?.1.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "heic" => "image/heic",
+        _ => return None,
+    })
+}
@@ -0,0 +1,363 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use base64::{engine::general_purpose::STANDARD as Base64, Engine};
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+use crate::services::{sqlcipher, ServiceDescriptor};
+
+use super::{
+    ensure_schema, mime_type_for, AttachFilesInput, AttachmentContentDto, AttachmentDto,
+    AttachmentGcResult, AttachmentResult, AttachmentService, AttachmentServiceError, BlobStore,
+    MAX_ATTACHMENT_BYTES,
+};
+
+const DEFAULT_USER_ID: &str = "seed-user";
+
+const ATTACHMENT_SELECT: &str = r#"
+    SELECT id, transaction_id, file_name, mime_type, size_bytes, content_hash, created_at
+    FROM "TransactionAttachment"
+"#;
+
+#[derive(Clone)]
+pub struct SqliteAttachmentService {
+    db_path: PathBuf,
+    db_key: Option<String>,
+    user_id: String,
+    store: BlobStore,
+    // Held while blobs are written or collected so garbage collection never removes a
+    // blob whose row has not been committed yet.
+    store_lock: Arc<Mutex<()>>,
+}
+
+impl SqliteAttachmentService {
+    pub fn new(
+        db_path: PathBuf,
+        db_key: Option<String>,
+        user_id: Option<String>,
+        attachments_dir: PathBuf,
+        secret: &str,
+    ) -> AttachmentResult<Self> {
+        let service = Self {
+            db_path,
+            db_key,
+            user_id: user_id.unwrap_or_else(|| DEFAULT_USER_ID.to_string()),
+            store: BlobStore::new(attachments_dir, secret),
+            store_lock: Arc::new(Mutex::new(())),
+        };
+        let conn = service.connection()?;
+        ensure_schema(&conn).map_err(|err| {
+            AttachmentServiceError::Database(format!("Failed to create attachment table: {err}"))
+        })?;
+        // Pick up files orphaned by deletes that happened while the app was not running
+        service.collect_garbage()?;
+        Ok(service)
+    }
+
+    pub fn store(&self) -> &BlobStore {
+        &self.store
+    }
+
+    fn connection(&self) -> AttachmentResult<Connection> {
+        let conn = Connection::open(&self.db_path)
+            .map_err(|err| AttachmentServiceError::Database(err.to_string()))?;
+
+        if let Some(key) = &self.db_key {
+            sqlcipher::apply_key(&conn, key).map_err(|err| {
+                AttachmentServiceError::Database(format!("Failed to apply SQLCipher key: {err}"))
+            })?;
+        }
+
+        if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
+            return Err(AttachmentServiceError::Database(err.to_string()));
+        }
+
+        Ok(conn)
+    }
+
+    fn lock_store(&self) -> AttachmentResult<std::sync::MutexGuard<'_, ()>> {
+        self.store_lock
+            .lock()
+            .map_err(|_| AttachmentServiceError::Internal("Attachment store lock poisoned".into()))
+    }
+
+    fn map_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<AttachmentDto> {
+        Ok(AttachmentDto {
+            id: row.get(0)?,
+            transaction_id: row.get(1)?,
+            file_name: row.get(2)?,
+            mime_type: row.get(3)?,
+            size_bytes: row.get(4)?,
+            content_hash: row.get(5)?,
+            created_at: row.get(6)?,
+        })
+    }
+
+    fn fetch(&self, conn: &Connection, id: &str) -> AttachmentResult<AttachmentDto> {
+        conn.query_row(
+            &format!("{ATTACHMENT_SELECT} WHERE user_id = ? AND id = ?"),
+            params![self.user_id, id],
+            Self::map_row,
+        )
+        .optional()?
+        .ok_or_else(|| AttachmentServiceError::NotFound(id.to_string()))
+    }
+
+    /// Reads and validates a file before anything is written to the store.
+    fn read_source(path: &str) -> AttachmentResult<(String, &'static str, Vec<u8>)> {
+        let path = Path::new(path);
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| {
+                AttachmentServiceError::Validation(format!("{} is not a file", path.display()))
+            })?;
+        let mime_type = mime_type_for(&file_name).ok_or_else(|| {
+            AttachmentServiceError::Validation(format!(
+                "{file_name}: only images (PNG, JPEG, GIF, WebP, HEIC) and PDFs can be attached"
+            ))
+        })?;
+
+        let size = fs::metadata(path)
+            .map_err(|err| AttachmentServiceError::Validation(format!("{file_name}: {err}")))?
+            .len();
+        if size > MAX_ATTACHMENT_BYTES {
+            return Err(AttachmentServiceError::Validation(format!(
+                "{file_name} is larger than {} MB",
+                MAX_ATTACHMENT_BYTES / (1024 * 1024)
+            )));
+        }
+        if size == 0 {
+            return Err(AttachmentServiceError::Validation(format!(
+                "{file_name} is empty"
+            )));
+        }
+
+        let content = fs::read(path)
+            .map_err(|err| AttachmentServiceError::Validation(format!("{file_name}: {err}")))?;
+        Ok((file_name, mime_type, content))
+    }
+}
+
+impl AttachmentService for SqliteAttachmentService {
+    fn descriptor(&self) -> ServiceDescriptor {
+        ServiceDescriptor::new("AttachmentService", "sqlite")
+    }
+
+    fn attach_files(&self, input: AttachFilesInput) -> AttachmentResult<Vec<AttachmentDto>> {
+        if input.file_paths.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.connection()?;
+        let owned: bool = conn.query_row(
+            r#"SELECT EXISTS(SELECT 1 FROM "Transaction" WHERE id = ? AND user_id = ?)"#,
+            params![input.transaction_id, self.user_id],
+            |row| row.get(0),
+        )?;
+        if !owned {
+            return Err(AttachmentServiceError::NotFound(input.transaction_id));
+        }
+
+        let sources = input
+            .file_paths
+            .iter()
+            .map(|path| Self::read_source(path))
+            .collect::<AttachmentResult<Vec<_>>>()?;
+
+        let _guard = self.lock_store()?;
+        let tx = conn.transaction()?;
+        let mut ids = Vec::with_capacity(sources.len());
+        for (file_name, mime_type, content) in sources {
+            let hash = self.store.put(&content)?;
+            // Attaching the same file to a transaction twice keeps the first row
+            tx.execute(
+                r#"
+                INSERT INTO "TransactionAttachment" (
+                    id, user_id, transaction_id, content_hash, file_name, mime_type, size_bytes
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                ON CONFLICT (transaction_id, content_hash) DO NOTHING
+            "#,
+                params![
+                    format!("att_{}", Uuid::new_v4()),
+                    self.user_id,
+                    input.transaction_id,
+                    hash,
+                    file_name,
+                    mime_type,
+                    content.len() as i64
+                ],
+            )?;
+            ids.push(tx.query_row(
+                r#"SELECT id FROM "TransactionAttachment" WHERE transaction_id = ? AND content_hash = ?"#,
+                params![input.transaction_id, hash],
+                |row| row.get::<_, String>(0),
+            )?);
+        }
+        tx.commit()?;
+
+        ids.iter().map(|id| self.fetch(&conn, id)).collect()
+    }
+
+    fn list_attachments(&self, transaction_id: &str) -> AttachmentResult<Vec<AttachmentDto>> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "{ATTACHMENT_SELECT} WHERE user_id = ? AND transaction_id = ? ORDER BY created_at, file_name"
+        ))?;
+        let rows = stmt.query_map(params![self.user_id, transaction_id], Self::map_row)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn read_attachment(&self, id: &str) -> AttachmentResult<AttachmentContentDto> {
+        let conn = self.connection()?;
+        let attachment = self.fetch(&conn, id)?;
+        let content = self.store.get(&attachment.content_hash)?;
+        Ok(AttachmentContentDto {
+            id: attachment.id,
+            file_name: attachment.file_name,
+            mime_type: attachment.mime_type,
+            data_base64: Base64.encode(content),
+        })
+    }
+
+    fn remove_attachment(&self, id: &str) -> AttachmentResult<()> {
+        let conn = self.connection()?;
+        let attachment = self.fetch(&conn, id)?;
+        conn.execute(
+            r#"DELETE FROM "TransactionAttachment" WHERE id = ? AND user_id = ?"#,
+            params![id, self.user_id],
+        )?;
+
+        let _guard = self.lock_store()?;
+        let still_used: bool = conn.query_row(
+            r#"SELECT EXISTS(SELECT 1 FROM "TransactionAttachment" WHERE content_hash = ?)"#,
+            params![attachment.content_hash],
+            |row| row.get(0),
+        )?;
+        if !still_used {
+            self.store.remove(&attachment.content_hash)?;
+        }
+        Ok(())
+    }
+
+    fn collect_garbage(&self) -> AttachmentResult<AttachmentGcResult> {
+        let conn = self.connection()?;
+        let _guard = self.lock_store()?;
+        // Blobs are shared between users, so every user's rows count as references
+        let mut stmt =
+            conn.prepare(r#"SELECT DISTINCT content_hash FROM "TransactionAttachment""#)?;
+        let referenced = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<HashSet<_>, _>>()?;
+
+        let (removed_files, freed_bytes) = self.store.retain(&referenced)?;
+        if removed_files > 0 {
+            tracing::info!(
+                removed_files,
+                freed_bytes,
+                "Removed orphaned attachment files"
+            );
+        }
+        Ok(AttachmentGcResult {
+            removed_files,
+            freed_bytes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{
+        transactions::TransactionKind, CreateTransactionInput, SqliteTransactionService,
+        TransactionService,
+    };
+
+    #[test]
+    fn attachments_are_deduplicated_and_collected_with_their_transaction() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let transactions =
+            SqliteTransactionService::new(path.clone(), None, Some("seed-user".into())).unwrap();
+        let storage = tempfile::tempdir().unwrap();
+        let attachments = SqliteAttachmentService::new(
+            path,
+            None,
+            Some("seed-user".into()),
+            storage.path().join("attachments"),
+            "secret",
+        )
+        .unwrap();
+
+        let expense = |notes: &str| {
+            transactions
+                .create_transaction(CreateTransactionInput {
+                    id: None,
+                    account_id: "acct-default".into(),
+                    category_id: Some("cat-food".into()),
+                    kind: TransactionKind::Expense,
+                    amount_cents: 1_250,
+                    currency: "USD".into(),
+                    occurred_on: "2025-05-02T12:00:00Z".into(),
+                    notes: Some(notes.into()),
+                    tags: None,
+                    cleared: false,
+                    goal_id: None,
+                    transfer_account_id: None,
+                    splits: Vec::new(),
+                    recurrence_id: None,
+                    exchange_rate: None,
+                    payee: None,
+                    external_id: None,
+                })
+                .unwrap()
+        };
+        let lunch = expense("Lunch");
+        let dinner = expense("Dinner");
+
+        let receipt = storage.path().join("receipt.PDF");
+        fs::write(&receipt, b"%PDF-1.7 receipt").unwrap();
+        let notes = storage.path().join("notes.txt");
+        fs::write(&notes, b"not allowed").unwrap();
+
+        let attach = |transaction_id: &str, files: &[&Path]| {
+            attachments.attach_files(AttachFilesInput {
+                transaction_id: transaction_id.into(),
+                file_paths: files
+                    .iter()
+                    .map(|file| file.to_string_lossy().to_string())
+                    .collect(),
+            })
+        };
+        assert!(matches!(
+            attach(&lunch.id, &[&receipt, &notes]),
+            Err(AttachmentServiceError::Validation(_))
+        ));
+        let first = attach(&lunch.id, &[&receipt]).unwrap();
+        assert_eq!(first[0].mime_type, "application/pdf");
+        assert_eq!(attach(&lunch.id, &[&receipt]).unwrap()[0].id, first[0].id);
+        attach(&dinner.id, &[&receipt]).unwrap();
+        assert_eq!(attachments.list_attachments(&lunch.id).unwrap().len(), 1);
+        assert_eq!(attachments.store().list().unwrap().len(), 1);
+
+        let content = attachments.read_attachment(&first[0].id).unwrap();
+        assert_eq!(
+            Base64.decode(content.data_base64).unwrap(),
+            b"%PDF-1.7 receipt"
+        );
+
+        // The blob survives until the last transaction referencing it is gone
+        transactions.delete_transaction(&lunch.id, false).unwrap();
+        assert_eq!(attachments.collect_garbage().unwrap().removed_files, 0);
+        transactions.delete_transaction(&dinner.id, false).unwrap();
+        let collected = attachments.collect_garbage().unwrap();
+        assert_eq!(collected.removed_files, 1);
+        assert!(attachments.store().list().unwrap().is_empty());
+    }
+}
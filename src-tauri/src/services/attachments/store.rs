@@ -0,0 +1,249 @@
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use sha2::Sha256;
+
+use super::{AttachmentResult, AttachmentServiceError};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const MAGIC: &[u8; 4] = b"PFA1";
+const NONCE_LEN: usize = 12;
+
+/// Encrypted, content-addressed blob storage under `attachments_dir`.
+///
+/// Blobs are addressed by an HMAC of their plaintext (so identical files are stored once
+/// without revealing a plain SHA-256 of the content) and stored as `MAGIC || nonce ||
+/// AES-256-GCM ciphertext` at `<dir>/<hash[..2]>/<hash>`. Both keys are derived from the
+/// app secret that also keys the database.
+#[derive(Clone)]
+pub struct BlobStore {
+    dir: PathBuf,
+    address_key: [u8; 32],
+    cipher_key: [u8; 32],
+}
+
+impl BlobStore {
+    pub fn new(dir: PathBuf, secret: &str) -> Self {
+        Self {
+            dir,
+            address_key: derive_key(secret, b"attachments/address"),
+            cipher_key: derive_key(secret, b"attachments/encryption"),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn address(&self, content: &[u8]) -> String {
+        let mut mac =
+            <HmacSha256 as Mac>::new_from_slice(&self.address_key).expect("hmac accepts any key");
+        mac.update(content);
+        to_hex(&mac.finalize().into_bytes())
+    }
+
+    fn path_for(&self, hash: &str) -> PathBuf {
+        self.dir.join(&hash[..2]).join(hash)
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new_from_slice(&self.cipher_key).expect("key is 32 bytes")
+    }
+
+    /// Stores `content` unless an identical blob already exists and returns its address.
+    pub fn put(&self, content: &[u8]) -> AttachmentResult<String> {
+        let hash = self.address(content);
+        let path = self.path_for(&hash);
+        if path.exists() {
+            return Ok(hash);
+        }
+
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: content,
+                    aad: hash.as_bytes(),
+                },
+            )
+            .map_err(|_| AttachmentServiceError::Internal("Failed to encrypt attachment".into()))?;
+
+        let parent = path.parent().expect("blob path has a parent");
+        fs::create_dir_all(parent)?;
+        // Write to a temporary name first so a crash never leaves a truncated blob behind
+        let tmp = parent.join(format!(".{hash}.tmp"));
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(MAGIC)?;
+        file.write_all(&nonce)?;
+        file.write_all(&ciphertext)?;
+        file.sync_all()?;
+        fs::rename(&tmp, &path)?;
+        Ok(hash)
+    }
+
+    /// Decrypts the blob stored at `hash`, failing if it was modified on disk.
+    pub fn get(&self, hash: &str) -> AttachmentResult<Vec<u8>> {
+        if !is_address(hash) {
+            return Err(AttachmentServiceError::NotFound(hash.to_string()));
+        }
+        let bytes = match fs::read(self.path_for(hash)) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(AttachmentServiceError::NotFound(hash.to_string()))
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let corrupt = || AttachmentServiceError::Storage(format!("Attachment {hash} is corrupt"));
+        let body = bytes.strip_prefix(MAGIC.as_slice()).ok_or_else(corrupt)?;
+        if body.len() < NONCE_LEN {
+            return Err(corrupt());
+        }
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        let content = self
+            .cipher()
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: hash.as_bytes(),
+                },
+            )
+            .map_err(|_| corrupt())?;
+        if self.address(&content) != hash {
+            return Err(corrupt());
+        }
+        Ok(content)
+    }
+
+    /// Addresses and on-disk sizes of every stored blob.
+    pub fn list(&self) -> AttachmentResult<Vec<(String, u64)>> {
+        Ok(stored_blobs(&self.dir)?
+            .into_iter()
+            .map(|(hash, _, size)| (hash, size))
+            .collect())
+    }
+
+    pub fn remove(&self, hash: &str) -> AttachmentResult<()> {
+        if !is_address(hash) {
+            return Ok(());
+        }
+        match fs::remove_file(self.path_for(hash)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Deletes every blob not in `referenced`; returns how many files and bytes were freed.
+    pub fn retain(&self, referenced: &HashSet<String>) -> AttachmentResult<(usize, u64)> {
+        let mut removed = 0;
+        let mut freed = 0;
+        for (hash, size) in self.list()? {
+            if referenced.contains(&hash) {
+                continue;
+            }
+            match fs::remove_file(self.path_for(&hash)) {
+                Ok(()) => {
+                    removed += 1;
+                    freed += size;
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok((removed, freed))
+    }
+}
+
+/// Address, path and size of every blob file under `dir`, skipping anything else
+/// (temporary files, stray directories).
+pub fn stored_blobs(dir: &Path) -> io::Result<Vec<(String, PathBuf, u64)>> {
+    let mut blobs = Vec::new();
+    let shards = match fs::read_dir(dir) {
+        Ok(shards) => shards,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(blobs),
+        Err(err) => return Err(err),
+    };
+    for shard in shards {
+        let shard = shard?;
+        if !shard.file_type()?.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(shard.path())? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if is_address(&name) && name[..2] == *shard.file_name().to_string_lossy() {
+                let size = entry.metadata()?.len();
+                blobs.push((name, entry.path(), size));
+            }
+        }
+    }
+    Ok(blobs)
+}
+
+fn derive_key(secret: &str, purpose: &[u8]) -> [u8; 32] {
+    let mut mac =
+        <HmacSha256 as Mac>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key");
+    mac.update(purpose);
+    mac.finalize().into_bytes().into()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Whether `name` looks like a blob address (guards against path traversal via ids).
+pub fn is_address(name: &str) -> bool {
+    name.len() == 64
+        && name
+            .bytes()
+            .all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_deduplicates_and_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BlobStore::new(dir.path().to_path_buf(), "secret");
+
+        let hash = store.put(b"receipt").unwrap();
+        assert_eq!(store.put(b"receipt").unwrap(), hash);
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert_eq!(store.get(&hash).unwrap(), b"receipt");
+
+        // Stored bytes are not the plaintext, and other keys cannot read them
+        let path = store.path_for(&hash);
+        let mut raw = fs::read(&path).unwrap();
+        assert!(!raw.windows(7).any(|window| window == b"receipt"));
+        let other = BlobStore::new(dir.path().to_path_buf(), "other secret");
+        assert!(other.get(&hash).is_err());
+
+        let last = raw.len() - 1;
+        raw[last] ^= 1;
+        fs::write(&path, raw).unwrap();
+        assert!(matches!(
+            store.get(&hash),
+            Err(AttachmentServiceError::Storage(_))
+        ));
+
+        assert_eq!(store.retain(&HashSet::new()).unwrap().0, 1);
+        assert!(store.list().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::Path,
+};
+
+use chrono::{SecondsFormat, Utc};
+use rusqlite::Connection;
+use serde::Serialize;
+use thiserror::Error;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use super::{attachments::stored_blobs, sqlcipher};
+
+/// Archive layout version recorded in `manifest.json`.
+const BACKUP_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSummary {
+    pub file_path: String,
+    pub file_name: String,
+    pub created_at: String,
+    pub attachment_count: usize,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupManifest {
+    format_version: u32,
+    created_at: String,
+    database: &'static str,
+    attachment_count: usize,
+    /// Attachment blobs are copied as stored, i.e. still encrypted with the app key.
+    attachments_encrypted: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("database error: {0}")]
+    Database(String),
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("archive error: {0}")]
+    Archive(#[from] zip::result::ZipError),
+}
+
+impl From<rusqlite::Error> for BackupError {
+    fn from(err: rusqlite::Error) -> Self {
+        BackupError::Database(err.to_string())
+    }
+}
+
+/// Writes `backup-<timestamp>.zip` to `dest_dir` with a consistent snapshot of the database
+/// (`app.db`), every attachment blob under `attachments/`, and a `manifest.json`.
+///
+/// Restoring means unpacking the archive into the storage directory while the app is
+/// closed; the database snapshot keeps the SQLCipher encryption, so it and the attachments
+/// stay readable only with the same `secrets.json` key.
+pub fn create_backup(
+    db_path: &Path,
+    db_key: Option<&str>,
+    attachments_dir: &Path,
+    dest_dir: &Path,
+) -> Result<BackupSummary, BackupError> {
+    fs::create_dir_all(dest_dir)?;
+    let now = Utc::now();
+    let file_name = format!("backup-{}.zip", now.format("%Y%m%d-%H%M%S"));
+    let file_path = dest_dir.join(&file_name);
+
+    let snapshot = dest_dir.join(format!(".{file_name}.db"));
+    let partial = dest_dir.join(format!(".{file_name}.partial"));
+    let result = write_archive(db_path, db_key, attachments_dir, &snapshot, &partial, now);
+    let _ = fs::remove_file(&snapshot);
+    let attachment_count = match result {
+        Ok(count) => count,
+        Err(err) => {
+            let _ = fs::remove_file(&partial);
+            return Err(err);
+        }
+    };
+    fs::rename(&partial, &file_path)?;
+
+    Ok(BackupSummary {
+        size_bytes: fs::metadata(&file_path)?.len(),
+        file_path: file_path.to_string_lossy().to_string(),
+        file_name,
+        created_at: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        attachment_count,
+    })
+}
+
+fn write_archive(
+    db_path: &Path,
+    db_key: Option<&str>,
+    attachments_dir: &Path,
+    snapshot: &Path,
+    partial: &Path,
+    now: chrono::DateTime<Utc>,
+) -> Result<usize, BackupError> {
+    // VACUUM INTO gives a consistent copy even while other connections are writing
+    let conn = Connection::open(db_path)?;
+    if let Some(key) = db_key {
+        sqlcipher::apply_key(&conn, key)?;
+    }
+    let _ = fs::remove_file(snapshot);
+    conn.execute("VACUUM INTO ?", [snapshot.to_string_lossy().to_string()])?;
+    drop(conn);
+
+    let mut zip = ZipWriter::new(File::create(partial)?);
+    let deflated = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+    // Encrypted blobs do not compress
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+    zip.start_file("app.db", deflated)?;
+    io::copy(&mut File::open(snapshot)?, &mut zip)?;
+
+    let blobs = stored_blobs(attachments_dir)?;
+    for (hash, path, _) in &blobs {
+        zip.start_file(format!("attachments/{}/{hash}", &hash[..2]), stored)?;
+        io::copy(&mut File::open(path)?, &mut zip)?;
+    }
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        created_at: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        database: "app.db",
+        attachment_count: blobs.len(),
+        attachments_encrypted: true,
+    };
+    zip.start_file("manifest.json", deflated)?;
+    zip.write_all(
+        &serde_json::to_vec_pretty(&manifest)
+            .map_err(|err| BackupError::Io(io::Error::other(err)))?,
+    )?;
+    zip.finish()?.sync_all()?;
+
+    Ok(blobs.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::attachments::BlobStore;
+
+    #[test]
+    fn backup_contains_database_attachments_and_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("app.db");
+        Connection::open(&db_path)
+            .unwrap()
+            .execute_batch("CREATE TABLE t (id TEXT); INSERT INTO t VALUES ('row');")
+            .unwrap();
+        let store = BlobStore::new(dir.path().join("attachments"), "secret");
+        let hash = store.put(b"%PDF receipt").unwrap();
+
+        let summary =
+            create_backup(&db_path, None, store.dir(), &dir.path().join("backups")).unwrap();
+        assert_eq!(summary.attachment_count, 1);
+
+        let mut archive = zip::ZipArchive::new(File::open(&summary.file_path).unwrap()).unwrap();
+        let mut names: Vec<_> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "app.db".to_string(),
+                format!("attachments/{}/{hash}", &hash[..2]),
+                "manifest.json".to_string(),
+            ]
+        );
+
+        let restored = dir.path().join("restored.db");
+        io::copy(
+            &mut archive.by_name("app.db").unwrap(),
+            &mut File::create(&restored).unwrap(),
+        )
+        .unwrap();
+        let value: String = Connection::open(restored)
+            .unwrap()
+            .query_row("SELECT id FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(value, "row");
+        assert_eq!(fs::read_dir(dir.path().join("backups")).unwrap().count(), 1);
+    }
+}
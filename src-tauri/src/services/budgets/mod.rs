@@ -1,9 +1,11 @@
+mod period;
 mod sqlite;
 
 pub use sqlite::SqliteBudgetService;
 
+use chrono::NaiveDate;
 use rusqlite;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use thiserror::Error;
 
 use super::ServiceDescriptor;
@@ -19,10 +21,19 @@ pub struct BudgetDto {
     pub category_id: Option<String>,
     pub category_name: Option<String>,
     pub amount_cents: i64,
+    /// For recurring budgets, the bounds of the period this DTO reports on.
     pub start_date: String,
     pub end_date: String,
+    /// Renews every `period`; `start_date`/`end_date` then follow the calendar.
+    pub recurring: bool,
     pub rollover: bool,
+    /// Largest amount carried into this budget in either direction; `None` carries all.
+    pub rollover_cap_cents: Option<i64>,
     pub alert_threshold: f64,
+    /// Remainder of the previous period carried in: negative after overspending.
+    pub carried_over_cents: i64,
+    /// `amount_cents` plus `carried_over_cents`; progress is measured against this.
+    pub available_cents: i64,
     pub spent_cents: i64,
     pub remaining_cents: i64,
     pub progress_percent: f64,
@@ -55,6 +66,9 @@ impl BudgetPeriod {
 pub enum BudgetType {
     Envelope,
     Overall,
+    /// A monthly category envelope holding money assigned from the month's income.
+    #[serde(rename = "zero_based")]
+    ZeroBased,
 }
 
 impl BudgetType {
@@ -62,6 +76,7 @@ impl BudgetType {
         match self {
             BudgetType::Envelope => "envelope",
             BudgetType::Overall => "overall",
+            BudgetType::ZeroBased => "zero_based",
         }
     }
 }
@@ -84,7 +99,13 @@ pub struct CreateBudgetInput {
     pub amount_cents: i64,
     pub start_date: String,
     pub end_date: String,
+    /// When set, the budget covers the period containing `start_date` and `end_date` is
+    /// ignored.
+    #[serde(default)]
+    pub recurring: bool,
     pub rollover: bool,
+    #[serde(default)]
+    pub rollover_cap_cents: Option<i64>,
     pub alert_threshold: Option<f64>,
 }
 
@@ -99,10 +120,23 @@ pub struct UpdateBudgetInput {
     pub amount_cents: Option<i64>,
     pub start_date: Option<String>,
     pub end_date: Option<String>,
+    pub recurring: Option<bool>,
     pub rollover: Option<bool>,
+    /// Omit to keep the cap, `null` to remove it.
+    #[serde(default, deserialize_with = "nullable")]
+    pub rollover_cap_cents: Option<Option<i64>>,
     pub alert_threshold: Option<f64>,
 }
 
+/// Tells an explicit `null` (`Some(None)`) apart from a missing field (`None`).
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BudgetEntryDto {
@@ -123,6 +157,101 @@ pub struct RecordSnapshotInput {
     pub snapshot_date: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListBudgetSnapshotsInput {
+    pub budget_id: String,
+    /// Inclusive bounds on `snapshot_date` (`YYYY-MM-DD` or RFC 3339).
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+}
+
+/// How one period of a budget went, with its daily snapshots for a burn-down chart.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetPeriodSummaryDto {
+    /// The budget as it stood in the period, so `start_date`/`end_date` bound it.
+    pub budget: BudgetDto,
+    pub days_total: i64,
+    pub days_elapsed: i64,
+    /// The period has ended; `projected_cents` is then the final spend.
+    pub complete: bool,
+    pub average_daily_cents: i64,
+    /// Spending at the end of the period at the current run rate.
+    pub projected_cents: i64,
+    /// `available_cents` minus `projected_cents`: negative when heading over.
+    pub variance_cents: i64,
+    pub snapshots: Vec<BudgetEntryDto>,
+}
+
+/// Zero-based budgeting for one month: income feeds the "to be assigned" pool, which
+/// the month's `zero_based` envelopes draw from.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZeroBasedMonthDto {
+    pub month_start: String,
+    pub month_end: String,
+    pub income_cents: i64,
+    pub assigned_cents: i64,
+    /// `income_cents` minus `assigned_cents`; negative only when income shrank after
+    /// money was assigned.
+    pub to_be_assigned_cents: i64,
+    pub envelopes: Vec<BudgetDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignToEnvelopeInput {
+    pub category_id: String,
+    /// Any date in the month (`YYYY-MM-DD` or RFC 3339); defaults to the current month.
+    #[serde(default)]
+    pub month: Option<String>,
+    /// Added to the envelope, creating it if needed; negative returns money to the pool.
+    pub amount_cents: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveEnvelopeFundsInput {
+    pub from_budget_id: String,
+    pub to_budget_id: String,
+    pub amount_cents: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetAlertKind {
+    /// Spending reached the budget's `alert_threshold`.
+    Threshold,
+    Over,
+}
+
+impl BudgetAlertKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetAlertKind::Threshold => "threshold",
+            BudgetAlertKind::Over => "over",
+        }
+    }
+}
+
+/// A threshold crossing, recorded once per budget, period and kind.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetAlertDto {
+    pub id: String,
+    pub budget_id: String,
+    pub budget_name: String,
+    pub kind: BudgetAlertKind,
+    pub period_start: String,
+    pub spent_cents: i64,
+    pub available_cents: i64,
+    pub progress_percent: f64,
+    pub created_at: String,
+}
+
 #[derive(Debug, Error)]
 pub enum BudgetServiceError {
     #[error("database error: {0}")]
@@ -145,12 +274,38 @@ impl From<rusqlite::Error> for BudgetServiceError {
 
 pub trait BudgetService: Send + Sync {
     fn descriptor(&self) -> ServiceDescriptor;
-    fn list_budgets(&self) -> BudgetResult<Vec<BudgetDto>>;
+    /// Without `as_of`, every budget in its current period. With a date, the budgets that
+    /// cover it, recurring ones reporting on the period containing that date.
+    fn list_budgets(&self, as_of: Option<&str>) -> BudgetResult<Vec<BudgetDto>>;
     fn get_budget(&self, id: &str) -> BudgetResult<BudgetDto>;
     fn create_budget(&self, input: CreateBudgetInput) -> BudgetResult<BudgetDto>;
     fn update_budget(&self, input: UpdateBudgetInput) -> BudgetResult<BudgetDto>;
     fn delete_budget(&self, id: &str) -> BudgetResult<()>;
     fn record_snapshot(&self, input: RecordSnapshotInput) -> BudgetResult<BudgetEntryDto>;
+    /// Records an alert for every budget active on `today` that reached its threshold or
+    /// went over, and returns only those not recorded before in the current period.
+    fn evaluate_alerts(&self, today: NaiveDate) -> BudgetResult<Vec<BudgetAlertDto>>;
+    fn list_budget_alerts(&self, limit: u32) -> BudgetResult<Vec<BudgetAlertDto>>;
+    /// Writes the snapshot for `today` of every budget active then, filling in the days
+    /// of the period missed since the last one. Returns how many were written.
+    fn record_daily_snapshots(&self, today: NaiveDate) -> BudgetResult<usize>;
+    fn list_budget_snapshots(
+        &self,
+        input: ListBudgetSnapshotsInput,
+    ) -> BudgetResult<Vec<BudgetEntryDto>>;
+    /// The period containing `as_of` (default today).
+    fn get_period_summary(
+        &self,
+        budget_id: &str,
+        as_of: Option<&str>,
+    ) -> BudgetResult<BudgetPeriodSummaryDto>;
+    /// The month containing `month` (default today).
+    fn get_zero_based_month(&self, month: Option<&str>) -> BudgetResult<ZeroBasedMonthDto>;
+    /// Fails when it would assign more than the month's income.
+    fn assign_to_envelope(&self, input: AssignToEnvelopeInput) -> BudgetResult<ZeroBasedMonthDto>;
+    /// Moves assigned money between two envelopes of the same month.
+    fn move_envelope_funds(&self, input: MoveEnvelopeFundsInput)
+        -> BudgetResult<ZeroBasedMonthDto>;
     #[allow(dead_code)]
     fn calculate_budget_progress(
         &self,
@@ -0,0 +1,116 @@
+use chrono::{Datelike, Duration, NaiveDate};
+
+use super::BudgetPeriod;
+
+impl BudgetPeriod {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "weekly" => Some(BudgetPeriod::Weekly),
+            "monthly" => Some(BudgetPeriod::Monthly),
+            "quarterly" => Some(BudgetPeriod::Quarterly),
+            "yearly" => Some(BudgetPeriod::Yearly),
+            _ => None,
+        }
+    }
+
+    /// The period containing `date` as `[start, end)`. Weeks start on `week_starts_on`
+    /// (0 = Sunday, as in the user settings).
+    pub(crate) fn window(&self, date: NaiveDate, week_starts_on: u32) -> (NaiveDate, NaiveDate) {
+        match self {
+            BudgetPeriod::Weekly => {
+                let offset = (date.weekday().num_days_from_sunday() + 7 - week_starts_on % 7) % 7;
+                let start = date - Duration::days(i64::from(offset));
+                (start, start + Duration::days(7))
+            }
+            BudgetPeriod::Monthly => month_window(date.year(), date.month(), 1),
+            BudgetPeriod::Quarterly => month_window(date.year(), (date.month() - 1) / 3 * 3 + 1, 3),
+            BudgetPeriod::Yearly => month_window(date.year(), 1, 12),
+        }
+    }
+}
+
+fn month_window(year: i32, month: u32, months: u32) -> (NaiveDate, NaiveDate) {
+    let start = NaiveDate::from_ymd_opt(year, month, 1).expect("valid first of month");
+    let end = start
+        .checked_add_months(chrono::Months::new(months))
+        .expect("date in range");
+    (start, end)
+}
+
+/// Period boundaries are stored as UTC midnight, in the RFC 3339 form budgets use.
+pub(crate) fn format_boundary(date: NaiveDate) -> String {
+    format!("{}T00:00:00Z", date.format("%Y-%m-%d"))
+}
+
+/// The UTC date of a stored RFC 3339 timestamp or a plain `YYYY-MM-DD` date.
+pub(crate) fn parse_boundary(value: &str) -> Option<NaiveDate> {
+    let date = value.get(..10).unwrap_or(value);
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// What spending reaches by `end` if it keeps the pace it had in `[start, day]`.
+pub(crate) fn projected_spend(spent: i64, start: NaiveDate, end: NaiveDate, day: NaiveDate) -> i64 {
+    let days_total = (end - start).num_days().max(1);
+    let days_elapsed = ((day - start).num_days() + 1).clamp(1, days_total);
+    spent * days_total / days_elapsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        parse_boundary(value).unwrap()
+    }
+
+    #[test]
+    fn weeks_start_on_the_configured_day() {
+        // 2025-03-12 is a Wednesday
+        assert_eq!(
+            BudgetPeriod::Weekly.window(date("2025-03-12"), 1),
+            (date("2025-03-10"), date("2025-03-17"))
+        );
+        assert_eq!(
+            BudgetPeriod::Weekly.window(date("2025-03-12"), 0),
+            (date("2025-03-09"), date("2025-03-16"))
+        );
+        assert_eq!(
+            BudgetPeriod::Weekly.window(date("2025-03-09"), 1),
+            (date("2025-03-03"), date("2025-03-10"))
+        );
+    }
+
+    #[test]
+    fn calendar_periods_cover_the_containing_month_quarter_and_year() {
+        assert_eq!(
+            BudgetPeriod::Monthly.window(date("2024-12-31"), 1),
+            (date("2024-12-01"), date("2025-01-01"))
+        );
+        assert_eq!(
+            BudgetPeriod::Quarterly.window(date("2025-08-20"), 1),
+            (date("2025-07-01"), date("2025-10-01"))
+        );
+        assert_eq!(
+            BudgetPeriod::Yearly.window(date("2025-08-20"), 1),
+            (date("2025-01-01"), date("2026-01-01"))
+        );
+    }
+
+    #[test]
+    fn projects_spend_at_the_current_run_rate() {
+        let (start, end) = (date("2025-04-01"), date("2025-05-01"));
+        assert_eq!(
+            projected_spend(1_000, start, end, date("2025-04-10")),
+            3_000
+        );
+        assert_eq!(
+            projected_spend(1_000, start, end, date("2025-04-30")),
+            1_000
+        );
+        // Days outside the period count as its first or last day
+        assert_eq!(
+            projected_spend(1_000, start, end, date("2025-06-01")),
+            1_000
+        );
+    }
+}
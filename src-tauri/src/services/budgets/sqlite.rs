@@ -1,18 +1,47 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
-use chrono::{DateTime, Utc};
-use rusqlite::{params, params_from_iter, Connection, ToSql};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, ToSql};
 use uuid::Uuid;
 
-use crate::services::ServiceDescriptor;
+use crate::services::{
+    sqlcipher,
+    transactions::{CATEGORY_ANCESTRY_CTE, TRANSACTION_LINES_CTE},
+    ServiceDescriptor,
+};
 
 use super::{
-    BudgetDto, BudgetEntryDto, BudgetPeriod, BudgetResult, BudgetService, BudgetServiceError,
-    BudgetStatus, BudgetType, CreateBudgetInput, RecordSnapshotInput, UpdateBudgetInput,
+    period::{format_boundary, parse_boundary, projected_spend},
+    AssignToEnvelopeInput, BudgetAlertDto, BudgetAlertKind, BudgetDto, BudgetEntryDto,
+    BudgetPeriod, BudgetPeriodSummaryDto, BudgetResult, BudgetService, BudgetServiceError,
+    BudgetStatus, BudgetType, CreateBudgetInput, ListBudgetSnapshotsInput, MoveEnvelopeFundsInput,
+    RecordSnapshotInput, UpdateBudgetInput, ZeroBasedMonthDto,
 };
 
 const DEFAULT_USER_ID: &str = "seed-user";
 
+const BUDGET_SELECT: &str = r#"
+    SELECT 
+        b.id,
+        b.user_id,
+        b.name,
+        b.period,
+        b.type,
+        b.category_id,
+        c.name as category_name,
+        b.amount_cents,
+        b.start_date,
+        b.end_date,
+        b.rollover,
+        b.alert_threshold,
+        b.created_at,
+        b.rollover_cap_cents,
+        b.recurring
+    FROM "Budget" b
+    LEFT JOIN "Category" c ON b.category_id = c.id
+"#;
+
+#[derive(Clone)]
 pub struct SqliteBudgetService {
     db_path: PathBuf,
     db_key: Option<String>,
@@ -30,57 +59,290 @@ impl SqliteBudgetService {
             db_key,
             user_id: user_id.unwrap_or_else(|| DEFAULT_USER_ID.to_string()),
         };
+        service.ensure_schema()?;
         Ok(service)
     }
 
+    /// The `Budget` table itself comes with the initial migration, which the transaction
+    /// service applies first.
+    fn ensure_schema(&self) -> BudgetResult<()> {
+        let conn = self.connection()?;
+        let migrations = [
+            (
+                "Budget",
+                "rollover_cap_cents",
+                include_str!(
+                    "../../../../prisma/migrations/20261017107000_add_budget_rollover_cap/migration.sql"
+                ),
+            ),
+            (
+                "Budget",
+                "recurring",
+                include_str!(
+                    "../../../../prisma/migrations/20261017108000_add_periodic_budgets/migration.sql"
+                ),
+            ),
+            (
+                "BudgetAlert",
+                "id",
+                include_str!(
+                    "../../../../prisma/migrations/20261017109000_add_budget_alerts/migration.sql"
+                ),
+            ),
+        ];
+
+        for (table, column, migration) in migrations {
+            let has_column: bool = conn
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM pragma_table_info(?) WHERE name = ?)",
+                    params![table, column],
+                    |row| row.get(0),
+                )
+                .unwrap_or(false);
+
+            if !has_column {
+                conn.execute_batch(migration).map_err(|err| {
+                    BudgetServiceError::Database(format!("Failed to add {table}.{column}: {err}"))
+                })?;
+            }
+        }
+
+        // Allowing the `zero_based` type rebuilds the table, so there is no column to look for.
+        // A failed probe must not be mistaken for an old table and trigger the rebuild.
+        let allows_zero_based: bool = conn.query_row(
+            r#"SELECT EXISTS(SELECT 1 FROM sqlite_master
+               WHERE type = 'table' AND name = 'Budget' AND sql LIKE '%zero_based%')"#,
+            [],
+            |row| row.get(0),
+        )?;
+        if !allows_zero_based {
+            // The rebuild runs in its own transaction; if it fails part way, dropping `conn`
+            // rolls it back and leaves the old table in place
+            conn.execute_batch(include_str!(
+                "../../../../prisma/migrations/20261017110000_add_zero_based_budgets/migration.sql"
+            ))
+            .map_err(|err| {
+                BudgetServiceError::Database(format!("Failed to allow zero-based budgets: {err}"))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn week_starts_on(&self, conn: &Connection) -> BudgetResult<u32> {
+        let week_starts_on: Option<i32> = conn
+            .query_row(
+                r#"SELECT week_starts_on FROM "User" WHERE id = ?"#,
+                params![self.user_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(week_starts_on.unwrap_or(1).rem_euclid(7) as u32)
+    }
+
+    /// Moves recurring budgets whose period has ended into the period containing `today`,
+    /// recording each elapsed period with the amount it had.
+    fn renew_budgets(&self, conn: &Connection, today: NaiveDate) -> BudgetResult<()> {
+        let mut stmt = conn.prepare(&format!(
+            "{BUDGET_SELECT} WHERE b.user_id = ? AND b.recurring = 1 AND b.end_date <= ?"
+        ))?;
+        let due = stmt
+            .query_map(
+                params![self.user_id, format_boundary(today)],
+                map_budget_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+        if due.is_empty() {
+            return Ok(());
+        }
+
+        let week_starts_on = self.week_starts_on(conn)?;
+        let tx = conn.unchecked_transaction()?;
+        for budget in due {
+            let period = parse_period(&budget.period)?;
+            let (mut start, mut end) = budget_window(&budget)?;
+            while end <= today {
+                tx.execute(
+                    r#"INSERT OR IGNORE INTO "BudgetPeriodHistory"
+                       (id, budget_id, period_start, period_end, amount_cents, created_at)
+                       VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)"#,
+                    params![
+                        Uuid::new_v4().to_string(),
+                        budget.id,
+                        format_boundary(start),
+                        format_boundary(end),
+                        budget.amount_cents
+                    ],
+                )?;
+                (start, end) = period.window(end, week_starts_on);
+            }
+            tx.execute(
+                r#"UPDATE "Budget" SET start_date = ?, end_date = ? WHERE id = ?"#,
+                params![format_boundary(start), format_boundary(end), budget.id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Every budget, each in the period containing `as_of` and leaving out those that don't
+    /// cover it, or as stored when there is no date.
+    fn budget_rows(
+        &self,
+        conn: &Connection,
+        as_of: Option<NaiveDate>,
+    ) -> BudgetResult<Vec<BudgetRow>> {
+        let mut stmt = conn
+            .prepare(&format!(
+                "{BUDGET_SELECT} WHERE b.user_id = ? ORDER BY b.created_at DESC"
+            ))
+            .map_err(|err| BudgetServiceError::Database(err.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![self.user_id], map_budget_row)
+            .map_err(|err| BudgetServiceError::Database(err.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| BudgetServiceError::Database(err.to_string()))?;
+
+        drop(stmt);
+
+        let Some(as_of) = as_of else {
+            return Ok(rows);
+        };
+        let week_starts_on = self.week_starts_on(conn)?;
+        let mut budgets = Vec::new();
+        for row in rows {
+            if let Some(row) = self.budget_as_of(conn, row, as_of, week_starts_on)? {
+                budgets.push(row);
+            }
+        }
+        Ok(budgets)
+    }
+
+    fn snapshots_between(
+        &self,
+        conn: &Connection,
+        budget_id: &str,
+        from: Option<&str>,
+        until: Option<&str>,
+    ) -> BudgetResult<Vec<BudgetEntryDto>> {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, budget_id, actual_cents, projected_cents, snapshot_date, created_at
+            FROM "BudgetEntry"
+            WHERE budget_id = ?
+              AND (?2 IS NULL OR snapshot_date >= ?2)
+              AND (?3 IS NULL OR snapshot_date < ?3)
+            ORDER BY snapshot_date
+            "#,
+        )?;
+        let entries = stmt
+            .query_map(params![budget_id, from, until], map_entry_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// `budget` as it stood in the period containing `as_of`, or `None` when it did not
+    /// cover that date.
+    fn budget_as_of(
+        &self,
+        conn: &Connection,
+        budget: BudgetRow,
+        as_of: NaiveDate,
+        week_starts_on: u32,
+    ) -> BudgetResult<Option<BudgetRow>> {
+        let (start, end) = budget_window(&budget)?;
+        if as_of >= start && as_of < end {
+            return Ok(Some(budget));
+        }
+        if !budget.recurring {
+            return Ok(None);
+        }
+
+        if as_of >= end {
+            // Later periods have not happened yet; show them with today's settings
+            let (start, end) = parse_period(&budget.period)?.window(as_of, week_starts_on);
+            return Ok(Some(BudgetRow {
+                start_date: format_boundary(start),
+                end_date: format_boundary(end),
+                ..budget
+            }));
+        }
+
+        let date = format_boundary(as_of);
+        let period = conn
+            .query_row(
+                r#"SELECT period_start, period_end, amount_cents FROM "BudgetPeriodHistory"
+                   WHERE budget_id = ? AND period_start <= ? AND period_end > ?"#,
+                params![budget.id, date, date],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let row = period.map(|(start_date, end_date, amount_cents)| BudgetRow {
+            start_date,
+            end_date,
+            amount_cents,
+            ..budget
+        });
+        Ok(row)
+    }
+
     fn connection(&self) -> BudgetResult<Connection> {
         let conn = Connection::open(&self.db_path)
             .map_err(|err| BudgetServiceError::Database(err.to_string()))?;
 
-        if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
-            return Err(BudgetServiceError::Database(err.to_string()));
+        if let Some(key) = &self.db_key {
+            sqlcipher::apply_key(&conn, key).map_err(|err| {
+                BudgetServiceError::Database(format!("Failed to apply SQLCipher key: {err}"))
+            })?;
         }
 
-        if let Some(key) = &self.db_key {
-            if let Err(err) = conn.pragma_update(None, "key", key) {
-                tracing::warn!(error = %err, "Failed to apply SQLCipher key; continuing without encryption");
-            }
+        if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
+            return Err(BudgetServiceError::Database(err.to_string()));
         }
 
         Ok(conn)
     }
 
-    fn calculate_spent(&self, conn: &Connection, budget_id: &str) -> BudgetResult<i64> {
-        let budget = self.fetch_budget_row(conn, budget_id)?;
+    fn calculate_spent(&self, conn: &Connection, budget: &BudgetRow) -> BudgetResult<i64> {
         let categories = parse_categories(budget.category_id.clone());
 
         let spent: i64 = if categories.is_empty() {
             conn.query_row(
-                r#"
+                &format!(
+                    r#"
+                WITH {TRANSACTION_LINES_CTE}
                 SELECT COALESCE(SUM(amount_cents), 0)
-                FROM "Transaction"
+                FROM transaction_lines
                 WHERE user_id = ? 
                   AND type = 'expense'
                   AND occurred_on >= ? 
                   AND occurred_on < ?
-                "#,
+                "#
+                ),
                 params![self.user_id, budget.start_date, budget.end_date],
                 |row| row.get(0),
             )
             .unwrap_or(0)
         } else {
             let placeholders = format!("({})", std::iter::repeat("?").take(categories.len()).collect::<Vec<_>>().join(","));
+            // Budgeting a parent category also counts spending in its subcategories, and
+            // split transactions only count the lines booked to the budgeted categories
             let sql = format!(
                 r#"
+                WITH RECURSIVE {}, {}
                 SELECT COALESCE(SUM(amount_cents), 0)
-                FROM "Transaction"
+                FROM transaction_lines
                 WHERE user_id = ? 
                   AND type = 'expense'
                   AND occurred_on >= ? 
                   AND occurred_on < ?
-                  AND category_id IN {}
+                  AND category_id IN (
+                      SELECT category_id FROM category_ancestry WHERE ancestor_id IN {}
+                  )
                 "#,
-                placeholders
+                CATEGORY_ANCESTRY_CTE, TRANSACTION_LINES_CTE, placeholders
             );
 
             let mut params: Vec<Box<dyn ToSql>> =
@@ -102,6 +364,10 @@ impl SqliteBudgetService {
         spent_cents: i64,
         alert_threshold: f64,
     ) -> (f64, BudgetStatus) {
+        // Only a carried-over overspend makes the available amount negative
+        if amount_cents < 0 {
+            return (100.0, BudgetStatus::Over);
+        }
         if amount_cents <= 0 {
             return (0.0, BudgetStatus::Normal);
         }
@@ -121,46 +387,27 @@ impl SqliteBudgetService {
         (progress, status)
     }
 
+    /// Progress against what a period has available once its carry is added. A carry that
+    /// leaves nothing available makes any spending over, where an unfunded budget is not.
+    fn period_progress(
+        &self,
+        available_cents: i64,
+        carried_over_cents: i64,
+        spent_cents: i64,
+        alert_threshold: f64,
+    ) -> (f64, BudgetStatus) {
+        if available_cents == 0 && carried_over_cents != 0 && spent_cents > 0 {
+            return (100.0, BudgetStatus::Over);
+        }
+        self.calculate_progress(available_cents, spent_cents, alert_threshold)
+    }
+
     fn fetch_budget_row(&self, conn: &Connection, id: &str) -> BudgetResult<BudgetRow> {
         let row = conn
             .query_row(
-                r#"
-                SELECT 
-                    b.id,
-                    b.user_id,
-                    b.name,
-                    b.period,
-                    b.type,
-                    b.category_id,
-                    c.name as category_name,
-                    b.amount_cents,
-                    b.start_date,
-                    b.end_date,
-                    b.rollover,
-                    b.alert_threshold,
-                    b.created_at
-                FROM "Budget" b
-                LEFT JOIN "Category" c ON b.category_id = c.id
-                WHERE b.id = ? AND b.user_id = ?
-                "#,
+                &format!("{BUDGET_SELECT} WHERE b.id = ? AND b.user_id = ?"),
                 params![id, self.user_id],
-                |row| {
-                    Ok(BudgetRow {
-                        id: row.get(0)?,
-                        user_id: row.get(1)?,
-                        name: row.get(2)?,
-                        period: row.get(3)?,
-                        budget_type: row.get(4)?,
-                        category_id: row.get(5)?,
-                        category_name: row.get(6)?,
-                        amount_cents: row.get(7)?,
-                        start_date: row.get(8)?,
-                        end_date: row.get(9)?,
-                        rollover: row.get(10)?,
-                        alert_threshold: row.get(11)?,
-                        created_at: row.get(12)?,
-                    })
-                },
+                map_budget_row,
             )
             .map_err(|err| {
                 if let rusqlite::Error::QueryReturnedNoRows = err {
@@ -173,23 +420,352 @@ impl SqliteBudgetService {
         Ok(row)
     }
 
-    fn row_to_dto(&self, conn: &Connection, row: BudgetRow) -> BudgetResult<BudgetDto> {
-        let spent = self.calculate_spent(conn, &row.id)?;
-        let remaining = (row.amount_cents - spent).max(0);
-        let (progress_percent, status) =
-            self.calculate_progress(row.amount_cents, spent, row.alert_threshold);
-
-        let period = match row.period.as_str() {
-            "weekly" => BudgetPeriod::Weekly,
-            "monthly" => BudgetPeriod::Monthly,
-            "quarterly" => BudgetPeriod::Quarterly,
-            "yearly" => BudgetPeriod::Yearly,
-            _ => return Err(BudgetServiceError::Validation("Invalid period".to_string())),
+    /// The period before `budget`: its own previous period when it recurs, otherwise the
+    /// latest budget for the same category, type and period that ended by the time it
+    /// starts.
+    fn fetch_previous_budget(
+        &self,
+        conn: &Connection,
+        budget: &BudgetRow,
+    ) -> BudgetResult<Option<BudgetRow>> {
+        if budget.recurring {
+            let period = conn
+                .query_row(
+                    r#"SELECT period_start, period_end, amount_cents FROM "BudgetPeriodHistory"
+                       WHERE budget_id = ? AND period_end <= ?
+                       ORDER BY period_end DESC
+                       LIMIT 1"#,
+                    params![budget.id, budget.start_date],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .optional()?;
+            if let Some((start_date, end_date, amount_cents)) = period {
+                return Ok(Some(BudgetRow {
+                    start_date,
+                    end_date,
+                    amount_cents,
+                    ..budget.clone()
+                }));
+            }
+        }
+
+        conn.query_row(
+            &format!(
+                r#"{BUDGET_SELECT}
+                WHERE b.user_id = ? AND b.id <> ? AND b.type = ? AND b.period = ?
+                  AND b.category_id IS ? AND b.start_date < b.end_date AND b.end_date <= ?
+                ORDER BY b.end_date DESC
+                LIMIT 1"#
+            ),
+            params![
+                self.user_id,
+                budget.id,
+                budget.budget_type,
+                budget.period,
+                budget.category_id,
+                budget.start_date
+            ],
+            map_budget_row,
+        )
+        .optional()
+        .map_err(|err| BudgetServiceError::Database(err.to_string()))
+    }
+
+    /// What the periods before `budget` carry into it. Walks back through predecessors
+    /// while they roll over, stopping at one whose carry is in `carries`, then settles the
+    /// chain oldest first, capping each carry with the cap of the budget receiving it.
+    /// Every period settled on the way is added to `carries`, so a listing walks each
+    /// chain once.
+    fn calculate_carry(
+        &self,
+        conn: &Connection,
+        budget: &BudgetRow,
+        carries: &mut Carries,
+    ) -> BudgetResult<i64> {
+        if let Some(&carry) = carries.get(&period_key(budget)) {
+            return Ok(carry);
+        }
+
+        let mut chain = Vec::new();
+        let mut receiver = budget.clone();
+        let mut carry = 0;
+        while receiver.rollover {
+            let Some(previous) = self.fetch_previous_budget(conn, &receiver)? else {
+                break;
+            };
+            let settled = carries.get(&period_key(&previous)).copied();
+            chain.push((
+                period_key(&receiver),
+                receiver.rollover_cap_cents,
+                previous.clone(),
+            ));
+            if let Some(settled) = settled {
+                carry = settled;
+                break;
+            }
+            receiver = previous;
+        }
+        if !receiver.rollover {
+            carries.insert(period_key(&receiver), 0);
+        }
+
+        for (key, cap, previous) in chain.into_iter().rev() {
+            let spent = self.calculate_spent(conn, &previous)?;
+            carry = cap_carry(previous.amount_cents + carry - spent, cap);
+            carries.insert(key, carry);
+        }
+        Ok(carry)
+    }
+
+    fn month_income(
+        &self,
+        conn: &Connection,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> BudgetResult<i64> {
+        let income = conn.query_row(
+            &format!(
+                r#"
+                WITH {TRANSACTION_LINES_CTE}
+                SELECT COALESCE(SUM(amount_cents), 0)
+                FROM transaction_lines
+                WHERE user_id = ?
+                  AND type = 'income'
+                  AND occurred_on >= ?
+                  AND occurred_on < ?
+                "#
+            ),
+            params![self.user_id, format_boundary(start), format_boundary(end)],
+            |row| row.get(0),
+        )?;
+        Ok(income)
+    }
+
+    /// The zero-based envelope for `category_id` in the month starting on `month_start`.
+    fn find_envelope(
+        &self,
+        conn: &Connection,
+        category_id: &str,
+        month_start: NaiveDate,
+    ) -> BudgetResult<Option<BudgetRow>> {
+        let row = conn
+            .query_row(
+                &format!(
+                    "{BUDGET_SELECT} WHERE b.user_id = ? AND b.type = 'zero_based'
+                       AND b.category_id = ? AND b.start_date = ?"
+                ),
+                params![self.user_id, category_id, format_boundary(month_start)],
+                map_budget_row,
+            )
+            .optional()?;
+        Ok(row)
+    }
+
+    /// Checks that a zero-based envelope is monthly, tied to one category and alone in its
+    /// month, and returns the month containing `start`.
+    fn zero_based_window(
+        &self,
+        conn: &Connection,
+        budget_id: Option<&str>,
+        period: &str,
+        category_id: Option<&str>,
+        recurring: bool,
+        start: NaiveDate,
+    ) -> BudgetResult<(NaiveDate, NaiveDate)> {
+        if period != BudgetPeriod::Monthly.as_str() || recurring {
+            return Err(BudgetServiceError::Validation(
+                "Zero-based budgets cover a single month".to_string(),
+            ));
+        }
+        let Some(category_id) = category_id else {
+            return Err(BudgetServiceError::Validation(
+                "Zero-based budgets need a category".to_string(),
+            ));
         };
 
+        let (start, end) = month_of(start);
+        if let Some(other) = self.find_envelope(conn, category_id, start)? {
+            if Some(other.id.as_str()) != budget_id {
+                return Err(BudgetServiceError::Validation(format!(
+                    "{} already has money assigned this month; assign to it instead",
+                    other.name
+                )));
+            }
+        }
+        Ok((start, end))
+    }
+
+    /// Fails when raising an envelope of the month from `previous_cents` to `amount_cents`
+    /// assigns more than the month's income. Lowering one is always allowed.
+    fn check_assignable(
+        &self,
+        conn: &Connection,
+        (start, end): (NaiveDate, NaiveDate),
+        budget_id: Option<&str>,
+        previous_cents: i64,
+        amount_cents: i64,
+    ) -> BudgetResult<()> {
+        if amount_cents <= previous_cents {
+            return Ok(());
+        }
+
+        let assigned_elsewhere: i64 = conn.query_row(
+            r#"SELECT COALESCE(SUM(amount_cents), 0) FROM "Budget"
+               WHERE user_id = ? AND type = 'zero_based' AND start_date = ? AND id IS NOT ?"#,
+            params![self.user_id, format_boundary(start), budget_id],
+            |row| row.get(0),
+        )?;
+        let available = self.month_income(conn, start, end)? - assigned_elsewhere;
+        if amount_cents > available {
+            return Err(BudgetServiceError::Validation(format!(
+                "Only {} cents of this month's income are left to assign, not {}",
+                available.max(0),
+                amount_cents
+            )));
+        }
+        Ok(())
+    }
+
+    /// Validates and inserts a budget on `conn`, so callers can run it in their own
+    /// transaction, and returns its id.
+    fn insert_budget(&self, conn: &Connection, input: CreateBudgetInput) -> BudgetResult<String> {
+        if input.amount_cents < 0 {
+            return Err(BudgetServiceError::Validation(
+                "Amount must be non-negative".to_string(),
+            ));
+        }
+
+        if input.alert_threshold.is_some()
+            && (input.alert_threshold.unwrap() < 0.0 || input.alert_threshold.unwrap() > 1.0)
+        {
+            return Err(BudgetServiceError::Validation(
+                "Alert threshold must be between 0 and 1".to_string(),
+            ));
+        }
+
+        validate_rollover_cap(input.rollover_cap_cents)?;
+
+        let start_date = DateTime::parse_from_rfc3339(&input.start_date)
+            .map_err(|err| BudgetServiceError::Validation(format!("Invalid start_date: {}", err)))?
+            .with_timezone(&Utc);
+
+        let end_date = DateTime::parse_from_rfc3339(&input.end_date)
+            .map_err(|err| BudgetServiceError::Validation(format!("Invalid end_date: {}", err)))?
+            .with_timezone(&Utc);
+
+        let zero_based = input.budget_type == BudgetType::ZeroBased;
+        if !input.recurring && !zero_based && end_date <= start_date {
+            return Err(BudgetServiceError::Validation(
+                "end_date must be after start_date".to_string(),
+            ));
+        }
+
+        let (start_date, end_date) = if zero_based {
+            let month = self.zero_based_window(
+                conn,
+                None,
+                input.period.as_str(),
+                input.category_id.as_deref(),
+                input.recurring,
+                start_date.date_naive(),
+            )?;
+            self.check_assignable(conn, month, None, 0, input.amount_cents)?;
+            (format_boundary(month.0), format_boundary(month.1))
+        } else if input.recurring {
+            let (start, end) = input
+                .period
+                .window(start_date.date_naive(), self.week_starts_on(conn)?);
+            (format_boundary(start), format_boundary(end))
+        } else {
+            (input.start_date, input.end_date)
+        };
+        let id = Uuid::new_v4().to_string();
+        let alert_threshold = input.alert_threshold.unwrap_or(0.8);
+
+        conn.execute(
+            r#"
+            INSERT INTO "Budget" (
+                id, user_id, name, period, type, category_id, amount_cents,
+                start_date, end_date, recurring, rollover, rollover_cap_cents, alert_threshold,
+                created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+            "#,
+            params![
+                id,
+                self.user_id,
+                input.name,
+                input.period.as_str(),
+                input.budget_type.as_str(),
+                input.category_id,
+                input.amount_cents,
+                start_date,
+                end_date,
+                input.recurring,
+                input.rollover,
+                input.rollover_cap_cents,
+                alert_threshold
+            ],
+        )
+        .map_err(|err| BudgetServiceError::Database(err.to_string()))?;
+
+        Ok(id)
+    }
+
+    fn zero_based_month(
+        &self,
+        conn: &Connection,
+        date: NaiveDate,
+    ) -> BudgetResult<ZeroBasedMonthDto> {
+        let (start, end) = month_of(date);
+        let mut stmt = conn.prepare(&format!(
+            "{BUDGET_SELECT} WHERE b.user_id = ? AND b.type = 'zero_based' AND b.start_date = ?
+             ORDER BY b.name"
+        ))?;
+        let rows = stmt
+            .query_map(
+                params![self.user_id, format_boundary(start)],
+                map_budget_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        let mut envelopes = Vec::new();
+        let mut carries = Carries::new();
+        for row in rows {
+            envelopes.push(self.row_to_dto(conn, row, &mut carries)?);
+        }
+        let income_cents = self.month_income(conn, start, end)?;
+        let assigned_cents = envelopes.iter().map(|envelope| envelope.amount_cents).sum();
+
+        Ok(ZeroBasedMonthDto {
+            month_start: format_boundary(start),
+            month_end: format_boundary(end),
+            income_cents,
+            assigned_cents,
+            to_be_assigned_cents: income_cents - assigned_cents,
+            envelopes,
+        })
+    }
+
+    fn row_to_dto(
+        &self,
+        conn: &Connection,
+        row: BudgetRow,
+        carries: &mut Carries,
+    ) -> BudgetResult<BudgetDto> {
+        let spent = self.calculate_spent(conn, &row)?;
+        let carried_over = self.calculate_carry(conn, &row, carries)?;
+        let available = row.amount_cents + carried_over;
+        let remaining = (available - spent).max(0);
+        let (progress_percent, status) =
+            self.period_progress(available, carried_over, spent, row.alert_threshold);
+
+        let period = parse_period(&row.period)?;
+
         let budget_type = match row.budget_type.as_str() {
             "envelope" => BudgetType::Envelope,
             "overall" => BudgetType::Overall,
+            "zero_based" => BudgetType::ZeroBased,
             _ => {
                 return Err(BudgetServiceError::Validation(
                     "Invalid budget type".to_string(),
@@ -208,8 +784,12 @@ impl SqliteBudgetService {
             amount_cents: row.amount_cents,
             start_date: row.start_date,
             end_date: row.end_date,
+            recurring: row.recurring,
             rollover: row.rollover,
+            rollover_cap_cents: row.rollover_cap_cents,
             alert_threshold: row.alert_threshold,
+            carried_over_cents: carried_over,
+            available_cents: available,
             spent_cents: spent,
             remaining_cents: remaining,
             progress_percent,
@@ -219,6 +799,7 @@ impl SqliteBudgetService {
     }
 }
 
+#[derive(Clone)]
 struct BudgetRow {
     id: String,
     user_id: String,
@@ -233,6 +814,131 @@ struct BudgetRow {
     rollover: bool,
     alert_threshold: f64,
     created_at: String,
+    rollover_cap_cents: Option<i64>,
+    recurring: bool,
+}
+
+fn map_budget_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<BudgetRow> {
+    Ok(BudgetRow {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        name: row.get(2)?,
+        period: row.get(3)?,
+        budget_type: row.get(4)?,
+        category_id: row.get(5)?,
+        category_name: row.get(6)?,
+        amount_cents: row.get(7)?,
+        start_date: row.get(8)?,
+        end_date: row.get(9)?,
+        rollover: row.get(10)?,
+        alert_threshold: row.get(11)?,
+        created_at: row.get(12)?,
+        rollover_cap_cents: row.get(13)?,
+        recurring: row.get(14)?,
+    })
+}
+
+/// Records `kind` for the budget's current period; `None` when it already was.
+fn insert_alert(
+    conn: &Connection,
+    budget: &BudgetDto,
+    kind: BudgetAlertKind,
+) -> BudgetResult<Option<BudgetAlertDto>> {
+    let id = Uuid::new_v4().to_string();
+    let created_at = Utc::now().to_rfc3339();
+    let inserted = conn.execute(
+        r#"INSERT OR IGNORE INTO "BudgetAlert"
+           (id, budget_id, period_start, kind, spent_cents, available_cents, progress_percent,
+            created_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
+        params![
+            id,
+            budget.id,
+            budget.start_date,
+            kind.as_str(),
+            budget.spent_cents,
+            budget.available_cents,
+            budget.progress_percent,
+            created_at
+        ],
+    )?;
+    if inserted == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(BudgetAlertDto {
+        id,
+        budget_id: budget.id.clone(),
+        budget_name: budget.name.clone(),
+        kind,
+        period_start: budget.start_date.clone(),
+        spent_cents: budget.spent_cents,
+        available_cents: budget.available_cents,
+        progress_percent: budget.progress_percent,
+        created_at,
+    }))
+}
+
+fn map_entry_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<BudgetEntryDto> {
+    Ok(BudgetEntryDto {
+        id: row.get(0)?,
+        budget_id: row.get(1)?,
+        actual_cents: row.get(2)?,
+        projected_cents: row.get(3)?,
+        snapshot_date: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+fn parse_period(value: &str) -> BudgetResult<BudgetPeriod> {
+    BudgetPeriod::parse(value)
+        .ok_or_else(|| BudgetServiceError::Validation("Invalid period".to_string()))
+}
+
+fn budget_window(budget: &BudgetRow) -> BudgetResult<(NaiveDate, NaiveDate)> {
+    let parse = |value: &str| {
+        parse_boundary(value).ok_or_else(|| {
+            BudgetServiceError::Validation(format!("Invalid date on budget {}: {value}", budget.id))
+        })
+    };
+    Ok((parse(&budget.start_date)?, parse(&budget.end_date)?))
+}
+
+/// The calendar month containing `date`, which zero-based budgets cover.
+fn month_of(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    // Only weekly periods depend on the first day of the week
+    BudgetPeriod::Monthly.window(date, 0)
+}
+
+fn parse_date(value: Option<&str>, name: &str) -> BudgetResult<NaiveDate> {
+    match value {
+        Some(value) => parse_boundary(value)
+            .ok_or_else(|| BudgetServiceError::Validation(format!("Invalid {name} date: {value}"))),
+        None => Ok(Utc::now().date_naive()),
+    }
+}
+
+/// Carry into each budget period settled so far, by budget id and period start.
+type Carries = HashMap<(String, String), i64>;
+
+fn period_key(budget: &BudgetRow) -> (String, String) {
+    (budget.id.clone(), budget.start_date.clone())
+}
+
+fn cap_carry(carry: i64, cap: Option<i64>) -> i64 {
+    match cap {
+        Some(cap) => carry.clamp(-cap, cap),
+        None => carry,
+    }
+}
+
+fn validate_rollover_cap(cap: Option<i64>) -> BudgetResult<()> {
+    if cap.is_some_and(|cap| cap < 0) {
+        return Err(BudgetServiceError::Validation(
+            "Rollover cap must be non-negative".to_string(),
+        ));
+    }
+    Ok(())
 }
 
 impl BudgetService for SqliteBudgetService {
@@ -240,61 +946,21 @@ impl BudgetService for SqliteBudgetService {
         ServiceDescriptor::new("BudgetService", "sqlite")
     }
 
-    fn list_budgets(&self) -> BudgetResult<Vec<BudgetDto>> {
-        let conn = self.connection()?;
-
-        let mut stmt = conn
-            .prepare(
-                r#"
-                SELECT 
-                    b.id,
-                    b.user_id,
-                    b.name,
-                    b.period,
-                    b.type,
-                    b.category_id,
-                    c.name as category_name,
-                    b.amount_cents,
-                    b.start_date,
-                    b.end_date,
-                    b.rollover,
-                    b.alert_threshold,
-                    b.created_at
-                FROM "Budget" b
-                LEFT JOIN "Category" c ON b.category_id = c.id
-                WHERE b.user_id = ?
-                ORDER BY b.created_at DESC
-                "#,
-            )
-            .map_err(|err| BudgetServiceError::Database(err.to_string()))?;
-
-        let rows = stmt
-            .query_map(params![self.user_id], |row| {
-                Ok(BudgetRow {
-                    id: row.get(0)?,
-                    user_id: row.get(1)?,
-                    name: row.get(2)?,
-                    period: row.get(3)?,
-                    budget_type: row.get(4)?,
-                    category_id: row.get(5)?,
-                    category_name: row.get(6)?,
-                    amount_cents: row.get(7)?,
-                    start_date: row.get(8)?,
-                    end_date: row.get(9)?,
-                    rollover: row.get(10)?,
-                    alert_threshold: row.get(11)?,
-                    created_at: row.get(12)?,
+    fn list_budgets(&self, as_of: Option<&str>) -> BudgetResult<Vec<BudgetDto>> {
+        let as_of = as_of
+            .map(|value| {
+                parse_boundary(value).ok_or_else(|| {
+                    BudgetServiceError::Validation(format!("Invalid as_of date: {value}"))
                 })
             })
-            .map_err(|err| BudgetServiceError::Database(err.to_string()))?
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|err| BudgetServiceError::Database(err.to_string()))?;
-
-        drop(stmt);
+            .transpose()?;
+        let conn = self.connection()?;
+        self.renew_budgets(&conn, Utc::now().date_naive())?;
 
         let mut budgets = Vec::new();
-        for row in rows {
-            budgets.push(self.row_to_dto(&conn, row)?);
+        let mut carries = Carries::new();
+        for row in self.budget_rows(&conn, as_of)? {
+            budgets.push(self.row_to_dto(&conn, row, &mut carries)?);
         }
 
         Ok(budgets)
@@ -302,74 +968,33 @@ impl BudgetService for SqliteBudgetService {
 
     fn get_budget(&self, id: &str) -> BudgetResult<BudgetDto> {
         let conn = self.connection()?;
+        self.renew_budgets(&conn, Utc::now().date_naive())?;
         let row = self.fetch_budget_row(&conn, id)?;
-        self.row_to_dto(&conn, row)
+        self.row_to_dto(&conn, row, &mut Carries::new())
     }
 
     fn create_budget(&self, input: CreateBudgetInput) -> BudgetResult<BudgetDto> {
-        if input.amount_cents < 0 {
-            return Err(BudgetServiceError::Validation(
-                "Amount must be non-negative".to_string(),
-            ));
-        }
-
-        if input.alert_threshold.is_some()
-            && (input.alert_threshold.unwrap() < 0.0 || input.alert_threshold.unwrap() > 1.0)
-        {
-            return Err(BudgetServiceError::Validation(
-                "Alert threshold must be between 0 and 1".to_string(),
-            ));
-        }
-
-        let start_date = DateTime::parse_from_rfc3339(&input.start_date)
-            .map_err(|err| BudgetServiceError::Validation(format!("Invalid start_date: {}", err)))?
-            .with_timezone(&Utc);
-
-        let end_date = DateTime::parse_from_rfc3339(&input.end_date)
-            .map_err(|err| BudgetServiceError::Validation(format!("Invalid end_date: {}", err)))?
-            .with_timezone(&Utc);
-
-        if end_date <= start_date {
-            return Err(BudgetServiceError::Validation(
-                "end_date must be after start_date".to_string(),
-            ));
-        }
-
         let conn = self.connection()?;
-        let id = Uuid::new_v4().to_string();
-        let alert_threshold = input.alert_threshold.unwrap_or(0.8);
-
-        conn.execute(
-            r#"
-            INSERT INTO "Budget" (
-                id, user_id, name, period, type, category_id, amount_cents,
-                start_date, end_date, rollover, alert_threshold, created_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
-            "#,
-            params![
-                id,
-                self.user_id,
-                input.name,
-                input.period.as_str(),
-                input.budget_type.as_str(),
-                input.category_id,
-                input.amount_cents,
-                input.start_date,
-                input.end_date,
-                input.rollover,
-                alert_threshold
-            ],
-        )
-        .map_err(|err| BudgetServiceError::Database(err.to_string()))?;
-
+        let tx = conn.unchecked_transaction()?;
+        let id = self.insert_budget(&tx, input)?;
+        tx.commit()?;
         self.get_budget(&id)
     }
 
     fn update_budget(&self, input: UpdateBudgetInput) -> BudgetResult<BudgetDto> {
         let conn = self.connection()?;
-        let existing = self.fetch_budget_row(&conn, &input.id)?;
+        // The envelope check and the write share a transaction, as in `assign_to_envelope`
+        let tx = conn.unchecked_transaction()?;
+        let existing = self.fetch_budget_row(&tx, &input.id)?;
+        let existing_envelope = (existing.budget_type == BudgetType::ZeroBased.as_str())
+            .then(|| (existing.start_date.clone(), existing.amount_cents));
 
         let name = input.name.unwrap_or(existing.name);
+        let realign = input.period.is_some()
+            || input.start_date.is_some()
+            || input
+                .recurring
+                .is_some_and(|recurring| recurring && !existing.recurring);
         let period = input
             .period
             .map(|p| p.as_str().to_string())
@@ -380,9 +1005,21 @@ impl BudgetService for SqliteBudgetService {
             .unwrap_or(existing.budget_type);
         let category_id = input.category_id.or(existing.category_id);
         let amount_cents = input.amount_cents.unwrap_or(existing.amount_cents);
-        let start_date = input.start_date.unwrap_or(existing.start_date);
-        let end_date = input.end_date.unwrap_or(existing.end_date);
+        let mut start_date = input.start_date.unwrap_or(existing.start_date);
+        let mut end_date = input.end_date.unwrap_or(existing.end_date);
+        let recurring = input.recurring.unwrap_or(existing.recurring);
+        if recurring && realign {
+            let start = parse_boundary(&start_date).ok_or_else(|| {
+                BudgetServiceError::Validation(format!("Invalid start_date: {start_date}"))
+            })?;
+            let (start, end) = parse_period(&period)?.window(start, self.week_starts_on(&tx)?);
+            start_date = format_boundary(start);
+            end_date = format_boundary(end);
+        }
         let rollover = input.rollover.unwrap_or(existing.rollover);
+        let rollover_cap_cents = input
+            .rollover_cap_cents
+            .unwrap_or(existing.rollover_cap_cents);
         let alert_threshold = input.alert_threshold.unwrap_or(existing.alert_threshold);
 
         if amount_cents < 0 {
@@ -391,17 +1028,41 @@ impl BudgetService for SqliteBudgetService {
             ));
         }
 
+        if budget_type == BudgetType::ZeroBased.as_str() {
+            let start = parse_boundary(&start_date).ok_or_else(|| {
+                BudgetServiceError::Validation(format!("Invalid start_date: {start_date}"))
+            })?;
+            let month = self.zero_based_window(
+                &tx,
+                Some(&input.id),
+                &period,
+                category_id.as_deref(),
+                recurring,
+                start,
+            )?;
+            start_date = format_boundary(month.0);
+            end_date = format_boundary(month.1);
+            // Money already assigned to this envelope in the month stays assigned
+            let previous_cents = existing_envelope
+                .filter(|(month_start, _)| *month_start == start_date)
+                .map_or(0, |(_, amount_cents)| amount_cents);
+            self.check_assignable(&tx, month, Some(&input.id), previous_cents, amount_cents)?;
+        }
+
         if !(0.0..=1.0).contains(&alert_threshold) {
             return Err(BudgetServiceError::Validation(
                 "Alert threshold must be between 0 and 1".to_string(),
             ));
         }
 
-        conn.execute(
+        validate_rollover_cap(rollover_cap_cents)?;
+
+        tx.execute(
             r#"
             UPDATE "Budget"
             SET name = ?, period = ?, type = ?, category_id = ?, amount_cents = ?,
-                start_date = ?, end_date = ?, rollover = ?, alert_threshold = ?
+                start_date = ?, end_date = ?, recurring = ?, rollover = ?,
+                rollover_cap_cents = ?, alert_threshold = ?
             WHERE id = ? AND user_id = ?
             "#,
             params![
@@ -412,13 +1073,16 @@ impl BudgetService for SqliteBudgetService {
                 amount_cents,
                 start_date,
                 end_date,
+                recurring,
                 rollover,
+                rollover_cap_cents,
                 alert_threshold,
                 input.id,
                 self.user_id
             ],
         )
         .map_err(|err| BudgetServiceError::Database(err.to_string()))?;
+        tx.commit()?;
 
         self.get_budget(&input.id)
     }
@@ -477,32 +1141,358 @@ impl BudgetService for SqliteBudgetService {
                 WHERE budget_id = ? AND snapshot_date = ?
                 "#,
                 params![input.budget_id, input.snapshot_date],
-                |row| {
-                    Ok(BudgetEntryDto {
-                        id: row.get(0)?,
-                        budget_id: row.get(1)?,
-                        actual_cents: row.get(2)?,
-                        projected_cents: row.get(3)?,
-                        snapshot_date: row.get(4)?,
-                        created_at: row.get(5)?,
-                    })
-                },
+                map_entry_row,
             )
             .map_err(|err| BudgetServiceError::Database(err.to_string()))?;
 
         Ok(entry)
     }
 
+    fn evaluate_alerts(&self, today: NaiveDate) -> BudgetResult<Vec<BudgetAlertDto>> {
+        let budgets = self.list_budgets(Some(&today.to_string()))?;
+        let conn = self.connection()?;
+
+        let mut alerts = Vec::new();
+        for budget in budgets {
+            let kind = match budget.status {
+                BudgetStatus::Normal => continue,
+                BudgetStatus::AtRisk => BudgetAlertKind::Threshold,
+                BudgetStatus::Over => BudgetAlertKind::Over,
+            };
+            if kind == BudgetAlertKind::Over {
+                // Going straight past the threshold counts as crossing it too, so dropping
+                // back below 100% later doesn't raise a second alert
+                insert_alert(&conn, &budget, BudgetAlertKind::Threshold)?;
+            }
+            if let Some(alert) = insert_alert(&conn, &budget, kind)? {
+                alerts.push(alert);
+            }
+        }
+
+        Ok(alerts)
+    }
+
+    fn list_budget_alerts(&self, limit: u32) -> BudgetResult<Vec<BudgetAlertDto>> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT a.id, a.budget_id, b.name, a.kind, a.period_start, a.spent_cents,
+                   a.available_cents, a.progress_percent, a.created_at
+            FROM "BudgetAlert" a
+            JOIN "Budget" b ON b.id = a.budget_id
+            WHERE b.user_id = ?
+            ORDER BY a.created_at DESC, a.rowid DESC
+            LIMIT ?
+            "#,
+        )?;
+        let alerts = stmt
+            .query_map(params![self.user_id, limit], |row| {
+                let kind: String = row.get(3)?;
+                Ok(BudgetAlertDto {
+                    id: row.get(0)?,
+                    budget_id: row.get(1)?,
+                    budget_name: row.get(2)?,
+                    kind: if kind == "over" {
+                        BudgetAlertKind::Over
+                    } else {
+                        BudgetAlertKind::Threshold
+                    },
+                    period_start: row.get(4)?,
+                    spent_cents: row.get(5)?,
+                    available_cents: row.get(6)?,
+                    progress_percent: row.get(7)?,
+                    created_at: row.get(8)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(alerts)
+    }
+
+    fn record_daily_snapshots(&self, today: NaiveDate) -> BudgetResult<usize> {
+        let conn = self.connection()?;
+        self.renew_budgets(&conn, today)?;
+
+        let mut written = 0;
+        for budget in self.budget_rows(&conn, Some(today))? {
+            let (start, end) = budget_window(&budget)?;
+            let last: Option<String> = conn.query_row(
+                r#"SELECT MAX(snapshot_date) FROM "BudgetEntry"
+                   WHERE budget_id = ? AND snapshot_date >= ? AND snapshot_date < ?"#,
+                params![budget.id, format_boundary(start), format_boundary(end)],
+                |row| row.get(0),
+            )?;
+            // Today's snapshot is rewritten on every run so it keeps up with new spending
+            let mut day = last
+                .as_deref()
+                .and_then(parse_boundary)
+                .map_or(start, |last| last + Duration::days(1))
+                .min(today);
+
+            while day <= today {
+                let until = (day + Duration::days(1)).min(end);
+                let spent = self.calculate_spent(
+                    &conn,
+                    &BudgetRow {
+                        end_date: format_boundary(until),
+                        ..budget.clone()
+                    },
+                )?;
+                conn.execute(
+                    r#"
+                    INSERT INTO "BudgetEntry" (id, budget_id, actual_cents, projected_cents, snapshot_date, created_at)
+                    VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+                    ON CONFLICT(budget_id, snapshot_date) DO UPDATE SET
+                        actual_cents = excluded.actual_cents,
+                        projected_cents = excluded.projected_cents
+                    "#,
+                    params![
+                        Uuid::new_v4().to_string(),
+                        budget.id,
+                        spent,
+                        projected_spend(spent, start, end, day),
+                        format_boundary(day)
+                    ],
+                )?;
+                written += 1;
+                day += Duration::days(1);
+            }
+        }
+
+        Ok(written)
+    }
+
+    fn list_budget_snapshots(
+        &self,
+        input: ListBudgetSnapshotsInput,
+    ) -> BudgetResult<Vec<BudgetEntryDto>> {
+        let bound = |value: Option<String>, name: &str| {
+            value
+                .map(|value| {
+                    parse_boundary(&value).ok_or_else(|| {
+                        BudgetServiceError::Validation(format!("Invalid {name} date: {value}"))
+                    })
+                })
+                .transpose()
+        };
+        let from = bound(input.from, "from")?.map(format_boundary);
+        let until = bound(input.to, "to")?.map(|to| format_boundary(to + Duration::days(1)));
+
+        let conn = self.connection()?;
+        self.fetch_budget_row(&conn, &input.budget_id)?;
+        self.snapshots_between(&conn, &input.budget_id, from.as_deref(), until.as_deref())
+    }
+
+    fn get_period_summary(
+        &self,
+        budget_id: &str,
+        as_of: Option<&str>,
+    ) -> BudgetResult<BudgetPeriodSummaryDto> {
+        let today = Utc::now().date_naive();
+        let as_of = match as_of {
+            Some(value) => parse_boundary(value).ok_or_else(|| {
+                BudgetServiceError::Validation(format!("Invalid as_of date: {value}"))
+            })?,
+            None => today,
+        };
+
+        let conn = self.connection()?;
+        self.renew_budgets(&conn, today)?;
+        let row = self.fetch_budget_row(&conn, budget_id)?;
+        let week_starts_on = self.week_starts_on(&conn)?;
+        let row = self
+            .budget_as_of(&conn, row, as_of, week_starts_on)?
+            .ok_or_else(|| {
+                BudgetServiceError::NotFound(format!(
+                    "Budget {budget_id} has no period containing {as_of}"
+                ))
+            })?;
+
+        let (start, end) = budget_window(&row)?;
+        let snapshots = self.snapshots_between(
+            &conn,
+            budget_id,
+            Some(&format_boundary(start)),
+            Some(&format_boundary(end)),
+        )?;
+        let budget = self.row_to_dto(&conn, row, &mut Carries::new())?;
+
+        let days_total = (end - start).num_days().max(1);
+        let complete = today >= end;
+        let days_elapsed = if today < start {
+            0
+        } else {
+            ((today - start).num_days() + 1).min(days_total)
+        };
+        let projected_cents = match days_elapsed {
+            0 => 0,
+            _ if complete => budget.spent_cents,
+            _ => projected_spend(budget.spent_cents, start, end, today),
+        };
+
+        Ok(BudgetPeriodSummaryDto {
+            days_total,
+            days_elapsed,
+            complete,
+            average_daily_cents: budget.spent_cents / days_elapsed.max(1),
+            projected_cents,
+            variance_cents: budget.available_cents - projected_cents,
+            snapshots,
+            budget,
+        })
+    }
+
+    fn get_zero_based_month(&self, month: Option<&str>) -> BudgetResult<ZeroBasedMonthDto> {
+        let month = parse_date(month, "month")?;
+        let conn = self.connection()?;
+        self.zero_based_month(&conn, month)
+    }
+
+    fn assign_to_envelope(&self, input: AssignToEnvelopeInput) -> BudgetResult<ZeroBasedMonthDto> {
+        if input.amount_cents == 0 {
+            return Err(BudgetServiceError::Validation(
+                "Amount must not be zero".to_string(),
+            ));
+        }
+        let month = parse_date(input.month.as_deref(), "month")?;
+        let (start, end) = month_of(month);
+        let conn = self.connection()?;
+        // Checking the month's income and writing the envelope in one transaction keeps a
+        // concurrent assignment from spending the same money
+        let tx = conn.unchecked_transaction()?;
+
+        match self.find_envelope(&tx, &input.category_id, start)? {
+            Some(envelope) => {
+                let amount_cents = envelope.amount_cents + input.amount_cents;
+                if amount_cents < 0 {
+                    return Err(BudgetServiceError::Validation(format!(
+                        "{} only holds {} cents",
+                        envelope.name, envelope.amount_cents
+                    )));
+                }
+                self.check_assignable(
+                    &tx,
+                    (start, end),
+                    Some(&envelope.id),
+                    envelope.amount_cents,
+                    amount_cents,
+                )?;
+                tx.execute(
+                    r#"UPDATE "Budget" SET amount_cents = ? WHERE id = ? AND user_id = ?"#,
+                    params![amount_cents, envelope.id, self.user_id],
+                )?;
+            }
+            None => {
+                if input.amount_cents < 0 {
+                    return Err(BudgetServiceError::Validation(
+                        "Nothing is assigned to this category in that month".to_string(),
+                    ));
+                }
+                let name: String = tx
+                    .query_row(
+                        r#"SELECT name FROM "Category"
+                           WHERE id = ? AND user_id = ? AND type = 'expense'"#,
+                        params![input.category_id, self.user_id],
+                        |row| row.get(0),
+                    )
+                    .optional()?
+                    .ok_or_else(|| {
+                        BudgetServiceError::NotFound(format!(
+                            "Expense category {} not found",
+                            input.category_id
+                        ))
+                    })?;
+                self.insert_budget(
+                    &tx,
+                    CreateBudgetInput {
+                        name,
+                        period: BudgetPeriod::Monthly,
+                        budget_type: BudgetType::ZeroBased,
+                        category_id: Some(input.category_id),
+                        amount_cents: input.amount_cents,
+                        start_date: format_boundary(start),
+                        end_date: format_boundary(end),
+                        recurring: false,
+                        rollover: false,
+                        rollover_cap_cents: None,
+                        alert_threshold: None,
+                    },
+                )?;
+            }
+        }
+        tx.commit()?;
+
+        self.zero_based_month(&conn, month)
+    }
+
+    fn move_envelope_funds(
+        &self,
+        input: MoveEnvelopeFundsInput,
+    ) -> BudgetResult<ZeroBasedMonthDto> {
+        if input.amount_cents <= 0 {
+            return Err(BudgetServiceError::Validation(
+                "Amount must be positive".to_string(),
+            ));
+        }
+        if input.from_budget_id == input.to_budget_id {
+            return Err(BudgetServiceError::Validation(
+                "Choose two different envelopes".to_string(),
+            ));
+        }
+
+        let conn = self.connection()?;
+        // Read the balances in the transaction that moves them, so a concurrent change
+        // can't leave the source envelope below zero
+        let tx = conn.unchecked_transaction()?;
+        let from = self.fetch_budget_row(&tx, &input.from_budget_id)?;
+        let to = self.fetch_budget_row(&tx, &input.to_budget_id)?;
+        for envelope in [&from, &to] {
+            if envelope.budget_type != BudgetType::ZeroBased.as_str() {
+                return Err(BudgetServiceError::Validation(format!(
+                    "{} is not a zero-based budget",
+                    envelope.name
+                )));
+            }
+        }
+        if from.start_date != to.start_date {
+            return Err(BudgetServiceError::Validation(
+                "Money can only move between envelopes of the same month".to_string(),
+            ));
+        }
+        if from.amount_cents < input.amount_cents {
+            return Err(BudgetServiceError::Validation(format!(
+                "{} only holds {} cents",
+                from.name, from.amount_cents
+            )));
+        }
+
+        for (id, delta) in [
+            (&from.id, -input.amount_cents),
+            (&to.id, input.amount_cents),
+        ] {
+            tx.execute(
+                r#"UPDATE "Budget" SET amount_cents = amount_cents + ? WHERE id = ? AND user_id = ?"#,
+                params![delta, id, self.user_id],
+            )?;
+        }
+        tx.commit()?;
+
+        let (month, _) = budget_window(&from)?;
+        self.zero_based_month(&conn, month)
+    }
+
     fn calculate_budget_progress(
         &self,
         budget_id: &str,
     ) -> BudgetResult<(i64, i64, f64, BudgetStatus)> {
         let conn = self.connection()?;
+        self.renew_budgets(&conn, Utc::now().date_naive())?;
         let budget = self.fetch_budget_row(&conn, budget_id)?;
-        let spent = self.calculate_spent(&conn, budget_id)?;
-        let remaining = (budget.amount_cents - spent).max(0);
+        let spent = self.calculate_spent(&conn, &budget)?;
+        let carried_over = self.calculate_carry(&conn, &budget, &mut Carries::new())?;
+        let available = budget.amount_cents + carried_over;
+        let remaining = (available - spent).max(0);
         let (progress_percent, status) =
-            self.calculate_progress(budget.amount_cents, spent, budget.alert_threshold);
+            self.period_progress(available, carried_over, spent, budget.alert_threshold);
 
         Ok((spent, remaining, progress_percent, status))
     }
@@ -521,6 +1511,61 @@ fn parse_categories(value: Option<String>) -> Vec<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::transactions::{
+        CreateTransactionInput, SqliteTransactionService, TransactionKind, TransactionService,
+    };
+
+    /// A transaction service and a budget service sharing a fresh seeded database.
+    fn setup() -> (SqliteTransactionService, SqliteBudgetService) {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let transactions =
+            SqliteTransactionService::new(path.clone(), None, Some("seed-user".into())).unwrap();
+        let service = SqliteBudgetService::new(path, None, Some("seed-user".into())).unwrap();
+        (transactions, service)
+    }
+
+    fn record(
+        transactions: &SqliteTransactionService,
+        kind: TransactionKind,
+        category_id: &str,
+        amount_cents: i64,
+        occurred_on: &str,
+    ) {
+        transactions
+            .create_transaction(CreateTransactionInput {
+                id: None,
+                account_id: "acct-default".into(),
+                category_id: Some(category_id.into()),
+                kind,
+                amount_cents,
+                currency: "USD".into(),
+                occurred_on: occurred_on.into(),
+                notes: None,
+                tags: None,
+                cleared: false,
+                goal_id: None,
+                transfer_account_id: None,
+                splits: Vec::new(),
+                recurrence_id: None,
+                exchange_rate: None,
+                payee: None,
+                external_id: None,
+            })
+            .unwrap();
+    }
+
+    /// Records a food expense.
+    fn spend(transactions: &SqliteTransactionService, amount_cents: i64, occurred_on: &str) {
+        record(
+            transactions,
+            TransactionKind::Expense,
+            "cat-food",
+            amount_cents,
+            occurred_on,
+        );
+    }
 
     #[test]
     fn test_calculate_progress_normal() {
@@ -573,4 +1618,362 @@ mod tests {
         assert_eq!(progress, 0.0);
         assert_eq!(status, BudgetStatus::Normal);
     }
+
+    #[test]
+    fn rollover_carries_remainders_forward_up_to_the_cap() {
+        let (transactions, service) = setup();
+
+        let budget = |month: u32, rollover_cap_cents: Option<i64>| {
+            service
+                .create_budget(CreateBudgetInput {
+                    name: format!("Food {month}"),
+                    period: BudgetPeriod::Monthly,
+                    budget_type: BudgetType::Envelope,
+                    category_id: Some("cat-food".into()),
+                    amount_cents: 10_000,
+                    start_date: format!("2025-{month:02}-01T00:00:00Z"),
+                    end_date: format!("2025-{:02}-01T00:00:00Z", month + 1),
+                    recurring: false,
+                    rollover: true,
+                    rollover_cap_cents,
+                    alert_threshold: None,
+                })
+                .unwrap()
+        };
+
+        // January leaves 40.00 unspent, February overspends its 140.00 by 30.00
+        spend(&transactions, 6_000, "2025-01-15T12:00:00Z");
+        spend(&transactions, 17_000, "2025-02-15T12:00:00Z");
+        let january = budget(1, None);
+        let february = budget(2, None);
+        let march = budget(3, Some(2_000));
+
+        assert_eq!(january.carried_over_cents, 0);
+        assert_eq!(february.carried_over_cents, 4_000);
+        assert_eq!(february.available_cents, 14_000);
+        assert_eq!(february.remaining_cents, 0);
+        assert_eq!(february.status, BudgetStatus::Over);
+        assert_eq!(march.carried_over_cents, -2_000);
+        assert_eq!(march.available_cents, 8_000);
+
+        let march = service
+            .update_budget(UpdateBudgetInput {
+                id: march.id,
+                name: None,
+                period: None,
+                budget_type: None,
+                category_id: None,
+                amount_cents: None,
+                start_date: None,
+                end_date: None,
+                recurring: None,
+                rollover: None,
+                rollover_cap_cents: Some(None),
+                alert_threshold: None,
+            })
+            .unwrap();
+        assert_eq!(march.rollover_cap_cents, None);
+        assert_eq!(march.carried_over_cents, -3_000);
+    }
+
+    #[test]
+    fn spending_after_a_carry_leaves_nothing_available_is_over() {
+        let (transactions, service) = setup();
+
+        // January overspends its 100.00 by exactly February's 100.00
+        spend(&transactions, 20_000, "2025-01-15T12:00:00Z");
+        spend(&transactions, 500, "2025-02-15T12:00:00Z");
+        let budgets = [1, 2].map(|month: u32| {
+            service
+                .create_budget(CreateBudgetInput {
+                    name: format!("Food {month}"),
+                    period: BudgetPeriod::Monthly,
+                    budget_type: BudgetType::Envelope,
+                    category_id: Some("cat-food".into()),
+                    amount_cents: 10_000,
+                    start_date: format!("2025-{month:02}-01T00:00:00Z"),
+                    end_date: format!("2025-{:02}-01T00:00:00Z", month + 1),
+                    recurring: false,
+                    rollover: true,
+                    rollover_cap_cents: None,
+                    alert_threshold: None,
+                })
+                .unwrap()
+        });
+
+        let february = &budgets[1];
+        assert_eq!(february.carried_over_cents, -10_000);
+        assert_eq!(february.available_cents, 0);
+        assert_eq!(february.progress_percent, 100.0);
+        assert_eq!(february.status, BudgetStatus::Over);
+    }
+
+    #[test]
+    fn recurring_budgets_renew_and_keep_past_periods() {
+        let (_, service) = setup();
+
+        let (this_month, next_month) = BudgetPeriod::Monthly.window(Utc::now().date_naive(), 1);
+        let (last_month, _) = BudgetPeriod::Monthly.window(this_month.pred_opt().unwrap(), 1);
+        let (first_month, _) = BudgetPeriod::Monthly.window(last_month.pred_opt().unwrap(), 1);
+
+        let created = service
+            .create_budget(CreateBudgetInput {
+                name: "Food".into(),
+                period: BudgetPeriod::Monthly,
+                budget_type: BudgetType::Envelope,
+                category_id: Some("cat-food".into()),
+                amount_cents: 10_000,
+                start_date: format!("{}T09:30:00Z", first_month.format("%Y-%m-%d")),
+                end_date: format!("{}T09:30:00Z", first_month.format("%Y-%m-%d")),
+                recurring: true,
+                rollover: true,
+                rollover_cap_cents: None,
+                alert_threshold: None,
+            })
+            .unwrap();
+        assert_eq!(created.start_date, format_boundary(this_month));
+        assert_eq!(created.end_date, format_boundary(next_month));
+        // Two untouched 100.00 periods carry in
+        assert_eq!(created.carried_over_cents, 20_000);
+
+        service
+            .update_budget(UpdateBudgetInput {
+                id: created.id.clone(),
+                name: None,
+                period: None,
+                budget_type: None,
+                category_id: None,
+                amount_cents: Some(15_000),
+                start_date: None,
+                end_date: None,
+                recurring: None,
+                rollover: None,
+                rollover_cap_cents: None,
+                alert_threshold: None,
+            })
+            .unwrap();
+
+        let current = service.list_budgets(None).unwrap();
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].amount_cents, 15_000);
+
+        let past = service
+            .list_budgets(Some(&last_month.format("%Y-%m-%d").to_string()))
+            .unwrap();
+        assert_eq!(past.len(), 1);
+        assert_eq!(past[0].id, created.id);
+        assert_eq!(past[0].start_date, format_boundary(last_month));
+        assert_eq!(past[0].amount_cents, 10_000);
+        assert_eq!(past[0].carried_over_cents, 10_000);
+
+        let before = service
+            .list_budgets(Some(&first_month.pred_opt().unwrap().to_string()))
+            .unwrap();
+        assert!(before.is_empty());
+    }
+
+    #[test]
+    fn alerts_fire_once_per_period_and_kind() {
+        let (transactions, service) = setup();
+
+        let now = Utc::now().to_rfc3339();
+        let budget = service
+            .create_budget(CreateBudgetInput {
+                name: "Food".into(),
+                period: BudgetPeriod::Monthly,
+                budget_type: BudgetType::Envelope,
+                category_id: Some("cat-food".into()),
+                amount_cents: 10_000,
+                start_date: Utc::now().to_rfc3339(),
+                end_date: Utc::now().to_rfc3339(),
+                recurring: true,
+                rollover: false,
+                rollover_cap_cents: None,
+                alert_threshold: Some(0.8),
+            })
+            .unwrap();
+
+        let today = Utc::now().date_naive();
+        spend(&transactions, 5_000, &now);
+        assert!(service.evaluate_alerts(today).unwrap().is_empty());
+
+        spend(&transactions, 4_000, &now);
+        let alerts = service.evaluate_alerts(today).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, BudgetAlertKind::Threshold);
+        assert_eq!(alerts[0].period_start, budget.start_date);
+        assert!(service.evaluate_alerts(today).unwrap().is_empty());
+
+        spend(&transactions, 2_000, &now);
+        let alerts = service.evaluate_alerts(today).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, BudgetAlertKind::Over);
+        assert_eq!(alerts[0].spent_cents, 11_000);
+        assert!(service.evaluate_alerts(today).unwrap().is_empty());
+
+        let recorded = service.list_budget_alerts(10).unwrap();
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(recorded[0].kind, BudgetAlertKind::Over);
+        assert_eq!(recorded[0].budget_name, "Food");
+    }
+
+    #[test]
+    fn daily_snapshots_fill_the_period_and_feed_the_summary() {
+        let (transactions, service) = setup();
+
+        let today = Utc::now().date_naive();
+        let start = today - Duration::days(4);
+        let noon = |day: NaiveDate| format!("{}T12:00:00Z", day.format("%Y-%m-%d"));
+        let budget = service
+            .create_budget(CreateBudgetInput {
+                name: "Food".into(),
+                period: BudgetPeriod::Monthly,
+                budget_type: BudgetType::Envelope,
+                category_id: Some("cat-food".into()),
+                amount_cents: 30_000,
+                start_date: format_boundary(start),
+                end_date: format_boundary(start + Duration::days(30)),
+                recurring: false,
+                rollover: false,
+                rollover_cap_cents: None,
+                alert_threshold: None,
+            })
+            .unwrap();
+        spend(&transactions, 3_000, &noon(start));
+        spend(&transactions, 1_000, &noon(today));
+
+        assert_eq!(service.record_daily_snapshots(today).unwrap(), 5);
+        // Only today's snapshot is refreshed on later runs
+        assert_eq!(service.record_daily_snapshots(today).unwrap(), 1);
+
+        let snapshots = service
+            .list_budget_snapshots(ListBudgetSnapshotsInput {
+                budget_id: budget.id.clone(),
+                from: None,
+                to: None,
+            })
+            .unwrap();
+        assert_eq!(snapshots.len(), 5);
+        assert_eq!(snapshots[0].snapshot_date, format_boundary(start));
+        assert_eq!(snapshots[0].actual_cents, 3_000);
+        assert_eq!(snapshots[0].projected_cents, 90_000);
+        assert_eq!(snapshots[4].actual_cents, 4_000);
+        assert_eq!(snapshots[4].projected_cents, 24_000);
+
+        let summary = service.get_period_summary(&budget.id, None).unwrap();
+        assert_eq!(summary.days_total, 30);
+        assert_eq!(summary.days_elapsed, 5);
+        assert!(!summary.complete);
+        assert_eq!(summary.average_daily_cents, 800);
+        assert_eq!(summary.projected_cents, 24_000);
+        assert_eq!(summary.variance_cents, 6_000);
+        assert_eq!(summary.snapshots.len(), 5);
+    }
+
+    #[test]
+    fn zero_based_assignments_stay_within_the_months_income() {
+        let (transactions, service) = setup();
+
+        let assign = |category_id: &str, amount_cents: i64| {
+            service.assign_to_envelope(AssignToEnvelopeInput {
+                category_id: category_id.into(),
+                month: Some("2025-04-15".into()),
+                amount_cents,
+            })
+        };
+        let is_validation = |result: BudgetResult<ZeroBasedMonthDto>| {
+            matches!(result, Err(BudgetServiceError::Validation(_)))
+        };
+
+        record(
+            &transactions,
+            TransactionKind::Income,
+            "cat-income",
+            30_000,
+            "2025-04-03T12:00:00Z",
+        );
+        spend(&transactions, 4_000, "2025-04-03T12:00:00Z");
+
+        let month = assign("cat-food", 20_000).unwrap();
+        assert_eq!(month.month_start, "2025-04-01T00:00:00Z");
+        assert_eq!(month.income_cents, 30_000);
+        assert_eq!(month.to_be_assigned_cents, 10_000);
+        let food = month.envelopes[0].clone();
+        assert_eq!(food.budget_type, BudgetType::ZeroBased);
+        assert_eq!(food.spent_cents, 4_000);
+
+        assert!(is_validation(assign("cat-rent", 15_000)));
+        let month = assign("cat-rent", 10_000).unwrap();
+        assert_eq!(month.assigned_cents, 30_000);
+        assert_eq!(month.to_be_assigned_cents, 0);
+        let rent = month
+            .envelopes
+            .iter()
+            .find(|envelope| envelope.id != food.id)
+            .unwrap()
+            .clone();
+
+        let move_funds = |from: &str, to: &str, amount_cents: i64| {
+            service.move_envelope_funds(MoveEnvelopeFundsInput {
+                from_budget_id: from.into(),
+                to_budget_id: to.into(),
+                amount_cents,
+            })
+        };
+        let month = move_funds(&food.id, &rent.id, 5_000).unwrap();
+        assert_eq!(month.to_be_assigned_cents, 0);
+        let amounts: Vec<i64> = month.envelopes.iter().map(|e| e.amount_cents).collect();
+        assert!(amounts.contains(&15_000) && amounts.len() == 2);
+        assert!(is_validation(move_funds(&food.id, &rent.id, 20_000)));
+
+        // Unassigning returns money to the pool
+        let month = assign("cat-food", -5_000).unwrap();
+        assert_eq!(month.to_be_assigned_cents, 5_000);
+        assert!(is_validation(assign("cat-food", -20_000)));
+
+        let update_amount = |amount_cents: i64| {
+            service.update_budget(UpdateBudgetInput {
+                id: rent.id.clone(),
+                name: None,
+                period: None,
+                budget_type: None,
+                category_id: None,
+                amount_cents: Some(amount_cents),
+                start_date: None,
+                end_date: None,
+                recurring: None,
+                rollover: None,
+                rollover_cap_cents: None,
+                alert_threshold: None,
+            })
+        };
+        assert!(matches!(
+            update_amount(25_000),
+            Err(BudgetServiceError::Validation(_))
+        ));
+        assert_eq!(update_amount(20_000).unwrap().amount_cents, 20_000);
+
+        let duplicate = service.create_budget(CreateBudgetInput {
+            name: "Food again".into(),
+            period: BudgetPeriod::Monthly,
+            budget_type: BudgetType::ZeroBased,
+            category_id: Some("cat-food".into()),
+            amount_cents: 0,
+            start_date: "2025-04-20T00:00:00Z".into(),
+            end_date: "2025-04-20T00:00:00Z".into(),
+            recurring: false,
+            rollover: false,
+            rollover_cap_cents: None,
+            alert_threshold: None,
+        });
+        assert!(matches!(duplicate, Err(BudgetServiceError::Validation(_))));
+        assert!(matches!(
+            assign("cat-income", 1_000),
+            Err(BudgetServiceError::NotFound(_))
+        ));
+
+        let may = service.get_zero_based_month(Some("2025-05-10")).unwrap();
+        assert_eq!(may.income_cents, 0);
+        assert!(may.envelopes.is_empty());
+    }
 }
@@ -0,0 +1,226 @@
+mod rates_file;
+mod sqlite;
+
+pub use rates_file::{parse_rates, ParsedRate};
+pub use sqlite::SqliteCurrencyService;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::ServiceDescriptor;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeRateDto {
+    pub id: String,
+    /// One unit of `base_currency` buys `rate` units of `quote_currency`.
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: f64,
+    pub rate_date: String,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeRateQuery {
+    pub base_currency: Option<String>,
+    pub quote_currency: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertExchangeRateInput {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: f64,
+    /// `YYYY-MM-DD`; one rate per currency pair and day.
+    pub rate_date: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RateFileFormat {
+    /// Long `date,base,quote,rate` rows, or the ECB wide `Date,USD,JPY,...` layout.
+    Csv,
+    /// ECB `eurofxref` daily/historical XML.
+    EcbXml,
+}
+
+impl RateFileFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateFileFormat::Csv => "csv",
+            RateFileFormat::EcbXml => "ecb",
+        }
+    }
+
+    pub fn from_path(path: &str) -> Option<Self> {
+        let lower = path.to_ascii_lowercase();
+        if lower.ends_with(".csv") {
+            Some(RateFileFormat::Csv)
+        } else if lower.ends_with(".xml") {
+            Some(RateFileFormat::EcbXml)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportExchangeRatesInput {
+    pub format: RateFileFormat,
+    pub contents: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportExchangeRatesResult {
+    pub imported: usize,
+    pub currencies: Vec<String>,
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertAmountInput {
+    pub amount_cents: i64,
+    pub from_currency: String,
+    /// Defaults to the user's `default_currency`.
+    pub to_currency: Option<String>,
+    /// Defaults to today.
+    pub on: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionDto {
+    pub amount_cents: i64,
+    pub from_currency: String,
+    pub converted_cents: i64,
+    pub to_currency: String,
+    pub rate: f64,
+}
+
+#[derive(Debug, Error)]
+pub enum CurrencyServiceError {
+    #[error("database error: {0}")]
+    Database(String),
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("validation error: {0}")]
+    Validation(String),
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+pub type CurrencyResult<T> = Result<T, CurrencyServiceError>;
+
+impl From<rusqlite::Error> for CurrencyServiceError {
+    fn from(err: rusqlite::Error) -> Self {
+        CurrencyServiceError::Database(err.to_string())
+    }
+}
+
+pub trait CurrencyService: Send + Sync {
+    fn descriptor(&self) -> ServiceDescriptor;
+    fn list_exchange_rates(&self, query: ExchangeRateQuery)
+        -> CurrencyResult<Vec<ExchangeRateDto>>;
+    fn upsert_exchange_rate(
+        &self,
+        input: UpsertExchangeRateInput,
+    ) -> CurrencyResult<ExchangeRateDto>;
+    fn delete_exchange_rate(&self, id: &str) -> CurrencyResult<()>;
+    fn import_exchange_rates(
+        &self,
+        input: ImportExchangeRatesInput,
+    ) -> CurrencyResult<ImportExchangeRatesResult>;
+    fn convert_amount(&self, input: ConvertAmountInput) -> CurrencyResult<ConversionDto>;
+}
+
+/// Rate lookup for a row exposing `fx_from`, `fx_to` and `fx_on` (a `YYYY-MM-DD` date).
+/// Tries the direct pair, then the inverse pair, then a cross rate through any shared base
+/// published on the same day (e.g. ECB's EUR rates), taking the latest rate on or before
+/// `fx_on` and only then the earliest later one. Yields NULL when no rate is known.
+macro_rules! exchange_rate_sql {
+    () => {
+        r#"CASE WHEN fx_from = fx_to THEN 1.0 ELSE COALESCE(
+            (SELECT r.rate FROM "ExchangeRate" r
+             WHERE r.base_currency = fx_from AND r.quote_currency = fx_to AND r.rate_date <= fx_on
+             ORDER BY r.rate_date DESC LIMIT 1),
+            (SELECT 1.0 / r.rate FROM "ExchangeRate" r
+             WHERE r.base_currency = fx_to AND r.quote_currency = fx_from AND r.rate_date <= fx_on
+             ORDER BY r.rate_date DESC LIMIT 1),
+            (SELECT q.rate / b.rate FROM "ExchangeRate" b
+             JOIN "ExchangeRate" q ON q.base_currency = b.base_currency AND q.rate_date = b.rate_date
+             WHERE b.quote_currency = fx_from AND q.quote_currency = fx_to AND b.rate_date <= fx_on
+             ORDER BY b.rate_date DESC LIMIT 1),
+            (SELECT r.rate FROM "ExchangeRate" r
+             WHERE r.base_currency = fx_from AND r.quote_currency = fx_to AND r.rate_date > fx_on
+             ORDER BY r.rate_date LIMIT 1),
+            (SELECT 1.0 / r.rate FROM "ExchangeRate" r
+             WHERE r.base_currency = fx_to AND r.quote_currency = fx_from AND r.rate_date > fx_on
+             ORDER BY r.rate_date LIMIT 1),
+            (SELECT q.rate / b.rate FROM "ExchangeRate" b
+             JOIN "ExchangeRate" q ON q.base_currency = b.base_currency AND q.rate_date = b.rate_date
+             WHERE b.quote_currency = fx_from AND q.quote_currency = fx_to AND b.rate_date > fx_on
+             ORDER BY b.rate_date LIMIT 1)
+        ) END"#
+    };
+}
+pub(crate) use exchange_rate_sql;
+
+/// See [`exchange_rate_sql`]. Aggregates wrap it in `COALESCE(..., 1.0)` so amounts in a
+/// currency without any known rate are counted at face value rather than dropped.
+pub(crate) const EXCHANGE_RATE_SQL: &str = exchange_rate_sql!();
+
+pub(crate) fn ensure_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS "ExchangeRate" (
+            "id" TEXT NOT NULL PRIMARY KEY,
+            "base_currency" TEXT NOT NULL,
+            "quote_currency" TEXT NOT NULL,
+            "rate" REAL NOT NULL,
+            "rate_date" TEXT NOT NULL,
+            "source" TEXT NOT NULL DEFAULT 'manual',
+            "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            "updated_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS "ExchangeRate_base_currency_quote_currency_rate_date_key" ON "ExchangeRate"("base_currency", "quote_currency", "rate_date");
+        CREATE INDEX IF NOT EXISTS "ExchangeRate_quote_currency_rate_date_idx" ON "ExchangeRate"("quote_currency", "rate_date");
+        "#,
+    )
+}
+
+/// Rate converting `from` into `to` on `on` (`YYYY-MM-DD`), if one is known.
+pub(crate) fn lookup_rate(
+    conn: &Connection,
+    from: &str,
+    to: &str,
+    on: &str,
+) -> rusqlite::Result<Option<f64>> {
+    conn.query_row(
+        &format!(
+            "SELECT {EXCHANGE_RATE_SQL} FROM (SELECT ?1 AS fx_from, ?2 AS fx_to, ?3 AS fx_on)"
+        ),
+        params![from, to, on],
+        |row| row.get::<_, Option<f64>>(0),
+    )
+    .optional()
+    .map(Option::flatten)
+}
+
+pub(crate) fn normalize_currency(value: &str) -> CurrencyResult<String> {
+    let code = value.trim().to_ascii_uppercase();
+    if code.len() != 3 || !code.chars().all(|ch| ch.is_ascii_alphabetic()) {
+        return Err(CurrencyServiceError::Validation(format!(
+            "Invalid currency code: {value}"
+        )));
+    }
+    Ok(code)
+}
@@ -0,0 +1,222 @@
+use chrono::NaiveDate;
+
+use super::{normalize_currency, CurrencyResult, CurrencyServiceError, RateFileFormat};
+
+/// ECB reference rates are always quoted against the euro.
+const ECB_BASE_CURRENCY: &str = "EUR";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedRate {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: f64,
+    pub rate_date: String,
+}
+
+pub fn parse_rates(format: RateFileFormat, contents: &str) -> CurrencyResult<Vec<ParsedRate>> {
+    let rates = match format {
+        RateFileFormat::Csv => parse_csv(contents)?,
+        RateFileFormat::EcbXml => parse_ecb_xml(contents)?,
+    };
+
+    if rates.is_empty() {
+        return Err(CurrencyServiceError::Validation(
+            "No exchange rates found in file".into(),
+        ));
+    }
+    Ok(rates)
+}
+
+fn parse_csv(contents: &str) -> CurrencyResult<Vec<ParsedRate>> {
+    let mut lines = contents
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim())
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty());
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| CurrencyServiceError::Validation("Rate file is empty".into()))?;
+    let delimiter = if header.contains(';') { ';' } else { ',' };
+    let columns = split_row(header, delimiter)
+        .into_iter()
+        .map(|column| column.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+
+    let position = |names: &[&str]| {
+        columns
+            .iter()
+            .position(|column| names.contains(&column.as_str()))
+    };
+    let date_index = position(&["date", "rate_date", "ratedate"])
+        .ok_or_else(|| CurrencyServiceError::Validation("Rate file needs a date column".into()))?;
+    let long_layout = (
+        position(&["base", "base_currency", "from"]),
+        position(&["quote", "quote_currency", "to", "currency"]),
+        position(&["rate"]),
+    );
+
+    let mut rates = Vec::new();
+    for (index, line) in lines {
+        let row = split_row(line, delimiter);
+        let cell = |column: usize| row.get(column).map(String::as_str).unwrap_or_default();
+        let row_error = |message: String| {
+            CurrencyServiceError::Validation(format!("Line {}: {message}", index + 1))
+        };
+        let rate_date = parse_rate_date(cell(date_index)).map_err(row_error)?;
+
+        match long_layout {
+            (Some(base), Some(quote), Some(rate)) => {
+                rates.push(ParsedRate {
+                    base_currency: normalize_currency(cell(base))?,
+                    quote_currency: normalize_currency(cell(quote))?,
+                    rate: parse_rate_value(cell(rate)).map_err(row_error)?,
+                    rate_date,
+                });
+            }
+            // ECB wide layout: `Date,USD,JPY,...` with one column per currency against EUR.
+            _ => {
+                for (column, code) in columns.iter().enumerate() {
+                    if column == date_index || code.len() != 3 {
+                        continue;
+                    }
+                    let value = cell(column);
+                    if value.is_empty() || value.eq_ignore_ascii_case("n/a") {
+                        continue;
+                    }
+                    rates.push(ParsedRate {
+                        base_currency: ECB_BASE_CURRENCY.to_string(),
+                        quote_currency: normalize_currency(code)?,
+                        rate: parse_rate_value(value).map_err(row_error)?,
+                        rate_date: rate_date.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(rates)
+}
+
+fn parse_ecb_xml(contents: &str) -> CurrencyResult<Vec<ParsedRate>> {
+    let mut rates = Vec::new();
+    let mut current_date: Option<String> = None;
+
+    for tag in contents.split('<').skip(1) {
+        let Some(body) = tag.strip_prefix("Cube") else {
+            continue;
+        };
+        let body = body.split('>').next().unwrap_or_default();
+
+        if let Some(time) = attribute(body, "time") {
+            current_date = Some(parse_rate_date(&time).map_err(CurrencyServiceError::Validation)?);
+        }
+
+        if let (Some(currency), Some(rate)) = (attribute(body, "currency"), attribute(body, "rate"))
+        {
+            let rate_date = current_date.clone().ok_or_else(|| {
+                CurrencyServiceError::Validation(format!("Rate for {currency} has no date"))
+            })?;
+            rates.push(ParsedRate {
+                base_currency: ECB_BASE_CURRENCY.to_string(),
+                quote_currency: normalize_currency(&currency)?,
+                rate: parse_rate_value(&rate).map_err(CurrencyServiceError::Validation)?,
+                rate_date,
+            });
+        }
+    }
+
+    Ok(rates)
+}
+
+/// Value of `name='...'` or `name="..."` inside an XML start tag.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    while let Some(start) = rest.find(name) {
+        let preceded_by_space = start == 0
+            || rest[..start]
+                .chars()
+                .last()
+                .is_some_and(char::is_whitespace);
+        let after = rest[start + name.len()..].trim_start();
+        rest = &rest[start + name.len()..];
+        if !preceded_by_space {
+            continue;
+        }
+        let Some(value) = after.strip_prefix('=').map(str::trim_start) else {
+            continue;
+        };
+        let quote = value
+            .chars()
+            .next()
+            .filter(|ch| *ch == '\'' || *ch == '"')?;
+        return value[1..].split(quote).next().map(str::to_string);
+    }
+    None
+}
+
+fn split_row(line: &str, delimiter: char) -> Vec<String> {
+    line.split(delimiter)
+        .map(|cell| cell.trim().trim_matches('"').trim().to_string())
+        .collect()
+}
+
+fn parse_rate_date(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    ["%Y-%m-%d", "%d %B %Y", "%Y%m%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .ok_or_else(|| format!("Invalid rate date: {value}"))
+}
+
+fn parse_rate_value(value: &str) -> Result<f64, String> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|rate| rate.is_finite() && *rate > 0.0)
+        .ok_or_else(|| format!("Invalid exchange rate: {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ecb_daily_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+  <Cube>
+    <Cube time='2025-01-02'>
+      <Cube currency='USD' rate='1.0353'/>
+      <Cube currency='JPY' rate='163.07'/>
+    </Cube>
+  </Cube>
+</gesmes:Envelope>"#;
+        let rates = parse_rates(RateFileFormat::EcbXml, xml).unwrap();
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[0].base_currency, "EUR");
+        assert_eq!(rates[0].quote_currency, "USD");
+        assert_eq!(rates[1].rate, 163.07);
+        assert_eq!(rates[1].rate_date, "2025-01-02");
+    }
+
+    #[test]
+    fn parses_long_and_wide_csv() {
+        let long = "date,base,quote,rate\n2025-01-02,usd,gbp,0.80\n";
+        let rates = parse_rates(RateFileFormat::Csv, long).unwrap();
+        assert_eq!(rates[0].base_currency, "USD");
+        assert_eq!(rates[0].quote_currency, "GBP");
+
+        let wide = "Date, USD, JPY, \n02 January 2025, 1.0353, N/A, \n";
+        let rates = parse_rates(RateFileFormat::Csv, wide).unwrap();
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rates[0].rate_date, "2025-01-02");
+
+        assert!(parse_rates(
+            RateFileFormat::Csv,
+            "date,base,quote,rate\n2025-01-02,USD,GBP,-1\n"
+        )
+        .is_err());
+    }
+}
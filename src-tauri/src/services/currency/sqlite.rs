@@ -0,0 +1,389 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use chrono::{Local, NaiveDate};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, ToSql};
+use uuid::Uuid;
+
+use crate::services::{sqlcipher, ServiceDescriptor};
+
+use super::{
+    ensure_schema, lookup_rate, normalize_currency, parse_rates, ConversionDto, ConvertAmountInput,
+    CurrencyResult, CurrencyService, CurrencyServiceError, ExchangeRateDto, ExchangeRateQuery,
+    ImportExchangeRatesInput, ImportExchangeRatesResult, UpsertExchangeRateInput,
+};
+
+const DEFAULT_USER_ID: &str = "seed-user";
+
+pub struct SqliteCurrencyService {
+    db_path: PathBuf,
+    db_key: Option<String>,
+    user_id: String,
+}
+
+impl SqliteCurrencyService {
+    pub fn new(
+        db_path: PathBuf,
+        db_key: Option<String>,
+        user_id: Option<String>,
+    ) -> CurrencyResult<Self> {
+        let service = Self {
+            db_path,
+            db_key,
+            user_id: user_id.unwrap_or_else(|| DEFAULT_USER_ID.to_string()),
+        };
+        let conn = service.connection()?;
+        ensure_schema(&conn).map_err(|err| {
+            CurrencyServiceError::Database(format!("Failed to create exchange rate table: {err}"))
+        })?;
+        Ok(service)
+    }
+
+    fn connection(&self) -> CurrencyResult<Connection> {
+        let conn = Connection::open(&self.db_path)
+            .map_err(|err| CurrencyServiceError::Database(err.to_string()))?;
+
+        if let Some(key) = &self.db_key {
+            sqlcipher::apply_key(&conn, key).map_err(|err| {
+                CurrencyServiceError::Database(format!("Failed to apply SQLCipher key: {err}"))
+            })?;
+        }
+
+        if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
+            return Err(CurrencyServiceError::Database(err.to_string()));
+        }
+
+        Ok(conn)
+    }
+
+    fn default_currency(&self, conn: &Connection) -> CurrencyResult<String> {
+        let value: Option<String> = conn
+            .query_row(
+                r#"SELECT default_currency FROM "User" WHERE id = ?"#,
+                params![self.user_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(value.unwrap_or_else(|| "USD".to_string()))
+    }
+
+    fn map_rate_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ExchangeRateDto> {
+        Ok(ExchangeRateDto {
+            id: row.get(0)?,
+            base_currency: row.get(1)?,
+            quote_currency: row.get(2)?,
+            rate: row.get(3)?,
+            rate_date: row.get(4)?,
+            source: row.get(5)?,
+        })
+    }
+
+    fn upsert_rate(
+        conn: &Connection,
+        base: &str,
+        quote: &str,
+        rate: f64,
+        rate_date: &str,
+        source: &str,
+    ) -> CurrencyResult<()> {
+        conn.execute(
+            r#"
+            INSERT INTO "ExchangeRate" (id, base_currency, quote_currency, rate, rate_date, source, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, CURRENT_TIMESTAMP)
+            ON CONFLICT (base_currency, quote_currency, rate_date)
+            DO UPDATE SET rate = excluded.rate, source = excluded.source, updated_at = CURRENT_TIMESTAMP
+        "#,
+            params![
+                format!("fx_{}", Uuid::new_v4().simple()),
+                base,
+                quote,
+                rate,
+                rate_date,
+                source
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl CurrencyService for SqliteCurrencyService {
+    fn descriptor(&self) -> ServiceDescriptor {
+        ServiceDescriptor::new("CurrencyService", "sqlite")
+    }
+
+    fn list_exchange_rates(
+        &self,
+        query: ExchangeRateQuery,
+    ) -> CurrencyResult<Vec<ExchangeRateDto>> {
+        let conn = self.connection()?;
+        let mut sql = String::from(
+            r#"SELECT id, base_currency, quote_currency, rate, rate_date, source FROM "ExchangeRate" WHERE 1 = 1"#,
+        );
+        let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+
+        if let Some(base) = query.base_currency.as_deref() {
+            sql.push_str(" AND base_currency = ?");
+            values.push(Box::new(normalize_currency(base)?));
+        }
+        if let Some(quote) = query.quote_currency.as_deref() {
+            sql.push_str(" AND quote_currency = ?");
+            values.push(Box::new(normalize_currency(quote)?));
+        }
+        sql.push_str(" ORDER BY rate_date DESC, base_currency, quote_currency LIMIT ?");
+        values.push(Box::new(query.limit.unwrap_or(500).clamp(1, 10_000)));
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(
+            params_from_iter(values.iter().map(|value| &**value)),
+            Self::map_rate_row,
+        )?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn upsert_exchange_rate(
+        &self,
+        input: UpsertExchangeRateInput,
+    ) -> CurrencyResult<ExchangeRateDto> {
+        let base = normalize_currency(&input.base_currency)?;
+        let quote = normalize_currency(&input.quote_currency)?;
+        if base == quote {
+            return Err(CurrencyServiceError::Validation(
+                "Base and quote currency must differ".into(),
+            ));
+        }
+        if !input.rate.is_finite() || input.rate <= 0.0 {
+            return Err(CurrencyServiceError::Validation(
+                "Exchange rate must be greater than zero".into(),
+            ));
+        }
+        let rate_date = parse_date(&input.rate_date)?;
+
+        let conn = self.connection()?;
+        Self::upsert_rate(&conn, &base, &quote, input.rate, &rate_date, "manual")?;
+
+        Ok(conn.query_row(
+            r#"
+            SELECT id, base_currency, quote_currency, rate, rate_date, source
+            FROM "ExchangeRate"
+            WHERE base_currency = ? AND quote_currency = ? AND rate_date = ?
+        "#,
+            params![base, quote, rate_date],
+            Self::map_rate_row,
+        )?)
+    }
+
+    fn delete_exchange_rate(&self, id: &str) -> CurrencyResult<()> {
+        let conn = self.connection()?;
+        let affected = conn.execute(r#"DELETE FROM "ExchangeRate" WHERE id = ?"#, params![id])?;
+        if affected == 0 {
+            return Err(CurrencyServiceError::NotFound(format!(
+                "Exchange rate {id}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn import_exchange_rates(
+        &self,
+        input: ImportExchangeRatesInput,
+    ) -> CurrencyResult<ImportExchangeRatesResult> {
+        let rates = parse_rates(input.format, &input.contents)?;
+
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        for rate in &rates {
+            Self::upsert_rate(
+                &tx,
+                &rate.base_currency,
+                &rate.quote_currency,
+                rate.rate,
+                &rate.rate_date,
+                input.format.as_str(),
+            )?;
+        }
+        tx.commit()?;
+
+        let currencies = rates
+            .iter()
+            .flat_map(|rate| [rate.base_currency.clone(), rate.quote_currency.clone()])
+            .collect::<BTreeSet<_>>();
+        let dates = rates
+            .iter()
+            .map(|rate| rate.rate_date.as_str())
+            .collect::<BTreeSet<_>>();
+
+        Ok(ImportExchangeRatesResult {
+            imported: rates.len(),
+            currencies: currencies.into_iter().collect(),
+            from_date: dates.first().map(|date| date.to_string()),
+            to_date: dates.last().map(|date| date.to_string()),
+        })
+    }
+
+    fn convert_amount(&self, input: ConvertAmountInput) -> CurrencyResult<ConversionDto> {
+        let conn = self.connection()?;
+        let from = normalize_currency(&input.from_currency)?;
+        let to = match input.to_currency.as_deref() {
+            Some(to) => normalize_currency(to)?,
+            None => self.default_currency(&conn)?,
+        };
+        let on = match input.on.as_deref() {
+            Some(on) => parse_date(on)?,
+            None => Local::now().date_naive().format("%Y-%m-%d").to_string(),
+        };
+
+        let rate = lookup_rate(&conn, &from, &to, &on)?.ok_or_else(|| {
+            CurrencyServiceError::NotFound(format!("No exchange rate from {from} to {to}"))
+        })?;
+
+        Ok(ConversionDto {
+            amount_cents: input.amount_cents,
+            from_currency: from,
+            converted_cents: (input.amount_cents as f64 * rate).round() as i64,
+            to_currency: to,
+            rate,
+        })
+    }
+}
+
+fn parse_date(value: &str) -> CurrencyResult<String> {
+    let date = value.get(..10).unwrap_or(value);
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .map_err(|_| CurrencyServiceError::Validation(format!("Invalid date: {value}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{
+        currency::RateFileFormat, CreateTransactionInput, SqliteTransactionService,
+        TransactionService, UpdateTransactionInput,
+    };
+
+    fn setup() -> (SqliteCurrencyService, SqliteTransactionService, PathBuf) {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+
+        let transactions =
+            SqliteTransactionService::new(path.clone(), None, Some("seed-user".into())).unwrap();
+        let currency =
+            SqliteCurrencyService::new(path.clone(), None, Some("seed-user".into())).unwrap();
+        (currency, transactions, path)
+    }
+
+    #[test]
+    fn converts_through_inverse_and_cross_rates() {
+        let (service, _, _) = setup();
+        service
+            .import_exchange_rates(ImportExchangeRatesInput {
+                format: RateFileFormat::Csv,
+                contents: "Date,USD,GBP\n2025-01-02,1.25,0.80\n".into(),
+            })
+            .unwrap();
+
+        let convert = |from: &str, to: &str| {
+            service
+                .convert_amount(ConvertAmountInput {
+                    amount_cents: 10_000,
+                    from_currency: from.into(),
+                    to_currency: Some(to.into()),
+                    on: Some("2025-03-01".into()),
+                })
+                .map(|result| result.converted_cents)
+        };
+        assert_eq!(convert("EUR", "USD").unwrap(), 12_500);
+        assert_eq!(convert("USD", "EUR").unwrap(), 8_000);
+        assert_eq!(convert("USD", "GBP").unwrap(), 6_400);
+        assert!(convert("USD", "JPY").is_err());
+    }
+
+    #[test]
+    fn cross_currency_transfer_records_rate_and_converts_destination_leg() {
+        let (service, transactions, _) = setup();
+        service
+            .upsert_exchange_rate(UpsertExchangeRateInput {
+                base_currency: "USD".into(),
+                quote_currency: "EUR".into(),
+                rate: 0.9,
+                rate_date: "2025-01-01".into(),
+            })
+            .unwrap();
+        let euro = transactions
+            .create_account(crate::services::CreateAccountInput {
+                id: None,
+                name: "Euro savings".into(),
+                account_type: "savings".into(),
+                currency: "EUR".into(),
+                institution: None,
+                color_token: None,
+                sync_external_id: None,
+                opening_balance_cents: 0,
+            })
+            .unwrap();
+
+        let transfer = transactions
+            .create_transaction(CreateTransactionInput {
+                id: None,
+                account_id: "acct-default".into(),
+                category_id: None,
+                kind: crate::services::transactions::TransactionKind::Transfer,
+                amount_cents: 10_000,
+                currency: "USD".into(),
+                occurred_on: "2025-02-01T12:00:00Z".into(),
+                notes: None,
+                tags: None,
+                cleared: false,
+                goal_id: None,
+                transfer_account_id: Some(euro.id.clone()),
+                splits: Vec::new(),
+                recurrence_id: None,
+                exchange_rate: None,
+                payee: None,
+                external_id: None,
+            })
+            .unwrap();
+        assert_eq!(transfer.exchange_rate, Some(0.9));
+
+        let accounts = transactions.list_accounts(false, false).unwrap();
+        let balance = |id: &str| {
+            accounts
+                .iter()
+                .find(|acct| acct.id == id)
+                .unwrap()
+                .balance_cents
+        };
+        assert_eq!(balance(&euro.id), 9_000);
+        assert_eq!(balance("acct-default"), -10_000);
+
+        let update = |exchange_rate: Option<f64>, notes: &str| {
+            transactions
+                .update_transaction(UpdateTransactionInput {
+                    id: transfer.id.clone(),
+                    account_id: "acct-default".into(),
+                    category_id: None,
+                    kind: crate::services::transactions::TransactionKind::Transfer,
+                    amount_cents: 10_000,
+                    currency: "USD".into(),
+                    occurred_on: "2025-02-01T12:00:00Z".into(),
+                    notes: Some(notes.into()),
+                    tags: None,
+                    cleared: false,
+                    goal_id: None,
+                    transfer_account_id: Some(euro.id.clone()),
+                    splits: Vec::new(),
+                    exchange_rate,
+                    payee: None,
+                    override_reconciled: false,
+                })
+                .unwrap()
+        };
+        assert_eq!(update(Some(0.95), "Bank rate").exchange_rate, Some(0.95));
+        // An edit that leaves the rate out keeps the one entered by hand
+        assert_eq!(update(None, "Savings").exchange_rate, Some(0.95));
+        let accounts = transactions.list_accounts(false, false).unwrap();
+        let euro_balance = accounts.iter().find(|acct| acct.id == euro.id).unwrap();
+        assert_eq!(euro_balance.balance_cents, 9_500);
+    }
+}
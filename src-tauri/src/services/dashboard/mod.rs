@@ -1,11 +1,14 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
-use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, Timelike};
-use rusqlite::{params, params_from_iter, Connection, ToSql};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, Timelike, Utc};
+use rusqlite::{params, Connection};
 use serde::Serialize;
 use thiserror::Error;
 
-use crate::services::ServiceDescriptor;
+use crate::services::{
+    budgets::BudgetService, currency::EXCHANGE_RATE_SQL, sqlcipher,
+    transactions::TRANSACTION_LINES_CTE, ServiceDescriptor,
+};
 
 const DEFAULT_USER_ID: &str = "seed-user";
 
@@ -65,29 +68,32 @@ pub struct SqliteDashboardService {
     db_path: PathBuf,
     db_key: Option<String>,
     user_id: String,
+    budgets: Arc<dyn BudgetService>,
 }
 
 impl SqliteDashboardService {
+    /// Budget totals come from `budgets` so the dashboard sees the same renewed periods
+    /// and carries as the budgets page.
     pub fn new(
         db_path: PathBuf,
         db_key: Option<String>,
         user_id: Option<String>,
+        budgets: Arc<dyn BudgetService>,
     ) -> DashboardResult<Self> {
         Ok(Self {
             db_path,
             db_key,
             user_id: user_id.unwrap_or_else(|| DEFAULT_USER_ID.to_string()),
+            budgets,
         })
     }
 
     fn connection(&self) -> DashboardResult<Connection> {
         let conn = Connection::open(&self.db_path)?;
-        conn.execute("PRAGMA foreign_keys = ON;", [])?;
         if let Some(key) = &self.db_key {
-            if let Err(err) = conn.pragma_update(None, "key", key) {
-                tracing::warn!(error = %err, "Failed to apply SQLCipher key for dashboard service");
-            }
+            sqlcipher::apply_key(&conn, key)?;
         }
+        conn.execute("PRAGMA foreign_keys = ON;", [])?;
         Ok(conn)
     }
 
@@ -107,13 +113,23 @@ impl SqliteDashboardService {
         // Активы: cash/checking/savings/investment/wallet. Всё остальное считаем пассивами.
         let (assets, liabilities): (i64, i64) = conn
             .query_row(
-                r#"
+                &format!(
+                    r#"
                 SELECT
                   COALESCE(SUM(CASE WHEN type IN ('cash','checking','savings','investment','wallet') THEN balance_cents ELSE 0 END), 0) as assets,
                   COALESCE(SUM(CASE WHEN type NOT IN ('cash','checking','savings','investment','wallet') THEN balance_cents ELSE 0 END), 0)  as liabilities
-                FROM "Account"
-                WHERE user_id = ?
-                "#,
+                FROM (
+                  SELECT fx.type, CAST(ROUND(fx.balance_cents * COALESCE({EXCHANGE_RATE_SQL}, 1.0)) AS INTEGER) as balance_cents
+                  FROM (
+                    SELECT a.type, a.balance_cents, a.currency as fx_from,
+                           COALESCE(u.default_currency, a.currency) as fx_to, DATE('now') as fx_on
+                    FROM "Account" a
+                    LEFT JOIN "User" u ON u.id = a.user_id
+                    WHERE a.user_id = ?
+                  ) fx
+                )
+                "#
+                ),
                 params![self.user_id],
                 |row| Ok((row.get(0)?, row.get(1)?)),
             )
@@ -125,14 +141,17 @@ impl SqliteDashboardService {
     fn net_worth_delta(&self, conn: &Connection) -> DashboardResult<i64> {
         let delta: i64 = conn
             .query_row(
-                r#"
+                &format!(
+                    r#"
+                WITH {TRANSACTION_LINES_CTE}
                 SELECT COALESCE(SUM(CASE 
                     WHEN type = 'income' THEN amount_cents 
                     WHEN type = 'expense' THEN -amount_cents 
                     ELSE 0 END), 0)
-                FROM "Transaction"
+                FROM transaction_lines
                 WHERE user_id = ? AND datetime(occurred_on) >= datetime('now', '-7 days')
-            "#,
+            "#
+                ),
                 params![self.user_id],
                 |row| row.get(0),
             )
@@ -150,16 +169,19 @@ impl SqliteDashboardService {
 
         let current: i64 = conn
             .query_row(
-                r#"
+                &format!(
+                    r#"
+                WITH {TRANSACTION_LINES_CTE}
                 SELECT COALESCE(SUM(CASE 
                     WHEN type = 'income' THEN amount_cents 
                     WHEN type = 'expense' THEN -amount_cents 
                     ELSE 0 END), 0)
-                FROM "Transaction"
+                FROM transaction_lines
                 WHERE user_id = ? 
                   AND datetime(occurred_on) >= datetime(?)
                   AND datetime(occurred_on) <  datetime(?)
-            "#,
+            "#
+                ),
                 params![
                     self.user_id,
                     format_iso_start(current_start),
@@ -171,16 +193,19 @@ impl SqliteDashboardService {
 
         let previous: i64 = conn
             .query_row(
-                r#"
+                &format!(
+                    r#"
+                WITH {TRANSACTION_LINES_CTE}
                 SELECT COALESCE(SUM(CASE 
                     WHEN type = 'income' THEN amount_cents 
                     WHEN type = 'expense' THEN -amount_cents 
                     ELSE 0 END), 0)
-                FROM "Transaction"
+                FROM transaction_lines
                 WHERE user_id = ?
                   AND datetime(occurred_on) >= datetime(?)
                   AND datetime(occurred_on) <  datetime(?)
-            "#,
+            "#
+                ),
                 params![
                     self.user_id,
                     format_iso_start(previous_start),
@@ -193,71 +218,18 @@ impl SqliteDashboardService {
         Ok((current, previous))
     }
 
-    fn budget_summary(&self, conn: &Connection) -> DashboardResult<(i64, i64)> {
-        let mut stmt = conn.prepare(
-            r#"
-            SELECT id, amount_cents, start_date, end_date, category_id
-            FROM "Budget"
-            WHERE user_id = ?
-              AND datetime('now', 'localtime') >= datetime(start_date)
-              AND datetime('now', 'localtime') < datetime(end_date)
-        "#,
-        )?;
-
-        let mut rows = stmt.query(params![self.user_id.clone()])?;
-        let mut total = 0i64;
-        let mut spent = 0i64;
-
-        while let Some(row) = rows.next()? {
-            let amount: i64 = row.get(1)?;
-            let start: String = row.get(2)?;
-            let end: String = row.get(3)?;
-            let category_id: Option<String> = row.get(4)?;
-            let categories = parse_categories(category_id.clone());
-
-            total += amount;
-
-            let period_spent: i64 = if categories.is_empty() {
-                conn.query_row(
-                    r#"
-                    SELECT COALESCE(SUM(amount_cents), 0)
-                    FROM "Transaction"
-                    WHERE user_id = ?
-                      AND type = 'expense'
-                      AND datetime(occurred_on) >= datetime(?)
-                      AND datetime(occurred_on) <  datetime(?)
-                "#,
-                    params![self.user_id, start, end],
-                    |r| r.get(0),
-                )
-                .unwrap_or(0)
-            } else {
-                let placeholders = format!("({})", std::iter::repeat("?").take(categories.len()).collect::<Vec<_>>().join(","));
-                let mut sql = format!(
-                    r#"
-                    SELECT COALESCE(SUM(amount_cents), 0)
-                    FROM "Transaction"
-                    WHERE user_id = ?
-                      AND type = 'expense'
-                      AND datetime(occurred_on) >= datetime(?)
-                      AND datetime(occurred_on) <  datetime(?)
-                      AND category_id IN {}
-                "#,
-                    placeholders
-                );
-                let mut params: Vec<Box<dyn ToSql>> =
-                    vec![Box::new(self.user_id.clone()), Box::new(start), Box::new(end)];
-                for cat in categories {
-                    params.push(Box::new(cat));
-                }
-                let sqlite_params = params_from_iter(params.iter().map(|p| &**p));
-                conn.query_row(&sql, sqlite_params, |r| r.get(0)).unwrap_or(0)
-            };
-
-            spent += period_spent;
-        }
-
-        Ok((total, spent))
+    /// Amount available and spent across the budget periods covering today, as the budget
+    /// service reports them after renewing recurring budgets and applying carries.
+    fn budget_summary(&self) -> DashboardResult<(i64, i64)> {
+        let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+        let budgets = self
+            .budgets
+            .list_budgets(Some(&today))
+            .map_err(|err| DashboardServiceError::Internal(err.to_string()))?;
+
+        Ok(budgets.iter().fold((0, 0), |(total, spent), budget| {
+            (total + budget.available_cents, spent + budget.spent_cents)
+        }))
     }
 
     fn weekly_spending(&self, conn: &Connection) -> DashboardResult<Vec<WeeklySpendingPoint>> {
@@ -265,18 +237,19 @@ impl SqliteDashboardService {
         let week_start = start_of_week_sunday(today);
         let week_end = week_start + Duration::days(7);
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"
+            WITH {TRANSACTION_LINES_CTE}
             SELECT strftime('%Y-%m-%d', datetime(occurred_on, 'localtime')) as day,
                    COALESCE(SUM(amount_cents), 0) as total
-            FROM "Transaction"
+            FROM transaction_lines
             WHERE user_id = ?
               AND type = 'expense'
               AND datetime(occurred_on) >= datetime(?)
               AND datetime(occurred_on) <  datetime(?)
             GROUP BY day
         "#,
-        )?;
+        ))?;
         let mut rows = stmt.query(params![
             self.user_id.clone(),
             format_iso_start(start_of_day(week_start)),
@@ -357,16 +330,6 @@ fn start_of_week_sunday(today: NaiveDate) -> NaiveDate {
     today - Duration::days(days_from_sunday)
 }
 
-fn parse_categories(value: Option<String>) -> Vec<String> {
-    value
-        .unwrap_or_default()
-        .split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
-        .collect()
-}
-
 impl DashboardService for SqliteDashboardService {
     fn descriptor(&self) -> ServiceDescriptor {
         ServiceDescriptor::new("DashboardService", "sqlite")
@@ -378,7 +341,7 @@ impl DashboardService for SqliteDashboardService {
         let net_worth = self.net_worth(&conn)?;
         let net_delta = self.net_worth_delta(&conn)?;
         let (cash_flow, cash_prev) = self.cash_flow(&conn)?;
-        let (budget_total, budget_spent) = self.budget_summary(&conn)?;
+        let (budget_total, budget_spent) = self.budget_summary()?;
         let weekly = self.weekly_spending(&conn)?;
         let accounts = self.account_highlights(&conn)?;
 
@@ -0,0 +1,368 @@
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{SecondsFormat, Utc};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+/// `format` marker of passphrase-encrypted JSON exports.
+const FORMAT: &str = "pfd-encrypted-json";
+/// Envelope layout version; version 1 was the unencrypted base64 wrapper.
+const ENVELOPE_VERSION: u64 = 2;
+const CIPHER: &str = "aes-256-gcm";
+const KDF_ALGORITHM: &str = "argon2id";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const MIN_PASSPHRASE_CHARS: usize = 8;
+
+/// Upper bounds for KDF parameters read from a file, so a crafted file cannot make
+/// decryption allocate or spin without limit.
+const MAX_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_ITERATIONS: u32 = 16;
+const MAX_PARALLELISM: u32 = 16;
+
+#[derive(Debug, Error)]
+pub enum ExportCryptoError {
+    #[error("{0}")]
+    Validation(String),
+    #[error("invalid encrypted file: {0}")]
+    InvalidFormat(String),
+    #[error("unsupported encrypted file version {0}")]
+    UnsupportedVersion(String),
+    #[error("wrong passphrase, or the file was modified")]
+    Decryption,
+    #[error("key derivation failed: {0}")]
+    Kdf(String),
+}
+
+pub type ExportCryptoResult<T> = Result<T, ExportCryptoError>;
+
+/// Argon2id cost parameters, stored in the envelope so they can be raised later without
+/// breaking older files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfCost {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfCost {
+    fn default() -> Self {
+        Self {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KdfHeader {
+    algorithm: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope {
+    format: String,
+    version: u64,
+    /// Kept from version 1 so readers can tell an encrypted file from a plain export.
+    encrypted: bool,
+    created_at: String,
+    cipher: String,
+    kdf: KdfHeader,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl Envelope {
+    /// Header fields authenticated alongside the ciphertext.
+    fn associated_data(&self) -> String {
+        format!(
+            "{}|{}|{}|{}|{}|{}|{}|{}|{}",
+            self.format,
+            self.version,
+            self.created_at,
+            self.cipher,
+            self.kdf.algorithm,
+            self.kdf.memory_kib,
+            self.kdf.iterations,
+            self.kdf.parallelism,
+            self.kdf.salt
+        )
+    }
+}
+
+/// Contents of an encrypted export.
+#[derive(Debug)]
+pub struct OpenedExport {
+    pub plaintext: Vec<u8>,
+    /// Set for version 1 files, which were only base64-encoded and can be read without a
+    /// passphrase.
+    pub legacy: bool,
+}
+
+/// Encrypts `plaintext` with a key derived from `passphrase` and returns the envelope as
+/// pretty-printed JSON.
+pub fn seal(plaintext: &[u8], passphrase: &str) -> ExportCryptoResult<String> {
+    seal_with_cost(plaintext, passphrase, KdfCost::default())
+}
+
+pub fn seal_with_cost(
+    plaintext: &[u8],
+    passphrase: &str,
+    cost: KdfCost,
+) -> ExportCryptoResult<String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(ExportCryptoError::Validation(format!(
+            "Passphrase must be at least {MIN_PASSPHRASE_CHARS} characters"
+        )));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let mut envelope = Envelope {
+        format: FORMAT.into(),
+        version: ENVELOPE_VERSION,
+        encrypted: true,
+        created_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        cipher: CIPHER.into(),
+        kdf: KdfHeader {
+            algorithm: KDF_ALGORITHM.into(),
+            memory_kib: cost.memory_kib,
+            iterations: cost.iterations,
+            parallelism: cost.parallelism,
+            salt: STANDARD.encode(salt),
+        },
+        nonce: STANDARD.encode(nonce),
+        ciphertext: String::new(),
+    };
+
+    let key = derive_key(passphrase, &salt, cost)?;
+    let aad = envelope.associated_data();
+    let ciphertext = cipher(&key)
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| ExportCryptoError::Validation("Failed to encrypt export".into()))?;
+    envelope.ciphertext = STANDARD.encode(ciphertext);
+
+    serde_json::to_string_pretty(&envelope)
+        .map_err(|err| ExportCryptoError::InvalidFormat(err.to_string()))
+}
+
+/// Decrypts an encrypted export. Version 1 files are decoded without a passphrase and
+/// flagged as `legacy`.
+pub fn open(contents: &str, passphrase: Option<&str>) -> ExportCryptoResult<OpenedExport> {
+    let value: Value = serde_json::from_str(contents)
+        .map_err(|err| ExportCryptoError::InvalidFormat(err.to_string()))?;
+    if !value
+        .get("encrypted")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+    {
+        return Err(ExportCryptoError::InvalidFormat(
+            "file is not marked as encrypted".into(),
+        ));
+    }
+
+    match value.get("version") {
+        Some(Value::Number(version)) if version.as_u64() == Some(ENVELOPE_VERSION) => {
+            let envelope: Envelope = serde_json::from_value(value)
+                .map_err(|err| ExportCryptoError::InvalidFormat(err.to_string()))?;
+            let passphrase = passphrase.filter(|p| !p.is_empty()).ok_or_else(|| {
+                ExportCryptoError::Validation("A passphrase is required for this file".into())
+            })?;
+            open_envelope(&envelope, passphrase).map(|plaintext| OpenedExport {
+                plaintext,
+                legacy: false,
+            })
+        }
+        Some(Value::String(version)) if version == "1.0" => open_legacy(&value),
+        Some(version) => Err(ExportCryptoError::UnsupportedVersion(version.to_string())),
+        None => Err(ExportCryptoError::InvalidFormat("missing version".into())),
+    }
+}
+
+fn open_envelope(envelope: &Envelope, passphrase: &str) -> ExportCryptoResult<Vec<u8>> {
+    if envelope.format != FORMAT {
+        return Err(ExportCryptoError::InvalidFormat(format!(
+            "unknown format {}",
+            envelope.format
+        )));
+    }
+    if envelope.cipher != CIPHER || envelope.kdf.algorithm != KDF_ALGORITHM {
+        return Err(ExportCryptoError::UnsupportedVersion(format!(
+            "{} with {}",
+            envelope.cipher, envelope.kdf.algorithm
+        )));
+    }
+    let cost = KdfCost {
+        memory_kib: envelope.kdf.memory_kib,
+        iterations: envelope.kdf.iterations,
+        parallelism: envelope.kdf.parallelism,
+    };
+    if cost.memory_kib > MAX_MEMORY_KIB
+        || cost.iterations > MAX_ITERATIONS
+        || cost.parallelism > MAX_PARALLELISM
+    {
+        return Err(ExportCryptoError::InvalidFormat(
+            "key derivation parameters are out of range".into(),
+        ));
+    }
+
+    let decode = |field: &str, value: &str| {
+        STANDARD
+            .decode(value)
+            .map_err(|_| ExportCryptoError::InvalidFormat(format!("{field} is not base64")))
+    };
+    let salt = decode("salt", &envelope.kdf.salt)?;
+    let nonce = decode("nonce", &envelope.nonce)?;
+    let ciphertext = decode("ciphertext", &envelope.ciphertext)?;
+    if salt.len() < SALT_LEN || nonce.len() != NONCE_LEN {
+        return Err(ExportCryptoError::InvalidFormat(
+            "salt or nonce has the wrong length".into(),
+        ));
+    }
+
+    let key = derive_key(passphrase, &salt, cost)?;
+    let aad = envelope.associated_data();
+    cipher(&key)
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| ExportCryptoError::Decryption)
+}
+
+/// Version 1 wrapper: base64 data with a `DefaultHasher` checksum, which only catches
+/// accidental corruption.
+fn open_legacy(value: &Value) -> ExportCryptoResult<OpenedExport> {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let field = |name: &str| {
+        value
+            .get(name)
+            .and_then(Value::as_str)
+            .ok_or_else(|| ExportCryptoError::InvalidFormat(format!("missing {name} field")))
+    };
+    let data = field("data")?;
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    if format!("{:x}", hasher.finish()) != field("checksum")? {
+        return Err(ExportCryptoError::InvalidFormat(
+            "checksum mismatch, the file is corrupted".into(),
+        ));
+    }
+    let plaintext = STANDARD
+        .decode(data)
+        .map_err(|_| ExportCryptoError::InvalidFormat("data is not base64".into()))?;
+    Ok(OpenedExport {
+        plaintext,
+        legacy: true,
+    })
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], cost: KdfCost) -> ExportCryptoResult<[u8; 32]> {
+    let params = Params::new(cost.memory_kib, cost.iterations, cost.parallelism, Some(32))
+        .map_err(|err| ExportCryptoError::Kdf(err.to_string()))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| ExportCryptoError::Kdf(err.to_string()))?;
+    Ok(key)
+}
+
+fn cipher(key: &[u8; 32]) -> Aes256Gcm {
+    Aes256Gcm::new_from_slice(key).expect("key is 32 bytes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHEAP: KdfCost = KdfCost {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn round_trips_and_rejects_wrong_passphrase_or_tampering() {
+        let sealed = seal_with_cost(br#"{"month":"2025-01"}"#, "correct horse", CHEAP).unwrap();
+        assert!(!sealed.contains("2025-01"));
+
+        let opened = open(&sealed, Some("correct horse")).unwrap();
+        assert_eq!(opened.plaintext, br#"{"month":"2025-01"}"#);
+        assert!(!opened.legacy);
+
+        assert!(matches!(
+            open(&sealed, Some("wrong horse")),
+            Err(ExportCryptoError::Decryption)
+        ));
+        assert!(matches!(
+            open(&sealed, None),
+            Err(ExportCryptoError::Validation(_))
+        ));
+
+        let mut envelope: Envelope = serde_json::from_str(&sealed).unwrap();
+        envelope.created_at = "2000-01-01T00:00:00Z".into();
+        let tampered = serde_json::to_string(&envelope).unwrap();
+        assert!(matches!(
+            open(&tampered, Some("correct horse")),
+            Err(ExportCryptoError::Decryption)
+        ));
+
+        assert!(seal_with_cost(b"{}", "short", CHEAP).is_err());
+    }
+
+    #[test]
+    fn reads_legacy_base64_files() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let data = STANDARD.encode(br#"{"month":"2024-12"}"#);
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        let legacy = serde_json::json!({
+            "version": "1.0",
+            "encrypted": true,
+            "timestamp": "2025-01-01T00:00:00Z",
+            "data": data,
+            "checksum": format!("{:x}", hasher.finish()),
+        })
+        .to_string();
+
+        let opened = open(&legacy, None).unwrap();
+        assert!(opened.legacy);
+        assert_eq!(opened.plaintext, br#"{"month":"2024-12"}"#);
+
+        let corrupted = legacy.replace(&data, &STANDARD.encode(b"{}"));
+        assert!(open(&corrupted, None).is_err());
+        assert!(matches!(
+            open(r#"{"version":3,"encrypted":true}"#, None),
+            Err(ExportCryptoError::UnsupportedVersion(_))
+        ));
+    }
+}
@@ -4,7 +4,7 @@ use chrono::{DateTime, Duration, Utc};
 use rusqlite::{params, Connection};
 use uuid::Uuid;
 
-use crate::services::ServiceDescriptor;
+use crate::services::{sqlcipher, transactions::TRANSACTION_LINES_CTE, ServiceDescriptor};
 
 use super::{
     AddContributionInput, CreateGoalInput, GoalDto, GoalResult, GoalService, GoalServiceError,
@@ -37,14 +37,14 @@ impl SqliteGoalService {
         let conn = Connection::open(&self.db_path)
             .map_err(|err| GoalServiceError::Database(err.to_string()))?;
 
-        if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
-            return Err(GoalServiceError::Database(err.to_string()));
+        if let Some(key) = &self.db_key {
+            sqlcipher::apply_key(&conn, key).map_err(|err| {
+                GoalServiceError::Database(format!("Failed to apply SQLCipher key: {err}"))
+            })?;
         }
 
-        if let Some(key) = &self.db_key {
-            if let Err(err) = conn.pragma_update(None, "key", key) {
-                tracing::warn!(error = %err, "Failed to apply SQLCipher key; continuing without encryption");
-            }
+        if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
+            return Err(GoalServiceError::Database(err.to_string()));
         }
 
         Ok(conn)
@@ -54,15 +54,18 @@ impl SqliteGoalService {
         // Доходы увеличивают накопления, расходы уменьшают
         let current: i64 = conn
             .query_row(
-                r#"
+                &format!(
+                    r#"
+                WITH {TRANSACTION_LINES_CTE}
                 SELECT COALESCE(SUM(CASE 
                     WHEN type = 'income' THEN amount_cents 
                     WHEN type = 'expense' THEN -amount_cents 
                     ELSE 0 END), 0)
-                FROM "Transaction"
+                FROM transaction_lines
                 WHERE user_id = ? 
                   AND goal_id = ?
-                "#,
+                "#
+                ),
                 params![self.user_id, goal_id],
                 |row| row.get(0),
             )
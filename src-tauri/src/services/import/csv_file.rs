@@ -0,0 +1,355 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use encoding_rs::{Encoding, UTF_8};
+
+use crate::services::transactions::TransactionKind;
+
+use super::{
+    ofx::parse_amount, CsvImportProfileInput, CsvSignConvention, ImportResult, ImportServiceError,
+};
+
+/// A CSV file read with a mapping profile.
+#[derive(Debug)]
+pub struct CsvTable {
+    /// Header row, or `1`, `2`, ... for files without one so positions can be mapped.
+    pub headers: Vec<String>,
+    pub rows: Vec<CsvRow>,
+}
+
+#[derive(Debug)]
+pub struct CsvRow {
+    /// 1-based line in the file.
+    pub line: usize,
+    pub values: Vec<String>,
+    pub parsed: Result<CsvParsedRow, Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvParsedRow {
+    /// `YYYY-MM-DD`.
+    pub occurred_on: String,
+    pub kind: TransactionKind,
+    /// Always positive; `kind` carries the direction.
+    pub amount_cents: i64,
+    pub payee: Option<String>,
+    pub notes: Option<String>,
+    pub category: Option<String>,
+    pub external_id: Option<String>,
+}
+
+/// Column positions resolved against the file's header row.
+struct Columns {
+    date: usize,
+    amount: Option<usize>,
+    debit: Option<usize>,
+    credit: Option<usize>,
+    payee: Option<usize>,
+    notes: Option<usize>,
+    category: Option<usize>,
+    external_id: Option<usize>,
+}
+
+pub fn read_csv(bytes: &[u8], profile: &CsvImportProfileInput) -> ImportResult<CsvTable> {
+    let encoding = Encoding::for_label(profile.encoding.trim().as_bytes()).unwrap_or(UTF_8);
+    // Also drops a byte order mark, switching encoding if the mark says so
+    let (text, _, _) = encoding.decode(bytes);
+
+    let offset = profile.header_row_offset as usize;
+    let body = text.split_inclusive('\n').skip(offset).collect::<String>();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(profile.delimiter.as_bytes()[0])
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(body.as_bytes());
+
+    let mut records = Vec::new();
+    // The reader's own line count lags on CRLF files, so count newlines up to each record.
+    // A record can start on the `\n` of the previous CRLF, hence the inclusive range.
+    let (mut scanned, mut newlines) = (0, 0);
+    for record in reader.records() {
+        let record = record
+            .map_err(|err| ImportServiceError::Validation(format!("Invalid CSV file: {err}")))?;
+        let start = record
+            .position()
+            .map_or(0, |position| position.byte() as usize);
+        let end = (start + 1).min(body.len());
+        if end > scanned {
+            newlines += body.as_bytes()[scanned..end]
+                .iter()
+                .filter(|byte| **byte == b'\n')
+                .count();
+            scanned = end;
+        }
+        let line = newlines + 1 + offset;
+        let values: Vec<String> = record
+            .iter()
+            .map(|value| value.trim().to_string())
+            .collect();
+        if values.iter().all(String::is_empty) {
+            continue;
+        }
+        records.push((line, values));
+    }
+
+    let headers = if profile.has_header {
+        if records.is_empty() {
+            return Err(ImportServiceError::Validation(
+                "The CSV file has no header row".into(),
+            ));
+        }
+        records.remove(0).1
+    } else {
+        let width = records
+            .iter()
+            .map(|(_, values)| values.len())
+            .max()
+            .unwrap_or(0);
+        (1..=width).map(|position| position.to_string()).collect()
+    };
+
+    let columns = resolve_columns(&headers, profile)?;
+    let rows = records
+        .into_iter()
+        .map(|(line, values)| CsvRow {
+            line,
+            parsed: parse_row(&values, &columns, profile),
+            values,
+        })
+        .collect();
+
+    Ok(CsvTable { headers, rows })
+}
+
+fn resolve_columns(headers: &[String], profile: &CsvImportProfileInput) -> ImportResult<Columns> {
+    let find = |column: &Option<String>| -> ImportResult<Option<usize>> {
+        let Some(column) = column.as_deref().map(str::trim).filter(|c| !c.is_empty()) else {
+            return Ok(None);
+        };
+        headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(column))
+            .or_else(|| {
+                column
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|position| (1..=headers.len()).contains(position))
+                    .map(|position| position - 1)
+            })
+            .map(Some)
+            .ok_or_else(|| {
+                ImportServiceError::Validation(format!("Column {column} not found in the file"))
+            })
+    };
+
+    Ok(Columns {
+        date: find(&Some(profile.date_column.clone()))?.unwrap_or_default(),
+        amount: find(&profile.amount_column)?,
+        debit: find(&profile.debit_column)?,
+        credit: find(&profile.credit_column)?,
+        payee: find(&profile.payee_column)?,
+        notes: find(&profile.notes_column)?,
+        category: find(&profile.category_column)?,
+        external_id: find(&profile.external_id_column)?,
+    })
+}
+
+fn parse_row(
+    values: &[String],
+    columns: &Columns,
+    profile: &CsvImportProfileInput,
+) -> Result<CsvParsedRow, Vec<String>> {
+    let cell = |index: Option<usize>| {
+        index
+            .and_then(|index| values.get(index))
+            .map(String::as_str)
+            .unwrap_or_default()
+    };
+    let text =
+        |index: Option<usize>| Some(cell(index).to_string()).filter(|value| !value.is_empty());
+    let decimal = if profile.decimal_separator == "," {
+        ','
+    } else {
+        '.'
+    };
+    let mut errors = Vec::new();
+
+    let date = cell(Some(columns.date));
+    let occurred_on = parse_csv_date(date, &profile.date_format);
+    if occurred_on.is_none() {
+        errors.push(format!(
+            "Invalid date {date:?} for format {}",
+            profile.date_format
+        ));
+    }
+
+    // Money into the account is positive
+    let inflow = match columns.amount {
+        Some(index) => {
+            let value = cell(Some(index));
+            match parse_csv_amount(value, decimal) {
+                Some(cents) if profile.sign_convention == CsvSignConvention::PositiveIsExpense => {
+                    Some(-cents)
+                }
+                Some(cents) => Some(cents),
+                None => {
+                    errors.push(format!("Invalid amount {value:?}"));
+                    None
+                }
+            }
+        }
+        None => {
+            let mut side = |index: Option<usize>| {
+                let value = cell(index);
+                if value.is_empty() {
+                    return Some(0);
+                }
+                let cents = parse_csv_amount(value, decimal).map(i64::abs);
+                if cents.is_none() {
+                    errors.push(format!("Invalid amount {value:?}"));
+                }
+                cents
+            };
+            let debit = side(columns.debit);
+            let credit = side(columns.credit);
+            debit.zip(credit).map(|(debit, credit)| credit - debit)
+        }
+    };
+    if inflow == Some(0) {
+        errors.push("Amount is zero or missing".into());
+    }
+
+    match (occurred_on, inflow) {
+        (Some(occurred_on), Some(inflow)) if errors.is_empty() => Ok(CsvParsedRow {
+            occurred_on: occurred_on.format("%Y-%m-%d").to_string(),
+            kind: if inflow < 0 {
+                TransactionKind::Expense
+            } else {
+                TransactionKind::Income
+            },
+            amount_cents: inflow.abs(),
+            payee: text(columns.payee),
+            notes: text(columns.notes),
+            category: text(columns.category),
+            external_id: text(columns.external_id),
+        }),
+        _ => Err(errors),
+    }
+}
+
+/// Parses with a chrono format; formats with a time part keep only the date.
+fn parse_csv_date(value: &str, format: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, format)
+        .or_else(|_| NaiveDateTime::parse_from_str(value, format).map(|value| value.date()))
+        .ok()
+}
+
+/// Reads `-1,234.56`, `(12.00)`, `12.00-`, `€ 1.234,56` and similar into signed cents.
+/// Everything but digits and the decimal separator is dropped, so thousands separators,
+/// spaces and currency symbols are ignored; letters make the value invalid.
+pub(crate) fn parse_csv_amount(value: &str, decimal: char) -> Option<i64> {
+    let value = value.trim();
+    if value.chars().any(|c| c.is_alphabetic()) {
+        return None;
+    }
+    let negative = value.starts_with('-')
+        || value.ends_with('-')
+        || (value.starts_with('(') && value.ends_with(')'));
+    let digits: String = value
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == decimal)
+        .map(|c| if c == decimal { '.' } else { c })
+        .collect();
+    if digits.matches('.').count() > 1 || !digits.bytes().any(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let cents = parse_amount(&digits)?;
+    Some(if negative { -cents } else { cents })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> CsvImportProfileInput {
+        CsvImportProfileInput {
+            name: "Bank".into(),
+            account_id: None,
+            delimiter: ";".into(),
+            encoding: "windows-1252".into(),
+            header_row_offset: 2,
+            has_header: true,
+            date_column: "Buchungstag".into(),
+            date_format: "%d.%m.%Y".into(),
+            amount_column: None,
+            debit_column: Some("Soll".into()),
+            credit_column: Some("Haben".into()),
+            sign_convention: CsvSignConvention::NegativeIsExpense,
+            decimal_separator: ",".into(),
+            payee_column: Some("Empf\u{e4}nger".into()),
+            notes_column: Some("5".into()),
+            category_column: None,
+            external_id_column: None,
+        }
+    }
+
+    #[test]
+    fn reads_debit_credit_layout_with_offset() {
+        let file =
+            b"Kontoauszug\r\nKonto 123\r\nBuchungstag;Empf\xe4nger;Soll;Haben;Verwendungszweck\r\n\
+05.01.2025;B\xe4ckerei;1.234,50;;Br\xf6tchen\r\n\
+06.01.2025;Arbeitgeber;;2.000,00;Gehalt\r\n\
+;;;;\r\n\
+32.01.2025;Kaputt;abc;;\r\n";
+        let table = read_csv(file, &profile()).unwrap();
+        assert_eq!(table.headers[1], "Empfänger");
+        assert_eq!(table.rows.len(), 3);
+
+        let bakery = table.rows[0].parsed.as_ref().unwrap();
+        assert_eq!(table.rows[0].line, 4);
+        assert_eq!(bakery.occurred_on, "2025-01-05");
+        assert_eq!(bakery.kind, TransactionKind::Expense);
+        assert_eq!(bakery.amount_cents, 123_450);
+        assert_eq!(bakery.payee.as_deref(), Some("Bäckerei"));
+        assert_eq!(bakery.notes.as_deref(), Some("Brötchen"));
+        assert_eq!(
+            table.rows[1].parsed.as_ref().unwrap().kind,
+            TransactionKind::Income
+        );
+
+        let errors = table.rows[2].parsed.as_ref().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(table.rows[2].line, 7);
+    }
+
+    #[test]
+    fn reads_signed_amounts_by_convention() {
+        let mut profile = profile();
+        profile.delimiter = ",".into();
+        profile.encoding = "utf-8".into();
+        profile.header_row_offset = 0;
+        profile.has_header = false;
+        profile.date_column = "1".into();
+        profile.date_format = "%Y-%m-%dT%H:%M:%S".into();
+        profile.decimal_separator = ".".into();
+        profile.debit_column = None;
+        profile.credit_column = None;
+        profile.amount_column = Some("2".into());
+        profile.payee_column = None;
+        profile.notes_column = None;
+        profile.sign_convention = CsvSignConvention::PositiveIsExpense;
+
+        let table = read_csv(b"2025-02-01T10:00:00,\"$1,020.00\"\n", &profile).unwrap();
+        assert_eq!(table.headers, ["1", "2"]);
+        let row = table.rows[0].parsed.as_ref().unwrap();
+        assert_eq!(
+            (row.kind.clone(), row.amount_cents),
+            (TransactionKind::Expense, 102_000)
+        );
+
+        profile.amount_column = Some("7".into());
+        assert!(read_csv(b"2025-02-01T10:00:00,1\n", &profile).is_err());
+        assert_eq!(parse_csv_amount("(12.50)", '.'), Some(-1_250));
+        assert_eq!(parse_csv_amount("12.50-", '.'), Some(-1_250));
+        assert_eq!(parse_csv_amount("1.2.3", '.'), None);
+    }
+}
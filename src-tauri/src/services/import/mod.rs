@@ -0,0 +1,401 @@
+mod csv_file;
+mod ofx;
+mod profiles;
+mod qif;
+mod sqlite;
+
+pub use ofx::{decode_statement_file, parse_ofx, OfxStatement, OfxTransaction};
+pub use qif::{parse_qif, write_qif, QifAccountType, QifSection, QifSplit, QifTransaction};
+pub use sqlite::SqliteImportService;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    transactions::{DuplicateReason, ImportDecisionInput, TransactionKind},
+    CategoryDto, ServiceDescriptor, SuspectedDuplicateDto, TransactionDto, TransactionServiceError,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOfxInput {
+    /// `.ofx`/`.qfx` file, OFX 1.x (SGML) or 2.x (XML).
+    pub file_path: String,
+    /// Account for a single-statement file whose `ACCTID` matches no account's
+    /// `syncExternalId`. The number is remembered on the account for later imports.
+    #[serde(default)]
+    pub account_id: Option<String>,
+    /// What to do with lines an earlier import of the same file flagged, by their index.
+    #[serde(default)]
+    pub decisions: Vec<ImportDecisionInput>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfxStatementSummaryDto {
+    /// `ACCTID` as it appears in the file.
+    pub account_number: String,
+    /// Matched account; `None` when no account carries this number, in which case the
+    /// statement was not imported.
+    pub account_id: Option<String>,
+    pub account_name: Option<String>,
+    pub currency: Option<String>,
+    pub transaction_count: usize,
+    pub imported: usize,
+    /// Lines skipped or merged because their `FITID` was imported before, or because an
+    /// existing transaction has the same amount, date and memo.
+    pub duplicates: usize,
+    pub ledger_balance_cents: Option<i64>,
+    pub ledger_balance_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfxImportResult {
+    /// Import batch the transactions were recorded under; rolling it back undoes them.
+    pub batch_id: String,
+    pub statements: Vec<OfxStatementSummaryDto>,
+    pub transactions: Vec<TransactionDto>,
+    /// Lines skipped only because they look like existing transactions.
+    pub flagged: Vec<SuspectedDuplicateDto>,
+}
+
+/// Field order of `D` dates, which QIF leaves to the exporting program's locale.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum QifDateOrder {
+    #[default]
+    MonthFirst,
+    DayFirst,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportQifInput {
+    pub file_path: String,
+    /// Account for registers without an `!Account` name, or for a single-register file
+    /// whose name matches no account.
+    #[serde(default)]
+    pub account_id: Option<String>,
+    #[serde(default)]
+    pub date_order: QifDateOrder,
+    /// What to do with lines an earlier import of the same file flagged, by their index.
+    #[serde(default)]
+    pub decisions: Vec<ImportDecisionInput>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QifSectionSummaryDto {
+    /// Name from the `!Account` block, if the file has one.
+    pub account_name: Option<String>,
+    /// `Bank`, `Cash`, `CCard`, `Oth A` or `Oth L`.
+    pub account_type: String,
+    /// Matched account; `None` when the register was not imported.
+    pub account_id: Option<String>,
+    pub transaction_count: usize,
+    pub imported: usize,
+    /// Zero-amount lines, and incoming transfers whose sending side is in the same file.
+    pub skipped: usize,
+    /// Lines skipped or merged because an existing transaction has the same amount, date
+    /// and memo.
+    pub duplicates: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QifImportResult {
+    pub batch_id: String,
+    pub sections: Vec<QifSectionSummaryDto>,
+    pub transactions: Vec<TransactionDto>,
+    /// Categories created for `Parent:Child` paths that did not exist yet.
+    pub created_categories: Vec<CategoryDto>,
+    /// Lines skipped only because they look like existing transactions.
+    pub flagged: Vec<SuspectedDuplicateDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportQifInput {
+    /// Accounts to export; every account when empty.
+    #[serde(default)]
+    pub account_ids: Vec<String>,
+    /// Inclusive `YYYY-MM-DD` bounds.
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    #[serde(default)]
+    pub date_order: QifDateOrder,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QifExportDto {
+    pub contents: String,
+    pub account_count: usize,
+    pub transaction_count: usize,
+}
+
+/// How a single signed amount column maps onto income and expenses.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CsvSignConvention {
+    /// Negative amounts are expenses, as most bank statements write them.
+    #[default]
+    NegativeIsExpense,
+    /// Positive amounts are expenses, as many credit card statements write them.
+    PositiveIsExpense,
+}
+
+impl CsvSignConvention {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CsvSignConvention::NegativeIsExpense => "negative_is_expense",
+            CsvSignConvention::PositiveIsExpense => "positive_is_expense",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "positive_is_expense" => CsvSignConvention::PositiveIsExpense,
+            _ => CsvSignConvention::NegativeIsExpense,
+        }
+    }
+}
+
+/// Saved column mapping for one bank's CSV layout. Columns are header names, matched
+/// case-insensitively, or 1-based positions for files without a header row.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvImportProfileDto {
+    pub id: String,
+    pub name: String,
+    pub account_id: Option<String>,
+    pub delimiter: String,
+    pub encoding: String,
+    pub header_row_offset: u32,
+    pub has_header: bool,
+    pub date_column: String,
+    pub date_format: String,
+    pub amount_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub credit_column: Option<String>,
+    pub sign_convention: CsvSignConvention,
+    pub decimal_separator: String,
+    pub payee_column: Option<String>,
+    pub notes_column: Option<String>,
+    pub category_column: Option<String>,
+    pub external_id_column: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvImportProfileInput {
+    pub name: String,
+    /// Account imports with this profile go to unless the request names one.
+    #[serde(default)]
+    pub account_id: Option<String>,
+    #[serde(default = "default_csv_delimiter")]
+    pub delimiter: String,
+    /// `encoding_rs` label such as `utf-8`, `windows-1252` or `iso-8859-1`.
+    #[serde(default = "default_csv_encoding")]
+    pub encoding: String,
+    /// Lines to skip before the header row, or before the first data row without one.
+    #[serde(default)]
+    pub header_row_offset: u32,
+    #[serde(default = "default_csv_has_header")]
+    pub has_header: bool,
+    pub date_column: String,
+    /// chrono format string such as `%d/%m/%Y`; a time part is ignored.
+    #[serde(default = "default_csv_date_format")]
+    pub date_format: String,
+    /// Signed amount column, read with `sign_convention`.
+    #[serde(default)]
+    pub amount_column: Option<String>,
+    /// Money out, for layouts that split amounts into two unsigned columns.
+    #[serde(default)]
+    pub debit_column: Option<String>,
+    /// Money in.
+    #[serde(default)]
+    pub credit_column: Option<String>,
+    #[serde(default)]
+    pub sign_convention: CsvSignConvention,
+    /// `.` or `,`; the other character is read as a thousands separator.
+    #[serde(default = "default_csv_decimal_separator")]
+    pub decimal_separator: String,
+    #[serde(default)]
+    pub payee_column: Option<String>,
+    #[serde(default)]
+    pub notes_column: Option<String>,
+    /// Category name or `Parent:Child` path; unknown names are left uncategorized.
+    #[serde(default)]
+    pub category_column: Option<String>,
+    /// Bank reference used to skip rows imported before.
+    #[serde(default)]
+    pub external_id_column: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCsvImportProfileInput {
+    pub id: String,
+    #[serde(flatten)]
+    pub profile: CsvImportProfileInput,
+}
+
+fn default_csv_delimiter() -> String {
+    ",".into()
+}
+
+fn default_csv_encoding() -> String {
+    "utf-8".into()
+}
+
+fn default_csv_has_header() -> bool {
+    true
+}
+
+fn default_csv_date_format() -> String {
+    "%Y-%m-%d".into()
+}
+
+fn default_csv_decimal_separator() -> String {
+    ".".into()
+}
+
+/// A CSV file plus the mapping to read it with: a saved profile, or an unsaved `profile`
+/// while the user is still adjusting columns.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvImportInput {
+    pub file_path: String,
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    #[serde(default)]
+    pub profile: Option<CsvImportProfileInput>,
+    /// Overrides the profile's account.
+    #[serde(default)]
+    pub account_id: Option<String>,
+    /// Preview only: rows to return. Counts always cover the whole file.
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Commit only: import the valid rows even when others have errors.
+    #[serde(default)]
+    pub skip_invalid: bool,
+    /// Commit only: what to do with rows the preview flagged as duplicates, with `index`
+    /// holding the row's `line`. Flagged rows without a decision are skipped.
+    #[serde(default)]
+    pub decisions: Vec<ImportDecisionInput>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvParsedTransactionDto {
+    /// `YYYY-MM-DD`.
+    pub occurred_on: String,
+    #[serde(rename = "type")]
+    pub kind: TransactionKind,
+    pub amount_cents: i64,
+    pub payee: Option<String>,
+    pub notes: Option<String>,
+    pub category_id: Option<String>,
+    pub category_name: Option<String>,
+    pub external_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvPreviewRowDto {
+    /// 1-based line in the file.
+    pub line: usize,
+    pub values: Vec<String>,
+    pub transaction: Option<CsvParsedTransactionDto>,
+    pub errors: Vec<String>,
+    /// Set when the row looks like a transaction already on the account.
+    pub duplicate_reason: Option<DuplicateReason>,
+    pub duplicate_of: Vec<TransactionDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvPreviewDto {
+    pub headers: Vec<String>,
+    pub rows: Vec<CsvPreviewRowDto>,
+    pub total_rows: usize,
+    pub error_rows: usize,
+    /// Rows suspected to be duplicates; only checked once an account is chosen.
+    pub duplicate_rows: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvCommitResultDto {
+    pub batch_id: String,
+    pub transactions: Vec<TransactionDto>,
+    /// Existing transactions that duplicate rows were merged into.
+    pub merged: Vec<TransactionDto>,
+    /// Rows left out because they failed validation.
+    pub invalid_rows: usize,
+    /// Suspected duplicates that were skipped.
+    pub duplicates: usize,
+}
+
+#[derive(Debug, Error)]
+pub enum ImportServiceError {
+    #[error("database error: {0}")]
+    Database(String),
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("validation error: {0}")]
+    Validation(String),
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+pub type ImportResult<T> = Result<T, ImportServiceError>;
+
+impl From<rusqlite::Error> for ImportServiceError {
+    fn from(err: rusqlite::Error) -> Self {
+        ImportServiceError::Database(err.to_string())
+    }
+}
+
+impl From<TransactionServiceError> for ImportServiceError {
+    fn from(err: TransactionServiceError) -> Self {
+        match err {
+            TransactionServiceError::Database(message) => ImportServiceError::Database(message),
+            TransactionServiceError::NotFound(message) => ImportServiceError::NotFound(message),
+            TransactionServiceError::Validation(message)
+            | TransactionServiceError::Reconciled(message) => {
+                ImportServiceError::Validation(message)
+            }
+            TransactionServiceError::Internal(message) => ImportServiceError::Internal(message),
+        }
+    }
+}
+
+pub trait ImportService: Send + Sync {
+    fn descriptor(&self) -> ServiceDescriptor;
+    /// Imports every bank and credit card statement in an OFX/QFX file into the account
+    /// matched by its `ACCTID`, skipping lines whose `FITID` was already imported.
+    fn import_ofx(&self, input: ImportOfxInput) -> ImportResult<OfxImportResult>;
+    /// Imports the registers of a QIF file into accounts matched by name, creating missing
+    /// categories. `[Account]` transfers become transfers between the two accounts.
+    fn import_qif(&self, input: ImportQifInput) -> ImportResult<QifImportResult>;
+    fn export_qif(&self, input: ExportQifInput) -> ImportResult<QifExportDto>;
+    fn list_csv_profiles(&self) -> ImportResult<Vec<CsvImportProfileDto>>;
+    fn create_csv_profile(&self, input: CsvImportProfileInput)
+        -> ImportResult<CsvImportProfileDto>;
+    fn update_csv_profile(
+        &self,
+        input: UpdateCsvImportProfileInput,
+    ) -> ImportResult<CsvImportProfileDto>;
+    fn delete_csv_profile(&self, id: &str) -> ImportResult<()>;
+    /// Parses the file with the given mapping without writing anything, reporting
+    /// validation errors and suspected duplicates per row.
+    fn preview_csv(&self, input: CsvImportInput) -> ImportResult<CsvPreviewDto>;
+    /// Imports the parsed rows in one `import_transactions` call. Fails when any row is
+    /// invalid unless `skip_invalid` is set; suspected duplicates follow `decisions`.
+    fn commit_csv(&self, input: CsvImportInput) -> ImportResult<CsvCommitResultDto>;
+}
@@ -0,0 +1,429 @@
+use chrono::NaiveDate;
+use encoding_rs::{Encoding, WINDOWS_1252};
+
+use super::{ImportResult, ImportServiceError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfxStatement {
+    /// `ACCTID` of the bank or credit card account.
+    pub account_number: String,
+    pub bank_id: Option<String>,
+    /// `ACCTTYPE` for bank accounts (`CHECKING`, `SAVINGS`, ...), `CREDITCARD` otherwise.
+    pub account_type: String,
+    pub currency: Option<String>,
+    pub transactions: Vec<OfxTransaction>,
+    pub ledger_balance_cents: Option<i64>,
+    pub ledger_balance_date: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfxTransaction {
+    pub fitid: Option<String>,
+    pub transaction_type: Option<String>,
+    /// `YYYY-MM-DD`.
+    pub posted_on: String,
+    /// Signed as in the file: negative amounts leave the account.
+    pub amount_cents: i64,
+    pub name: Option<String>,
+    pub memo: Option<String>,
+    pub check_number: Option<String>,
+}
+
+/// Decodes a downloaded statement. UTF-8 is used when valid; otherwise the charset
+/// declared in the OFX header or XML prolog, falling back to Windows-1252, which is what
+/// US and European banks send in practice.
+pub fn decode_statement_file(bytes: &[u8]) -> String {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.trim_start_matches('\u{feff}').to_string();
+    }
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]);
+    let encoding = declared_charset(&head)
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(WINDOWS_1252);
+    encoding.decode(bytes).0.into_owned()
+}
+
+fn declared_charset(head: &str) -> Option<String> {
+    for line in head.lines() {
+        if let Some(value) = line.trim().strip_prefix("CHARSET:") {
+            let value = value.trim();
+            // OFX 1.x writes bare code page numbers
+            return match value {
+                "NONE" | "" => None,
+                value if value.bytes().all(|byte| byte.is_ascii_digit()) => {
+                    Some(format!("windows-{value}"))
+                }
+                value => Some(value.to_string()),
+            };
+        }
+    }
+    let start = head.find("encoding=")? + "encoding=".len();
+    let quote = head[start..].chars().next()?;
+    if !matches!(quote, '"' | '\'') {
+        return None;
+    }
+    let rest = &head[start + 1..];
+    Some(rest[..rest.find(quote)?].to_string())
+}
+
+/// Parses the bank (`STMTRS`) and credit card (`CCSTMTRS`) statements in an OFX file.
+///
+/// OFX 1.x is SGML, where value elements have no closing tag; 2.x is XML. Both are read
+/// with the same tolerant reader: an element holding text ends at the next tag, and a
+/// closing tag ends every element opened since its match.
+pub fn parse_ofx(contents: &str) -> ImportResult<Vec<OfxStatement>> {
+    let root = parse_tree(contents)?;
+
+    let mut statements = Vec::new();
+    for (aggregate, account) in [("STMTRS", "BANKACCTFROM"), ("CCSTMTRS", "CCACCTFROM")] {
+        let mut found = Vec::new();
+        root.descendants(aggregate, &mut found);
+        for statement in found {
+            statements.push(parse_statement(statement, account)?);
+        }
+    }
+
+    if statements.is_empty() {
+        return Err(ImportServiceError::Validation(
+            "No bank or credit card statements found in OFX file".into(),
+        ));
+    }
+    Ok(statements)
+}
+
+fn parse_statement(statement: &Element, account_tag: &str) -> ImportResult<OfxStatement> {
+    let account = statement
+        .child(account_tag)
+        .ok_or_else(|| invalid(&format!("statement without {account_tag}")))?;
+    let account_number = account
+        .value("ACCTID")
+        .ok_or_else(|| invalid("statement without ACCTID"))?
+        .to_string();
+
+    let mut transactions = Vec::new();
+    if let Some(list) = statement.child("BANKTRANLIST") {
+        for line in list.children.iter().filter(|child| child.name == "STMTTRN") {
+            transactions.push(parse_transaction(line)?);
+        }
+    }
+
+    let ledger = statement.child("LEDGERBAL");
+    Ok(OfxStatement {
+        bank_id: account.value("BANKID").map(str::to_string),
+        account_type: account
+            .value("ACCTTYPE")
+            .unwrap_or("CREDITCARD")
+            .to_string(),
+        account_number,
+        currency: statement.value("CURDEF").map(str::to_ascii_uppercase),
+        transactions,
+        ledger_balance_cents: ledger
+            .and_then(|ledger| ledger.value("BALAMT"))
+            .and_then(parse_amount),
+        ledger_balance_date: ledger
+            .and_then(|ledger| ledger.value("DTASOF"))
+            .and_then(parse_date),
+    })
+}
+
+fn parse_transaction(line: &Element) -> ImportResult<OfxTransaction> {
+    let fitid = line.value("FITID").map(str::to_string);
+    let label = fitid.as_deref().unwrap_or("without FITID");
+    let posted_on = line
+        .value("DTPOSTED")
+        .and_then(parse_date)
+        .ok_or_else(|| invalid(&format!("transaction {label} has no valid DTPOSTED")))?;
+    let amount_cents = line
+        .value("TRNAMT")
+        .and_then(parse_amount)
+        .ok_or_else(|| invalid(&format!("transaction {label} has no valid TRNAMT")))?;
+    let name = line
+        .value("NAME")
+        .or_else(|| line.child("PAYEE").and_then(|payee| payee.value("NAME")));
+
+    Ok(OfxTransaction {
+        transaction_type: line.value("TRNTYPE").map(str::to_string),
+        posted_on,
+        amount_cents,
+        name: name.map(str::to_string),
+        memo: line.value("MEMO").map(str::to_string),
+        check_number: line.value("CHECKNUM").map(str::to_string),
+        fitid,
+    })
+}
+
+fn invalid(reason: &str) -> ImportServiceError {
+    ImportServiceError::Validation(format!("Invalid OFX file: {reason}"))
+}
+
+/// `YYYYMMDD[HHMMSS[.XXX]][[offset:TZ]]`; only the calendar date is kept.
+fn parse_date(value: &str) -> Option<String> {
+    let date = NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()?;
+    Some(date.format("%Y-%m-%d").to_string())
+}
+
+/// Decimal amount in cents. Accepts `,` as the decimal separator, which some banks use.
+pub(crate) fn parse_amount(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let digits = digits.replace(',', ".");
+    let (whole, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+    if whole.is_empty() && fraction.is_empty()
+        || !whole.bytes().all(|byte| byte.is_ascii_digit())
+        || !fraction.bytes().all(|byte| byte.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let whole: i64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().ok()?
+    };
+    let mut fraction = fraction.to_string();
+    fraction.push_str("000");
+    let thousandths: i64 = fraction[..3].parse().ok()?;
+    let cents = whole.checked_mul(100)? + (thousandths + 5) / 10;
+    Some(if negative { -cents } else { cents })
+}
+
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    text: Option<String>,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.child(name)?.text.as_deref()
+    }
+
+    fn descendants<'a>(&'a self, name: &str, found: &mut Vec<&'a Element>) {
+        for child in &self.children {
+            if child.name == name {
+                found.push(child);
+            } else {
+                child.descendants(name, found);
+            }
+        }
+    }
+}
+
+fn parse_tree(contents: &str) -> ImportResult<Element> {
+    // Everything before <OFX> is the 1.x header or the XML prolog
+    let start = contents
+        .find("<OFX>")
+        .or_else(|| contents.to_ascii_uppercase().find("<OFX>"))
+        .ok_or_else(|| invalid("missing <OFX> element"))?;
+
+    let mut stack = vec![Element::default()];
+    let mut rest = &contents[start..];
+    while let Some(open) = rest.find('<') {
+        push_text(&mut stack, &rest[..open]);
+        rest = &rest[open..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let close = rest.find('>').ok_or_else(|| invalid("unterminated tag"))?;
+        let tag = rest[1..close].trim();
+        rest = &rest[close + 1..];
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+
+        if let Some(name) = tag.strip_prefix('/') {
+            close_element(&mut stack, &name.trim().to_ascii_uppercase());
+            continue;
+        }
+        // A value element ends where the next tag begins
+        if stack.len() > 1 && stack.last().is_some_and(|top| top.text.is_some()) {
+            pop_element(&mut stack);
+        }
+        let self_closing = tag.ends_with('/');
+        let name = tag
+            .trim_end_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        stack.push(Element {
+            name,
+            ..Element::default()
+        });
+        if self_closing {
+            pop_element(&mut stack);
+        }
+    }
+    push_text(&mut stack, rest);
+
+    while stack.len() > 1 {
+        pop_element(&mut stack);
+    }
+    let mut root = stack.pop().unwrap_or_default();
+    root.children
+        .pop()
+        .filter(|ofx| ofx.name == "OFX")
+        .ok_or_else(|| invalid("missing <OFX> element"))
+}
+
+fn push_text(stack: &mut [Element], text: &str) {
+    let text = text.trim();
+    if text.is_empty() || stack.len() < 2 {
+        return;
+    }
+    if let Some(top) = stack.last_mut() {
+        let decoded = decode_entities(text);
+        match &mut top.text {
+            Some(existing) => existing.push_str(&decoded),
+            None => top.text = Some(decoded),
+        }
+    }
+}
+
+fn pop_element(stack: &mut Vec<Element>) {
+    if let Some(element) = stack.pop() {
+        if let Some(parent) = stack.last_mut() {
+            parent.children.push(element);
+        }
+    }
+}
+
+fn close_element(stack: &mut Vec<Element>, name: &str) {
+    let Some(depth) = stack
+        .iter()
+        .skip(1)
+        .rposition(|element| element.name == name)
+    else {
+        return;
+    };
+    while stack.len() > depth + 1 {
+        pop_element(stack);
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(end) = rest.find(';').filter(|end| *end <= 10) else {
+            decoded.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..end];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match character {
+            Some(character) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SGML: &str = "OFXHEADER:100\r\nDATA:OFXSGML\r\nVERSION:102\r\nCHARSET:1252\r\n\r\n\
+<OFX><SIGNONMSGSRSV1><SONRS><STATUS><CODE>0<SEVERITY>INFO</STATUS><DTSERVER>20250601</SONRS></SIGNONMSGSRSV1>\
+<BANKMSGSRSV1><STMTTRNRS><TRNUID>1<STMTRS><CURDEF>usd\
+<BANKACCTFROM><BANKID>121000248<ACCTID>000123456<ACCTTYPE>CHECKING</BANKACCTFROM>\
+<BANKTRANLIST><DTSTART>20250501<DTEND>20250531\
+<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250502120000.000[-5:EST]<TRNAMT>-42.5<FITID>2025050201<NAME>SHELL OIL &amp; GAS<MEMO>Card 1234</STMTTRN>\
+<STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20250515<TRNAMT>1500,00<FITID>2025051501<NAME>ACME PAYROLL</STMTTRN>\
+</BANKTRANLIST><LEDGERBAL><BALAMT>1457.50<DTASOF>20250531</LEDGERBAL></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<?OFX OFXHEADER="200" VERSION="220" SECURITY="NONE" OLDFILEUID="NONE" NEWFILEUID="NONE"?>
+<OFX>
+  <CREDITCARDMSGSRSV1><CCSTMTTRNRS><TRNUID>1</TRNUID><CCSTMTRS>
+    <CURDEF>EUR</CURDEF>
+    <CCACCTFROM><ACCTID>4111XXXXXXXX1111</ACCTID></CCACCTFROM>
+    <BANKTRANLIST>
+      <STMTTRN>
+        <TRNTYPE>DEBIT</TRNTYPE><DTPOSTED>20250610</DTPOSTED><TRNAMT>-12.99</TRNAMT>
+        <FITID>CC-1</FITID><PAYEE><NAME>Caf&#233; Central</NAME></PAYEE>
+      </STMTTRN>
+    </BANKTRANLIST>
+  </CCSTMTRS></CCSTMTTRNRS></CREDITCARDMSGSRSV1>
+</OFX>"#;
+
+    #[test]
+    fn parses_sgml_bank_statement() {
+        let statements = parse_ofx(SGML).unwrap();
+        assert_eq!(statements.len(), 1);
+        let statement = &statements[0];
+        assert_eq!(statement.account_number, "000123456");
+        assert_eq!(statement.account_type, "CHECKING");
+        assert_eq!(statement.currency.as_deref(), Some("USD"));
+        assert_eq!(statement.ledger_balance_cents, Some(145_750));
+        assert_eq!(statement.ledger_balance_date.as_deref(), Some("2025-05-31"));
+
+        let fuel = &statement.transactions[0];
+        assert_eq!(fuel.posted_on, "2025-05-02");
+        assert_eq!(fuel.amount_cents, -4_250);
+        assert_eq!(fuel.name.as_deref(), Some("SHELL OIL & GAS"));
+        assert_eq!(fuel.memo.as_deref(), Some("Card 1234"));
+        assert_eq!(fuel.fitid.as_deref(), Some("2025050201"));
+        assert_eq!(statement.transactions[1].amount_cents, 150_000);
+    }
+
+    #[test]
+    fn parses_xml_credit_card_statement() {
+        let statements = parse_ofx(XML).unwrap();
+        assert_eq!(statements[0].account_number, "4111XXXXXXXX1111");
+        assert_eq!(statements[0].account_type, "CREDITCARD");
+        let line = &statements[0].transactions[0];
+        assert_eq!(line.name.as_deref(), Some("Café Central"));
+        assert_eq!(line.amount_cents, -1_299);
+        assert!(parse_ofx("<OFX></OFX>").is_err());
+    }
+
+    #[test]
+    fn decodes_declared_windows_1252() {
+        let bytes = b"OFXHEADER:100\r\nCHARSET:1252\r\n\r\n<OFX><NAME>Caf\xe9 \x80</OFX>";
+        assert!(decode_statement_file(bytes).contains("Café €"));
+    }
+
+    #[test]
+    fn ignores_unquoted_xml_encoding() {
+        // Invalid UTF-8 turns the byte after `encoding=` into U+FFFD in the header
+        let bytes = b"<?xml version=\"1.0\" encoding=\xff1252\"?><OFX>Caf\xe9</OFX>";
+        assert!(decode_statement_file(bytes).contains("Café"));
+    }
+}
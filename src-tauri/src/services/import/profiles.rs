@@ -0,0 +1,297 @@
+use encoding_rs::Encoding;
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+use super::{
+    CsvImportProfileDto, CsvImportProfileInput, CsvSignConvention, ImportResult,
+    ImportServiceError, UpdateCsvImportProfileInput,
+};
+
+const PROFILE_SELECT: &str = r#"
+    SELECT id, name, account_id, delimiter, encoding, header_row_offset, has_header,
+           date_column, date_format, amount_column, debit_column, credit_column,
+           sign_convention, decimal_separator, payee_column, notes_column, category_column,
+           external_id_column, created_at, updated_at
+    FROM "CsvImportProfile"
+"#;
+
+pub(crate) fn ensure_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS "CsvImportProfile" (
+            "id" TEXT NOT NULL PRIMARY KEY,
+            "user_id" TEXT NOT NULL,
+            "name" TEXT NOT NULL,
+            "account_id" TEXT,
+            "delimiter" TEXT NOT NULL DEFAULT ',',
+            "encoding" TEXT NOT NULL DEFAULT 'utf-8',
+            "header_row_offset" INTEGER NOT NULL DEFAULT 0,
+            "has_header" BOOLEAN NOT NULL DEFAULT true,
+            "date_column" TEXT NOT NULL,
+            "date_format" TEXT NOT NULL DEFAULT '%Y-%m-%d',
+            "amount_column" TEXT,
+            "debit_column" TEXT,
+            "credit_column" TEXT,
+            "sign_convention" TEXT NOT NULL DEFAULT 'negative_is_expense',
+            "decimal_separator" TEXT NOT NULL DEFAULT '.',
+            "payee_column" TEXT,
+            "notes_column" TEXT,
+            "category_column" TEXT,
+            "external_id_column" TEXT,
+            "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            "updated_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            CONSTRAINT "CsvImportProfile_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+            CONSTRAINT "CsvImportProfile_account_id_fkey" FOREIGN KEY ("account_id") REFERENCES "Account" ("id") ON DELETE SET NULL ON UPDATE CASCADE
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS "CsvImportProfile_user_id_name_key" ON "CsvImportProfile"("user_id", "name");
+        "#,
+    )
+}
+
+fn map_profile(row: &rusqlite::Row<'_>) -> rusqlite::Result<CsvImportProfileDto> {
+    let sign_convention: String = row.get(12)?;
+    Ok(CsvImportProfileDto {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        account_id: row.get(2)?,
+        delimiter: row.get(3)?,
+        encoding: row.get(4)?,
+        header_row_offset: row.get(5)?,
+        has_header: row.get(6)?,
+        date_column: row.get(7)?,
+        date_format: row.get(8)?,
+        amount_column: row.get(9)?,
+        debit_column: row.get(10)?,
+        credit_column: row.get(11)?,
+        sign_convention: CsvSignConvention::from_db(&sign_convention),
+        decimal_separator: row.get(13)?,
+        payee_column: row.get(14)?,
+        notes_column: row.get(15)?,
+        category_column: row.get(16)?,
+        external_id_column: row.get(17)?,
+        created_at: row.get(18)?,
+        updated_at: row.get(19)?,
+    })
+}
+
+pub(crate) fn list(conn: &Connection, user_id: &str) -> ImportResult<Vec<CsvImportProfileDto>> {
+    let sql = format!("{PROFILE_SELECT} WHERE user_id = ? ORDER BY name COLLATE NOCASE");
+    let mut stmt = conn.prepare(&sql)?;
+    let profiles = stmt
+        .query_map(params![user_id], map_profile)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(profiles)
+}
+
+pub(crate) fn fetch(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+) -> ImportResult<CsvImportProfileDto> {
+    let sql = format!("{PROFILE_SELECT} WHERE user_id = ? AND id = ?");
+    conn.query_row(&sql, params![user_id, id], map_profile)
+        .optional()?
+        .ok_or_else(|| ImportServiceError::NotFound(format!("Import profile {id} not found")))
+}
+
+/// Checks a mapping before it is saved or used for a one-off import.
+pub(crate) fn validate(input: &CsvImportProfileInput) -> ImportResult<()> {
+    let invalid = |message: &str| Err(ImportServiceError::Validation(message.to_string()));
+    if input.name.trim().is_empty() {
+        return invalid("Profile name is required");
+    }
+    if input.delimiter.chars().count() != 1 || !input.delimiter.is_ascii() {
+        return invalid("Delimiter must be a single ASCII character");
+    }
+    if Encoding::for_label(input.encoding.trim().as_bytes()).is_none() {
+        return Err(ImportServiceError::Validation(format!(
+            "Unknown encoding: {}",
+            input.encoding
+        )));
+    }
+    if !matches!(input.decimal_separator.as_str(), "." | ",") {
+        return invalid("Decimal separator must be '.' or ','");
+    }
+    if input.decimal_separator == input.delimiter {
+        return invalid("Decimal separator and delimiter must differ");
+    }
+    if input.date_column.trim().is_empty() || input.date_format.trim().is_empty() {
+        return invalid("Date column and date format are required");
+    }
+    let set = |column: &Option<String>| column.as_deref().is_some_and(|c| !c.trim().is_empty());
+    if !set(&input.amount_column) && !set(&input.debit_column) && !set(&input.credit_column) {
+        return invalid("Map an amount column or debit/credit columns");
+    }
+    Ok(())
+}
+
+fn ensure_account(conn: &Connection, user_id: &str, account_id: Option<&str>) -> ImportResult<()> {
+    let Some(account_id) = account_id else {
+        return Ok(());
+    };
+    let exists: bool = conn.query_row(
+        r#"SELECT EXISTS(SELECT 1 FROM "Account" WHERE user_id = ? AND id = ?)"#,
+        params![user_id, account_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(ImportServiceError::NotFound(format!(
+            "Account {account_id} not found"
+        )));
+    }
+    Ok(())
+}
+
+fn ensure_unique_name(
+    conn: &Connection,
+    user_id: &str,
+    id: Option<&str>,
+    name: &str,
+) -> ImportResult<()> {
+    let taken: bool = conn.query_row(
+        r#"SELECT EXISTS(SELECT 1 FROM "CsvImportProfile"
+           WHERE user_id = ?1 AND name = ?2 AND (?3 IS NULL OR id <> ?3))"#,
+        params![user_id, name, id],
+        |row| row.get(0),
+    )?;
+    if taken {
+        return Err(ImportServiceError::Validation(format!(
+            "An import profile named {name} already exists"
+        )));
+    }
+    Ok(())
+}
+
+fn column(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+pub(crate) fn create(
+    conn: &Connection,
+    user_id: &str,
+    input: CsvImportProfileInput,
+) -> ImportResult<CsvImportProfileDto> {
+    validate(&input)?;
+    ensure_account(conn, user_id, input.account_id.as_deref())?;
+    let name = input.name.trim();
+    ensure_unique_name(conn, user_id, None, name)?;
+
+    let id = format!("csvprof_{}", Uuid::new_v4());
+    conn.execute(
+        r#"INSERT INTO "CsvImportProfile" (
+               id, user_id, name, account_id, delimiter, encoding, header_row_offset,
+               has_header, date_column, date_format, amount_column, debit_column,
+               credit_column, sign_convention, decimal_separator, payee_column, notes_column,
+               category_column, external_id_column, created_at, updated_at
+           ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                     ?17, ?18, ?19, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)"#,
+        params![
+            id,
+            user_id,
+            name,
+            input.account_id,
+            input.delimiter,
+            input.encoding.trim(),
+            input.header_row_offset,
+            input.has_header,
+            input.date_column.trim(),
+            input.date_format,
+            column(&input.amount_column),
+            column(&input.debit_column),
+            column(&input.credit_column),
+            input.sign_convention.as_str(),
+            input.decimal_separator,
+            column(&input.payee_column),
+            column(&input.notes_column),
+            column(&input.category_column),
+            column(&input.external_id_column),
+        ],
+    )?;
+    fetch(conn, user_id, &id)
+}
+
+pub(crate) fn update(
+    conn: &Connection,
+    user_id: &str,
+    input: UpdateCsvImportProfileInput,
+) -> ImportResult<CsvImportProfileDto> {
+    let UpdateCsvImportProfileInput { id, profile } = input;
+    fetch(conn, user_id, &id)?;
+    validate(&profile)?;
+    ensure_account(conn, user_id, profile.account_id.as_deref())?;
+    let name = profile.name.trim();
+    ensure_unique_name(conn, user_id, Some(&id), name)?;
+
+    conn.execute(
+        r#"UPDATE "CsvImportProfile" SET
+               name = ?3, account_id = ?4, delimiter = ?5, encoding = ?6,
+               header_row_offset = ?7, has_header = ?8, date_column = ?9, date_format = ?10,
+               amount_column = ?11, debit_column = ?12, credit_column = ?13,
+               sign_convention = ?14, decimal_separator = ?15, payee_column = ?16,
+               notes_column = ?17, category_column = ?18, external_id_column = ?19,
+               updated_at = CURRENT_TIMESTAMP
+           WHERE user_id = ?1 AND id = ?2"#,
+        params![
+            user_id,
+            id,
+            name,
+            profile.account_id,
+            profile.delimiter,
+            profile.encoding.trim(),
+            profile.header_row_offset,
+            profile.has_header,
+            profile.date_column.trim(),
+            profile.date_format,
+            column(&profile.amount_column),
+            column(&profile.debit_column),
+            column(&profile.credit_column),
+            profile.sign_convention.as_str(),
+            profile.decimal_separator,
+            column(&profile.payee_column),
+            column(&profile.notes_column),
+            column(&profile.category_column),
+            column(&profile.external_id_column),
+        ],
+    )?;
+    fetch(conn, user_id, &id)
+}
+
+pub(crate) fn delete(conn: &Connection, user_id: &str, id: &str) -> ImportResult<()> {
+    let deleted = conn.execute(
+        r#"DELETE FROM "CsvImportProfile" WHERE user_id = ? AND id = ?"#,
+        params![user_id, id],
+    )?;
+    if deleted == 0 {
+        return Err(ImportServiceError::NotFound(format!(
+            "Import profile {id} not found"
+        )));
+    }
+    Ok(())
+}
+
+impl From<CsvImportProfileDto> for CsvImportProfileInput {
+    fn from(profile: CsvImportProfileDto) -> Self {
+        CsvImportProfileInput {
+            name: profile.name,
+            account_id: profile.account_id,
+            delimiter: profile.delimiter,
+            encoding: profile.encoding,
+            header_row_offset: profile.header_row_offset,
+            has_header: profile.has_header,
+            date_column: profile.date_column,
+            date_format: profile.date_format,
+            amount_column: profile.amount_column,
+            debit_column: profile.debit_column,
+            credit_column: profile.credit_column,
+            sign_convention: profile.sign_convention,
+            decimal_separator: profile.decimal_separator,
+            payee_column: profile.payee_column,
+            notes_column: profile.notes_column,
+            category_column: profile.category_column,
+            external_id_column: profile.external_id_column,
+        }
+    }
+}
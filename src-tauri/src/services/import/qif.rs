@@ -0,0 +1,444 @@
+use chrono::{Datelike, NaiveDate};
+
+use super::{ofx::parse_amount, ImportResult, ImportServiceError, QifDateOrder};
+
+/// Register types QIF can carry transactions for. Investment registers (`!Type:Invst`)
+/// use a different record layout and are skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QifAccountType {
+    Bank,
+    Cash,
+    CreditCard,
+    OtherAsset,
+    OtherLiability,
+}
+
+impl QifAccountType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QifAccountType::Bank => "Bank",
+            QifAccountType::Cash => "Cash",
+            QifAccountType::CreditCard => "CCard",
+            QifAccountType::OtherAsset => "Oth A",
+            QifAccountType::OtherLiability => "Oth L",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "bank" => Some(QifAccountType::Bank),
+            "cash" => Some(QifAccountType::Cash),
+            "ccard" => Some(QifAccountType::CreditCard),
+            "oth a" => Some(QifAccountType::OtherAsset),
+            "oth l" => Some(QifAccountType::OtherLiability),
+            _ => None,
+        }
+    }
+
+    /// Register type used when exporting an account of the given app type.
+    pub fn for_account_type(account_type: &str) -> Self {
+        match account_type {
+            "cash" | "wallet" => QifAccountType::Cash,
+            "credit" => QifAccountType::CreditCard,
+            "investment" => QifAccountType::OtherAsset,
+            _ => QifAccountType::Bank,
+        }
+    }
+}
+
+/// One register of a QIF file; multi-account files name it in the preceding `!Account`
+/// block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QifSection {
+    pub account_name: Option<String>,
+    pub account_type: QifAccountType,
+    pub transactions: Vec<QifTransaction>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QifTransaction {
+    /// `YYYY-MM-DD`.
+    pub date: String,
+    /// Signed as in the register: negative amounts leave the account.
+    pub amount_cents: i64,
+    pub cleared: bool,
+    pub reconciled: bool,
+    pub number: Option<String>,
+    pub payee: Option<String>,
+    pub memo: Option<String>,
+    /// `Parent:Child` path from `L`.
+    pub category: Option<String>,
+    /// Account name from an `L[Account]` transfer.
+    pub transfer_account: Option<String>,
+    pub splits: Vec<QifSplit>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QifSplit {
+    pub category: Option<String>,
+    pub transfer_account: Option<String>,
+    pub memo: Option<String>,
+    /// Signed like the parent transaction's amount.
+    pub amount_cents: i64,
+}
+
+/// Parses the bank, cash, credit card and other asset/liability registers of a QIF file.
+/// Category, class, memorized and investment lists are skipped.
+pub fn parse_qif(contents: &str, date_order: QifDateOrder) -> ImportResult<Vec<QifSection>> {
+    enum Mode {
+        Accounts,
+        Register,
+        Skip,
+    }
+
+    let mut sections: Vec<QifSection> = Vec::new();
+    let mut mode = Mode::Skip;
+    let mut account_name: Option<String> = None;
+    let mut record: Vec<(char, &str)> = Vec::new();
+
+    for (index, raw) in contents.lines().enumerate() {
+        let line = raw.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('!') {
+            let header = header.trim();
+            record.clear();
+            if header.eq_ignore_ascii_case("Account") {
+                mode = Mode::Accounts;
+            } else if let Some(kind) = header
+                .get(..5)
+                .filter(|prefix| prefix.eq_ignore_ascii_case("Type:"))
+                .map(|_| &header[5..])
+            {
+                match QifAccountType::parse(kind) {
+                    Some(account_type) => {
+                        sections.push(QifSection {
+                            account_name: account_name.clone(),
+                            account_type,
+                            transactions: Vec::new(),
+                        });
+                        mode = Mode::Register;
+                    }
+                    None => mode = Mode::Skip,
+                }
+            }
+            // `!Option:AutoSwitch` and `!Clear:AutoSwitch` only bracket the account list
+            continue;
+        }
+
+        if !line.starts_with('^') {
+            let mut chars = line.chars();
+            if let Some(code) = chars.next() {
+                record.push((code, chars.as_str().trim()));
+            }
+            continue;
+        }
+
+        match mode {
+            Mode::Accounts => {
+                if let Some((_, name)) = record.iter().find(|(code, _)| *code == 'N') {
+                    account_name = Some(name.to_string()).filter(|name| !name.is_empty());
+                }
+            }
+            Mode::Register => {
+                let transaction = parse_record(&record, date_order)
+                    .map_err(|reason| invalid(&format!("line {}: {reason}", index + 1)))?;
+                if let (Some(section), Some(transaction)) = (sections.last_mut(), transaction) {
+                    section.transactions.push(transaction);
+                }
+            }
+            Mode::Skip => {}
+        }
+        record.clear();
+    }
+
+    if sections.is_empty() {
+        return Err(invalid("no bank, cash or credit card register found"));
+    }
+    Ok(sections)
+}
+
+fn invalid(reason: &str) -> ImportServiceError {
+    ImportServiceError::Validation(format!("Invalid QIF file: {reason}"))
+}
+
+fn parse_record(
+    record: &[(char, &str)],
+    date_order: QifDateOrder,
+) -> Result<Option<QifTransaction>, String> {
+    if record.is_empty() {
+        return Ok(None);
+    }
+
+    let mut transaction = QifTransaction::default();
+    let mut date = None;
+    let mut amount = None;
+    for &(code, value) in record {
+        let text = Some(value.to_string()).filter(|value| !value.is_empty());
+        match code {
+            'D' => date = Some(value),
+            // `U` repeats `T` in newer Quicken exports
+            'T' => amount = Some(value),
+            'U' if amount.is_none() => amount = Some(value),
+            'C' => {
+                transaction.cleared = !value.is_empty();
+                transaction.reconciled = matches!(value, "X" | "x" | "R" | "r");
+            }
+            'N' => transaction.number = text,
+            'P' => transaction.payee = text,
+            'M' => transaction.memo = text,
+            'L' => (transaction.category, transaction.transfer_account) = parse_category(value),
+            'S' => {
+                let (category, transfer_account) = parse_category(value);
+                transaction.splits.push(QifSplit {
+                    category,
+                    transfer_account,
+                    ..QifSplit::default()
+                });
+            }
+            'E' => {
+                if let Some(split) = transaction.splits.last_mut() {
+                    split.memo = text;
+                }
+            }
+            '$' => {
+                if let Some(split) = transaction.splits.last_mut() {
+                    split.amount_cents = parse_qif_amount(value)
+                        .ok_or_else(|| format!("invalid split amount {value:?}"))?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let date = date.ok_or("transaction without a date")?;
+    transaction.date = parse_qif_date(date, date_order)
+        .ok_or_else(|| format!("invalid date {date:?}"))?
+        .format("%Y-%m-%d")
+        .to_string();
+    transaction.amount_cents = match amount {
+        Some(value) => {
+            parse_qif_amount(value).ok_or_else(|| format!("invalid amount {value:?}"))?
+        }
+        None => transaction
+            .splits
+            .iter()
+            .map(|split| split.amount_cents)
+            .sum(),
+    };
+    Ok(Some(transaction))
+}
+
+/// Splits an `L`/`S` value into a category path or a `[Account]` transfer, dropping the
+/// `/Class` suffix.
+fn parse_category(value: &str) -> (Option<String>, Option<String>) {
+    let value = value.split('/').next().unwrap_or_default().trim();
+    if let Some(account) = value
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        let account = account.trim();
+        return (
+            None,
+            Some(account.to_string()).filter(|name| !name.is_empty()),
+        );
+    }
+    let path = value
+        .split(':')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(":");
+    (Some(path).filter(|path| !path.is_empty()), None)
+}
+
+/// Accepts `1/5/2025`, `01/05/25`, Quicken's `1/ 5'25`, `5.1.2025` and `2025-01-05`.
+/// Two-digit years after `'` are 20xx; otherwise years below 70 are taken as 20xx.
+pub(crate) fn parse_qif_date(value: &str, date_order: QifDateOrder) -> Option<NaiveDate> {
+    let value: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    let apostrophe = value.contains('\'');
+    let parts: Vec<&str> = value.split(['/', '-', '.', '\'']).collect();
+    let [first, second, third] = parts.as_slice() else {
+        return None;
+    };
+
+    if first.len() == 4 {
+        return NaiveDate::from_ymd_opt(
+            first.parse().ok()?,
+            second.parse().ok()?,
+            third.parse().ok()?,
+        );
+    }
+    let (month, day) = match date_order {
+        QifDateOrder::MonthFirst => (first, second),
+        QifDateOrder::DayFirst => (second, first),
+    };
+    let mut year: i32 = third.parse().ok()?;
+    if third.len() <= 2 {
+        year += if apostrophe || year < 70 { 2000 } else { 1900 };
+    }
+    NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)
+}
+
+/// Decimal amount in cents; `,` is read as a thousands separator unless it is the only
+/// separator and is followed by one or two digits.
+fn parse_qif_amount(value: &str) -> Option<i64> {
+    let value: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    let decimal_comma = !value.contains('.')
+        && value.matches(',').count() == 1
+        && value
+            .rsplit(',')
+            .next()
+            .is_some_and(|tail| (1..=2).contains(&tail.len()));
+    if decimal_comma {
+        parse_amount(&value)
+    } else {
+        parse_amount(&value.replace(',', ""))
+    }
+}
+
+/// Writes registers as a multi-account QIF file, each preceded by its `!Account` block.
+pub fn write_qif(sections: &[QifSection], date_order: QifDateOrder) -> String {
+    let mut out = String::new();
+    for section in sections {
+        let kind = section.account_type.as_str();
+        if let Some(name) = &section.account_name {
+            out.push_str(&format!("!Account\nN{}\nT{kind}\n^\n", clean(name)));
+        }
+        out.push_str(&format!("!Type:{kind}\n"));
+        for transaction in &section.transactions {
+            write_transaction(&mut out, transaction, date_order);
+        }
+    }
+    out
+}
+
+fn write_transaction(out: &mut String, transaction: &QifTransaction, date_order: QifDateOrder) {
+    let date = NaiveDate::parse_from_str(&transaction.date, "%Y-%m-%d").ok();
+    let date = match (date, date_order) {
+        (Some(date), QifDateOrder::MonthFirst) => {
+            format!("{:02}/{:02}/{}", date.month(), date.day(), date.year())
+        }
+        (Some(date), QifDateOrder::DayFirst) => {
+            format!("{:02}/{:02}/{}", date.day(), date.month(), date.year())
+        }
+        (None, _) => transaction.date.clone(),
+    };
+    out.push_str(&format!(
+        "D{date}\nT{}\n",
+        format_amount(transaction.amount_cents)
+    ));
+    if transaction.reconciled {
+        out.push_str("CX\n");
+    } else if transaction.cleared {
+        out.push_str("C*\n");
+    }
+    for (code, value) in [
+        ('N', &transaction.number),
+        ('P', &transaction.payee),
+        ('M', &transaction.memo),
+    ] {
+        if let Some(value) = value {
+            out.push_str(&format!("{code}{}\n", clean(value)));
+        }
+    }
+    if let Some(category) = category_field(&transaction.category, &transaction.transfer_account) {
+        out.push_str(&format!("L{category}\n"));
+    }
+    for split in &transaction.splits {
+        let category = category_field(&split.category, &split.transfer_account).unwrap_or_default();
+        out.push_str(&format!("S{category}\n"));
+        if let Some(memo) = &split.memo {
+            out.push_str(&format!("E{}\n", clean(memo)));
+        }
+        out.push_str(&format!("${}\n", format_amount(split.amount_cents)));
+    }
+    out.push_str("^\n");
+}
+
+fn category_field(category: &Option<String>, transfer_account: &Option<String>) -> Option<String> {
+    match (category, transfer_account) {
+        (_, Some(account)) => Some(format!("[{}]", clean(account))),
+        (Some(category), None) => Some(clean(category).replace('/', "-")),
+        (None, None) => None,
+    }
+}
+
+/// QIF fields are single lines.
+fn clean(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+fn format_amount(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.unsigned_abs();
+    format!("{sign}{}.{:02}", cents / 100, cents % 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "!Option:AutoSwitch\n!Account\nNChecking\nTBank\n^\nNVisa\nTCCard\n^\n!Clear:AutoSwitch\n\
+!Account\nNChecking\nTBank\n^\n!Type:Bank\n\
+D1/ 5'25\nT-1,234.56\nCX\nN1042\nPLandlord\nMJanuary rent\nLHousing:Rent\n^\n\
+D01/06/2025\nT-100.00\nPSupermarket\nSFood:Groceries\nEweekly shop\n$-70.00\nSHousehold\n$-30.00\n^\n\
+D1/7/25\nT-250.00\nL[Visa]\n^\n\
+!Type:Cat\nNFood\nE\n^\n\
+!Account\nNVisa\nTCCard\n^\n!Type:CCard\n\
+D01/07/2025\nT250.00\nC*\nL[Checking]\n^\n";
+
+    #[test]
+    fn parses_multi_account_file() {
+        let sections = parse_qif(FILE, QifDateOrder::MonthFirst).unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].account_name.as_deref(), Some("Checking"));
+        assert_eq!(sections[1].account_type, QifAccountType::CreditCard);
+
+        let rent = &sections[0].transactions[0];
+        assert_eq!(rent.date, "2025-01-05");
+        assert_eq!(rent.amount_cents, -123_456);
+        assert!(rent.cleared && rent.reconciled);
+        assert_eq!(rent.category.as_deref(), Some("Housing:Rent"));
+        assert_eq!(rent.number.as_deref(), Some("1042"));
+
+        let groceries = &sections[0].transactions[1];
+        assert_eq!(groceries.splits.len(), 2);
+        assert_eq!(
+            groceries.splits[0].category.as_deref(),
+            Some("Food:Groceries")
+        );
+        assert_eq!(groceries.splits[0].memo.as_deref(), Some("weekly shop"));
+        assert_eq!(groceries.splits[1].amount_cents, -3_000);
+
+        assert_eq!(
+            sections[0].transactions[2].transfer_account.as_deref(),
+            Some("Visa")
+        );
+        assert_eq!(sections[1].transactions[0].amount_cents, 25_000);
+    }
+
+    #[test]
+    fn reads_day_first_dates_and_decimal_commas() {
+        let file = "!Type:Cash\nD05.01.2025\nT-12,50\nPBakery\n^\n";
+        let sections = parse_qif(file, QifDateOrder::DayFirst).unwrap();
+        assert_eq!(sections[0].account_name, None);
+        assert_eq!(sections[0].transactions[0].date, "2025-01-05");
+        assert_eq!(sections[0].transactions[0].amount_cents, -1_250);
+        assert!(parse_qif("!Type:Bank\nDnope\nT1\n^\n", QifDateOrder::MonthFirst).is_err());
+        assert!(parse_qif("!Type:Invst\nD1/1/25\n^\n", QifDateOrder::MonthFirst).is_err());
+    }
+
+    #[test]
+    fn written_file_parses_back() {
+        let sections = parse_qif(FILE, QifDateOrder::MonthFirst).unwrap();
+        let written = write_qif(&sections, QifDateOrder::DayFirst);
+        assert!(written.contains("D05/01/2025\nT-1234.56\nCX\n"));
+        assert_eq!(
+            parse_qif(&written, QifDateOrder::DayFirst).unwrap(),
+            sections
+        );
+    }
+}
@@ -0,0 +1,1245 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::services::{
+    sqlcipher,
+    transactions::{
+        ImportBatchInput, ImportDecisionInput, ImportSource, TransactionKind,
+        TransactionSplitInput, TransferDirection,
+    },
+    AccountDto, CategoryDto, CreateCategoryInput, CreateTransactionInput, ImportTransactionsInput,
+    ServiceDescriptor, TransactionDto, TransactionQuery, TransactionService,
+};
+
+use super::{
+    csv_file::{read_csv, CsvTable},
+    decode_statement_file, parse_ofx, parse_qif, profiles, write_qif, CsvCommitResultDto,
+    CsvImportInput, CsvImportProfileDto, CsvImportProfileInput, CsvParsedTransactionDto,
+    CsvPreviewDto, CsvPreviewRowDto, ExportQifInput, ImportOfxInput, ImportQifInput, ImportResult,
+    ImportService, ImportServiceError, OfxImportResult, OfxStatement, OfxStatementSummaryDto,
+    OfxTransaction, QifAccountType, QifExportDto, QifImportResult, QifSection,
+    QifSectionSummaryDto, QifSplit, QifTransaction, UpdateCsvImportProfileInput,
+};
+
+const DEFAULT_USER_ID: &str = "seed-user";
+
+/// Transactions fetched per `list_transactions` call while exporting.
+const EXPORT_PAGE_SIZE: i64 = 500;
+
+/// Rows a CSV preview returns when the request sets no limit.
+const DEFAULT_PREVIEW_ROWS: usize = 100;
+
+#[derive(Clone)]
+pub struct SqliteImportService {
+    db_path: PathBuf,
+    db_key: Option<String>,
+    user_id: String,
+    transactions: Arc<dyn TransactionService>,
+}
+
+/// Account a statement is imported into.
+struct MatchedAccount {
+    id: String,
+    name: String,
+    currency: String,
+    sync_external_id: Option<String>,
+}
+
+impl SqliteImportService {
+    /// Imported lines are written through `transactions` so balances, payee resolution and
+    /// auto-categorization rules apply exactly as for manual entries.
+    pub fn new(
+        db_path: PathBuf,
+        db_key: Option<String>,
+        user_id: Option<String>,
+        transactions: Arc<dyn TransactionService>,
+    ) -> ImportResult<Self> {
+        let service = Self {
+            db_path,
+            db_key,
+            user_id: user_id.unwrap_or_else(|| DEFAULT_USER_ID.to_string()),
+            transactions,
+        };
+        service.ensure_schema()?;
+        Ok(service)
+    }
+
+    fn connection(&self) -> ImportResult<Connection> {
+        let conn = Connection::open(&self.db_path)
+            .map_err(|err| ImportServiceError::Database(err.to_string()))?;
+
+        if let Some(key) = &self.db_key {
+            sqlcipher::apply_key(&conn, key).map_err(|err| {
+                ImportServiceError::Database(format!("Failed to apply SQLCipher key: {err}"))
+            })?;
+        }
+
+        if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
+            return Err(ImportServiceError::Database(err.to_string()));
+        }
+
+        Ok(conn)
+    }
+
+    fn ensure_schema(&self) -> ImportResult<()> {
+        let conn = self.connection()?;
+        profiles::ensure_schema(&conn)?;
+        Ok(())
+    }
+
+    /// The mapping a CSV request is read with: its unsaved `profile`, else the saved one.
+    fn csv_profile(&self, input: &CsvImportInput) -> ImportResult<CsvImportProfileInput> {
+        if let Some(profile) = &input.profile {
+            profiles::validate(profile)?;
+            return Ok(profile.clone());
+        }
+        let Some(profile_id) = &input.profile_id else {
+            return Err(ImportServiceError::Validation(
+                "Choose an import profile or provide a column mapping".into(),
+            ));
+        };
+        let conn = self.connection()?;
+        Ok(profiles::fetch(&conn, &self.user_id, profile_id)?.into())
+    }
+
+    fn read_csv_file(
+        &self,
+        input: &CsvImportInput,
+    ) -> ImportResult<(CsvImportProfileInput, CsvTable, ImportBatchInput)> {
+        let profile = self.csv_profile(input)?;
+        let bytes = std::fs::read(&input.file_path)
+            .map_err(|err| ImportServiceError::Validation(format!("Cannot read file: {err}")))?;
+        let table = read_csv(&bytes, &profile)?;
+        let batch = batch_input(
+            ImportSource::Csv,
+            &input.file_path,
+            &bytes,
+            input.profile_id.clone(),
+        );
+        Ok((profile, table, batch))
+    }
+
+    /// Category id and name keyed by `category_key` of each name and `Parent:Child` path.
+    /// A bare name shared by several categories resolves to the first in sort order.
+    fn category_lookup(&self) -> ImportResult<HashMap<String, (String, String)>> {
+        let categories = self.transactions.list_categories(false)?;
+        let paths = category_paths(&categories);
+        let mut lookup = HashMap::new();
+        for category in &categories {
+            let entry = (category.id.clone(), category.name.clone());
+            if let Some(path) = paths.get(&category.id) {
+                lookup
+                    .entry(category_key(path))
+                    .or_insert_with(|| entry.clone());
+            }
+            lookup.entry(category_key(&category.name)).or_insert(entry);
+        }
+        Ok(lookup)
+    }
+
+    /// The account a CSV file goes to: the request's, else the profile's default.
+    fn csv_account(
+        &self,
+        input: &CsvImportInput,
+        profile: &CsvImportProfileInput,
+    ) -> ImportResult<Option<AccountDto>> {
+        let Some(account_id) = input.account_id.as_ref().or(profile.account_id.as_ref()) else {
+            return Ok(None);
+        };
+        self.transactions
+            .list_accounts(false, true)?
+            .into_iter()
+            .find(|account| &account.id == account_id)
+            .map(Some)
+            .ok_or_else(|| ImportServiceError::NotFound(format!("Account {account_id} not found")))
+    }
+
+    fn account_by(
+        &self,
+        conn: &Connection,
+        column: &str,
+        value: &str,
+    ) -> ImportResult<Option<MatchedAccount>> {
+        let sql = format!(
+            r#"SELECT id, name, currency, sync_external_id FROM "Account"
+               WHERE user_id = ? AND {column} = ? ORDER BY sort_order LIMIT 1"#
+        );
+        conn.query_row(&sql, params![self.user_id, value], |row| {
+            Ok(MatchedAccount {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                currency: row.get(2)?,
+                sync_external_id: row.get(3)?,
+            })
+        })
+        .optional()
+        .map_err(ImportServiceError::from)
+    }
+
+    fn match_account(
+        &self,
+        conn: &Connection,
+        statement: &OfxStatement,
+        fallback: Option<&str>,
+    ) -> ImportResult<Option<MatchedAccount>> {
+        if let Some(account) =
+            self.account_by(conn, "sync_external_id", &statement.account_number)?
+        {
+            return Ok(Some(account));
+        }
+        let Some(account_id) = fallback else {
+            return Ok(None);
+        };
+        let account = self.account_by(conn, "id", account_id)?.ok_or_else(|| {
+            ImportServiceError::NotFound(format!("Account {account_id} not found"))
+        })?;
+        if let Some(existing) = account
+            .sync_external_id
+            .as_deref()
+            .filter(|existing| !existing.is_empty())
+        {
+            return Err(ImportServiceError::Validation(format!(
+                "Account {} is linked to bank account {existing}, not {}",
+                account.name, statement.account_number
+            )));
+        }
+        Ok(Some(account))
+    }
+}
+
+fn transaction_input(
+    account: &MatchedAccount,
+    line: &OfxTransaction,
+) -> Option<CreateTransactionInput> {
+    let kind = match line.amount_cents {
+        0 => return None,
+        amount if amount < 0 => TransactionKind::Expense,
+        _ => TransactionKind::Income,
+    };
+    let notes = match (&line.memo, &line.check_number) {
+        (Some(memo), _) => Some(memo.clone()),
+        (None, Some(number)) => Some(format!("Check {number}")),
+        (None, None) => None,
+    };
+
+    Some(CreateTransactionInput {
+        id: Some(format!("tx_{}", Uuid::new_v4())),
+        account_id: account.id.clone(),
+        category_id: None,
+        kind,
+        amount_cents: line.amount_cents.abs(),
+        currency: account.currency.clone(),
+        occurred_on: format!("{}T12:00:00Z", line.posted_on),
+        notes,
+        tags: None,
+        // Posted by the bank, so already cleared
+        cleared: true,
+        goal_id: None,
+        transfer_account_id: None,
+        splits: Vec::new(),
+        recurrence_id: None,
+        exchange_rate: None,
+        payee: line.name.clone(),
+        external_id: line.fitid.clone(),
+    })
+}
+
+/// Looks up `Parent:Child` category paths, queueing the levels that do not exist yet.
+/// Queued categories get their ids up front and are created with the import.
+struct CategoryResolver {
+    categories: Vec<CategoryDto>,
+    pending: Vec<CreateCategoryInput>,
+}
+
+impl CategoryResolver {
+    /// New top-level categories get `category_type`; subcategories take their parent's.
+    fn resolve(&mut self, path: &str, category_type: &str) -> Option<String> {
+        let mut parent: Option<CategoryDto> = None;
+        for name in path.split(':') {
+            let parent_id = parent.as_ref().map(|parent| parent.id.as_str());
+            let existing = self
+                .categories
+                .iter()
+                .filter(|category| {
+                    category.parent_id.as_deref() == parent_id
+                        && category.name.trim().eq_ignore_ascii_case(name)
+                })
+                .max_by_key(|category| category.category_type == category_type)
+                .cloned();
+            let category = match existing {
+                Some(category) => category,
+                None => {
+                    let category = CategoryDto {
+                        id: format!("cat_{}", Uuid::new_v4()),
+                        name: name.trim().to_string(),
+                        category_type: parent
+                            .as_ref()
+                            .map_or(category_type, |parent| parent.category_type.as_str())
+                            .to_string(),
+                        sort_order: 0,
+                        parent_id: parent_id.map(str::to_string),
+                        icon: None,
+                        archived: false,
+                    };
+                    self.pending.push(CreateCategoryInput {
+                        id: Some(category.id.clone()),
+                        name: category.name.clone(),
+                        category_type: category.category_type.clone(),
+                        parent_id: category.parent_id.clone(),
+                        icon: None,
+                    });
+                    self.categories.push(category.clone());
+                    category
+                }
+            };
+            parent = Some(category);
+        }
+        parent.map(|category| category.id)
+    }
+}
+
+/// Maps one QIF register line onto the account it was read from. Returns `None` for lines
+/// that are not imported: zero amounts, and incoming transfers whose sending register is
+/// part of the same file and creates the transfer itself.
+fn qif_transaction_input(
+    categories: &mut CategoryResolver,
+    accounts: &[AccountDto],
+    in_file: &HashSet<&str>,
+    account: &AccountDto,
+    line: &QifTransaction,
+) -> ImportResult<Option<CreateTransactionInput>> {
+    if line.amount_cents == 0 {
+        return Ok(None);
+    }
+    let outgoing = line.amount_cents < 0;
+    let category_type = if outgoing { "expense" } else { "income" };
+    let counterpart = line
+        .transfer_account
+        .as_deref()
+        .and_then(|name| account_named(accounts, name))
+        .filter(|other| other.id != account.id);
+
+    let (kind, source, destination) = match counterpart {
+        Some(other) if outgoing => (TransactionKind::Transfer, account, Some(other)),
+        Some(other) if in_file.contains(other.id.as_str()) => return Ok(None),
+        Some(other) if other.currency == account.currency => {
+            (TransactionKind::Transfer, other, Some(account))
+        }
+        _ if outgoing => (TransactionKind::Expense, account, None),
+        _ => (TransactionKind::Income, account, None),
+    };
+    let amount_cents = line.amount_cents.abs();
+
+    let mut category_id = None;
+    let mut splits = Vec::new();
+    if destination.is_none() {
+        if let Some(path) = &line.category {
+            category_id = categories.resolve(path, category_type);
+        }
+        // The ledger needs two or more lines carrying the transaction's sign
+        let usable = line.splits.len() > 1
+            && line
+                .splits
+                .iter()
+                .all(|split| split.amount_cents.signum() == line.amount_cents.signum())
+            && line
+                .splits
+                .iter()
+                .map(|split| split.amount_cents)
+                .sum::<i64>()
+                == line.amount_cents;
+        if usable {
+            category_id = None;
+            for split in &line.splits {
+                let category_id = match &split.category {
+                    Some(path) => categories.resolve(path, category_type),
+                    None => None,
+                };
+                splits.push(TransactionSplitInput {
+                    category_id,
+                    amount_cents: split.amount_cents.abs(),
+                    notes: split.memo.clone(),
+                    goal_id: None,
+                });
+            }
+        } else if let [split] = line.splits.as_slice() {
+            if let Some(path) = &split.category {
+                category_id = categories.resolve(path, category_type);
+            }
+        }
+    }
+
+    let notes = match (&line.memo, &line.number) {
+        (Some(memo), _) => Some(memo.clone()),
+        (None, Some(number)) => Some(format!("Check {number}")),
+        (None, None) => None,
+    };
+
+    Ok(Some(CreateTransactionInput {
+        id: Some(format!("tx_{}", Uuid::new_v4())),
+        account_id: source.id.clone(),
+        category_id,
+        kind,
+        amount_cents,
+        currency: source.currency.clone(),
+        occurred_on: format!("{}T12:00:00Z", line.date),
+        notes,
+        tags: None,
+        cleared: line.cleared,
+        goal_id: None,
+        transfer_account_id: destination.map(|account| account.id.clone()),
+        splits,
+        recurrence_id: None,
+        exchange_rate: None,
+        payee: line.payee.clone(),
+        external_id: None,
+    }))
+}
+
+fn account_named<'a>(accounts: &'a [AccountDto], name: &str) -> Option<&'a AccountDto> {
+    accounts
+        .iter()
+        .find(|account| account.name.trim().eq_ignore_ascii_case(name.trim()))
+}
+
+/// Batch details for an imported file: its name and content hash.
+fn batch_input(
+    source: ImportSource,
+    file_path: &str,
+    bytes: &[u8],
+    profile_id: Option<String>,
+) -> ImportBatchInput {
+    let file_name = Path::new(file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned());
+    let file_hash = Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    ImportBatchInput {
+        source,
+        file_name,
+        file_hash: Some(file_hash),
+        profile_id,
+    }
+}
+
+/// Transactions for the valid rows of a CSV file, with the line each came from.
+fn csv_transaction_inputs(
+    table: &CsvTable,
+    account: &AccountDto,
+    categories: &HashMap<String, (String, String)>,
+) -> (Vec<usize>, Vec<CreateTransactionInput>) {
+    table
+        .rows
+        .iter()
+        .filter_map(|row| row.parsed.as_ref().ok().map(|parsed| (row.line, parsed)))
+        .map(|(line, parsed)| {
+            let input = CreateTransactionInput {
+                id: None,
+                account_id: account.id.clone(),
+                category_id: parsed
+                    .category
+                    .as_ref()
+                    .and_then(|name| categories.get(&category_key(name)))
+                    .map(|(id, _)| id.clone()),
+                kind: parsed.kind.clone(),
+                amount_cents: parsed.amount_cents,
+                currency: account.currency.clone(),
+                occurred_on: format!("{}T12:00:00Z", parsed.occurred_on),
+                notes: parsed.notes.clone(),
+                tags: None,
+                cleared: false,
+                goal_id: None,
+                transfer_account_id: None,
+                splits: Vec::new(),
+                recurrence_id: None,
+                exchange_rate: None,
+                payee: parsed.payee.clone(),
+                external_id: parsed.external_id.clone(),
+            };
+            (line, input)
+        })
+        .unzip()
+}
+
+/// Case-insensitive and ignoring leading emoji, so a bank's "Groceries" finds "🥕 Groceries".
+fn category_key(name: &str) -> String {
+    name.trim()
+        .trim_start_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// `Parent:Child` path for every category id.
+fn category_paths(categories: &[CategoryDto]) -> HashMap<String, String> {
+    let by_id: HashMap<&str, &CategoryDto> = categories
+        .iter()
+        .map(|category| (category.id.as_str(), category))
+        .collect();
+    categories
+        .iter()
+        .map(|category| {
+            let mut names = vec![category.name.trim()];
+            let mut parent = category.parent_id.as_deref();
+            while let Some(next) = parent.and_then(|id| by_id.get(id)) {
+                // Guards against a corrupted hierarchy looping forever
+                if names.len() > categories.len() {
+                    break;
+                }
+                names.push(next.name.trim());
+                parent = next.parent_id.as_deref();
+            }
+            names.reverse();
+            (category.id.clone(), names.join(":"))
+        })
+        .collect()
+}
+
+fn qif_transaction(
+    transaction: &TransactionDto,
+    paths: &HashMap<String, String>,
+) -> QifTransaction {
+    let sign = match (&transaction.kind, &transaction.transfer_direction) {
+        (TransactionKind::Income, _) | (TransactionKind::Transfer, Some(TransferDirection::In)) => {
+            1
+        }
+        _ => -1,
+    };
+    let path = |id: &Option<String>| id.as_ref().and_then(|id| paths.get(id)).cloned();
+
+    QifTransaction {
+        date: transaction
+            .occurred_on
+            .get(..10)
+            .unwrap_or(&transaction.occurred_on)
+            .to_string(),
+        amount_cents: sign * transaction.amount_cents,
+        cleared: transaction.cleared,
+        reconciled: transaction.reconciliation_id.is_some(),
+        number: None,
+        payee: transaction.payee_name.clone(),
+        memo: transaction.notes.clone(),
+        category: path(&transaction.category_id),
+        transfer_account: transaction.transfer_account_name.clone(),
+        splits: transaction
+            .splits
+            .iter()
+            .map(|split| QifSplit {
+                category: path(&split.category_id),
+                transfer_account: None,
+                memo: split.notes.clone(),
+                amount_cents: sign * split.amount_cents,
+            })
+            .collect(),
+    }
+}
+
+impl SqliteImportService {
+    /// Every transaction on the account within the export range, oldest first.
+    fn account_transactions(
+        &self,
+        account_id: &str,
+        input: &ExportQifInput,
+    ) -> ImportResult<Vec<TransactionDto>> {
+        let mut transactions = Vec::new();
+        loop {
+            let page = self.transactions.list_transactions(TransactionQuery {
+                limit: Some(EXPORT_PAGE_SIZE),
+                offset: Some(transactions.len() as i64),
+                account_id: Some(account_id.to_string()),
+                category_id: None,
+                search: None,
+                from_date: input.from_date.clone(),
+                to_date: input.to_date.clone(),
+                min_amount_cents: None,
+                max_amount_cents: None,
+                kind: None,
+                cleared: None,
+                goal_id: None,
+                tags: Vec::new(),
+                exclude_tags: Vec::new(),
+            })?;
+            let last_page = (page.len() as i64) < EXPORT_PAGE_SIZE;
+            transactions.extend(page);
+            if last_page {
+                break;
+            }
+        }
+        transactions.reverse();
+        Ok(transactions)
+    }
+}
+
+impl ImportService for SqliteImportService {
+    fn descriptor(&self) -> ServiceDescriptor {
+        ServiceDescriptor::new("ImportService", "sqlite")
+    }
+
+    fn list_csv_profiles(&self) -> ImportResult<Vec<CsvImportProfileDto>> {
+        let conn = self.connection()?;
+        profiles::list(&conn, &self.user_id)
+    }
+
+    fn create_csv_profile(
+        &self,
+        input: CsvImportProfileInput,
+    ) -> ImportResult<CsvImportProfileDto> {
+        let conn = self.connection()?;
+        profiles::create(&conn, &self.user_id, input)
+    }
+
+    fn update_csv_profile(
+        &self,
+        input: UpdateCsvImportProfileInput,
+    ) -> ImportResult<CsvImportProfileDto> {
+        let conn = self.connection()?;
+        profiles::update(&conn, &self.user_id, input)
+    }
+
+    fn delete_csv_profile(&self, id: &str) -> ImportResult<()> {
+        let conn = self.connection()?;
+        profiles::delete(&conn, &self.user_id, id)
+    }
+
+    fn preview_csv(&self, input: CsvImportInput) -> ImportResult<CsvPreviewDto> {
+        let (profile, table, _) = self.read_csv_file(&input)?;
+        let categories = self.category_lookup()?;
+        let total_rows = table.rows.len();
+        let error_rows = table.rows.iter().filter(|row| row.parsed.is_err()).count();
+
+        // Duplicates are per account, so they are only checked once one is chosen
+        let mut duplicates = HashMap::new();
+        if let Some(account) = self.csv_account(&input, &profile)? {
+            let (lines, items) = csv_transaction_inputs(&table, &account, &categories);
+            for suspected in self.transactions.find_import_duplicates(&items)? {
+                duplicates.insert(lines[suspected.index], suspected);
+            }
+        }
+        let duplicate_rows = duplicates.len();
+
+        let rows = table
+            .rows
+            .into_iter()
+            .take(input.limit.unwrap_or(DEFAULT_PREVIEW_ROWS))
+            .map(|row| {
+                let (transaction, errors) = match row.parsed {
+                    Ok(parsed) => {
+                        let category = parsed
+                            .category
+                            .as_ref()
+                            .and_then(|name| categories.get(&category_key(name)));
+                        let transaction = CsvParsedTransactionDto {
+                            occurred_on: parsed.occurred_on,
+                            kind: parsed.kind,
+                            amount_cents: parsed.amount_cents,
+                            payee: parsed.payee,
+                            notes: parsed.notes,
+                            category_id: category.map(|(id, _)| id.clone()),
+                            category_name: category.map(|(_, name)| name.clone()),
+                            external_id: parsed.external_id,
+                        };
+                        (Some(transaction), Vec::new())
+                    }
+                    Err(errors) => (None, errors),
+                };
+                let duplicate = duplicates.remove(&row.line);
+                CsvPreviewRowDto {
+                    line: row.line,
+                    values: row.values,
+                    transaction,
+                    errors,
+                    duplicate_reason: duplicate.as_ref().map(|duplicate| duplicate.reason),
+                    duplicate_of: duplicate
+                        .map(|duplicate| duplicate.matches)
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        Ok(CsvPreviewDto {
+            headers: table.headers,
+            rows,
+            total_rows,
+            error_rows,
+            duplicate_rows,
+        })
+    }
+
+    fn commit_csv(&self, input: CsvImportInput) -> ImportResult<CsvCommitResultDto> {
+        let (profile, table, batch) = self.read_csv_file(&input)?;
+        let account = self.csv_account(&input, &profile)?.ok_or_else(|| {
+            ImportServiceError::Validation("Choose the account to import into".into())
+        })?;
+
+        let invalid: Vec<(usize, &Vec<String>)> = table
+            .rows
+            .iter()
+            .filter_map(|row| row.parsed.as_ref().err().map(|errors| (row.line, errors)))
+            .collect();
+        if let Some((line, errors)) = invalid.first().filter(|_| !input.skip_invalid) {
+            return Err(ImportServiceError::Validation(format!(
+                "{} rows have errors; line {line}: {}",
+                invalid.len(),
+                errors.join(", ")
+            )));
+        }
+
+        let categories = self.category_lookup()?;
+        let (lines, items) = csv_transaction_inputs(&table, &account, &categories);
+        let decisions = input
+            .decisions
+            .into_iter()
+            .map(|decision| {
+                let index = lines
+                    .iter()
+                    .position(|line| *line == decision.index)
+                    .ok_or_else(|| {
+                        ImportServiceError::Validation(format!(
+                            "Line {} has no row to import",
+                            decision.index
+                        ))
+                    })?;
+                Ok(ImportDecisionInput { index, ..decision })
+            })
+            .collect::<ImportResult<Vec<_>>>()?;
+
+        let result = self
+            .transactions
+            .import_transactions(ImportTransactionsInput {
+                items,
+                categories: Vec::new(),
+                decisions,
+                batch,
+            })?;
+        Ok(CsvCommitResultDto {
+            batch_id: result.batch_id,
+            transactions: result.created,
+            merged: result.merged,
+            invalid_rows: invalid.len(),
+            duplicates: result.skipped.len(),
+        })
+    }
+
+    fn import_ofx(&self, input: ImportOfxInput) -> ImportResult<OfxImportResult> {
+        let bytes = std::fs::read(&input.file_path)
+            .map_err(|err| ImportServiceError::Validation(format!("Cannot read file: {err}")))?;
+        let statements = parse_ofx(&decode_statement_file(&bytes))?;
+        let fallback = input
+            .account_id
+            .as_deref()
+            .filter(|_| statements.len() == 1);
+
+        let conn = self.connection()?;
+        let mut summaries = Vec::with_capacity(statements.len());
+        let mut statement_ids = Vec::with_capacity(statements.len());
+        let mut items = Vec::new();
+        let mut linked = Vec::new();
+        for statement in &statements {
+            let account = self.match_account(&conn, statement, fallback)?;
+            let mut ids = Vec::new();
+            if let Some(account) = &account {
+                if let Some(currency) = &statement.currency {
+                    if !currency.eq_ignore_ascii_case(&account.currency) {
+                        return Err(ImportServiceError::Validation(format!(
+                            "Statement for {} is in {currency} but account {} is in {}",
+                            statement.account_number, account.name, account.currency
+                        )));
+                    }
+                }
+                for input in statement
+                    .transactions
+                    .iter()
+                    .filter_map(|line| transaction_input(account, line))
+                {
+                    ids.extend(input.id.clone());
+                    items.push(input);
+                }
+                if account
+                    .sync_external_id
+                    .as_deref()
+                    .is_none_or(str::is_empty)
+                {
+                    linked.push((account.id.clone(), statement.account_number.clone()));
+                }
+            }
+
+            summaries.push(OfxStatementSummaryDto {
+                account_number: statement.account_number.clone(),
+                account_id: account.as_ref().map(|account| account.id.clone()),
+                account_name: account.map(|account| account.name),
+                currency: statement.currency.clone(),
+                transaction_count: statement.transactions.len(),
+                imported: 0,
+                duplicates: 0,
+                ledger_balance_cents: statement.ledger_balance_cents,
+                ledger_balance_date: statement.ledger_balance_date.clone(),
+            });
+            statement_ids.push(ids);
+        }
+
+        let result = self
+            .transactions
+            .import_transactions(ImportTransactionsInput {
+                items,
+                categories: Vec::new(),
+                decisions: input.decisions,
+                batch: batch_input(ImportSource::Ofx, &input.file_path, &bytes, None),
+            })?;
+        let transactions = result.created;
+
+        let created: HashSet<&str> = transactions
+            .iter()
+            .map(|transaction| transaction.id.as_str())
+            .collect();
+        for (summary, ids) in summaries.iter_mut().zip(&statement_ids) {
+            summary.imported = ids
+                .iter()
+                .filter(|id| created.contains(id.as_str()))
+                .count();
+            summary.duplicates = ids.len() - summary.imported;
+        }
+
+        // Later imports of this bank account then match without asking again
+        for (account_id, account_number) in linked {
+            conn.execute(
+                r#"UPDATE "Account" SET sync_external_id = ?1, updated_at = CURRENT_TIMESTAMP
+                   WHERE user_id = ?2 AND id = ?3
+                     AND (sync_external_id IS NULL OR sync_external_id = '')"#,
+                params![account_number, self.user_id, account_id],
+            )?;
+        }
+
+        Ok(OfxImportResult {
+            batch_id: result.batch_id,
+            statements: summaries,
+            transactions,
+            flagged: result.flagged,
+        })
+    }
+
+    fn import_qif(&self, input: ImportQifInput) -> ImportResult<QifImportResult> {
+        let bytes = std::fs::read(&input.file_path)
+            .map_err(|err| ImportServiceError::Validation(format!("Cannot read file: {err}")))?;
+        let sections = parse_qif(&decode_statement_file(&bytes), input.date_order)?;
+
+        let accounts = self.transactions.list_accounts(false, true)?;
+        let fallback = match input.account_id.as_deref() {
+            Some(id) => Some(
+                accounts
+                    .iter()
+                    .find(|account| account.id == id)
+                    .ok_or_else(|| {
+                        ImportServiceError::NotFound(format!("Account {id} not found"))
+                    })?,
+            ),
+            None => None,
+        };
+        let single = sections.len() == 1;
+        let matched: Vec<Option<&AccountDto>> = sections
+            .iter()
+            .map(|section| {
+                section
+                    .account_name
+                    .as_deref()
+                    .and_then(|name| account_named(&accounts, name))
+                    .or(fallback.filter(|_| single || section.account_name.is_none()))
+            })
+            .collect();
+        let in_file: HashSet<&str> = matched
+            .iter()
+            .flatten()
+            .map(|account| account.id.as_str())
+            .collect();
+
+        let mut categories = CategoryResolver {
+            categories: self.transactions.list_categories(true)?,
+            pending: Vec::new(),
+        };
+        let mut summaries = Vec::with_capacity(sections.len());
+        let mut section_ids = Vec::with_capacity(sections.len());
+        let mut items = Vec::new();
+        for (section, account) in sections.iter().zip(&matched) {
+            let mut summary = QifSectionSummaryDto {
+                account_name: section.account_name.clone(),
+                account_type: section.account_type.as_str().to_string(),
+                account_id: account.map(|account| account.id.clone()),
+                transaction_count: section.transactions.len(),
+                imported: 0,
+                skipped: 0,
+                duplicates: 0,
+            };
+            let mut ids = Vec::new();
+            if let Some(account) = account {
+                for line in &section.transactions {
+                    let item =
+                        qif_transaction_input(&mut categories, &accounts, &in_file, account, line)?;
+                    match item {
+                        Some(item) => {
+                            ids.extend(item.id.clone());
+                            items.push(item);
+                        }
+                        None => summary.skipped += 1,
+                    }
+                }
+            }
+            summaries.push(summary);
+            section_ids.push(ids);
+        }
+
+        let result = self
+            .transactions
+            .import_transactions(ImportTransactionsInput {
+                items,
+                categories: categories.pending.clone(),
+                decisions: input.decisions,
+                batch: batch_input(ImportSource::Qif, &input.file_path, &bytes, None),
+            })?;
+        let written: HashSet<&str> = result
+            .created
+            .iter()
+            .map(|transaction| transaction.id.as_str())
+            .collect();
+        for (summary, ids) in summaries.iter_mut().zip(&section_ids) {
+            summary.imported = ids
+                .iter()
+                .filter(|id| written.contains(id.as_str()))
+                .count();
+            summary.duplicates = ids.len() - summary.imported;
+        }
+        let created: HashSet<_> = categories
+            .pending
+            .into_iter()
+            .filter_map(|category| category.id)
+            .collect();
+        let created_categories = self
+            .transactions
+            .list_categories(true)?
+            .into_iter()
+            .filter(|category| created.contains(&category.id))
+            .collect();
+        Ok(QifImportResult {
+            batch_id: result.batch_id,
+            sections: summaries,
+            transactions: result.created,
+            created_categories,
+            flagged: result.flagged,
+        })
+    }
+
+    fn export_qif(&self, input: ExportQifInput) -> ImportResult<QifExportDto> {
+        let accounts = self.transactions.list_accounts(false, true)?;
+        let selected = if input.account_ids.is_empty() {
+            accounts.iter().collect::<Vec<_>>()
+        } else {
+            input
+                .account_ids
+                .iter()
+                .map(|id| {
+                    accounts
+                        .iter()
+                        .find(|account| &account.id == id)
+                        .ok_or_else(|| {
+                            ImportServiceError::NotFound(format!("Account {id} not found"))
+                        })
+                })
+                .collect::<ImportResult<Vec<_>>>()?
+        };
+        let paths = category_paths(&self.transactions.list_categories(true)?);
+
+        let mut sections = Vec::with_capacity(selected.len());
+        for account in selected {
+            let transactions = self.account_transactions(&account.id, &input)?;
+            sections.push(QifSection {
+                account_name: Some(account.name.clone()),
+                account_type: QifAccountType::for_account_type(&account.account_type),
+                transactions: transactions
+                    .iter()
+                    .map(|transaction| qif_transaction(transaction, &paths))
+                    .collect(),
+            });
+        }
+
+        Ok(QifExportDto {
+            contents: write_qif(&sections, input.date_order),
+            account_count: sections.len(),
+            transaction_count: sections
+                .iter()
+                .map(|section| section.transactions.len())
+                .sum(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{
+        import::QifDateOrder, transactions::DuplicateAction, CreateAccountInput,
+        SqliteTransactionService,
+    };
+
+    const STATEMENT: &str = "OFXHEADER:100\nDATA:OFXSGML\nVERSION:102\n\n<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS>\
+<CURDEF>USD<BANKACCTFROM><BANKID>1<ACCTID>987654<ACCTTYPE>CHECKING</BANKACCTFROM><BANKTRANLIST>\
+<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250502<TRNAMT>-42.50<FITID>A1<NAME>Shell</STMTTRN>\
+<STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20250515<TRNAMT>100.00<FITID>A2<NAME>Payroll</STMTTRN>\
+</BANKTRANLIST><LEDGERBAL><BALAMT>57.50<DTASOF>20250531</LEDGERBAL></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+
+    fn setup() -> (SqliteTransactionService, SqliteImportService) {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let transactions =
+            SqliteTransactionService::new(path.clone(), None, Some("seed-user".into())).unwrap();
+        let service =
+            SqliteImportService::new(path, None, None, Arc::new(transactions.clone())).unwrap();
+        (transactions, service)
+    }
+
+    fn create_account(
+        transactions: &SqliteTransactionService,
+        name: &str,
+        account_type: &str,
+    ) -> AccountDto {
+        transactions
+            .create_account(CreateAccountInput {
+                id: None,
+                name: name.into(),
+                account_type: account_type.into(),
+                currency: "USD".into(),
+                institution: None,
+                color_token: None,
+                sync_external_id: None,
+                opening_balance_cents: 0,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn imports_statement_and_skips_known_fitids() {
+        let (transactions, service) = setup();
+        let account = create_account(&transactions, "Checking", "checking");
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), STATEMENT).unwrap();
+        let input = ImportOfxInput {
+            file_path: file.path().to_string_lossy().into_owned(),
+            account_id: Some(account.id.clone()),
+            decisions: Vec::new(),
+        };
+
+        let first = service.import_ofx(input.clone()).unwrap();
+        assert_eq!(first.statements[0].imported, 2);
+        assert_eq!(first.transactions[0].external_id.as_deref(), Some("A1"));
+        assert!(first
+            .transactions
+            .iter()
+            .all(|transaction| transaction.cleared));
+        let balance = transactions.list_accounts(false, false).unwrap();
+        let balance = balance.iter().find(|a| a.id == account.id).unwrap();
+        assert_eq!(balance.balance_cents, 5_750);
+        assert_eq!(balance.sync_external_id.as_deref(), Some("987654"));
+
+        // Matched by ACCTID now, and both FITIDs are known
+        let again = service
+            .import_ofx(ImportOfxInput {
+                account_id: None,
+                ..input
+            })
+            .unwrap();
+        assert_eq!(
+            again.statements[0].account_id.as_deref(),
+            Some(account.id.as_str())
+        );
+        assert_eq!(again.statements[0].imported, 0);
+        assert_eq!(again.statements[0].duplicates, 2);
+        assert!(again.transactions.is_empty());
+    }
+
+    const QIF: &str = "!Account\nNJoint\nTBank\n^\n!Type:Bank\n\
+D01/05/2025\nT-1,200.00\nCX\nPLandlord\nLHousing:Rent\n^\n\
+D01/06/2025\nT-100.00\nPSupermarket\nSGroceries:Produce\n$-70.00\nSHousing\nEbulbs\n$-30.00\n^\n\
+D01/07/2025\nT-250.00\nL[Visa]\n^\n\
+!Account\nNVisa\nTCCard\n^\n!Type:CCard\n\
+D01/07/2025\nT250.00\nL[Joint]\n^\n\
+D01/08/2025\nT0.00\nPVoided\n^\n";
+
+    #[test]
+    fn imports_and_exports_qif_registers() {
+        let (transactions, service) = setup();
+        let checking = create_account(&transactions, "Joint", "checking");
+        let visa = create_account(&transactions, "Visa", "credit");
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), QIF).unwrap();
+        let import = |decisions| {
+            service.import_qif(ImportQifInput {
+                file_path: file.path().to_string_lossy().into_owned(),
+                account_id: None,
+                date_order: QifDateOrder::MonthFirst,
+                decisions,
+            })
+        };
+        let imported = import(Vec::new()).unwrap();
+
+        assert_eq!(
+            imported.sections[0].account_id.as_deref(),
+            Some(checking.id.as_str())
+        );
+        assert_eq!(imported.sections[0].imported, 3);
+        // The Visa side of the transfer and the voided line
+        assert_eq!(imported.sections[1].imported, 0);
+        assert_eq!(imported.sections[1].skipped, 2);
+        assert!(imported.flagged.is_empty());
+        let created: Vec<&str> = imported
+            .created_categories
+            .iter()
+            .map(|category| category.name.as_str())
+            .collect();
+        assert_eq!(created, ["Housing", "Rent", "Groceries", "Produce"]);
+
+        let split = &imported.transactions[1];
+        assert_eq!(split.splits.len(), 2);
+        assert_eq!(split.splits[1].notes.as_deref(), Some("bulbs"));
+        let transfer = &imported.transactions[2];
+        assert_eq!(transfer.kind, TransactionKind::Transfer);
+        assert_eq!(
+            transfer.transfer_account_id.as_deref(),
+            Some(visa.id.as_str())
+        );
+
+        // Without FITIDs a second import only looks like the first; nothing is written
+        // until the user decides
+        let again = import(Vec::new()).unwrap();
+        assert_eq!(
+            (again.sections[0].imported, again.sections[0].duplicates),
+            (0, 3)
+        );
+        assert!(again.transactions.is_empty());
+        let flagged: Vec<usize> = again.flagged.iter().map(|item| item.index).collect();
+        assert_eq!(flagged, [0, 1, 2]);
+        assert_eq!(again.flagged[0].matches[0].id, imported.transactions[0].id);
+        let kept = import(vec![ImportDecisionInput {
+            index: 0,
+            action: DuplicateAction::Import,
+            target_id: None,
+        }])
+        .unwrap();
+        assert_eq!(
+            (kept.sections[0].imported, kept.sections[0].duplicates),
+            (1, 2)
+        );
+        transactions.rollback_import_batch(&kept.batch_id).unwrap();
+
+        let export = service
+            .export_qif(ExportQifInput {
+                account_ids: vec![checking.id.clone(), visa.id.clone()],
+                from_date: Some("2025-01-01".into()),
+                to_date: Some("2025-01-31".into()),
+                date_order: QifDateOrder::MonthFirst,
+            })
+            .unwrap();
+        assert_eq!(export.transaction_count, 4);
+        let sections = parse_qif(&export.contents, QifDateOrder::MonthFirst).unwrap();
+        assert_eq!(
+            sections[0].transactions[0].category.as_deref(),
+            Some("Housing:Rent")
+        );
+        assert_eq!(sections[0].transactions[0].amount_cents, -120_000);
+        assert_eq!(
+            sections[0].transactions[1].splits[0].category.as_deref(),
+            Some("Groceries:Produce")
+        );
+        assert_eq!(sections[1].account_type, QifAccountType::CreditCard);
+        assert_eq!(
+            sections[1].transactions[0].transfer_account.as_deref(),
+            Some("Joint")
+        );
+        assert_eq!(sections[1].transactions[0].amount_cents, 25_000);
+    }
+
+    #[test]
+    fn saves_profiles_and_commits_csv_rows() {
+        let (transactions, service) = setup();
+        let account = create_account(&transactions, "Savings", "savings");
+
+        let profile = service
+            .create_csv_profile(CsvImportProfileInput {
+                name: "Credit union".into(),
+                account_id: Some(account.id.clone()),
+                delimiter: ",".into(),
+                encoding: "utf-8".into(),
+                header_row_offset: 0,
+                has_header: true,
+                date_column: "Date".into(),
+                date_format: "%m/%d/%Y".into(),
+                amount_column: Some("Amount".into()),
+                debit_column: None,
+                credit_column: None,
+                sign_convention: Default::default(),
+                decimal_separator: ".".into(),
+                payee_column: Some("Description".into()),
+                notes_column: None,
+                category_column: Some("Category".into()),
+                external_id_column: Some("Reference".into()),
+            })
+            .unwrap();
+        let mut second_profile: CsvImportProfileInput = profile.clone().into();
+        assert!(service.create_csv_profile(second_profile.clone()).is_err());
+        second_profile.name = "Brokerage".into();
+        service.create_csv_profile(second_profile).unwrap();
+        assert_eq!(service.list_csv_profiles().unwrap().len(), 2);
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            file.path(),
+            "Date,Description,Amount,Category,Reference\n\
+03/01/2025,Market,-45.10,Food & Groceries,R1\n\
+03/02/2025,Salary,2500.00,,R2\n\
+03/31/2025,Broken,,,R3\n",
+        )
+        .unwrap();
+        let input = CsvImportInput {
+            file_path: file.path().to_string_lossy().into_owned(),
+            profile_id: Some(profile.id.clone()),
+            profile: None,
+            account_id: None,
+            limit: Some(2),
+            skip_invalid: false,
+            decisions: Vec::new(),
+        };
+
+        let preview = service.preview_csv(input.clone()).unwrap();
+        assert_eq!((preview.total_rows, preview.error_rows), (3, 1));
+        assert_eq!(preview.rows.len(), 2);
+        let market = preview.rows[0].transaction.as_ref().unwrap();
+        assert_eq!(market.amount_cents, 4_510);
+        assert_eq!(market.category_id.as_deref(), Some("cat-food"));
+
+        assert!(matches!(
+            service.commit_csv(input.clone()),
+            Err(ImportServiceError::Validation(_))
+        ));
+        let input = CsvImportInput {
+            skip_invalid: true,
+            ..input
+        };
+        let first = service.commit_csv(input.clone()).unwrap();
+        assert_eq!((first.transactions.len(), first.invalid_rows), (2, 1));
+        assert_eq!(first.transactions[0].account_id, account.id);
+        let second = service.commit_csv(input).unwrap();
+        assert_eq!((second.transactions.len(), second.duplicates), (0, 2));
+
+        service.delete_csv_profile(&profile.id).unwrap();
+        assert!(matches!(
+            service.delete_csv_profile(&profile.id),
+            Err(ImportServiceError::NotFound(_))
+        ));
+    }
+}
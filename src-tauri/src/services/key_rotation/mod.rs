@@ -0,0 +1,156 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use thiserror::Error;
+
+use super::{
+    attachments::{AttachmentServiceError, BlobStore},
+    sqlcipher,
+};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRotationSummary {
+    /// Attachment files re-encrypted under the new key.
+    pub attachments_rekeyed: usize,
+}
+
+#[derive(Debug, Error)]
+pub enum KeyRotationError {
+    #[error("database error: {0}")]
+    Database(String),
+    #[error("attachment error: {0}")]
+    Attachments(String),
+    #[error("neither the current nor the new key opens the database")]
+    KeyRejected,
+}
+
+impl From<rusqlite::Error> for KeyRotationError {
+    fn from(err: rusqlite::Error) -> Self {
+        KeyRotationError::Database(err.to_string())
+    }
+}
+
+impl From<AttachmentServiceError> for KeyRotationError {
+    fn from(err: AttachmentServiceError) -> Self {
+        KeyRotationError::Attachments(err.to_string())
+    }
+}
+
+/// Moves the database and attachment blobs from `old_key` to `new_key`.
+///
+/// Blobs are addressed by an HMAC under the key, so each one is copied to its new address
+/// first; then the database is re-keyed with `PRAGMA rekey` and attachment rows are
+/// pointed at the new addresses. Old blobs are left for the attachment garbage collector.
+/// Every step skips work that is already done, so an interrupted rotation is finished by
+/// running it again with the same keys.
+pub fn rotate_key(
+    db_path: &Path,
+    attachments_dir: &Path,
+    old_key: &str,
+    new_key: &str,
+) -> Result<KeyRotationSummary, KeyRotationError> {
+    let old_store = BlobStore::new(attachments_dir.to_path_buf(), old_key);
+    let new_store = BlobStore::new(attachments_dir.to_path_buf(), new_key);
+    let mut renamed = Vec::new();
+    for (hash, _) in old_store.list()? {
+        // Blobs written under the new key, or damaged ones, do not open with the old key
+        let Ok(content) = old_store.get(&hash) else {
+            continue;
+        };
+        renamed.push((hash, new_store.put(&content)?));
+    }
+
+    let mut conn = Connection::open(db_path)?;
+    if sqlcipher::apply_key(&conn, old_key).is_ok() {
+        conn.pragma_update(None, "rekey", new_key)?;
+    } else {
+        // Re-keyed by the interrupted run already
+        conn = Connection::open(db_path)?;
+        sqlcipher::apply_key(&conn, new_key).map_err(|_| KeyRotationError::KeyRejected)?;
+    }
+
+    let tx = conn.transaction()?;
+    for (old_hash, new_hash) in &renamed {
+        tx.execute(
+            r#"UPDATE "TransactionAttachment" SET content_hash = ?2 WHERE content_hash = ?1"#,
+            params![old_hash, new_hash],
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(KeyRotationSummary {
+        attachments_rekeyed: renamed.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::attachments::ensure_schema;
+
+    fn open(db_path: &Path, key: &str) -> rusqlite::Result<Connection> {
+        let conn = Connection::open(db_path)?;
+        sqlcipher::apply_key(&conn, key)?;
+        Ok(conn)
+    }
+
+    #[test]
+    fn rekeys_database_and_attachments_and_resumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("app.db");
+        let attachments = dir.path().join("attachments");
+        let old_store = BlobStore::new(attachments.clone(), "old key");
+        let hash = old_store.put(b"receipt").unwrap();
+
+        let conn = open(&db_path, "old key").unwrap();
+        conn.execute_batch(
+            r#"CREATE TABLE "User" (id TEXT PRIMARY KEY);
+               CREATE TABLE "Transaction" (id TEXT PRIMARY KEY);
+               INSERT INTO "Transaction" (id) VALUES ('tx');"#,
+        )
+        .unwrap();
+        ensure_schema(&conn).unwrap();
+        conn.execute(
+            r#"INSERT INTO "TransactionAttachment" (
+                   id, user_id, transaction_id, content_hash, file_name, mime_type, size_bytes
+               ) VALUES ('att-1', 'u', 'tx', ?1, 'r.pdf', 'application/pdf', 7)"#,
+            params![hash],
+        )
+        .unwrap();
+        drop(conn);
+
+        let summary = rotate_key(&db_path, &attachments, "old key", "new key").unwrap();
+        assert_eq!(summary.attachments_rekeyed, 1);
+        assert!(open(&db_path, "old key").is_err());
+
+        let new_store = BlobStore::new(attachments.clone(), "new key");
+        let conn = open(&db_path, "new key").unwrap();
+        let stored: String = conn
+            .query_row(
+                r#"SELECT content_hash FROM "TransactionAttachment""#,
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(new_store.get(&stored).unwrap(), b"receipt");
+        drop(conn);
+
+        // Running it again, as after a crash, changes nothing
+        rotate_key(&db_path, &attachments, "old key", "new key").unwrap();
+        let conn = open(&db_path, "new key").unwrap();
+        let again: String = conn
+            .query_row(
+                r#"SELECT content_hash FROM "TransactionAttachment""#,
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(again, stored);
+        assert!(matches!(
+            rotate_key(&db_path, &attachments, "wrong", "also wrong"),
+            Err(KeyRotationError::KeyRejected)
+        ));
+    }
+}
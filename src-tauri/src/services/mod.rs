@@ -1,18 +1,38 @@
 use std::sync::Arc;
 
+pub mod attachments;
+pub mod backup;
 pub mod budgets;
+pub mod currency;
 pub mod dashboard;
+pub mod export_crypto;
 pub mod goals;
+pub mod import;
+pub mod key_rotation;
+pub mod recurring;
 pub mod reminders;
 pub mod reports;
 pub mod settings;
+pub mod sqlcipher;
 pub mod planning;
 pub mod sync;
 pub mod transactions;
 
+pub use attachments::{
+    AttachFilesInput, AttachmentContentDto, AttachmentDto, AttachmentGcResult, AttachmentResult,
+    AttachmentService, AttachmentServiceError, SqliteAttachmentService,
+};
+pub use backup::{create_backup, BackupError, BackupSummary};
 pub use budgets::{
-    BudgetDto, BudgetEntryDto, BudgetResult, BudgetService, BudgetServiceError, BudgetStatus,
-    CreateBudgetInput, RecordSnapshotInput, SqliteBudgetService, UpdateBudgetInput,
+    AssignToEnvelopeInput, BudgetAlertDto, BudgetAlertKind, BudgetDto, BudgetEntryDto,
+    BudgetPeriodSummaryDto, BudgetResult, BudgetService, BudgetServiceError, BudgetStatus,
+    CreateBudgetInput, ListBudgetSnapshotsInput, MoveEnvelopeFundsInput, RecordSnapshotInput,
+    SqliteBudgetService, UpdateBudgetInput, ZeroBasedMonthDto,
+};
+pub use currency::{
+    ConversionDto, ConvertAmountInput, CurrencyResult, CurrencyService, CurrencyServiceError,
+    ExchangeRateDto, ExchangeRateQuery, ImportExchangeRatesInput, ImportExchangeRatesResult,
+    RateFileFormat, SqliteCurrencyService, UpsertExchangeRateInput,
 };
 pub use dashboard::{
     DashboardResult, DashboardService, DashboardServiceError, DashboardSnapshot,
@@ -22,6 +42,18 @@ pub use goals::{
     AddContributionInput, CreateGoalInput, GoalDto, GoalResult, GoalService, GoalServiceError,
     SqliteGoalService, UpdateGoalInput, UpdateGoalStatusInput,
 };
+pub use import::{
+    CsvCommitResultDto, CsvImportInput, CsvImportProfileDto, CsvImportProfileInput,
+    CsvParsedTransactionDto, CsvPreviewDto, CsvPreviewRowDto, CsvSignConvention, ExportQifInput,
+    ImportOfxInput, ImportQifInput, ImportResult, ImportService, ImportServiceError,
+    OfxImportResult, OfxStatementSummaryDto, QifDateOrder, QifExportDto, QifImportResult,
+    QifSectionSummaryDto, SqliteImportService, UpdateCsvImportProfileInput,
+};
+pub use recurring::{
+    CreateRecurringTransactionInput, RecurringResult, RecurringService, RecurringServiceError,
+    RecurringTransactionDto, SkipOccurrenceInput, SqliteRecurringService,
+    UpdateRecurringTransactionInput,
+};
 pub use reminders::{
     CreateReminderInput, DismissReminderInput, ReminderDto, ReminderResult, ReminderService,
     ReminderServiceError, SnoozeReminderInput, SqliteReminderService, UpdateReminderInput,
@@ -31,8 +63,9 @@ pub use reports::{
     SpendingByCategoryDto, SqliteReportService,
 };
 pub use settings::{
-    SettingsResult, SettingsService, SettingsServiceError, SqliteSettingsService,
-    UpdateCategoryOrderInput, UpdateUserSettingsInput, UserSettingsDto,
+    AppLockSettingsDto, SetAppLockPinInput, SettingsResult, SettingsService, SettingsServiceError,
+    SqliteSettingsService, UpdateAppLockSettingsInput, UpdateCategoryOrderInput,
+    UpdateUserSettingsInput, UserSettingsDto,
 };
 pub use sync::{
     SqliteSyncService, SyncDownloadInput, SyncDownloadResult, SyncService, SyncServiceError,
@@ -47,9 +80,17 @@ pub use planning::{
     UpdatePlannedSavingInput, GenerateDebtScheduleInput, ConfirmDebtPaymentInput,
 };
 pub use transactions::{
-    AccountDto, CategoryDto, CreateTransactionInput, ImportTransactionsInput,
-    SqliteTransactionService, TransactionDto, TransactionQuery, TransactionResult,
-    TransactionService, TransactionServiceError, UpdateTransactionInput,
+    AccountDto, AccountStatus, ApplyRulesInput, ApplyRulesResult, ArchiveCategoryInput,
+    CategoryDto, CreateAccountInput, CreateCategoryInput, CreatePayeeInput, CreateTransactionInput,
+    DeleteCategoryInput, DuplicateAction, DuplicateReason, ImportBatchDto, ImportBatchInput,
+    ImportDecisionInput, ImportSource, ImportTransactionsInput, ImportTransactionsResult,
+    MarkReconciliationInput, MergeCategoriesInput, PayeeDto, ReconciliationDetailDto,
+    ReconciliationDto, ReconciliationStatus, ReorderAccountsInput, SqliteTransactionService,
+    StartReconciliationInput, SuspectedDuplicateDto, TransactionDto, TransactionPage,
+    TransactionQuery, TransactionResult, TransactionRuleDto, TransactionRuleInput,
+    TransactionService, TransactionServiceError, UpdateAccountInput, UpdateAccountStatusInput,
+    UpdateCategoryInput, UpdatePayeeInput, UpdateReconciliationInput, UpdateTransactionInput,
+    UpdateTransactionRuleInput,
 };
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -76,17 +117,57 @@ struct NoopReminderService;
 struct NoopReportService;
 struct NoopSettingsService;
 struct NoopSyncService;
+struct NoopRecurringService;
+struct NoopImportService;
+struct NoopCurrencyService;
+struct NoopAttachmentService;
 
 impl TransactionService for NoopTransactionService {
     fn descriptor(&self) -> ServiceDescriptor {
         ServiceDescriptor::new("TransactionService", "noop")
     }
 
-    fn list_accounts(&self, _: bool) -> TransactionResult<Vec<AccountDto>> {
+    fn list_accounts(&self, _: bool, _: bool) -> TransactionResult<Vec<AccountDto>> {
+        not_configured()
+    }
+
+    fn create_account(&self, _: CreateAccountInput) -> TransactionResult<AccountDto> {
+        not_configured()
+    }
+
+    fn update_account(&self, _: UpdateAccountInput) -> TransactionResult<AccountDto> {
+        not_configured()
+    }
+
+    fn update_account_status(&self, _: UpdateAccountStatusInput) -> TransactionResult<AccountDto> {
+        not_configured()
+    }
+
+    fn reorder_accounts(&self, _: ReorderAccountsInput) -> TransactionResult<()> {
+        not_configured()
+    }
+
+    fn list_categories(&self, _: bool) -> TransactionResult<Vec<CategoryDto>> {
+        not_configured()
+    }
+
+    fn create_category(&self, _: CreateCategoryInput) -> TransactionResult<CategoryDto> {
+        not_configured()
+    }
+
+    fn update_category(&self, _: UpdateCategoryInput) -> TransactionResult<CategoryDto> {
+        not_configured()
+    }
+
+    fn archive_category(&self, _: ArchiveCategoryInput) -> TransactionResult<CategoryDto> {
+        not_configured()
+    }
+
+    fn merge_categories(&self, _: MergeCategoriesInput) -> TransactionResult<CategoryDto> {
         not_configured()
     }
 
-    fn list_categories(&self) -> TransactionResult<Vec<CategoryDto>> {
+    fn delete_category(&self, _: DeleteCategoryInput) -> TransactionResult<()> {
         not_configured()
     }
 
@@ -94,6 +175,10 @@ impl TransactionService for NoopTransactionService {
         not_configured()
     }
 
+    fn search_transactions(&self, _: TransactionQuery) -> TransactionResult<TransactionPage> {
+        not_configured()
+    }
+
     fn create_transaction(&self, _: CreateTransactionInput) -> TransactionResult<TransactionDto> {
         not_configured()
     }
@@ -102,14 +187,102 @@ impl TransactionService for NoopTransactionService {
         not_configured()
     }
 
-    fn delete_transaction(&self, _: &str) -> TransactionResult<()> {
+    fn delete_transaction(&self, _: &str, _: bool) -> TransactionResult<()> {
+        not_configured()
+    }
+
+    fn find_import_duplicates(
+        &self,
+        _: &[CreateTransactionInput],
+    ) -> TransactionResult<Vec<SuspectedDuplicateDto>> {
         not_configured()
     }
 
     fn import_transactions(
         &self,
-        _: Vec<CreateTransactionInput>,
-    ) -> TransactionResult<Vec<TransactionDto>> {
+        _: ImportTransactionsInput,
+    ) -> TransactionResult<ImportTransactionsResult> {
+        not_configured()
+    }
+
+    fn list_import_batches(&self) -> TransactionResult<Vec<ImportBatchDto>> {
+        not_configured()
+    }
+
+    fn rollback_import_batch(&self, _: &str) -> TransactionResult<ImportBatchDto> {
+        not_configured()
+    }
+
+    fn list_payees(&self) -> TransactionResult<Vec<PayeeDto>> {
+        not_configured()
+    }
+
+    fn create_payee(&self, _: CreatePayeeInput) -> TransactionResult<PayeeDto> {
+        not_configured()
+    }
+
+    fn update_payee(&self, _: UpdatePayeeInput) -> TransactionResult<PayeeDto> {
+        not_configured()
+    }
+
+    fn delete_payee(&self, _: &str) -> TransactionResult<()> {
+        not_configured()
+    }
+
+    fn list_rules(&self) -> TransactionResult<Vec<TransactionRuleDto>> {
+        not_configured()
+    }
+
+    fn create_rule(&self, _: TransactionRuleInput) -> TransactionResult<TransactionRuleDto> {
+        not_configured()
+    }
+
+    fn update_rule(&self, _: UpdateTransactionRuleInput) -> TransactionResult<TransactionRuleDto> {
+        not_configured()
+    }
+
+    fn delete_rule(&self, _: &str) -> TransactionResult<()> {
+        not_configured()
+    }
+
+    fn apply_rules(&self, _: ApplyRulesInput) -> TransactionResult<ApplyRulesResult> {
+        not_configured()
+    }
+
+    fn list_reconciliations(&self, _: Option<&str>) -> TransactionResult<Vec<ReconciliationDto>> {
+        not_configured()
+    }
+
+    fn get_reconciliation(&self, _: &str) -> TransactionResult<ReconciliationDetailDto> {
+        not_configured()
+    }
+
+    fn start_reconciliation(
+        &self,
+        _: StartReconciliationInput,
+    ) -> TransactionResult<ReconciliationDetailDto> {
+        not_configured()
+    }
+
+    fn update_reconciliation(
+        &self,
+        _: UpdateReconciliationInput,
+    ) -> TransactionResult<ReconciliationDetailDto> {
+        not_configured()
+    }
+
+    fn mark_reconciliation_transactions(
+        &self,
+        _: MarkReconciliationInput,
+    ) -> TransactionResult<ReconciliationDetailDto> {
+        not_configured()
+    }
+
+    fn finish_reconciliation(&self, _: &str) -> TransactionResult<ReconciliationDto> {
+        not_configured()
+    }
+
+    fn cancel_reconciliation(&self, _: &str) -> TransactionResult<()> {
         not_configured()
     }
 }
@@ -119,7 +292,7 @@ impl BudgetService for NoopBudgetService {
         ServiceDescriptor::new("BudgetService", "noop")
     }
 
-    fn list_budgets(&self) -> BudgetResult<Vec<BudgetDto>> {
+    fn list_budgets(&self, _: Option<&str>) -> BudgetResult<Vec<BudgetDto>> {
         not_configured_budget()
     }
 
@@ -143,6 +316,41 @@ impl BudgetService for NoopBudgetService {
         not_configured_budget()
     }
 
+    fn evaluate_alerts(&self, _: chrono::NaiveDate) -> BudgetResult<Vec<BudgetAlertDto>> {
+        not_configured_budget()
+    }
+
+    fn list_budget_alerts(&self, _: u32) -> BudgetResult<Vec<BudgetAlertDto>> {
+        not_configured_budget()
+    }
+
+    fn record_daily_snapshots(&self, _: chrono::NaiveDate) -> BudgetResult<usize> {
+        not_configured_budget()
+    }
+
+    fn list_budget_snapshots(
+        &self,
+        _: ListBudgetSnapshotsInput,
+    ) -> BudgetResult<Vec<BudgetEntryDto>> {
+        not_configured_budget()
+    }
+
+    fn get_period_summary(&self, _: &str, _: Option<&str>) -> BudgetResult<BudgetPeriodSummaryDto> {
+        not_configured_budget()
+    }
+
+    fn get_zero_based_month(&self, _: Option<&str>) -> BudgetResult<ZeroBasedMonthDto> {
+        not_configured_budget()
+    }
+
+    fn assign_to_envelope(&self, _: AssignToEnvelopeInput) -> BudgetResult<ZeroBasedMonthDto> {
+        not_configured_budget()
+    }
+
+    fn move_envelope_funds(&self, _: MoveEnvelopeFundsInput) -> BudgetResult<ZeroBasedMonthDto> {
+        not_configured_budget()
+    }
+
     fn calculate_budget_progress(&self, _: &str) -> BudgetResult<(i64, i64, f64, BudgetStatus)> {
         not_configured_budget()
     }
@@ -280,6 +488,25 @@ impl SettingsService for NoopSettingsService {
     fn update_category_order(&self, _: UpdateCategoryOrderInput) -> SettingsResult<()> {
         not_configured_settings()
     }
+
+    fn get_app_lock_settings(&self) -> SettingsResult<AppLockSettingsDto> {
+        not_configured_settings()
+    }
+
+    fn set_app_lock_pin(&self, _: SetAppLockPinInput) -> SettingsResult<AppLockSettingsDto> {
+        not_configured_settings()
+    }
+
+    fn update_app_lock_settings(
+        &self,
+        _: UpdateAppLockSettingsInput,
+    ) -> SettingsResult<AppLockSettingsDto> {
+        not_configured_settings()
+    }
+
+    fn verify_app_lock_pin(&self, _: &str) -> SettingsResult<bool> {
+        not_configured_settings()
+    }
 }
 
 impl SyncService for NoopSyncService {
@@ -300,6 +527,143 @@ impl SyncService for NoopSyncService {
     }
 }
 
+impl RecurringService for NoopRecurringService {
+    fn descriptor(&self) -> ServiceDescriptor {
+        ServiceDescriptor::new("RecurringService", "noop")
+    }
+
+    fn list_recurring(&self) -> RecurringResult<Vec<RecurringTransactionDto>> {
+        not_configured_recurring()
+    }
+
+    fn create_recurring(
+        &self,
+        _: CreateRecurringTransactionInput,
+    ) -> RecurringResult<RecurringTransactionDto> {
+        not_configured_recurring()
+    }
+
+    fn update_recurring(
+        &self,
+        _: UpdateRecurringTransactionInput,
+    ) -> RecurringResult<RecurringTransactionDto> {
+        not_configured_recurring()
+    }
+
+    fn delete_recurring(&self, _: &str) -> RecurringResult<()> {
+        not_configured_recurring()
+    }
+
+    fn skip_occurrence(&self, _: SkipOccurrenceInput) -> RecurringResult<RecurringTransactionDto> {
+        not_configured_recurring()
+    }
+
+    fn post_due(&self, _: chrono::NaiveDate) -> RecurringResult<Vec<TransactionDto>> {
+        not_configured_recurring()
+    }
+}
+
+impl CurrencyService for NoopCurrencyService {
+    fn descriptor(&self) -> ServiceDescriptor {
+        ServiceDescriptor::new("CurrencyService", "noop")
+    }
+
+    fn list_exchange_rates(&self, _: ExchangeRateQuery) -> CurrencyResult<Vec<ExchangeRateDto>> {
+        not_configured_currency()
+    }
+
+    fn upsert_exchange_rate(&self, _: UpsertExchangeRateInput) -> CurrencyResult<ExchangeRateDto> {
+        not_configured_currency()
+    }
+
+    fn delete_exchange_rate(&self, _: &str) -> CurrencyResult<()> {
+        not_configured_currency()
+    }
+
+    fn import_exchange_rates(
+        &self,
+        _: ImportExchangeRatesInput,
+    ) -> CurrencyResult<ImportExchangeRatesResult> {
+        not_configured_currency()
+    }
+
+    fn convert_amount(&self, _: ConvertAmountInput) -> CurrencyResult<ConversionDto> {
+        not_configured_currency()
+    }
+}
+
+impl AttachmentService for NoopAttachmentService {
+    fn descriptor(&self) -> ServiceDescriptor {
+        ServiceDescriptor::new("AttachmentService", "noop")
+    }
+
+    fn attach_files(&self, _: AttachFilesInput) -> AttachmentResult<Vec<AttachmentDto>> {
+        not_configured_attachment()
+    }
+
+    fn list_attachments(&self, _: &str) -> AttachmentResult<Vec<AttachmentDto>> {
+        not_configured_attachment()
+    }
+
+    fn read_attachment(&self, _: &str) -> AttachmentResult<AttachmentContentDto> {
+        not_configured_attachment()
+    }
+
+    fn remove_attachment(&self, _: &str) -> AttachmentResult<()> {
+        not_configured_attachment()
+    }
+
+    fn collect_garbage(&self) -> AttachmentResult<AttachmentGcResult> {
+        not_configured_attachment()
+    }
+}
+
+impl ImportService for NoopImportService {
+    fn descriptor(&self) -> ServiceDescriptor {
+        ServiceDescriptor::new("ImportService", "noop")
+    }
+
+    fn import_ofx(&self, _: ImportOfxInput) -> ImportResult<OfxImportResult> {
+        not_configured_import()
+    }
+
+    fn import_qif(&self, _: ImportQifInput) -> ImportResult<QifImportResult> {
+        not_configured_import()
+    }
+
+    fn export_qif(&self, _: ExportQifInput) -> ImportResult<QifExportDto> {
+        not_configured_import()
+    }
+
+    fn list_csv_profiles(&self) -> ImportResult<Vec<CsvImportProfileDto>> {
+        not_configured_import()
+    }
+
+    fn create_csv_profile(&self, _: CsvImportProfileInput) -> ImportResult<CsvImportProfileDto> {
+        not_configured_import()
+    }
+
+    fn update_csv_profile(
+        &self,
+        _: UpdateCsvImportProfileInput,
+    ) -> ImportResult<CsvImportProfileDto> {
+        not_configured_import()
+    }
+
+    fn delete_csv_profile(&self, _: &str) -> ImportResult<()> {
+        not_configured_import()
+    }
+
+    fn preview_csv(&self, _: CsvImportInput) -> ImportResult<CsvPreviewDto> {
+        not_configured_import()
+    }
+
+    fn commit_csv(&self, _: CsvImportInput) -> ImportResult<CsvCommitResultDto> {
+        not_configured_import()
+    }
+}
+
+#[derive(Clone)]
 pub struct ServiceRegistry {
     transaction: Arc<dyn TransactionService>,
     dashboard: Arc<dyn DashboardService>,
@@ -309,6 +673,10 @@ pub struct ServiceRegistry {
     report: Arc<dyn ReportService>,
     settings: Arc<dyn SettingsService>,
     sync: Arc<dyn SyncService>,
+    recurring: Arc<dyn RecurringService>,
+    currency: Arc<dyn CurrencyService>,
+    attachment: Arc<dyn AttachmentService>,
+    import: Arc<dyn ImportService>,
 }
 
 impl Default for ServiceRegistry {
@@ -328,6 +696,10 @@ impl ServiceRegistry {
             report: Arc::new(NoopReportService),
             settings: Arc::new(NoopSettingsService),
             sync: Arc::new(NoopSyncService),
+            recurring: Arc::new(NoopRecurringService),
+            currency: Arc::new(NoopCurrencyService),
+            attachment: Arc::new(NoopAttachmentService),
+            import: Arc::new(NoopImportService),
         }
     }
 
@@ -345,6 +717,10 @@ impl ServiceRegistry {
             self.report.descriptor(),
             self.settings.descriptor(),
             self.sync.descriptor(),
+            self.recurring.descriptor(),
+            self.currency.descriptor(),
+            self.attachment.descriptor(),
+            self.import.descriptor(),
         ]
     }
 
@@ -379,6 +755,22 @@ impl ServiceRegistry {
     pub fn sync(&self) -> Arc<dyn SyncService> {
         Arc::clone(&self.sync)
     }
+
+    pub fn recurring(&self) -> Arc<dyn RecurringService> {
+        Arc::clone(&self.recurring)
+    }
+
+    pub fn currency(&self) -> Arc<dyn CurrencyService> {
+        Arc::clone(&self.currency)
+    }
+
+    pub fn attachment(&self) -> Arc<dyn AttachmentService> {
+        Arc::clone(&self.attachment)
+    }
+
+    pub fn import(&self) -> Arc<dyn ImportService> {
+        Arc::clone(&self.import)
+    }
 }
 
 #[derive(Default)]
@@ -391,6 +783,10 @@ pub struct ServiceRegistryBuilder {
     report: Option<Arc<dyn ReportService>>,
     settings: Option<Arc<dyn SettingsService>>,
     sync: Option<Arc<dyn SyncService>>,
+    recurring: Option<Arc<dyn RecurringService>>,
+    currency: Option<Arc<dyn CurrencyService>>,
+    attachment: Option<Arc<dyn AttachmentService>>,
+    import: Option<Arc<dyn ImportService>>,
 }
 
 impl ServiceRegistryBuilder {
@@ -459,6 +855,38 @@ impl ServiceRegistryBuilder {
         self
     }
 
+    pub fn with_recurring<T>(mut self, service: T) -> Self
+    where
+        T: RecurringService + 'static,
+    {
+        self.recurring = Some(Arc::new(service));
+        self
+    }
+
+    pub fn with_currency<T>(mut self, service: T) -> Self
+    where
+        T: CurrencyService + 'static,
+    {
+        self.currency = Some(Arc::new(service));
+        self
+    }
+
+    pub fn with_attachment<T>(mut self, service: T) -> Self
+    where
+        T: AttachmentService + 'static,
+    {
+        self.attachment = Some(Arc::new(service));
+        self
+    }
+
+    pub fn with_import<T>(mut self, service: T) -> Self
+    where
+        T: ImportService + 'static,
+    {
+        self.import = Some(Arc::new(service));
+        self
+    }
+
     pub fn build(self) -> ServiceRegistry {
         ServiceRegistry {
             transaction: self
@@ -477,6 +905,16 @@ impl ServiceRegistryBuilder {
                 .settings
                 .unwrap_or_else(|| Arc::new(NoopSettingsService)),
             sync: self.sync.unwrap_or_else(|| Arc::new(NoopSyncService)),
+            recurring: self
+                .recurring
+                .unwrap_or_else(|| Arc::new(NoopRecurringService)),
+            currency: self
+                .currency
+                .unwrap_or_else(|| Arc::new(NoopCurrencyService)),
+            attachment: self
+                .attachment
+                .unwrap_or_else(|| Arc::new(NoopAttachmentService)),
+            import: self.import.unwrap_or_else(|| Arc::new(NoopImportService)),
         }
     }
 }
@@ -522,3 +960,27 @@ fn not_configured_settings<T>() -> SettingsResult<T> {
         "SettingsService is not configured".to_string(),
     ))
 }
+
+fn not_configured_recurring<T>() -> RecurringResult<T> {
+    Err(RecurringServiceError::Internal(
+        "RecurringService is not configured".to_string(),
+    ))
+}
+
+fn not_configured_currency<T>() -> CurrencyResult<T> {
+    Err(CurrencyServiceError::Internal(
+        "CurrencyService is not configured".to_string(),
+    ))
+}
+
+fn not_configured_attachment<T>() -> AttachmentResult<T> {
+    Err(AttachmentServiceError::Internal(
+        "AttachmentService is not configured".to_string(),
+    ))
+}
+
+fn not_configured_import<T>() -> ImportResult<T> {
+    Err(ImportServiceError::Internal(
+        "ImportService is not configured".to_string(),
+    ))
+}
@@ -4,7 +4,7 @@ use thiserror::Error;
 use uuid::Uuid;
 use chrono::Datelike;
 
-use crate::services::ServiceDescriptor;
+use crate::services::{sqlcipher, transactions::TRANSACTION_LINES_CTE, ServiceDescriptor};
 
 #[derive(Debug, Error)]
 pub enum PlanningError {
@@ -365,14 +365,14 @@ impl PlanningService {
         let conn = Connection::open(&self.db_path)
             .map_err(|e| PlanningError::Database(e.to_string()))?;
         
-        // Enable foreign keys
-        conn.execute("PRAGMA foreign_keys = ON;", [])
-            .map_err(|e| PlanningError::Database(format!("Failed to enable foreign keys: {}", e)))?;
-        
         if let Some(key) = &self.key {
-            conn.pragma_update(None, "key", key.as_str())
+            sqlcipher::apply_key(&conn, key)
                 .map_err(|err| PlanningError::Database(err.to_string()))?;
         }
+
+        // Enable foreign keys
+        conn.execute("PRAGMA foreign_keys = ON;", [])
+            .map_err(|e| PlanningError::Database(format!("Failed to enable foreign keys: {}", e)))?;
         Ok(conn)
     }
 
@@ -1299,16 +1299,22 @@ impl PlanningService {
 
         let actual_income: f64 = conn
             .query_row(
-                r#"SELECT COALESCE(SUM(amount_cents)/100.0,0) FROM transaction
-                   WHERE user_id = ?1 AND type = 'income' AND occurred_on >= ?2 AND occurred_on < ?3"#,
+                &format!(
+                    r#"WITH {TRANSACTION_LINES_CTE}
+                   SELECT COALESCE(SUM(amount_cents)/100.0,0) FROM transaction_lines
+                   WHERE user_id = ?1 AND type = 'income' AND occurred_on >= ?2 AND occurred_on < ?3"#
+                ),
                 params![self.user_id, start, end],
                 |row| row.get(0),
             )
             .unwrap_or(0.0);
         let actual_expense: f64 = conn
             .query_row(
-                r#"SELECT COALESCE(SUM(amount_cents)/100.0,0) FROM transaction
-                   WHERE user_id = ?1 AND type = 'expense' AND occurred_on >= ?2 AND occurred_on < ?3"#,
+                &format!(
+                    r#"WITH {TRANSACTION_LINES_CTE}
+                   SELECT COALESCE(SUM(amount_cents)/100.0,0) FROM transaction_lines
+                   WHERE user_id = ?1 AND type = 'expense' AND occurred_on >= ?2 AND occurred_on < ?3"#
+                ),
                 params![self.user_id, start, end],
                 |row| row.get(0),
             )
@@ -0,0 +1,506 @@
+mod sqlite;
+
+pub use sqlite::SqliteRecurringService;
+
+use std::fmt;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    transactions::TransactionKind, ServiceDescriptor, TransactionDto, TransactionServiceError,
+};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringTransactionDto {
+    pub id: String,
+    pub account_id: String,
+    pub account_name: Option<String>,
+    pub category_id: Option<String>,
+    pub category_name: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: TransactionKind,
+    pub amount_cents: i64,
+    pub currency: String,
+    pub notes: Option<String>,
+    pub tags: Vec<String>,
+    pub goal_id: Option<String>,
+    pub transfer_account_id: Option<String>,
+    pub rrule: String,
+    pub start_date: String,
+    pub end_date: Option<String>,
+    /// Next date that will be posted; `None` once the series has finished.
+    pub next_occurrence: Option<String>,
+    pub last_posted_on: Option<String>,
+    pub skipped_dates: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRecurringTransactionInput {
+    pub account_id: String,
+    pub category_id: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: TransactionKind,
+    pub amount_cents: i64,
+    pub currency: String,
+    pub notes: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub goal_id: Option<String>,
+    #[serde(default)]
+    pub transfer_account_id: Option<String>,
+    pub rrule: String,
+    /// First occurrence, `YYYY-MM-DD`.
+    pub start_date: String,
+    pub end_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRecurringTransactionInput {
+    pub id: String,
+    /// Occurrence from which the edit applies ("this and future"). Earlier occurrences keep
+    /// the old definition; omit to edit the whole series.
+    #[serde(default)]
+    pub effective_from: Option<String>,
+    pub account_id: String,
+    pub category_id: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: TransactionKind,
+    pub amount_cents: i64,
+    pub currency: String,
+    pub notes: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub goal_id: Option<String>,
+    #[serde(default)]
+    pub transfer_account_id: Option<String>,
+    pub rrule: String,
+    pub end_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkipOccurrenceInput {
+    pub id: String,
+    pub occurrence_date: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+}
+
+/// Supported subset of RFC 5545 RRULE: `FREQ` (DAILY/WEEKLY/MONTHLY/YEARLY), `INTERVAL`,
+/// `BYDAY` (weekly only), `BYMONTHDAY` (monthly only, negative counts from month end),
+/// `COUNT` and `UNTIL`. A bare frequency such as `MONTHLY` is accepted like reminders do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub by_day: Vec<Weekday>,
+    pub by_month_day: Option<i32>,
+    pub count: Option<u32>,
+    pub until: Option<NaiveDate>,
+}
+
+/// Upper bound on generated periods so malformed rules can never loop forever.
+const MAX_PERIODS: u32 = 100_000;
+
+/// Largest accepted `INTERVAL`; anything above this is a typo rather than a schedule.
+const MAX_INTERVAL: u32 = 1_000;
+
+impl RecurrenceRule {
+    pub fn parse(value: &str) -> RecurringResult<Self> {
+        let value = value.trim();
+        let value = value.strip_prefix("RRULE:").unwrap_or(value);
+        if value.is_empty() {
+            return Err(RecurringServiceError::Validation(
+                "Recurrence rule is required".into(),
+            ));
+        }
+
+        let mut frequency = None;
+        let mut rule = RecurrenceRule {
+            frequency: Frequency::Monthly,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: None,
+            count: None,
+            until: None,
+        };
+
+        for part in value.split(';').filter(|part| !part.is_empty()) {
+            let (key, raw) = part.split_once('=').unwrap_or(("FREQ", part));
+            let invalid =
+                || RecurringServiceError::Validation(format!("Invalid recurrence part: {part}"));
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match raw.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(invalid()),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = raw.parse().map_err(|_| invalid())?;
+                    if rule.interval == 0 || rule.interval > MAX_INTERVAL {
+                        return Err(invalid());
+                    }
+                }
+                "BYDAY" => {
+                    rule.by_day = raw
+                        .split(',')
+                        .map(|day| parse_weekday(day).ok_or_else(invalid))
+                        .collect::<RecurringResult<Vec<_>>>()?;
+                    rule.by_day.sort_by_key(|day| day.num_days_from_monday());
+                    rule.by_day.dedup();
+                }
+                "BYMONTHDAY" => {
+                    let day: i32 = raw.parse().map_err(|_| invalid())?;
+                    if day == 0 || !(-31..=31).contains(&day) {
+                        return Err(invalid());
+                    }
+                    rule.by_month_day = Some(day);
+                }
+                "COUNT" => rule.count = Some(raw.parse().map_err(|_| invalid())?),
+                "UNTIL" => {
+                    let date = raw.get(..8).ok_or_else(invalid)?;
+                    rule.until =
+                        Some(NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| invalid())?);
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        rule.frequency = frequency.ok_or_else(|| {
+            RecurringServiceError::Validation("Recurrence rule needs a FREQ".into())
+        })?;
+        if !rule.by_day.is_empty() && rule.frequency != Frequency::Weekly {
+            return Err(RecurringServiceError::Validation(
+                "BYDAY is only supported for weekly rules".into(),
+            ));
+        }
+        if rule.by_month_day.is_some() && rule.frequency != Frequency::Monthly {
+            return Err(RecurringServiceError::Validation(
+                "BYMONTHDAY is only supported for monthly rules".into(),
+            ));
+        }
+
+        Ok(rule)
+    }
+
+    /// Every occurrence of the series anchored at `start`, in chronological order.
+    pub fn occurrences(&self, start: NaiveDate) -> Occurrences<'_> {
+        Occurrences {
+            rule: self,
+            start,
+            period: 0,
+            pending: Vec::new(),
+            yielded: 0,
+        }
+    }
+
+    /// First occurrence strictly after `after` (or the first one when `after` is `None`).
+    pub fn occurrence_after(
+        &self,
+        start: NaiveDate,
+        after: Option<NaiveDate>,
+    ) -> Option<NaiveDate> {
+        self.occurrences(start)
+            .find(|date| after.is_none_or(|after| *date > after))
+    }
+
+    pub fn is_occurrence(&self, start: NaiveDate, date: NaiveDate) -> bool {
+        self.occurrences(start)
+            .take_while(|candidate| *candidate <= date)
+            .any(|candidate| candidate == date)
+    }
+
+    /// Dates of the `period`-th period, or `None` once they fall outside the calendar.
+    fn period_dates(&self, start: NaiveDate, period: u32) -> Option<Vec<NaiveDate>> {
+        let step = period.checked_mul(self.interval)?;
+        match self.frequency {
+            Frequency::Daily => Some(vec![
+                start.checked_add_signed(Duration::days(i64::from(step)))?
+            ]),
+            Frequency::Weekly if self.by_day.is_empty() => Some(vec![
+                start.checked_add_signed(Duration::weeks(i64::from(step)))?
+            ]),
+            Frequency::Weekly => {
+                let week_start = start
+                    .checked_sub_signed(Duration::days(i64::from(
+                        start.weekday().num_days_from_monday(),
+                    )))?
+                    .checked_add_signed(Duration::weeks(i64::from(step)))?;
+                self.by_day
+                    .iter()
+                    .map(|day| {
+                        week_start.checked_add_signed(Duration::days(i64::from(
+                            day.num_days_from_monday(),
+                        )))
+                    })
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let months = start.month0().checked_add(step)?;
+                let year = start.year().checked_add(i32::try_from(months / 12).ok()?)?;
+                let month = months % 12 + 1;
+                let last = last_day_of_month(year, month);
+                let day = match self.by_month_day {
+                    Some(day) if day > 0 => (day as u32).min(last),
+                    Some(day) => (last as i32 + day + 1).max(1) as u32,
+                    None => start.day().min(last),
+                };
+                NaiveDate::from_ymd_opt(year, month, day).map(|date| vec![date])
+            }
+            Frequency::Yearly => {
+                let year = start.year().checked_add(i32::try_from(step).ok()?)?;
+                let day = start.day().min(last_day_of_month(year, start.month()));
+                NaiveDate::from_ymd_opt(year, start.month(), day).map(|date| vec![date])
+            }
+        }
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.frequency.as_str())?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days = self
+                .by_day
+                .iter()
+                .map(|day| weekday_code(*day))
+                .collect::<Vec<_>>()
+                .join(",");
+            write!(f, ";BYDAY={days}")?;
+        }
+        if let Some(day) = self.by_month_day {
+            write!(f, ";BYMONTHDAY={day}")?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Occurrences<'a> {
+    rule: &'a RecurrenceRule,
+    start: NaiveDate,
+    period: u32,
+    pending: Vec<NaiveDate>,
+    yielded: u32,
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = NaiveDate;
+
+    fn next(&mut self) -> Option<NaiveDate> {
+        if self.rule.count.is_some_and(|count| self.yielded >= count) {
+            return None;
+        }
+
+        while self.pending.is_empty() {
+            if self.period >= MAX_PERIODS {
+                return None;
+            }
+            let Some(mut dates) = self.rule.period_dates(self.start, self.period) else {
+                // Past the last representable date, so the series is over
+                self.period = MAX_PERIODS;
+                return None;
+            };
+            self.period += 1;
+            dates.retain(|date| *date >= self.start);
+            dates.reverse();
+            self.pending = dates;
+        }
+
+        let next = self.pending.pop()?;
+        if self.rule.until.is_some_and(|until| next > until) {
+            self.pending.clear();
+            self.period = MAX_PERIODS;
+            return None;
+        }
+
+        self.yielded += 1;
+        Some(next)
+    }
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn last_day_of_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|date| date.pred_opt())
+        .map(|date| date.day())
+        .unwrap_or(28)
+}
+
+#[derive(Debug, Error)]
+pub enum RecurringServiceError {
+    #[error("database error: {0}")]
+    Database(String),
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("validation error: {0}")]
+    Validation(String),
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+pub type RecurringResult<T> = Result<T, RecurringServiceError>;
+
+impl From<rusqlite::Error> for RecurringServiceError {
+    fn from(err: rusqlite::Error) -> Self {
+        RecurringServiceError::Database(err.to_string())
+    }
+}
+
+impl From<TransactionServiceError> for RecurringServiceError {
+    fn from(err: TransactionServiceError) -> Self {
+        match err {
+            TransactionServiceError::Database(message) => RecurringServiceError::Database(message),
+            TransactionServiceError::NotFound(message) => RecurringServiceError::NotFound(message),
+            TransactionServiceError::Validation(message)
+            | TransactionServiceError::Reconciled(message) => {
+                RecurringServiceError::Validation(message)
+            }
+            TransactionServiceError::Internal(message) => RecurringServiceError::Internal(message),
+        }
+    }
+}
+
+pub trait RecurringService: Send + Sync {
+    fn descriptor(&self) -> ServiceDescriptor;
+    fn list_recurring(&self) -> RecurringResult<Vec<RecurringTransactionDto>>;
+    fn create_recurring(
+        &self,
+        input: CreateRecurringTransactionInput,
+    ) -> RecurringResult<RecurringTransactionDto>;
+    /// Edits the series; with `effective_from` the current template ends the day before
+    /// and a new template carries the edit forward.
+    fn update_recurring(
+        &self,
+        input: UpdateRecurringTransactionInput,
+    ) -> RecurringResult<RecurringTransactionDto>;
+    fn delete_recurring(&self, id: &str) -> RecurringResult<()>;
+    fn skip_occurrence(
+        &self,
+        input: SkipOccurrenceInput,
+    ) -> RecurringResult<RecurringTransactionDto>;
+    /// Posts every occurrence due up to and including `today`, catching up on any that were
+    /// missed while the app was closed.
+    fn post_due(&self, today: NaiveDate) -> RecurringResult<Vec<TransactionDto>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn monthly_rule_clamps_to_month_end() {
+        let rule = RecurrenceRule::parse("FREQ=MONTHLY;COUNT=3").unwrap();
+        let dates = rule.occurrences(date("2025-01-31")).collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![date("2025-01-31"), date("2025-02-28"), date("2025-03-31")]
+        );
+    }
+
+    #[test]
+    fn weekly_byday_rule_round_trips() {
+        let rule = RecurrenceRule::parse("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=FR,MO").unwrap();
+        let dates = rule
+            .occurrences(date("2025-03-05"))
+            .take(3)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![date("2025-03-07"), date("2025-03-17"), date("2025-03-21")]
+        );
+        assert_eq!(rule.to_string(), "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR");
+    }
+
+    #[test]
+    fn until_stops_series() {
+        let rule = RecurrenceRule::parse("FREQ=DAILY;UNTIL=20250103").unwrap();
+        assert_eq!(rule.occurrences(date("2025-01-01")).count(), 3);
+        assert!(RecurrenceRule::parse("FREQ=HOURLY").is_err());
+    }
+
+    #[test]
+    fn large_interval_is_rejected() {
+        assert!(RecurrenceRule::parse("FREQ=DAILY;INTERVAL=100000000").is_err());
+        assert!(RecurrenceRule::parse("FREQ=DAILY;INTERVAL=1000").is_ok());
+    }
+
+    #[test]
+    fn series_ends_at_calendar_limit() {
+        let start = date("2025-01-01");
+        for frequency in ["DAILY", "WEEKLY;BYDAY=MO,FR", "MONTHLY", "YEARLY"] {
+            let rule = RecurrenceRule::parse(&format!("FREQ={frequency};INTERVAL=1000")).unwrap();
+            let dates = rule.occurrences(start).collect::<Vec<_>>();
+            assert!(!dates.is_empty(), "{frequency}");
+            assert!(
+                dates.windows(2).all(|pair| pair[0] < pair[1]),
+                "{frequency}"
+            );
+        }
+    }
+}
@@ -0,0 +1,998 @@
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
+
+use chrono::{Datelike, Duration, NaiveDate};
+use rusqlite::{params, types::Type, Connection, OptionalExtension};
+use uuid::Uuid;
+
+use crate::services::{
+    sqlcipher, transactions::TransactionKind, CreateTransactionInput, ServiceDescriptor,
+    TransactionDto, TransactionService,
+};
+
+use super::{
+    CreateRecurringTransactionInput, Frequency, RecurrenceRule, RecurringResult, RecurringService,
+    RecurringServiceError, RecurringTransactionDto, SkipOccurrenceInput,
+    UpdateRecurringTransactionInput,
+};
+
+const DEFAULT_USER_ID: &str = "seed-user";
+
+/// Most occurrences a single template may post in one pass; a daily template left alone
+/// for longer catches up over subsequent polls.
+const MAX_CATCH_UP: usize = 400;
+
+const RECURRING_SELECT: &str = r#"
+    SELECT
+        r.id,
+        r.account_id,
+        a.name as account_name,
+        r.category_id,
+        c.name as category_name,
+        r.type,
+        r.amount_cents,
+        r.currency,
+        r.notes,
+        r.tags,
+        r.goal_id,
+        r.transfer_account_id,
+        r.rrule,
+        r.start_date,
+        r.end_date,
+        r.next_occurrence,
+        r.last_posted_on
+    FROM "RecurringTransaction" r
+    LEFT JOIN "Account" a ON a.id = r.account_id
+    LEFT JOIN "Category" c ON c.id = r.category_id
+"#;
+
+#[derive(Clone)]
+pub struct SqliteRecurringService {
+    db_path: PathBuf,
+    db_key: Option<String>,
+    user_id: String,
+    transactions: Arc<dyn TransactionService>,
+}
+
+impl SqliteRecurringService {
+    /// Posting goes through `transactions` so balances, transfer legs and validation stay
+    /// in one place.
+    pub fn new(
+        db_path: PathBuf,
+        db_key: Option<String>,
+        user_id: Option<String>,
+        transactions: Arc<dyn TransactionService>,
+    ) -> RecurringResult<Self> {
+        let service = Self {
+            db_path,
+            db_key,
+            user_id: user_id.unwrap_or_else(|| DEFAULT_USER_ID.to_string()),
+            transactions,
+        };
+        service.ensure_schema()?;
+        Ok(service)
+    }
+
+    fn connection(&self) -> RecurringResult<Connection> {
+        let conn = Connection::open(&self.db_path)
+            .map_err(|err| RecurringServiceError::Database(err.to_string()))?;
+
+        if let Some(key) = &self.db_key {
+            sqlcipher::apply_key(&conn, key).map_err(|err| {
+                RecurringServiceError::Database(format!("Failed to apply SQLCipher key: {err}"))
+            })?;
+        }
+
+        if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
+            return Err(RecurringServiceError::Database(err.to_string()));
+        }
+
+        Ok(conn)
+    }
+
+    fn ensure_schema(&self) -> RecurringResult<()> {
+        let conn = self.connection()?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS "RecurringTransaction" (
+                "id" TEXT NOT NULL PRIMARY KEY,
+                "user_id" TEXT NOT NULL,
+                "account_id" TEXT NOT NULL,
+                "category_id" TEXT,
+                "goal_id" TEXT,
+                "transfer_account_id" TEXT,
+                "type" TEXT NOT NULL,
+                "amount_cents" INTEGER NOT NULL,
+                "currency" TEXT NOT NULL,
+                "notes" TEXT,
+                "tags" TEXT,
+                "rrule" TEXT NOT NULL,
+                "start_date" TEXT NOT NULL,
+                "end_date" TEXT,
+                "next_occurrence" TEXT,
+                "last_posted_on" TEXT,
+                "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                "updated_at" DATETIME NOT NULL,
+                CONSTRAINT "RecurringTransaction_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+                CONSTRAINT "RecurringTransaction_account_id_fkey" FOREIGN KEY ("account_id") REFERENCES "Account" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+                CONSTRAINT "RecurringTransaction_transfer_account_id_fkey" FOREIGN KEY ("transfer_account_id") REFERENCES "Account" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+                CONSTRAINT "RecurringTransaction_category_id_fkey" FOREIGN KEY ("category_id") REFERENCES "Category" ("id") ON DELETE SET NULL ON UPDATE CASCADE,
+                CONSTRAINT "RecurringTransaction_goal_id_fkey" FOREIGN KEY ("goal_id") REFERENCES "Goal" ("id") ON DELETE SET NULL ON UPDATE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS "RecurringTransaction_user_id_next_occurrence_idx" ON "RecurringTransaction"("user_id", "next_occurrence");
+            CREATE TABLE IF NOT EXISTS "RecurringSkip" (
+                "recurring_id" TEXT NOT NULL,
+                "occurrence_date" TEXT NOT NULL,
+                "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY ("recurring_id", "occurrence_date"),
+                CONSTRAINT "RecurringSkip_recurring_id_fkey" FOREIGN KEY ("recurring_id") REFERENCES "RecurringTransaction" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS "Transaction_recurrence_id_idx" ON "Transaction"("recurrence_id");
+            "#,
+        )
+        .map_err(|err| {
+            RecurringServiceError::Database(format!("Failed to create recurring tables: {err}"))
+        })
+    }
+
+    fn fetch_template(&self, conn: &Connection, id: &str) -> RecurringResult<TemplateRow> {
+        let sql = format!("{RECURRING_SELECT} WHERE r.user_id = ? AND r.id = ?");
+        conn.query_row(&sql, params![self.user_id, id], TemplateRow::from_row)
+            .optional()?
+            .ok_or_else(|| RecurringServiceError::NotFound(format!("Recurring transaction {id}")))
+    }
+
+    fn fetch_templates(
+        &self,
+        conn: &Connection,
+        due_on: Option<NaiveDate>,
+    ) -> RecurringResult<Vec<TemplateRow>> {
+        let mut sql = format!("{RECURRING_SELECT} WHERE r.user_id = ?1");
+        if due_on.is_some() {
+            sql.push_str(" AND r.next_occurrence IS NOT NULL AND r.next_occurrence <= ?2");
+        }
+        sql.push_str(" ORDER BY r.next_occurrence IS NULL, r.next_occurrence, r.created_at");
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = match due_on {
+            Some(date) => stmt.query_map(
+                params![self.user_id, format_date(date)],
+                TemplateRow::from_row,
+            )?,
+            None => stmt.query_map(params![self.user_id], TemplateRow::from_row)?,
+        };
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| RecurringServiceError::Database(err.to_string()))
+    }
+
+    fn skipped_dates(&self, conn: &Connection, id: &str) -> RecurringResult<HashSet<NaiveDate>> {
+        let mut stmt = conn.prepare(
+            r#"SELECT occurrence_date FROM "RecurringSkip" WHERE recurring_id = ? ORDER BY occurrence_date"#,
+        )?;
+        let dates = stmt
+            .query_map(params![id], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        dates.iter().map(|date| parse_date(date)).collect()
+    }
+
+    fn to_dto(
+        &self,
+        conn: &Connection,
+        template: TemplateRow,
+    ) -> RecurringResult<RecurringTransactionDto> {
+        let mut skipped = self
+            .skipped_dates(conn, &template.id)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        skipped.sort();
+
+        Ok(RecurringTransactionDto {
+            id: template.id,
+            account_id: template.account_id,
+            account_name: template.account_name,
+            category_id: template.category_id,
+            category_name: template.category_name,
+            kind: template.kind,
+            amount_cents: template.amount_cents,
+            currency: template.currency,
+            notes: template.notes,
+            tags: split_tags(template.tags),
+            goal_id: template.goal_id,
+            transfer_account_id: template.transfer_account_id,
+            rrule: template.rule.to_string(),
+            start_date: format_date(template.start_date),
+            end_date: template.end_date.map(format_date),
+            next_occurrence: template.next_occurrence.map(format_date),
+            last_posted_on: template.last_posted_on.map(format_date),
+            skipped_dates: skipped.into_iter().map(format_date).collect(),
+        })
+    }
+
+    /// Next occurrence after `after` that is inside the series window and not skipped.
+    fn next_occurrence(
+        &self,
+        conn: &Connection,
+        id: &str,
+        rule: &RecurrenceRule,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        after: Option<NaiveDate>,
+    ) -> RecurringResult<Option<NaiveDate>> {
+        let skipped = self.skipped_dates(conn, id)?;
+        Ok(rule
+            .occurrences(start)
+            .filter(|date| after.is_none_or(|after| *date > after))
+            .take_while(|date| end.is_none_or(|end| *date <= end))
+            .find(|date| !skipped.contains(date)))
+    }
+
+    fn store_next_occurrence(
+        &self,
+        conn: &Connection,
+        template: &TemplateRow,
+    ) -> RecurringResult<()> {
+        let next = self.next_occurrence(
+            conn,
+            &template.id,
+            &template.rule,
+            template.start_date,
+            template.end_date,
+            template.last_posted_on,
+        )?;
+        conn.execute(
+            r#"UPDATE "RecurringTransaction" SET next_occurrence = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2 AND user_id = ?3"#,
+            params![next.map(format_date), template.id, self.user_id],
+        )?;
+        Ok(())
+    }
+
+    fn validate_references(
+        &self,
+        conn: &Connection,
+        fields: &TemplateFields,
+    ) -> RecurringResult<()> {
+        let accounts = std::iter::once(&fields.account_id).chain(fields.transfer_account_id.iter());
+        for account_id in accounts {
+            let exists: bool = conn.query_row(
+                r#"SELECT EXISTS(SELECT 1 FROM "Account" WHERE id = ? AND user_id = ?)"#,
+                params![account_id, self.user_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(RecurringServiceError::NotFound(format!(
+                    "Account {account_id}"
+                )));
+            }
+        }
+
+        if let Some(category_id) = &fields.category_id {
+            let exists: bool = conn.query_row(
+                r#"SELECT EXISTS(SELECT 1 FROM "Category" WHERE id = ? AND user_id = ?)"#,
+                params![category_id, self.user_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(RecurringServiceError::NotFound(format!(
+                    "Category {category_id}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn insert_template(
+        &self,
+        conn: &Connection,
+        id: &str,
+        fields: &TemplateFields,
+        start: NaiveDate,
+    ) -> RecurringResult<()> {
+        conn.execute(
+            r#"
+            INSERT INTO "RecurringTransaction" (
+                id, user_id, account_id, category_id, goal_id, transfer_account_id, type,
+                amount_cents, currency, notes, tags, rrule, start_date, end_date, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, CURRENT_TIMESTAMP)
+        "#,
+            params![
+                id,
+                self.user_id,
+                fields.account_id,
+                fields.category_id,
+                fields.goal_id,
+                fields.transfer_account_id,
+                fields.kind.as_str(),
+                fields.amount_cents,
+                fields.currency,
+                fields.notes,
+                fields.tags,
+                fields.rule.to_string(),
+                format_date(start),
+                fields.end_date.map(format_date),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn update_template_fields(
+        &self,
+        conn: &Connection,
+        id: &str,
+        fields: &TemplateFields,
+    ) -> RecurringResult<()> {
+        conn.execute(
+            r#"
+            UPDATE "RecurringTransaction" SET
+                account_id = ?1,
+                category_id = ?2,
+                goal_id = ?3,
+                transfer_account_id = ?4,
+                type = ?5,
+                amount_cents = ?6,
+                currency = ?7,
+                notes = ?8,
+                tags = ?9,
+                rrule = ?10,
+                end_date = ?11,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?12 AND user_id = ?13
+        "#,
+            params![
+                fields.account_id,
+                fields.category_id,
+                fields.goal_id,
+                fields.transfer_account_id,
+                fields.kind.as_str(),
+                fields.amount_cents,
+                fields.currency,
+                fields.notes,
+                fields.tags,
+                fields.rule.to_string(),
+                fields.end_date.map(format_date),
+                id,
+                self.user_id
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Splits the series at `effective_from`: the existing template ends the day before and a
+    /// new template carries the edited definition (and any later skips) forward.
+    fn split_series(
+        &self,
+        conn: &mut Connection,
+        template: &TemplateRow,
+        mut fields: TemplateFields,
+        effective_from: NaiveDate,
+    ) -> RecurringResult<String> {
+        // A COUNT left untouched keeps meaning "total occurrences of the series".
+        if let (Some(total), true) = (
+            template.rule.count,
+            fields.rule.count == template.rule.count,
+        ) {
+            let consumed = template
+                .rule
+                .occurrences(template.start_date)
+                .take_while(|date| *date < effective_from)
+                .count() as u32;
+            fields.rule.count = Some(total.saturating_sub(consumed).max(1));
+        }
+        // Keep a monthly series on its original day rather than a clamped month-end date.
+        if template.rule.frequency == Frequency::Monthly
+            && fields.rule.frequency == Frequency::Monthly
+            && fields.rule.by_month_day.is_none()
+        {
+            fields.rule.by_month_day = Some(
+                template
+                    .rule
+                    .by_month_day
+                    .unwrap_or(template.start_date.day() as i32),
+            );
+        }
+
+        let new_id = new_recurring_id();
+        let tx = conn.transaction()?;
+        let previous_day = effective_from - Duration::days(1);
+        tx.execute(
+            r#"UPDATE "RecurringTransaction" SET end_date = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2 AND user_id = ?3"#,
+            params![format_date(previous_day), template.id, self.user_id],
+        )?;
+        self.insert_template(&tx, &new_id, &fields, effective_from)?;
+        tx.execute(
+            r#"UPDATE "RecurringSkip" SET recurring_id = ?1 WHERE recurring_id = ?2 AND occurrence_date >= ?3"#,
+            params![new_id, template.id, format_date(effective_from)],
+        )?;
+
+        let ended = self.fetch_template(&tx, &template.id)?;
+        self.store_next_occurrence(&tx, &ended)?;
+        let created = self.fetch_template(&tx, &new_id)?;
+        self.store_next_occurrence(&tx, &created)?;
+        tx.commit()?;
+
+        Ok(new_id)
+    }
+
+    fn post_template(
+        &self,
+        conn: &Connection,
+        template: &TemplateRow,
+        today: NaiveDate,
+    ) -> RecurringResult<Vec<TransactionDto>> {
+        let mut posted = Vec::new();
+        let mut next = template.next_occurrence;
+
+        while let Some(date) = next.filter(|date| *date <= today) {
+            if posted.len() >= MAX_CATCH_UP {
+                break;
+            }
+
+            // Deterministic ids make posting idempotent if a previous pass was interrupted.
+            let transaction_id = format!("tx_rec_{}_{}", template.id, date.format("%Y%m%d"));
+            let exists: bool = conn.query_row(
+                r#"SELECT EXISTS(SELECT 1 FROM "Transaction" WHERE id = ?)"#,
+                params![transaction_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                let transaction = self
+                    .transactions
+                    .create_transaction(template.transaction_input(transaction_id, date))?;
+                posted.push(transaction);
+            }
+
+            next = self.next_occurrence(
+                conn,
+                &template.id,
+                &template.rule,
+                template.start_date,
+                template.end_date,
+                Some(date),
+            )?;
+            conn.execute(
+                r#"
+                UPDATE "RecurringTransaction"
+                SET last_posted_on = ?1, next_occurrence = ?2, updated_at = CURRENT_TIMESTAMP
+                WHERE id = ?3 AND user_id = ?4
+            "#,
+                params![
+                    format_date(date),
+                    next.map(format_date),
+                    template.id,
+                    self.user_id
+                ],
+            )?;
+        }
+
+        Ok(posted)
+    }
+}
+
+impl RecurringService for SqliteRecurringService {
+    fn descriptor(&self) -> ServiceDescriptor {
+        ServiceDescriptor::new("RecurringService", "sqlite")
+    }
+
+    fn list_recurring(&self) -> RecurringResult<Vec<RecurringTransactionDto>> {
+        let conn = self.connection()?;
+        self.fetch_templates(&conn, None)?
+            .into_iter()
+            .map(|template| self.to_dto(&conn, template))
+            .collect()
+    }
+
+    fn create_recurring(
+        &self,
+        input: CreateRecurringTransactionInput,
+    ) -> RecurringResult<RecurringTransactionDto> {
+        let start = parse_date(&input.start_date)?;
+        let fields = TemplateFields::new(
+            input.account_id,
+            input.category_id,
+            input.kind,
+            input.amount_cents,
+            input.currency,
+            input.notes,
+            input.tags,
+            input.goal_id,
+            input.transfer_account_id,
+            &input.rrule,
+            input.end_date.as_deref(),
+            start,
+        )?;
+
+        let conn = self.connection()?;
+        self.validate_references(&conn, &fields)?;
+
+        let id = new_recurring_id();
+        self.insert_template(&conn, &id, &fields, start)?;
+        let template = self.fetch_template(&conn, &id)?;
+        self.store_next_occurrence(&conn, &template)?;
+
+        let template = self.fetch_template(&conn, &id)?;
+        self.to_dto(&conn, template)
+    }
+
+    fn update_recurring(
+        &self,
+        input: UpdateRecurringTransactionInput,
+    ) -> RecurringResult<RecurringTransactionDto> {
+        let mut conn = self.connection()?;
+        let template = self.fetch_template(&conn, &input.id)?;
+        let effective_from = input
+            .effective_from
+            .as_deref()
+            .map(parse_date)
+            .transpose()?;
+        let series_start = effective_from.unwrap_or(template.start_date);
+
+        let fields = TemplateFields::new(
+            input.account_id,
+            input.category_id,
+            input.kind,
+            input.amount_cents,
+            input.currency,
+            input.notes,
+            input.tags,
+            input.goal_id,
+            input.transfer_account_id,
+            &input.rrule,
+            input.end_date.as_deref(),
+            series_start,
+        )?;
+        self.validate_references(&conn, &fields)?;
+
+        let id = match effective_from {
+            Some(date) if date != template.start_date => {
+                if !template.rule.is_occurrence(template.start_date, date)
+                    || template.end_date.is_some_and(|end| date > end)
+                {
+                    return Err(RecurringServiceError::Validation(format!(
+                        "{} is not an occurrence of this series",
+                        format_date(date)
+                    )));
+                }
+                if template.last_posted_on.is_some_and(|posted| date <= posted) {
+                    return Err(RecurringServiceError::Validation(format!(
+                        "The occurrence on {} has already been posted",
+                        format_date(date)
+                    )));
+                }
+                self.split_series(&mut conn, &template, fields, date)?
+            }
+            _ => {
+                self.update_template_fields(&conn, &template.id, &fields)?;
+                let updated = self.fetch_template(&conn, &template.id)?;
+                self.store_next_occurrence(&conn, &updated)?;
+                template.id
+            }
+        };
+
+        let template = self.fetch_template(&conn, &id)?;
+        self.to_dto(&conn, template)
+    }
+
+    fn delete_recurring(&self, id: &str) -> RecurringResult<()> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        // Posted transactions stay in the ledger; they just stop pointing at the template.
+        tx.execute(
+            r#"UPDATE "Transaction" SET recurrence_id = NULL WHERE recurrence_id = ? AND user_id = ?"#,
+            params![id, self.user_id],
+        )?;
+        let affected = tx.execute(
+            r#"DELETE FROM "RecurringTransaction" WHERE id = ? AND user_id = ?"#,
+            params![id, self.user_id],
+        )?;
+        if affected == 0 {
+            return Err(RecurringServiceError::NotFound(format!(
+                "Recurring transaction {id}"
+            )));
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn skip_occurrence(
+        &self,
+        input: SkipOccurrenceInput,
+    ) -> RecurringResult<RecurringTransactionDto> {
+        let conn = self.connection()?;
+        let template = self.fetch_template(&conn, &input.id)?;
+        let date = parse_date(&input.occurrence_date)?;
+
+        if !template.rule.is_occurrence(template.start_date, date)
+            || template.end_date.is_some_and(|end| date > end)
+        {
+            return Err(RecurringServiceError::Validation(format!(
+                "{} is not an occurrence of this series",
+                format_date(date)
+            )));
+        }
+        if template.last_posted_on.is_some_and(|posted| date <= posted) {
+            return Err(RecurringServiceError::Validation(format!(
+                "The occurrence on {} has already been posted",
+                format_date(date)
+            )));
+        }
+
+        conn.execute(
+            r#"INSERT OR IGNORE INTO "RecurringSkip" (recurring_id, occurrence_date) VALUES (?1, ?2)"#,
+            params![template.id, format_date(date)],
+        )?;
+        self.store_next_occurrence(&conn, &template)?;
+
+        let template = self.fetch_template(&conn, &input.id)?;
+        self.to_dto(&conn, template)
+    }
+
+    fn post_due(&self, today: NaiveDate) -> RecurringResult<Vec<TransactionDto>> {
+        let conn = self.connection()?;
+        let mut posted = Vec::new();
+
+        for template in self.fetch_templates(&conn, Some(today))? {
+            // One broken template (e.g. a closed account) must not hold up the others.
+            match self.post_template(&conn, &template, today) {
+                Ok(mut created) => posted.append(&mut created),
+                Err(err) => tracing::warn!(
+                    recurring_id = %template.id,
+                    error = %err,
+                    "Failed to post recurring transaction"
+                ),
+            }
+        }
+
+        Ok(posted)
+    }
+}
+
+struct TemplateRow {
+    id: String,
+    account_id: String,
+    account_name: Option<String>,
+    category_id: Option<String>,
+    category_name: Option<String>,
+    kind: TransactionKind,
+    amount_cents: i64,
+    currency: String,
+    notes: Option<String>,
+    tags: Option<String>,
+    goal_id: Option<String>,
+    transfer_account_id: Option<String>,
+    rule: RecurrenceRule,
+    start_date: NaiveDate,
+    end_date: Option<NaiveDate>,
+    next_occurrence: Option<NaiveDate>,
+    last_posted_on: Option<NaiveDate>,
+}
+
+impl TemplateRow {
+    fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self> {
+        let kind: String = row.get(5)?;
+        let rrule: String = row.get(12)?;
+        Ok(Self {
+            id: row.get(0)?,
+            account_id: row.get(1)?,
+            account_name: row.get(2)?,
+            category_id: row.get(3)?,
+            category_name: row.get(4)?,
+            kind: parse_kind(&kind),
+            amount_cents: row.get(6)?,
+            currency: row.get(7)?,
+            notes: row.get(8)?,
+            tags: row.get(9)?,
+            goal_id: row.get(10)?,
+            transfer_account_id: row.get(11)?,
+            rule: column(12, RecurrenceRule::parse(&rrule))?,
+            start_date: column(13, parse_date(&row.get::<_, String>(13)?))?,
+            end_date: date_column(row, 14)?,
+            next_occurrence: date_column(row, 15)?,
+            last_posted_on: date_column(row, 16)?,
+        })
+    }
+
+    fn transaction_input(&self, id: String, date: NaiveDate) -> CreateTransactionInput {
+        CreateTransactionInput {
+            id: Some(id),
+            account_id: self.account_id.clone(),
+            category_id: self.category_id.clone(),
+            kind: self.kind.clone(),
+            amount_cents: self.amount_cents,
+            currency: self.currency.clone(),
+            occurred_on: format!("{}T12:00:00Z", format_date(date)),
+            notes: self.notes.clone(),
+            tags: Some(split_tags(self.tags.clone())),
+            cleared: false,
+            goal_id: self.goal_id.clone(),
+            transfer_account_id: self.transfer_account_id.clone(),
+            splits: Vec::new(),
+            recurrence_id: Some(self.id.clone()),
+            exchange_rate: None,
+            payee: None,
+            external_id: None,
+        }
+    }
+}
+
+/// Validated, storage-ready template definition shared by create and update.
+struct TemplateFields {
+    account_id: String,
+    category_id: Option<String>,
+    goal_id: Option<String>,
+    transfer_account_id: Option<String>,
+    kind: TransactionKind,
+    amount_cents: i64,
+    currency: String,
+    notes: Option<String>,
+    tags: Option<String>,
+    rule: RecurrenceRule,
+    end_date: Option<NaiveDate>,
+}
+
+impl TemplateFields {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        account_id: String,
+        category_id: Option<String>,
+        kind: TransactionKind,
+        amount_cents: i64,
+        currency: String,
+        notes: Option<String>,
+        tags: Option<Vec<String>>,
+        goal_id: Option<String>,
+        transfer_account_id: Option<String>,
+        rrule: &str,
+        end_date: Option<&str>,
+        start: NaiveDate,
+    ) -> RecurringResult<Self> {
+        if amount_cents <= 0 {
+            return Err(RecurringServiceError::Validation(
+                "Amount must be greater than zero".into(),
+            ));
+        }
+
+        let transfer_account_id = match (&kind, transfer_account_id) {
+            (TransactionKind::Transfer, Some(destination)) if destination == account_id => {
+                return Err(RecurringServiceError::Validation(
+                    "Transfer destination must differ from the source account".into(),
+                ))
+            }
+            (TransactionKind::Transfer, Some(destination)) => Some(destination),
+            (TransactionKind::Transfer, None) => {
+                return Err(RecurringServiceError::Validation(
+                    "Transfers require a destination account".into(),
+                ))
+            }
+            _ => None,
+        };
+
+        let end_date = end_date.map(parse_date).transpose()?;
+        if end_date.is_some_and(|end| end < start) {
+            return Err(RecurringServiceError::Validation(
+                "End date must not be before the start date".into(),
+            ));
+        }
+
+        Ok(Self {
+            account_id,
+            category_id,
+            goal_id,
+            transfer_account_id,
+            kind,
+            amount_cents,
+            currency: currency.to_uppercase(),
+            notes,
+            tags: tags
+                .filter(|list| !list.is_empty())
+                .map(|list| list.join(",")),
+            rule: RecurrenceRule::parse(rrule)?,
+            end_date,
+        })
+    }
+}
+
+fn new_recurring_id() -> String {
+    format!("rec_{}", Uuid::new_v4().simple())
+}
+
+fn parse_date(value: &str) -> RecurringResult<NaiveDate> {
+    let date = value.get(..10).unwrap_or(value);
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| RecurringServiceError::Validation(format!("Invalid date: {value}")))
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Surfaces a stored value that no longer parses as a column conversion error.
+fn column<T>(index: usize, value: RecurringResult<T>) -> rusqlite::Result<T> {
+    value.map_err(|err| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(err)))
+}
+
+fn date_column(row: &rusqlite::Row<'_>, index: usize) -> rusqlite::Result<Option<NaiveDate>> {
+    let value: Option<String> = row.get(index)?;
+    column(index, value.as_deref().map(parse_date).transpose())
+}
+
+fn parse_kind(value: &str) -> TransactionKind {
+    match value {
+        "income" => TransactionKind::Income,
+        "expense" => TransactionKind::Expense,
+        _ => TransactionKind::Transfer,
+    }
+}
+
+fn split_tags(tags: Option<String>) -> Vec<String> {
+    tags.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{
+        CreateCategoryInput, DeleteCategoryInput, MergeCategoriesInput, SqliteTransactionService,
+        TransactionQuery,
+    };
+
+    fn setup() -> (SqliteRecurringService, Arc<SqliteTransactionService>) {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+
+        let transactions = Arc::new(
+            SqliteTransactionService::new(path.clone(), None, Some("seed-user".into())).unwrap(),
+        );
+        let service =
+            SqliteRecurringService::new(path, None, Some("seed-user".into()), transactions.clone())
+                .unwrap();
+        (service, transactions)
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).unwrap()
+    }
+
+    fn rent(rrule: &str, start: &str) -> CreateRecurringTransactionInput {
+        CreateRecurringTransactionInput {
+            account_id: "acct-default".into(),
+            category_id: Some("cat-rent".into()),
+            kind: TransactionKind::Expense,
+            amount_cents: 120_000,
+            currency: "usd".into(),
+            notes: Some("Rent".into()),
+            tags: Some(vec!["home".into()]),
+            goal_id: None,
+            transfer_account_id: None,
+            rrule: rrule.into(),
+            start_date: start.into(),
+            end_date: None,
+        }
+    }
+
+    #[test]
+    fn post_due_catches_up_and_honours_skips() {
+        let (service, transactions) = setup();
+        let template = service
+            .create_recurring(rent("FREQ=MONTHLY", "2025-01-01"))
+            .unwrap();
+        assert_eq!(template.next_occurrence.as_deref(), Some("2025-01-01"));
+
+        service
+            .skip_occurrence(SkipOccurrenceInput {
+                id: template.id.clone(),
+                occurrence_date: "2025-02-01".into(),
+            })
+            .unwrap();
+
+        let posted = service.post_due(date("2025-04-15")).unwrap();
+        let dates = posted
+            .iter()
+            .map(|tx| tx.occurred_on[..10].to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(dates, vec!["2025-01-01", "2025-03-01", "2025-04-01"]);
+        assert!(posted
+            .iter()
+            .all(|tx| tx.recurrence_id.as_deref() == Some(template.id.as_str())));
+
+        // A second pass on the same day is a no-op.
+        assert!(service.post_due(date("2025-04-15")).unwrap().is_empty());
+        let listed = transactions
+            .list_transactions(TransactionQuery::default())
+            .unwrap();
+        assert_eq!(listed.len(), 3);
+
+        let template = service.list_recurring().unwrap().remove(0);
+        assert_eq!(template.next_occurrence.as_deref(), Some("2025-05-01"));
+        assert_eq!(template.last_posted_on.as_deref(), Some("2025-04-01"));
+    }
+
+    #[test]
+    fn merging_a_category_repoints_its_recurring_series() {
+        let (service, transactions) = setup();
+        let category = |name: &str| {
+            transactions
+                .create_category(CreateCategoryInput {
+                    id: None,
+                    name: name.into(),
+                    category_type: "expense".into(),
+                    parent_id: None,
+                    icon: None,
+                })
+                .unwrap()
+        };
+        let lodging = category("Lodging");
+        let housing = category("Housing");
+        let template = service
+            .create_recurring(CreateRecurringTransactionInput {
+                category_id: Some(lodging.id.clone()),
+                ..rent("FREQ=MONTHLY", "2025-01-01")
+            })
+            .unwrap();
+
+        // Only the series uses the category, and that is enough to keep it
+        assert!(transactions
+            .delete_category(DeleteCategoryInput {
+                id: lodging.id.clone(),
+                reassign_to: None,
+            })
+            .is_err());
+
+        transactions
+            .merge_categories(MergeCategoriesInput {
+                source_id: lodging.id,
+                target_id: housing.id.clone(),
+            })
+            .unwrap();
+        let listed = service.list_recurring().unwrap();
+        let merged = listed.iter().find(|item| item.id == template.id).unwrap();
+        assert_eq!(merged.category_id.as_deref(), Some(housing.id.as_str()));
+
+        let posted = service.post_due(date("2025-01-15")).unwrap();
+        assert_eq!(posted.len(), 1);
+        assert_eq!(posted[0].category_id.as_deref(), Some(housing.id.as_str()));
+    }
+
+    #[test]
+    fn edit_this_and_future_splits_the_series() {
+        let (service, _) = setup();
+        let template = service
+            .create_recurring(rent("FREQ=MONTHLY;COUNT=12", "2025-01-31"))
+            .unwrap();
+        service.post_due(date("2025-02-28")).unwrap();
+
+        let mut update = UpdateRecurringTransactionInput {
+            id: template.id.clone(),
+            effective_from: Some("2025-02-28".into()),
+            account_id: "acct-default".into(),
+            category_id: Some("cat-rent".into()),
+            kind: TransactionKind::Expense,
+            amount_cents: 130_000,
+            currency: "USD".into(),
+            notes: Some("Rent".into()),
+            tags: None,
+            goal_id: None,
+            transfer_account_id: None,
+            rrule: "FREQ=MONTHLY;COUNT=12".into(),
+            end_date: None,
+        };
+        assert!(service.update_recurring(update.clone()).is_err());
+
+        update.effective_from = Some("2025-03-31".into());
+        let future = service.update_recurring(update).unwrap();
+        assert_ne!(future.id, template.id);
+        assert_eq!(future.rrule, "FREQ=MONTHLY;BYMONTHDAY=31;COUNT=10");
+        assert_eq!(future.next_occurrence.as_deref(), Some("2025-03-31"));
+
+        let all = service.list_recurring().unwrap();
+        let past = all.iter().find(|item| item.id == template.id).unwrap();
+        assert_eq!(past.end_date.as_deref(), Some("2025-03-30"));
+        assert_eq!(past.next_occurrence, None);
+        assert_eq!(past.amount_cents, 120_000);
+    }
+}
@@ -4,7 +4,7 @@ use chrono::{DateTime, Duration, Utc};
 use rusqlite::{params, Connection};
 use uuid::Uuid;
 
-use crate::services::ServiceDescriptor;
+use crate::services::{sqlcipher, ServiceDescriptor};
 
     use super::{
         CreateReminderInput, DismissReminderInput, ReminderChannel, ReminderDto, ReminderResult,
@@ -38,14 +38,14 @@ impl SqliteReminderService {
         let conn = Connection::open(&self.db_path)
             .map_err(|err| ReminderServiceError::Database(err.to_string()))?;
 
-        if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
-            return Err(ReminderServiceError::Database(err.to_string()));
+        if let Some(key) = &self.db_key {
+            sqlcipher::apply_key(&conn, key).map_err(|err| {
+                ReminderServiceError::Database(format!("Failed to apply SQLCipher key: {err}"))
+            })?;
         }
 
-        if let Some(key) = &self.db_key {
-            if let Err(err) = conn.pragma_update(None, "key", key) {
-                tracing::warn!(error = %err, "Failed to apply SQLCipher key; continuing without encryption");
-            }
+        if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
+            return Err(ReminderServiceError::Database(err.to_string()));
         }
 
         Ok(conn)
@@ -1,11 +1,16 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
 use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::services::ServiceDescriptor;
+use crate::services::{
+    budgets::BudgetService,
+    sqlcipher,
+    transactions::{CATEGORY_ANCESTRY_CTE, TRANSACTION_LINES_CTE},
+    ServiceDescriptor,
+};
 
 const DEFAULT_USER_ID: &str = "seed-user";
 const CACHE_TTL_MINUTES: i64 = 30;
@@ -117,29 +122,32 @@ pub struct SqliteReportService {
     db_path: PathBuf,
     db_key: Option<String>,
     user_id: String,
+    budgets: Arc<dyn BudgetService>,
 }
 
 impl SqliteReportService {
+    /// Budget summaries come from `budgets`, which renews recurring budgets and keeps
+    /// their past periods.
     pub fn new(
         db_path: PathBuf,
         db_key: Option<String>,
         user_id: Option<String>,
+        budgets: Arc<dyn BudgetService>,
     ) -> ReportResult<Self> {
         Ok(Self {
             db_path,
             db_key,
             user_id: user_id.unwrap_or_else(|| DEFAULT_USER_ID.to_string()),
+            budgets,
         })
     }
 
     fn connection(&self) -> ReportResult<Connection> {
         let conn = Connection::open(&self.db_path)?;
-        conn.execute("PRAGMA foreign_keys = ON;", [])?;
         if let Some(key) = &self.db_key {
-            if let Err(err) = conn.pragma_update(None, "key", key) {
-                tracing::warn!(error = %err, "Failed to apply SQLCipher key for report service");
-            }
+            sqlcipher::apply_key(&conn, key)?;
         }
+        conn.execute("PRAGMA foreign_keys = ON;", [])?;
         Ok(conn)
     }
 
@@ -209,23 +217,28 @@ impl SqliteReportService {
         start_date: &str,
         end_date: &str,
     ) -> ReportResult<Vec<SpendingByCategoryDto>> {
-        let mut stmt = conn.prepare(
+        // Split lines count toward their own categories, and subcategory spending is
+        // rolled up into its top-level category
+        let mut stmt = conn.prepare(&format!(
             r#"
+            WITH RECURSIVE {CATEGORY_ANCESTRY_CTE}, {TRANSACTION_LINES_CTE}
             SELECT 
-                t.category_id,
+                c.id,
                 COALESCE(c.name, 'Uncategorized') as category_name,
                 SUM(t.amount_cents) as amount_cents,
-                COUNT(*) as transaction_count
-            FROM "Transaction" t
-            LEFT JOIN "Category" c ON t.category_id = c.id
+                COUNT(DISTINCT t.transaction_id) as transaction_count
+            FROM transaction_lines t
+            LEFT JOIN category_ancestry a ON a.category_id = t.category_id
+                AND a.ancestor_id IN (SELECT id FROM "Category" WHERE parent_id IS NULL)
+            LEFT JOIN "Category" c ON c.id = a.ancestor_id
             WHERE t.user_id = ? 
               AND t.type = 'expense'
               AND DATE(t.occurred_on) >= ? 
               AND DATE(t.occurred_on) <= ?
-            GROUP BY t.category_id, c.name
+            GROUP BY c.id, c.name
             ORDER BY amount_cents DESC
             "#,
-        )?;
+        ))?;
 
         let rows = stmt.query_map(params![self.user_id, start_date, end_date], |row| {
             Ok(SpendingByCategoryDto {
@@ -252,19 +265,20 @@ impl SqliteReportService {
     fn monthly_trend(&self, conn: &Connection, months: i32) -> ReportResult<Vec<MonthlyTrendDto>> {
         let start_date = Utc::now().date_naive() - chrono::Duration::days((months * 30) as i64);
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"
+            WITH {TRANSACTION_LINES_CTE}
             SELECT 
                 strftime('%Y-%m', occurred_on) as month,
                 SUM(CASE WHEN type = 'income' THEN amount_cents ELSE 0 END) as income_cents,
                 SUM(CASE WHEN type = 'expense' THEN amount_cents ELSE 0 END) as expense_cents
-            FROM "Transaction"
+            FROM transaction_lines
             WHERE user_id = ? 
               AND DATE(occurred_on) >= ?
             GROUP BY strftime('%Y-%m', occurred_on)
             ORDER BY month ASC
-            "#,
-        )?;
+            "#
+        ))?;
 
         let rows = stmt.query_map(params![self.user_id, start_date], |row| {
             let income: i64 = row.get(1)?;
@@ -364,61 +378,53 @@ impl ReportService for SqliteReportService {
 
         // Calculate income vs expense for the month
         let income: i64 = conn.query_row(
-            r#"
+            &format!(
+                r#"
+            WITH {TRANSACTION_LINES_CTE}
             SELECT COALESCE(SUM(amount_cents), 0)
-            FROM "Transaction"
+            FROM transaction_lines
             WHERE user_id = ? 
               AND type = 'income'
               AND strftime('%Y-%m', occurred_on) = ?
-            "#,
+            "#
+            ),
             params![self.user_id, month],
             |row| row.get(0),
         )?;
 
         let expense: i64 = conn.query_row(
-            r#"
+            &format!(
+                r#"
+            WITH {TRANSACTION_LINES_CTE}
             SELECT COALESCE(SUM(amount_cents), 0)
-            FROM "Transaction"
+            FROM transaction_lines
             WHERE user_id = ? 
               AND type = 'expense'
               AND strftime('%Y-%m', occurred_on) = ?
-            "#,
+            "#
+            ),
             params![self.user_id, month],
             |row| row.get(0),
         )?;
 
-        // Get budget summaries
-        let mut budget_stmt = conn.prepare(
-            r#"
-            SELECT b.id, b.name, b.amount_cents,
-                   COALESCE(SUM(CASE WHEN t.type = 'expense' THEN t.amount_cents ELSE 0 END), 0) as spent_cents
-            FROM "Budget" b
-            LEFT JOIN "Transaction" t ON t.category_id = b.category_id 
-                AND DATE(t.occurred_on) >= DATE(b.start_date) 
-                AND DATE(t.occurred_on) <= DATE(b.end_date)
-            WHERE b.user_id = ?
-              AND strftime('%Y-%m', b.start_date) = ?
-            GROUP BY b.id, b.name, b.amount_cents
-            "#,
-        )?;
-
-        let budget_rows = budget_stmt.query_map(params![self.user_id, month], |row| {
-            let target: i64 = row.get(2)?;
-            let spent: i64 = row.get(3)?;
-            Ok(BudgetSummary {
-                budget_id: row.get(0)?,
-                budget_name: row.get(1)?,
-                target_cents: target,
-                spent_cents: spent,
-                progress_percent: if target > 0 {
-                    (spent as f64 / target as f64) * 100.0
+        // Budgets as they stood at the start of the month, renewed and with carries applied
+        let budget_summaries: Vec<BudgetSummary> = self
+            .budgets
+            .list_budgets(Some(&start_date))
+            .map_err(|err| ReportServiceError::Internal(err.to_string()))?
+            .into_iter()
+            .map(|budget| BudgetSummary {
+                progress_percent: if budget.available_cents > 0 {
+                    (budget.spent_cents as f64 / budget.available_cents as f64) * 100.0
                 } else {
                     0.0
                 },
+                budget_id: budget.id,
+                budget_name: budget.name,
+                target_cents: budget.available_cents,
+                spent_cents: budget.spent_cents,
             })
-        })?;
-
-        let budget_summaries: Vec<BudgetSummary> = budget_rows.collect::<Result<Vec<_>, _>>()?;
+            .collect();
 
         // Filter trend to relevant months
         let trend_filtered: Vec<TrendPoint> = trend
@@ -517,6 +523,13 @@ impl ReportService for SqliteReportService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::SqliteBudgetService;
+
+    fn report_service(path: PathBuf) -> SqliteReportService {
+        let budgets =
+            SqliteBudgetService::new(path.clone(), None, Some("seed-user".into())).unwrap();
+        SqliteReportService::new(path, None, Some("seed-user".into()), Arc::new(budgets)).unwrap()
+    }
 
     #[test]
     fn test_cache_key_generation() {
@@ -524,10 +537,147 @@ mod tests {
             PathBuf::from(":memory:"),
             None,
             Some("test-user".to_string()),
+            Arc::new(crate::services::NoopBudgetService),
         )
         .unwrap();
 
         let key = service.get_cache_key("monthly_report", "2025-01");
         assert_eq!(key, "monthly_report:test-user:2025-01");
     }
+
+    #[test]
+    fn spending_by_category_rolls_up_subcategories() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let transactions = crate::services::SqliteTransactionService::new(
+            path.clone(),
+            None,
+            Some("seed-user".into()),
+        )
+        .unwrap();
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            r#"
+            INSERT INTO "Category" (id, user_id, name, type, parent_id) VALUES
+                ('cat-coffee', 'seed-user', 'Coffee', 'expense', 'cat-food');
+            INSERT INTO "Transaction" (id, user_id, account_id, category_id, type, amount_cents, currency, occurred_on, updated_at) VALUES
+                ('tx-parent', 'seed-user', 'acct-default', 'cat-food', 'expense', 1000, 'USD', '2025-03-05T00:00:00+00:00', CURRENT_TIMESTAMP),
+                ('tx-child', 'seed-user', 'acct-default', 'cat-coffee', 'expense', 250, 'USD', '2025-03-06T00:00:00+00:00', CURRENT_TIMESTAMP);
+            "#,
+        )
+        .unwrap();
+        drop(transactions);
+
+        let service = report_service(path);
+        let spending = service
+            .get_spending_by_category("2025-03-01", "2025-03-31")
+            .unwrap();
+
+        assert_eq!(spending.len(), 1);
+        assert_eq!(spending[0].category_id.as_deref(), Some("cat-food"));
+        assert_eq!(spending[0].amount_cents, 1250);
+        assert_eq!(spending[0].transaction_count, 2);
+    }
+
+    #[test]
+    fn spending_by_category_uses_split_lines() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        crate::services::SqliteTransactionService::new(
+            path.clone(),
+            None,
+            Some("seed-user".into()),
+        )
+        .unwrap();
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            r#"
+            INSERT INTO "Transaction" (id, user_id, account_id, category_id, type, amount_cents, currency, occurred_on, updated_at) VALUES
+                ('tx-split', 'seed-user', 'acct-default', 'cat-food', 'expense', 4000, 'USD', '2025-03-05T00:00:00+00:00', CURRENT_TIMESTAMP);
+            INSERT INTO "TransactionSplit" (id, transaction_id, user_id, category_id, amount_cents) VALUES
+                ('split-1', 'tx-split', 'seed-user', 'cat-food', 2500),
+                ('split-2', 'tx-split', 'seed-user', 'cat-home', 1500);
+            "#,
+        )
+        .unwrap();
+
+        let service = report_service(path);
+        let spending = service
+            .get_spending_by_category("2025-03-01", "2025-03-31")
+            .unwrap();
+
+        let amount_for = |id: &str| {
+            spending
+                .iter()
+                .find(|item| item.category_id.as_deref() == Some(id))
+                .map(|item| item.amount_cents)
+        };
+        assert_eq!(amount_for("cat-food"), Some(2500));
+        assert_eq!(amount_for("cat-home"), Some(1500));
+    }
+    #[test]
+    fn monthly_report_shows_past_periods_of_recurring_budgets() {
+        use crate::services::budgets::{
+            BudgetPeriod, BudgetType, CreateBudgetInput, UpdateBudgetInput,
+        };
+
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        crate::services::SqliteTransactionService::new(
+            path.clone(),
+            None,
+            Some("seed-user".into()),
+        )
+        .unwrap();
+        let budgets =
+            SqliteBudgetService::new(path.clone(), None, Some("seed-user".into())).unwrap();
+
+        let (this_month, _) = BudgetPeriod::Monthly.window(Utc::now().date_naive(), 1);
+        let last_month = this_month.pred_opt().unwrap().format("%Y-%m").to_string();
+        let created = budgets
+            .create_budget(CreateBudgetInput {
+                name: "Food".into(),
+                period: BudgetPeriod::Monthly,
+                budget_type: BudgetType::Envelope,
+                category_id: Some("cat-food".into()),
+                amount_cents: 10_000,
+                start_date: format!("{last_month}-01T00:00:00Z"),
+                end_date: format!("{last_month}-01T00:00:00Z"),
+                recurring: true,
+                rollover: false,
+                rollover_cap_cents: None,
+                alert_threshold: None,
+            })
+            .unwrap();
+        budgets
+            .update_budget(UpdateBudgetInput {
+                id: created.id.clone(),
+                name: None,
+                period: None,
+                budget_type: None,
+                category_id: None,
+                amount_cents: Some(15_000),
+                start_date: None,
+                end_date: None,
+                recurring: None,
+                rollover: None,
+                rollover_cap_cents: None,
+                alert_threshold: None,
+            })
+            .unwrap();
+
+        let service = report_service(path);
+        let report = service.get_monthly_report(&last_month).unwrap();
+        assert_eq!(report.budget_summaries.len(), 1);
+        assert_eq!(report.budget_summaries[0].budget_id, created.id);
+        assert_eq!(report.budget_summaries[0].target_cents, 10_000);
+
+        let current = service
+            .get_monthly_report(&this_month.format("%Y-%m").to_string())
+            .unwrap();
+        assert_eq!(current.budget_summaries[0].target_cents, 15_000);
+    }
 }
@@ -37,6 +37,32 @@ pub struct UpdateCategoryOrderInput {
     pub category_ids: Vec<String>,
 }
 
+/// App lock configuration; the PIN hash itself never leaves the service.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLockSettingsDto {
+    pub pin_set: bool,
+    /// Locks after this many minutes without user activity; 0 turns the idle lock off.
+    pub idle_minutes: u32,
+    pub lock_on_sleep: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAppLockPinInput {
+    /// Required when a PIN is already set.
+    pub current_pin: Option<String>,
+    /// `None` or empty removes the PIN, which turns the app lock off.
+    pub new_pin: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAppLockSettingsInput {
+    pub idle_minutes: Option<u32>,
+    pub lock_on_sleep: Option<bool>,
+}
+
 #[derive(Debug, Error)]
 pub enum SettingsServiceError {
     #[error("database error: {0}")]
@@ -63,4 +89,13 @@ pub trait SettingsService: Send + Sync {
         input: UpdateUserSettingsInput,
     ) -> SettingsResult<UserSettingsDto>;
     fn update_category_order(&self, input: UpdateCategoryOrderInput) -> SettingsResult<()>;
+    fn get_app_lock_settings(&self) -> SettingsResult<AppLockSettingsDto>;
+    /// Sets, changes or removes the app lock PIN, stored as an Argon2id hash.
+    fn set_app_lock_pin(&self, input: SetAppLockPinInput) -> SettingsResult<AppLockSettingsDto>;
+    fn update_app_lock_settings(
+        &self,
+        input: UpdateAppLockSettingsInput,
+    ) -> SettingsResult<AppLockSettingsDto>;
+    /// Checks `pin` against the stored hash; always passes when no PIN is set.
+    fn verify_app_lock_pin(&self, pin: &str) -> SettingsResult<bool>;
 }
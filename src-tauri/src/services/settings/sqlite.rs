@@ -1,15 +1,22 @@
 use std::path::PathBuf;
 
-use rusqlite::{params, Connection};
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use rand::rngs::OsRng;
+use rusqlite::{params, Connection, OptionalExtension};
 
-use crate::services::ServiceDescriptor;
+use crate::services::{sqlcipher, ServiceDescriptor};
 
 use super::{
-    SettingsResult, SettingsService, SettingsServiceError, UpdateCategoryOrderInput,
-    UpdateUserSettingsInput, UserSettingsDto,
+    AppLockSettingsDto, SetAppLockPinInput, SettingsResult, SettingsService, SettingsServiceError,
+    UpdateAppLockSettingsInput, UpdateCategoryOrderInput, UpdateUserSettingsInput, UserSettingsDto,
 };
 
 const DEFAULT_USER_ID: &str = "seed-user";
+const MIN_PIN_LENGTH: usize = 4;
+const MAX_IDLE_MINUTES: u32 = 24 * 60;
 
 pub struct SqliteSettingsService {
     db_path: PathBuf,
@@ -39,6 +46,16 @@ impl SqliteSettingsService {
                 SettingsServiceError::Database(format!("Failed to open database: {}", err))
             })?;
 
+        if let Some(key) = &self.db_key {
+            if let Err(err) = sqlcipher::apply_key(&conn, key) {
+                tracing::error!(error = %err, "Failed to apply SQLCipher key");
+                return Err(SettingsServiceError::Database(format!(
+                    "Failed to apply SQLCipher key: {}",
+                    err
+                )));
+            }
+        }
+
         if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
             tracing::error!(error = %err, "Failed to enable foreign keys");
             return Err(SettingsServiceError::Database(format!(
@@ -47,13 +64,6 @@ impl SqliteSettingsService {
             )));
         }
 
-        if let Some(key) = &self.db_key {
-            // Align behavior with transaction service: if SQLCipher is unavailable, log and continue unencrypted
-            if let Err(err) = conn.pragma_update(None, "key", key) {
-                tracing::warn!(error = %err, "Failed to apply SQLCipher key for settings service; continuing without encryption");
-            }
-        }
-
         Ok(conn)
     }
 
@@ -64,6 +74,7 @@ impl SqliteSettingsService {
         self.init_schema(&conn)?;
         // Ensure schema is upgraded to latest shape
         self.ensure_theme_column(&conn)?;
+        self.ensure_app_lock_columns(&conn)?;
 
         // Ensure default user exists after schema is ready
         self.ensure_user_exists(&conn)?;
@@ -122,6 +133,38 @@ impl SqliteSettingsService {
         Ok(())
     }
 
+    fn ensure_app_lock_columns(&self, conn: &Connection) -> SettingsResult<()> {
+        let has_column: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM pragma_table_info('User') WHERE name = 'app_lock_hash')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if !has_column {
+            conn.execute_batch(include_str!(
+                "../../../../prisma/migrations/20261017106000_add_app_lock/migration.sql"
+            ))
+            .map_err(|err| {
+                SettingsServiceError::Database(format!("Failed to add app lock columns: {}", err))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn app_lock_hash(&self, conn: &Connection) -> SettingsResult<Option<String>> {
+        Ok(conn
+            .query_row(
+                r#"SELECT app_lock_hash FROM "User" WHERE id = ?"#,
+                params![self.user_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?
+            .flatten())
+    }
+
     fn ensure_user_exists(&self, conn: &Connection) -> SettingsResult<()> {
         let exists: bool = conn
             .query_row(
@@ -350,6 +393,105 @@ impl SettingsService for SqliteSettingsService {
 
         Ok(())
     }
+
+    fn get_app_lock_settings(&self) -> SettingsResult<AppLockSettingsDto> {
+        let conn = self.connection()?;
+        self.ensure_user_exists(&conn)?;
+
+        conn.query_row(
+            r#"SELECT app_lock_hash IS NOT NULL, app_lock_idle_minutes, app_lock_on_sleep FROM "User" WHERE id = ?"#,
+            params![self.user_id],
+            |row| {
+                Ok(AppLockSettingsDto {
+                    pin_set: row.get(0)?,
+                    idle_minutes: row.get(1)?,
+                    lock_on_sleep: row.get(2)?,
+                })
+            },
+        )
+        .map_err(|err| {
+            SettingsServiceError::Database(format!("Failed to fetch app lock settings: {}", err))
+        })
+    }
+
+    fn set_app_lock_pin(&self, input: SetAppLockPinInput) -> SettingsResult<AppLockSettingsDto> {
+        let current = input.current_pin.unwrap_or_default();
+        if !self.verify_app_lock_pin(&current)? {
+            return Err(SettingsServiceError::Validation(
+                "The current PIN is incorrect".to_string(),
+            ));
+        }
+
+        let hash = match input.new_pin.filter(|pin| !pin.is_empty()) {
+            Some(pin) if pin.chars().count() < MIN_PIN_LENGTH => {
+                return Err(SettingsServiceError::Validation(format!(
+                    "The PIN must be at least {MIN_PIN_LENGTH} characters"
+                )));
+            }
+            Some(pin) => Some(
+                Argon2::default()
+                    .hash_password(pin.as_bytes(), &SaltString::generate(&mut OsRng))
+                    .map_err(|err| SettingsServiceError::Internal(err.to_string()))?
+                    .to_string(),
+            ),
+            None => None,
+        };
+
+        let conn = self.connection()?;
+        conn.execute(
+            r#"UPDATE "User" SET app_lock_hash = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"#,
+            params![hash, self.user_id],
+        )
+        .map_err(|err| {
+            SettingsServiceError::Database(format!("Failed to update app lock PIN: {}", err))
+        })?;
+
+        self.get_app_lock_settings()
+    }
+
+    fn update_app_lock_settings(
+        &self,
+        input: UpdateAppLockSettingsInput,
+    ) -> SettingsResult<AppLockSettingsDto> {
+        let conn = self.connection()?;
+        self.ensure_user_exists(&conn)?;
+
+        if let Some(minutes) = input.idle_minutes {
+            if minutes > MAX_IDLE_MINUTES {
+                return Err(SettingsServiceError::Validation(format!(
+                    "The idle timeout cannot exceed {MAX_IDLE_MINUTES} minutes"
+                )));
+            }
+            conn.execute(
+                r#"UPDATE "User" SET app_lock_idle_minutes = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"#,
+                params![minutes, self.user_id],
+            )
+            .map_err(|err| SettingsServiceError::Database(format!("Failed to update idle timeout: {}", err)))?;
+        }
+
+        if let Some(lock_on_sleep) = input.lock_on_sleep {
+            conn.execute(
+                r#"UPDATE "User" SET app_lock_on_sleep = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"#,
+                params![lock_on_sleep, self.user_id],
+            )
+            .map_err(|err| SettingsServiceError::Database(format!("Failed to update lock on sleep: {}", err)))?;
+        }
+
+        self.get_app_lock_settings()
+    }
+
+    fn verify_app_lock_pin(&self, pin: &str) -> SettingsResult<bool> {
+        let conn = self.connection()?;
+        let Some(hash) = self.app_lock_hash(&conn)? else {
+            return Ok(true);
+        };
+        let parsed = PasswordHash::new(&hash).map_err(|err| {
+            SettingsServiceError::Internal(format!("Stored PIN hash is invalid: {}", err))
+        })?;
+        Ok(Argon2::default()
+            .verify_password(pin.as_bytes(), &parsed)
+            .is_ok())
+    }
 }
 
 #[cfg(test)]
@@ -427,4 +569,53 @@ mod tests {
         assert_eq!(settings.theme_preference.as_deref(), Some("light"));
         assert_eq!(settings.display_name.as_deref(), Some("Test User"));
     }
+
+    #[test]
+    fn app_lock_pin_is_hashed_and_verified() {
+        let service = setup_temp_service();
+        let settings = service.get_app_lock_settings().unwrap();
+        assert!(!settings.pin_set);
+        assert_eq!(settings.idle_minutes, 10);
+        assert!(settings.lock_on_sleep);
+
+        let settings = service
+            .set_app_lock_pin(SetAppLockPinInput {
+                current_pin: None,
+                new_pin: Some("4821".into()),
+            })
+            .unwrap();
+        assert!(settings.pin_set);
+        let hash = service
+            .app_lock_hash(&service.connection().unwrap())
+            .unwrap()
+            .unwrap();
+        assert!(!hash.contains("4821"));
+        assert!(service.verify_app_lock_pin("4821").unwrap());
+        assert!(!service.verify_app_lock_pin("1234").unwrap());
+
+        let err = service
+            .set_app_lock_pin(SetAppLockPinInput {
+                current_pin: Some("1234".into()),
+                new_pin: None,
+            })
+            .unwrap_err();
+        assert!(matches!(err, SettingsServiceError::Validation(_)));
+
+        let settings = service
+            .update_app_lock_settings(UpdateAppLockSettingsInput {
+                idle_minutes: Some(0),
+                lock_on_sleep: Some(false),
+            })
+            .unwrap();
+        assert_eq!(settings.idle_minutes, 0);
+        assert!(!settings.lock_on_sleep);
+
+        let settings = service
+            .set_app_lock_pin(SetAppLockPinInput {
+                current_pin: Some("4821".into()),
+                new_pin: None,
+            })
+            .unwrap();
+        assert!(!settings.pin_set);
+    }
 }
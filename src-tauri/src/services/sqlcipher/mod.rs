@@ -0,0 +1,164 @@
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use rusqlite::{params, Connection, DatabaseName, OptionalExtension};
+use thiserror::Error;
+
+/// First bytes of every unencrypted SQLite file; SQLCipher files start with random salt.
+const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+#[derive(Debug, Error)]
+pub enum SqlCipherError {
+    #[error("SQLCipher is not available in this build")]
+    Unavailable,
+    #[error("the database key was rejected: {0}")]
+    KeyRejected(String),
+    #[error("database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Keys a freshly opened connection and reads the schema so a wrong key fails here rather
+/// than on the first query. Must run before any other statement on `conn`.
+pub fn apply_key(conn: &Connection, key: &str) -> rusqlite::Result<()> {
+    conn.pragma_update(None, "key", key)?;
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })?;
+    Ok(())
+}
+
+/// Whether the file at `path` is an unencrypted SQLite database. Empty files count as
+/// not plaintext; SQLite writes the header on the first write.
+pub fn is_plaintext(path: &Path) -> io::Result<bool> {
+    let mut header = [0u8; 16];
+    match File::open(path)?.read_exact(&mut header) {
+        Ok(()) => Ok(&header == PLAINTEXT_HEADER),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Run once at startup, before any service opens the database. Checks that SQLCipher is
+/// linked, encrypts a plaintext database at `db_path` in place, and verifies that `key`
+/// opens it. Returns whether a plaintext database was converted.
+pub fn prepare_database(db_path: &Path, key: &str) -> Result<bool, SqlCipherError> {
+    let version: Option<String> = Connection::open_in_memory()?
+        .query_row("PRAGMA cipher_version", [], |row| row.get(0))
+        .optional()?;
+    if version.is_none() {
+        return Err(SqlCipherError::Unavailable);
+    }
+
+    if !db_path.exists() {
+        return Ok(false);
+    }
+    let converted = is_plaintext(db_path)?;
+    if converted {
+        encrypt_plaintext(db_path, key)?;
+    }
+
+    let conn = Connection::open(db_path)?;
+    apply_key(&conn, key).map_err(|err| SqlCipherError::KeyRejected(err.to_string()))?;
+    Ok(converted)
+}
+
+/// Copies the plaintext database into an encrypted file next to it with
+/// `sqlcipher_export`, then swaps it in. The original stays untouched until the copy has
+/// been reopened with `key`.
+fn encrypt_plaintext(db_path: &Path, key: &str) -> Result<(), SqlCipherError> {
+    let encrypted = sidecar(db_path, "encrypting");
+    remove_if_exists(&encrypted)?;
+
+    let conn = Connection::open(db_path)?;
+    // Fold a leftover WAL into the main file so nothing is lost with it
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    let user_version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+        params![encrypted.to_string_lossy(), key],
+    )?;
+    conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+    conn.pragma_update(
+        Some(DatabaseName::Attached("encrypted")),
+        "user_version",
+        user_version,
+    )?;
+    conn.execute("DETACH DATABASE encrypted", [])?;
+    drop(conn);
+
+    let check = Connection::open(&encrypted)?;
+    apply_key(&check, key).map_err(|err| SqlCipherError::KeyRejected(err.to_string()))?;
+    drop(check);
+
+    for suffix in ["wal", "shm", "journal"] {
+        remove_if_exists(&sidecar(db_path, suffix))?;
+    }
+    fs::rename(&encrypted, db_path)?;
+    tracing::info!(path = %db_path.display(), "Encrypted the plaintext database with SQLCipher");
+    Ok(())
+}
+
+/// `app.db` -> `app.db-<suffix>`, the naming SQLite uses for its own side files.
+fn sidecar(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = db_path.as_os_str().to_owned();
+    name.push(format!("-{suffix}"));
+    PathBuf::from(name)
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypts_plaintext_database_once_and_rejects_wrong_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("app.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE t (id TEXT); INSERT INTO t VALUES ('row'); PRAGMA user_version = 7;",
+        )
+        .unwrap();
+        drop(conn);
+        assert!(is_plaintext(&db_path).unwrap());
+
+        assert!(prepare_database(&db_path, "secret").unwrap());
+        assert!(!is_plaintext(&db_path).unwrap());
+        assert!(!fs::read(&db_path)
+            .unwrap()
+            .windows(3)
+            .any(|window| window == b"row"));
+        assert!(!prepare_database(&db_path, "secret").unwrap());
+
+        let conn = Connection::open(&db_path).unwrap();
+        apply_key(&conn, "secret").unwrap();
+        let value: String = conn
+            .query_row("SELECT id FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(value, "row");
+        let user_version: i64 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(user_version, 7);
+
+        assert!(matches!(
+            prepare_database(&db_path, "other"),
+            Err(SqlCipherError::KeyRejected(_))
+        ));
+        let unkeyed = Connection::open(&db_path).unwrap();
+        assert!(unkeyed
+            .query_row("SELECT count(*) FROM t", [], |row| row.get::<_, i64>(0))
+            .is_err());
+    }
+}
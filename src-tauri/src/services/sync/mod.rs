@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 use thiserror::Error;
 
-use crate::services::ServiceDescriptor;
+use crate::services::{sqlcipher, ServiceDescriptor};
 
 type HmacSha256 = Hmac<Sha256>;
 
@@ -135,7 +135,7 @@ impl SqliteSyncService {
     fn conn(&self) -> SyncServiceResult<Connection> {
         let conn = Connection::open(&self.db_path)?;
         if let Some(key) = &self.key {
-            conn.pragma_update(None, "key", key.as_str())
+            sqlcipher::apply_key(&conn, key)
                 .map_err(|err| SyncServiceError::Internal(err.to_string()))?;
         }
         Ok(conn)
@@ -0,0 +1,153 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+use super::{
+    ImportBatchDto, ImportBatchInput, ImportSource, TransactionResult, TransactionServiceError,
+};
+
+const BATCH_SELECT: &str = r#"
+    SELECT id, source, file_name, file_hash, profile_id, item_count, created_count,
+           merged_count, skipped_count, created_at, rolled_back_at
+    FROM "ImportBatch"
+"#;
+
+pub(crate) fn ensure_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS "ImportBatch" (
+            "id" TEXT NOT NULL PRIMARY KEY,
+            "user_id" TEXT NOT NULL,
+            "source" TEXT NOT NULL DEFAULT 'manual',
+            "file_name" TEXT,
+            "file_hash" TEXT,
+            "profile_id" TEXT,
+            "item_count" INTEGER NOT NULL DEFAULT 0,
+            "created_count" INTEGER NOT NULL DEFAULT 0,
+            "merged_count" INTEGER NOT NULL DEFAULT 0,
+            "skipped_count" INTEGER NOT NULL DEFAULT 0,
+            "rolled_back_at" DATETIME,
+            "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            "updated_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            CONSTRAINT "ImportBatch_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS "ImportBatch_user_id_created_at_idx" ON "ImportBatch"("user_id", "created_at");
+        "#,
+    )
+}
+
+fn db_error(err: rusqlite::Error) -> TransactionServiceError {
+    TransactionServiceError::Database(err.to_string())
+}
+
+fn map_batch(row: &rusqlite::Row<'_>) -> rusqlite::Result<ImportBatchDto> {
+    let source: String = row.get(1)?;
+    Ok(ImportBatchDto {
+        id: row.get(0)?,
+        source: ImportSource::from_db(&source),
+        file_name: row.get(2)?,
+        file_hash: row.get(3)?,
+        profile_id: row.get(4)?,
+        item_count: row.get(5)?,
+        created_count: row.get(6)?,
+        merged_count: row.get(7)?,
+        skipped_count: row.get(8)?,
+        created_at: row.get(9)?,
+        rolled_back_at: row.get(10)?,
+    })
+}
+
+pub(crate) fn list(conn: &Connection, user_id: &str) -> TransactionResult<Vec<ImportBatchDto>> {
+    let sql = format!("{BATCH_SELECT} WHERE user_id = ? ORDER BY created_at DESC, rowid DESC");
+    let mut stmt = conn.prepare(&sql).map_err(db_error)?;
+    let batches = stmt
+        .query_map(params![user_id], map_batch)
+        .map_err(db_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_error)?;
+    Ok(batches)
+}
+
+pub(crate) fn fetch(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+) -> TransactionResult<ImportBatchDto> {
+    let sql = format!("{BATCH_SELECT} WHERE user_id = ? AND id = ?");
+    conn.query_row(&sql, params![user_id, id], map_batch)
+        .optional()
+        .map_err(db_error)?
+        .ok_or_else(|| TransactionServiceError::NotFound(format!("Import batch {id} not found")))
+}
+
+/// Records the batch before its transactions are written so they can reference it.
+pub(crate) fn insert(
+    conn: &Connection,
+    user_id: &str,
+    input: &ImportBatchInput,
+    item_count: usize,
+) -> TransactionResult<String> {
+    let id = format!("batch_{}", Uuid::new_v4());
+    conn.execute(
+        r#"INSERT INTO "ImportBatch" (
+               id, user_id, source, file_name, file_hash, profile_id, item_count,
+               created_at, updated_at
+           ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)"#,
+        params![
+            id,
+            user_id,
+            input.source.as_str(),
+            input.file_name,
+            input.file_hash,
+            input.profile_id,
+            item_count as i64,
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(id)
+}
+
+pub(crate) fn record_counts(
+    conn: &Connection,
+    id: &str,
+    created: usize,
+    merged: usize,
+    skipped: usize,
+) -> TransactionResult<()> {
+    conn.execute(
+        r#"UPDATE "ImportBatch"
+           SET created_count = ?1, merged_count = ?2, skipped_count = ?3,
+               updated_at = CURRENT_TIMESTAMP
+           WHERE id = ?4"#,
+        params![created as i64, merged as i64, skipped as i64, id],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// Ids of the batch's transactions that are still stored, transfer legs included.
+pub(crate) fn transaction_ids(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+) -> TransactionResult<Vec<String>> {
+    let mut stmt = conn
+        .prepare(r#"SELECT id FROM "Transaction" WHERE user_id = ? AND import_batch_id = ?"#)
+        .map_err(db_error)?;
+    let ids = stmt
+        .query_map(params![user_id, id], |row| row.get(0))
+        .map_err(db_error)?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(db_error)?;
+    Ok(ids)
+}
+
+pub(crate) fn mark_rolled_back(conn: &Connection, id: &str) -> TransactionResult<()> {
+    conn.execute(
+        r#"UPDATE "ImportBatch"
+           SET rolled_back_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+           WHERE id = ?"#,
+        params![id],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use super::TransactionKind;
+
+/// Days either side of an item's date in which an existing row can match its fingerprint.
+pub(crate) const WINDOW_DAYS: i64 = 3;
+
+/// Rows an item with the given external id repeats. Binds user id, account id and
+/// external id.
+pub(crate) const EXTERNAL_ID_WHERE: &str =
+    "t.user_id = ? AND t.account_id = ? AND t.external_id = ?";
+
+/// Rows that can share an item's fingerprint; notes are compared after the query. Rows
+/// with a different external id are other bank lines, so only rows without one match an
+/// item that has one. Binds user id, account id, type, amount, the item's date,
+/// `WINDOW_DAYS` and its external id.
+pub(crate) const FINGERPRINT_WHERE: &str = "t.user_id = ?1 AND t.account_id = ?2 \
+    AND t.type = ?3 AND t.amount_cents = ?4 \
+    AND julianday(DATE(t.occurred_on)) BETWEEN julianday(DATE(?5)) - ?6 AND julianday(DATE(?5)) + ?6 \
+    AND (?7 IS NULL OR t.external_id IS NULL)";
+
+/// Lower-cased words of the notes, so punctuation and spacing differences between a
+/// bank export and a hand-entered row do not matter.
+pub(crate) fn normalize_notes(notes: Option<&str>) -> String {
+    notes
+        .unwrap_or_default()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Stable key for an import item: its external id when it has one, otherwise account,
+/// type, amount, date and normalized notes.
+pub(crate) fn fingerprint(
+    account_id: &str,
+    kind: &TransactionKind,
+    amount_cents: i64,
+    occurred_on: &str,
+    notes: Option<&str>,
+    external_id: Option<&str>,
+) -> String {
+    match external_id {
+        Some(external_id) => format!("{account_id}|ext|{external_id}"),
+        None => format!(
+            "{account_id}|{}|{amount_cents}|{}|{}",
+            kind.as_str(),
+            occurred_on.get(..10).unwrap_or(occurred_on),
+            normalize_notes(notes)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprints_ignore_note_formatting() {
+        assert_eq!(
+            normalize_notes(Some("  POS  Coffee-Shop #12 ")),
+            "pos coffee shop 12"
+        );
+        assert_eq!(normalize_notes(None), "");
+
+        let bank = fingerprint(
+            "acct",
+            &TransactionKind::Expense,
+            450,
+            "2025-03-01T12:00:00+00:00",
+            Some("Coffee shop"),
+            None,
+        );
+        assert_eq!(bank, "acct|expense|450|2025-03-01|coffee shop");
+        let with_id = fingerprint(
+            "acct",
+            &TransactionKind::Expense,
+            450,
+            "2025-03-01T12:00:00+00:00",
+            Some("Coffee shop"),
+            Some("F1"),
+        );
+        assert_eq!(with_id, "acct|ext|F1");
+    }
+}
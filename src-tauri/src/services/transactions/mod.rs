@@ -1,3 +1,8 @@
+mod batches;
+mod duplicates;
+mod reconcile;
+mod rules;
+mod search;
 mod sqlite;
 
 pub use sqlite::SqliteTransactionService;
@@ -19,6 +24,83 @@ pub struct AccountDto {
     pub balance_cents: i64,
     pub available_balance_cents: Option<i64>,
     pub color_token: Option<String>,
+    pub institution: Option<String>,
+    pub sync_external_id: Option<String>,
+    pub opening_balance_cents: i64,
+    pub sort_order: i32,
+    pub status: AccountStatus,
+    pub closed_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountStatus {
+    Active,
+    Archived,
+    Closed,
+}
+
+impl AccountStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountStatus::Active => "active",
+            AccountStatus::Archived => "archived",
+            AccountStatus::Closed => "closed",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "archived" => AccountStatus::Archived,
+            "closed" => AccountStatus::Closed,
+            _ => AccountStatus::Active,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAccountInput {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub account_type: String,
+    pub currency: String,
+    pub institution: Option<String>,
+    pub color_token: Option<String>,
+    pub sync_external_id: Option<String>,
+    #[serde(default)]
+    pub opening_balance_cents: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAccountInput {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub account_type: String,
+    pub currency: String,
+    pub institution: Option<String>,
+    pub color_token: Option<String>,
+    pub sync_external_id: Option<String>,
+    /// Leaves the opening balance untouched when omitted.
+    #[serde(default)]
+    pub opening_balance_cents: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAccountStatusInput {
+    pub id: String,
+    pub status: AccountStatus,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderAccountsInput {
+    pub account_ids: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -29,6 +111,56 @@ pub struct CategoryDto {
     #[serde(rename = "type")]
     pub category_type: String,
     pub sort_order: i32,
+    pub parent_id: Option<String>,
+    pub icon: Option<String>,
+    pub archived: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCategoryInput {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub category_type: String,
+    pub parent_id: Option<String>,
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCategoryInput {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub category_type: String,
+    pub parent_id: Option<String>,
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveCategoryInput {
+    pub id: String,
+    pub archived: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeCategoriesInput {
+    pub source_id: String,
+    pub target_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteCategoryInput {
+    pub id: String,
+    /// Category that inherits the deleted category's transactions, rules, budgets, goals
+    /// and recurring series. Required when the category is still referenced.
+    #[serde(default)]
+    pub reassign_to: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -48,6 +180,42 @@ pub struct TransactionDto {
     pub notes: Option<String>,
     pub tags: Vec<String>,
     pub goal_id: Option<String>,
+    pub transfer_account_id: Option<String>,
+    pub transfer_account_name: Option<String>,
+    pub transfer_direction: Option<TransferDirection>,
+    pub linked_transaction_id: Option<String>,
+    /// Recurring template that posted this transaction, if any.
+    pub recurrence_id: Option<String>,
+    /// Rate applied from this row's currency to its transfer counterpart's, when they differ.
+    pub exchange_rate: Option<f64>,
+    pub payee_id: Option<String>,
+    pub payee_name: Option<String>,
+    /// Completed reconciliation that locked this transaction, if any.
+    pub reconciliation_id: Option<String>,
+    pub external_id: Option<String>,
+    /// Import batch that created this transaction, if any.
+    pub import_batch_id: Option<String>,
+    pub splits: Vec<TransactionSplitDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSplitDto {
+    pub id: String,
+    pub category_id: Option<String>,
+    pub category_name: Option<String>,
+    pub amount_cents: i64,
+    pub notes: Option<String>,
+    pub goal_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSplitInput {
+    pub category_id: Option<String>,
+    pub amount_cents: i64,
+    pub notes: Option<String>,
+    pub goal_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -57,7 +225,22 @@ pub struct TransactionQuery {
     pub offset: Option<i64>,
     pub account_id: Option<String>,
     pub category_id: Option<String>,
+    /// Full-text search plus filters such as `tag:travel amount>100 before:2025-06-01`.
     pub search: Option<String>,
+    /// Inclusive `YYYY-MM-DD` bounds on `occurred_on`.
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    pub min_amount_cents: Option<i64>,
+    pub max_amount_cents: Option<i64>,
+    #[serde(rename = "type")]
+    pub kind: Option<TransactionKind>,
+    pub cleared: Option<bool>,
+    pub goal_id: Option<String>,
+    /// Transactions must carry every one of these tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub exclude_tags: Vec<String>,
 }
 
 impl Default for TransactionQuery {
@@ -68,10 +251,29 @@ impl Default for TransactionQuery {
             account_id: None,
             category_id: None,
             search: None,
+            from_date: None,
+            to_date: None,
+            min_amount_cents: None,
+            max_amount_cents: None,
+            kind: None,
+            cleared: None,
+            goal_id: None,
+            tags: Vec::new(),
+            exclude_tags: Vec::new(),
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionPage {
+    pub items: Vec<TransactionDto>,
+    /// Matching transactions across all pages.
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateTransactionInput {
@@ -90,6 +292,25 @@ pub struct CreateTransactionInput {
     #[serde(default)]
     pub cleared: bool,
     pub goal_id: Option<String>,
+    /// Destination account for `transfer` transactions.
+    #[serde(default)]
+    pub transfer_account_id: Option<String>,
+    /// Category split lines; when present they must add up to `amount_cents`.
+    #[serde(default)]
+    pub splits: Vec<TransactionSplitInput>,
+    /// Recurring template the transaction was posted from.
+    #[serde(default)]
+    pub recurrence_id: Option<String>,
+    /// Rate for a cross-currency transfer; defaults to the stored rate for `occurred_on`.
+    #[serde(default)]
+    pub exchange_rate: Option<f64>,
+    /// Payee name; matched case-insensitively against existing payees or created.
+    #[serde(default)]
+    pub payee: Option<String>,
+    /// Bank-assigned id (e.g. an OFX `FITID`); imports treat ids already on the account as
+    /// duplicates.
+    #[serde(default)]
+    pub external_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -109,12 +330,367 @@ pub struct UpdateTransactionInput {
     #[serde(default)]
     pub cleared: bool,
     pub goal_id: Option<String>,
+    /// Destination account for `transfer` transactions.
+    #[serde(default)]
+    pub transfer_account_id: Option<String>,
+    /// Category split lines; when present they must add up to `amount_cents`.
+    #[serde(default)]
+    pub splits: Vec<TransactionSplitInput>,
+    /// Rate for a cross-currency transfer; defaults to the rate the transfer already has,
+    /// or the stored rate for `occurred_on` when it had none.
+    #[serde(default)]
+    pub exchange_rate: Option<f64>,
+    /// Payee name; matched case-insensitively against existing payees or created.
+    #[serde(default)]
+    pub payee: Option<String>,
+    /// Allows changing the amount, account, date or cleared state of a reconciled
+    /// transaction.
+    #[serde(default)]
+    pub override_reconciled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayeeDto {
+    pub id: String,
+    pub name: String,
+    pub transaction_count: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePayeeInput {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePayeeInput {
+    pub id: String,
+    pub name: String,
+}
+
+/// Auto-categorization rule. Every condition that is set must match; rules run in
+/// `priority` order and only fill fields that are still empty (tags are merged).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionRuleDto {
+    pub id: String,
+    pub name: String,
+    pub priority: i32,
+    pub enabled: bool,
+    pub payee_contains: Option<String>,
+    pub notes_pattern: Option<String>,
+    pub min_amount_cents: Option<i64>,
+    pub max_amount_cents: Option<i64>,
+    pub account_id: Option<String>,
+    pub category_id: Option<String>,
+    pub tags: Vec<String>,
+    pub goal_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct TransactionRuleInput {
+    pub name: String,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default = "default_rule_enabled")]
+    pub enabled: bool,
+    /// Case-insensitive substring of the payee name.
+    #[serde(default)]
+    pub payee_contains: Option<String>,
+    /// Case-insensitive regular expression over the notes.
+    #[serde(default)]
+    pub notes_pattern: Option<String>,
+    #[serde(default)]
+    pub min_amount_cents: Option<i64>,
+    #[serde(default)]
+    pub max_amount_cents: Option<i64>,
+    #[serde(default)]
+    pub account_id: Option<String>,
+    #[serde(default)]
+    pub category_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub goal_id: Option<String>,
+}
+
+fn default_rule_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTransactionRuleInput {
+    pub id: String,
+    #[serde(flatten)]
+    pub rule: TransactionRuleInput,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyRulesInput {
+    /// Report what would change without writing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Let matching rules replace categories and goals that are already set.
+    #[serde(default)]
+    pub overwrite: bool,
+    /// Limit to these rules; all enabled rules when empty.
+    #[serde(default)]
+    pub rule_ids: Vec<String>,
+    #[serde(default)]
+    pub account_id: Option<String>,
+    /// Inclusive `YYYY-MM-DD` bounds on `occurred_on`.
+    #[serde(default)]
+    pub from_date: Option<String>,
+    #[serde(default)]
+    pub to_date: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleFieldsDto {
+    pub category_id: Option<String>,
+    pub goal_id: Option<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleChangeDto {
+    pub transaction_id: String,
+    pub occurred_on: String,
+    pub payee_name: Option<String>,
+    pub notes: Option<String>,
+    pub amount_cents: i64,
+    pub rule_ids: Vec<String>,
+    pub before: RuleFieldsDto,
+    pub after: RuleFieldsDto,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyRulesResult {
+    pub dry_run: bool,
+    /// Transactions the rules were evaluated against.
+    pub scanned: usize,
+    pub changes: Vec<RuleChangeDto>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReconciliationStatus {
+    InProgress,
+    Completed,
+}
+
+impl ReconciliationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReconciliationStatus::InProgress => "in_progress",
+            ReconciliationStatus::Completed => "completed",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "completed" => ReconciliationStatus::Completed,
+            _ => ReconciliationStatus::InProgress,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationDto {
+    pub id: String,
+    pub account_id: String,
+    pub account_name: String,
+    pub statement_date: String,
+    pub statement_balance_cents: i64,
+    /// Statement balance of the previous completed reconciliation, or the account's
+    /// opening balance for the first one.
+    pub starting_balance_cents: i64,
+    /// Starting balance plus the cleared transactions included in this reconciliation.
+    pub cleared_balance_cents: i64,
+    /// `statement_balance_cents - cleared_balance_cents`; must be zero to finish.
+    pub difference_cents: i64,
+    pub status: ReconciliationStatus,
+    /// Cleared transactions included so far, or locked when the session was finished.
+    pub transaction_count: i64,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationDetailDto {
+    pub reconciliation: ReconciliationDto,
+    /// While in progress: the account's unreconciled transactions up to the statement
+    /// date. Once completed: the transactions the reconciliation locked.
+    pub transactions: Vec<TransactionDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartReconciliationInput {
+    pub account_id: String,
+    /// `YYYY-MM-DD` statement end date.
+    pub statement_date: String,
+    pub statement_balance_cents: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateReconciliationInput {
+    pub id: String,
+    pub statement_date: String,
+    pub statement_balance_cents: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkReconciliationInput {
+    pub id: String,
+    pub transaction_ids: Vec<String>,
+    pub cleared: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ImportTransactionsInput {
     pub items: Vec<CreateTransactionInput>,
+    /// Categories the items refer to that do not exist yet, parents before children. They
+    /// are created in the import's own transaction, so they need their `id` set.
+    #[serde(default)]
+    pub categories: Vec<CreateCategoryInput>,
+    /// What to do with suspected duplicates, by item index. Suspected items without a
+    /// decision are skipped.
+    #[serde(default)]
+    pub decisions: Vec<ImportDecisionInput>,
+    /// Where the items came from, recorded on the import batch.
+    #[serde(default)]
+    pub batch: ImportBatchInput,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportSource {
+    /// Items sent straight to `import_transactions`, e.g. by the sample import.
+    #[default]
+    Manual,
+    Ofx,
+    Qif,
+    Csv,
+}
+
+impl ImportSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportSource::Manual => "manual",
+            ImportSource::Ofx => "ofx",
+            ImportSource::Qif => "qif",
+            ImportSource::Csv => "csv",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "ofx" => ImportSource::Ofx,
+            "qif" => ImportSource::Qif,
+            "csv" => ImportSource::Csv,
+            _ => ImportSource::Manual,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportBatchInput {
+    #[serde(default)]
+    pub source: ImportSource,
+    #[serde(default)]
+    pub file_name: Option<String>,
+    /// Hex SHA-256 of the imported file.
+    #[serde(default)]
+    pub file_hash: Option<String>,
+    /// CSV mapping profile the file was read with.
+    #[serde(default)]
+    pub profile_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportBatchDto {
+    pub id: String,
+    pub source: ImportSource,
+    pub file_name: Option<String>,
+    pub file_hash: Option<String>,
+    pub profile_id: Option<String>,
+    pub item_count: i64,
+    pub created_count: i64,
+    pub merged_count: i64,
+    pub skipped_count: i64,
+    pub created_at: String,
+    /// Set once the batch's transactions were removed by a rollback.
+    pub rolled_back_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateAction {
+    Skip,
+    /// Create the item even though it looks like an existing transaction.
+    Import,
+    /// Copy the item's external id, payee, notes and category onto the existing
+    /// transaction where it has none, and mark it cleared if the item is.
+    Merge,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportDecisionInput {
+    pub index: usize,
+    pub action: DuplicateAction,
+    /// Transaction to merge into; defaults to the first suspected match.
+    #[serde(default)]
+    pub target_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DuplicateReason {
+    /// An existing transaction on the account has the item's external id.
+    ExternalId,
+    /// Same account, type and amount within a few days, with the same notes.
+    Fingerprint,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuspectedDuplicateDto {
+    /// Position of the item in the import.
+    pub index: usize,
+    pub reason: DuplicateReason,
+    pub fingerprint: String,
+    pub matches: Vec<TransactionDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportTransactionsResult {
+    pub batch_id: String,
+    pub created: Vec<TransactionDto>,
+    /// Existing transactions as they are after merging.
+    pub merged: Vec<TransactionDto>,
+    /// Indexes of the items that were not imported.
+    pub skipped: Vec<usize>,
+    /// Skipped items that only look like existing transactions, for the user to review.
+    /// Importing them again with a decision for their index keeps them.
+    pub flagged: Vec<SuspectedDuplicateDto>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -143,6 +719,105 @@ impl TransactionKind {
     }
 }
 
+/// Which side of a transfer a ledger row represents. A transfer is stored as two
+/// linked rows: the `out` leg debits the source account, the `in` leg credits the
+/// destination account.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferDirection {
+    Out,
+    In,
+}
+
+impl TransferDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferDirection::Out => "out",
+            TransferDirection::In => "in",
+        }
+    }
+
+    pub fn from_db(value: Option<String>) -> Option<Self> {
+        match value.as_deref() {
+            Some("out") => Some(TransferDirection::Out),
+            Some("in") => Some(TransferDirection::In),
+            _ => None,
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            TransferDirection::Out => TransferDirection::In,
+            TransferDirection::In => TransferDirection::Out,
+        }
+    }
+
+    pub fn balance_delta(&self, amount: i64) -> i64 {
+        match self {
+            TransferDirection::Out => -amount,
+            TransferDirection::In => amount,
+        }
+    }
+}
+
+/// SQL expression for the signed effect of a `"Transaction"` row on its account balance.
+/// Transfer legs without a direction (rows written before paired legs existed) are neutral.
+pub(crate) const LEDGER_DELTA_SQL: &str = "CASE
+    WHEN type = 'income' THEN amount_cents
+    WHEN type = 'expense' THEN -amount_cents
+    WHEN type = 'transfer' AND transfer_direction = 'in' THEN amount_cents
+    WHEN type = 'transfer' AND transfer_direction = 'out' THEN -amount_cents
+    ELSE 0 END";
+
+/// Recursive CTE pairing every category with itself and each of its ancestors. Prefix a
+/// query with `WITH RECURSIVE {CATEGORY_ANCESTRY_CTE}` to roll child categories up into
+/// their parents through `category_ancestry(ancestor_id, category_id)`.
+pub(crate) const CATEGORY_ANCESTRY_CTE: &str = r#"category_ancestry(ancestor_id, category_id) AS (
+    SELECT id, id FROM "Category"
+    UNION
+    SELECT c.parent_id, a.category_id
+    FROM category_ancestry a
+    JOIN "Category" c ON c.id = a.ancestor_id
+    WHERE c.parent_id IS NOT NULL
+)"#;
+
+/// CTE expanding each `"Transaction"` row into its category lines: one line per split, or
+/// the row itself when it has no splits. Category and goal aggregates should read from
+/// `transaction_lines(transaction_id, user_id, type, category_id, goal_id, amount_cents,
+/// occurred_on)` so split amounts are attributed to the right categories. `amount_cents`
+/// is converted into the user's default currency at the rate for the transaction date;
+/// amounts without a known rate are counted at face value.
+pub(crate) const TRANSACTION_LINES_CTE: &str = concat!(
+    r#"transaction_lines(transaction_id, user_id, type, category_id, goal_id, amount_cents, occurred_on) AS (
+    SELECT
+        fx.transaction_id,
+        fx.user_id,
+        fx.type,
+        fx.category_id,
+        fx.goal_id,
+        CAST(ROUND(fx.amount_cents * COALESCE("#,
+    crate::services::currency::exchange_rate_sql!(),
+    r#", 1.0)) AS INTEGER),
+        fx.occurred_on
+    FROM (
+        SELECT
+            t.id AS transaction_id,
+            t.user_id,
+            t.type,
+            CASE WHEN s.id IS NULL THEN t.category_id ELSE s.category_id END AS category_id,
+            CASE WHEN s.id IS NULL THEN t.goal_id ELSE s.goal_id END AS goal_id,
+            CASE WHEN s.id IS NULL THEN t.amount_cents ELSE s.amount_cents END AS amount_cents,
+            t.occurred_on,
+            t.currency AS fx_from,
+            COALESCE(u.default_currency, t.currency) AS fx_to,
+            DATE(t.occurred_on) AS fx_on
+        FROM "Transaction" t
+        LEFT JOIN "TransactionSplit" s ON s.transaction_id = t.id
+        LEFT JOIN "User" u ON u.id = t.user_id
+    ) fx
+)"#
+);
+
 #[derive(Debug, Error)]
 pub enum TransactionServiceError {
     #[error("database error: {0}")]
@@ -151,6 +826,9 @@ pub enum TransactionServiceError {
     NotFound(String),
     #[error("validation error: {0}")]
     Validation(String),
+    /// The change would alter a transaction locked by a completed reconciliation.
+    #[error("reconciled: {0}")]
+    Reconciled(String),
     #[error("internal error: {0}")]
     Internal(String),
 }
@@ -165,9 +843,45 @@ impl From<rusqlite::Error> for TransactionServiceError {
 
 pub trait TransactionService: Send + Sync {
     fn descriptor(&self) -> ServiceDescriptor;
-    fn list_accounts(&self, include_balances: bool) -> TransactionResult<Vec<AccountDto>>;
-    fn list_categories(&self) -> TransactionResult<Vec<CategoryDto>>;
+    fn list_accounts(
+        &self,
+        include_balances: bool,
+        include_archived: bool,
+    ) -> TransactionResult<Vec<AccountDto>>;
+    fn create_account(&self, input: CreateAccountInput) -> TransactionResult<AccountDto>;
+    fn update_account(&self, input: UpdateAccountInput) -> TransactionResult<AccountDto>;
+    /// Archiving hides an account from default listings; closing additionally requires a
+    /// zero balance and blocks new transactions against it.
+    fn update_account_status(
+        &self,
+        input: UpdateAccountStatusInput,
+    ) -> TransactionResult<AccountDto>;
+    fn reorder_accounts(&self, input: ReorderAccountsInput) -> TransactionResult<()>;
+    fn list_categories(&self, include_archived: bool) -> TransactionResult<Vec<CategoryDto>>;
+    fn create_category(&self, input: CreateCategoryInput) -> TransactionResult<CategoryDto>;
+    fn update_category(&self, input: UpdateCategoryInput) -> TransactionResult<CategoryDto>;
+    fn archive_category(&self, input: ArchiveCategoryInput) -> TransactionResult<CategoryDto>;
+    /// Moves every transaction, budget, goal and subcategory from `source_id` onto
+    /// `target_id`, then removes the source category.
+    fn merge_categories(&self, input: MergeCategoriesInput) -> TransactionResult<CategoryDto>;
+    fn delete_category(&self, input: DeleteCategoryInput) -> TransactionResult<()>;
+    fn list_payees(&self) -> TransactionResult<Vec<PayeeDto>>;
+    fn create_payee(&self, input: CreatePayeeInput) -> TransactionResult<PayeeDto>;
+    fn update_payee(&self, input: UpdatePayeeInput) -> TransactionResult<PayeeDto>;
+    /// Removes the payee; its transactions keep their other fields and lose the payee.
+    fn delete_payee(&self, id: &str) -> TransactionResult<()>;
+    fn list_rules(&self) -> TransactionResult<Vec<TransactionRuleDto>>;
+    fn create_rule(&self, input: TransactionRuleInput) -> TransactionResult<TransactionRuleDto>;
+    fn update_rule(
+        &self,
+        input: UpdateTransactionRuleInput,
+    ) -> TransactionResult<TransactionRuleDto>;
+    fn delete_rule(&self, id: &str) -> TransactionResult<()>;
+    /// Re-runs rules over existing income/expense transactions, or previews the changes.
+    fn apply_rules(&self, input: ApplyRulesInput) -> TransactionResult<ApplyRulesResult>;
     fn list_transactions(&self, query: TransactionQuery) -> TransactionResult<Vec<TransactionDto>>;
+    /// Same filters as `list_transactions`, returning the page with the total match count.
+    fn search_transactions(&self, query: TransactionQuery) -> TransactionResult<TransactionPage>;
     fn create_transaction(
         &self,
         input: CreateTransactionInput,
@@ -176,9 +890,47 @@ pub trait TransactionService: Send + Sync {
         &self,
         input: UpdateTransactionInput,
     ) -> TransactionResult<TransactionDto>;
-    fn delete_transaction(&self, id: &str) -> TransactionResult<()>;
+    /// Reconciled transactions are only deleted when `override_reconciled` is set.
+    fn delete_transaction(&self, id: &str, override_reconciled: bool) -> TransactionResult<()>;
+    /// Items that look like transactions already on their account, with the matches.
+    fn find_import_duplicates(
+        &self,
+        items: &[CreateTransactionInput],
+    ) -> TransactionResult<Vec<SuspectedDuplicateDto>>;
+    /// Applies every item in one database transaction and records the call as an import
+    /// batch. Suspected duplicates, as reported by `find_import_duplicates`, follow their
+    /// decision and are skipped without one.
     fn import_transactions(
         &self,
-        items: Vec<CreateTransactionInput>,
-    ) -> TransactionResult<Vec<TransactionDto>>;
+        input: ImportTransactionsInput,
+    ) -> TransactionResult<ImportTransactionsResult>;
+    /// Import batches, newest first.
+    fn list_import_batches(&self) -> TransactionResult<Vec<ImportBatchDto>>;
+    /// Deletes the transactions a batch created and reverses their balance deltas in one
+    /// database transaction. Merges into existing transactions are kept.
+    fn rollback_import_batch(&self, id: &str) -> TransactionResult<ImportBatchDto>;
+    /// Reconciliation history, newest statement first, optionally for one account.
+    fn list_reconciliations(
+        &self,
+        account_id: Option<&str>,
+    ) -> TransactionResult<Vec<ReconciliationDto>>;
+    fn get_reconciliation(&self, id: &str) -> TransactionResult<ReconciliationDetailDto>;
+    /// Opens a session for the account; only one may be in progress per account.
+    fn start_reconciliation(
+        &self,
+        input: StartReconciliationInput,
+    ) -> TransactionResult<ReconciliationDetailDto>;
+    fn update_reconciliation(
+        &self,
+        input: UpdateReconciliationInput,
+    ) -> TransactionResult<ReconciliationDetailDto>;
+    /// Sets the cleared flag on transactions listed by an in-progress session.
+    fn mark_reconciliation_transactions(
+        &self,
+        input: MarkReconciliationInput,
+    ) -> TransactionResult<ReconciliationDetailDto>;
+    /// Locks the session's cleared transactions; the difference must be zero.
+    fn finish_reconciliation(&self, id: &str) -> TransactionResult<ReconciliationDto>;
+    /// Discards an in-progress session. Cleared flags set during it are kept.
+    fn cancel_reconciliation(&self, id: &str) -> TransactionResult<()>;
 }
@@ -0,0 +1,373 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+use super::{
+    search::parse_date, MarkReconciliationInput, ReconciliationDto, ReconciliationStatus,
+    StartReconciliationInput, TransactionResult, TransactionServiceError,
+    UpdateReconciliationInput, LEDGER_DELTA_SQL,
+};
+
+const RECONCILIATION_SELECT: &str = r#"
+    SELECT r.id, r.account_id, a.name, r.statement_date, r.statement_balance_cents,
+           r.starting_balance_cents, r.cleared_balance_cents, r.status, r.created_at,
+           r.completed_at,
+           (SELECT COUNT(*) FROM "Transaction" t WHERE t.reconciliation_id = r.id)
+    FROM "Reconciliation" r
+    JOIN "Account" a ON a.id = r.account_id
+"#;
+
+/// Transactions an in-progress session works through: the account's unreconciled rows
+/// up to the statement date. Binds user id, account id and statement date.
+pub(crate) const CANDIDATE_WHERE: &str = "t.user_id = ? AND t.account_id = ? \
+    AND t.reconciliation_id IS NULL AND DATE(t.occurred_on) <= ?";
+
+pub(crate) fn ensure_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS "Reconciliation" (
+            "id" TEXT NOT NULL PRIMARY KEY,
+            "user_id" TEXT NOT NULL,
+            "account_id" TEXT NOT NULL,
+            "statement_date" TEXT NOT NULL,
+            "statement_balance_cents" INTEGER NOT NULL,
+            "starting_balance_cents" INTEGER NOT NULL DEFAULT 0,
+            "cleared_balance_cents" INTEGER,
+            "status" TEXT NOT NULL DEFAULT 'in_progress' CHECK ("status" IN ('in_progress','completed')),
+            "completed_at" DATETIME,
+            "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            "updated_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            CONSTRAINT "Reconciliation_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+            CONSTRAINT "Reconciliation_account_id_fkey" FOREIGN KEY ("account_id") REFERENCES "Account" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS "Reconciliation_account_id_statement_date_idx" ON "Reconciliation"("account_id", "statement_date");
+        CREATE UNIQUE INDEX IF NOT EXISTS "Reconciliation_account_id_open_key" ON "Reconciliation"("account_id") WHERE "status" = 'in_progress';
+        "#,
+    )
+}
+
+fn db_error(err: rusqlite::Error) -> TransactionServiceError {
+    TransactionServiceError::Database(err.to_string())
+}
+
+fn map_reconciliation(row: &rusqlite::Row<'_>) -> rusqlite::Result<ReconciliationDto> {
+    let status: String = row.get(7)?;
+    let statement_balance_cents: i64 = row.get(4)?;
+    let cleared_balance_cents: Option<i64> = row.get(6)?;
+    let cleared_balance_cents = cleared_balance_cents.unwrap_or(statement_balance_cents);
+    Ok(ReconciliationDto {
+        id: row.get(0)?,
+        account_id: row.get(1)?,
+        account_name: row.get(2)?,
+        statement_date: row.get(3)?,
+        statement_balance_cents,
+        starting_balance_cents: row.get(5)?,
+        cleared_balance_cents,
+        difference_cents: statement_balance_cents - cleared_balance_cents,
+        status: ReconciliationStatus::from_db(&status),
+        created_at: row.get(8)?,
+        completed_at: row.get(9)?,
+        transaction_count: row.get(10)?,
+    })
+}
+
+/// Statement balance of the latest completed reconciliation, falling back to the
+/// account's opening balance.
+fn starting_balance(conn: &Connection, user_id: &str, account_id: &str) -> TransactionResult<i64> {
+    let previous: Option<i64> = conn
+        .query_row(
+            r#"SELECT statement_balance_cents FROM "Reconciliation"
+               WHERE user_id = ? AND account_id = ? AND status = 'completed'
+               ORDER BY statement_date DESC, completed_at DESC LIMIT 1"#,
+            params![user_id, account_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(db_error)?;
+    match previous {
+        Some(balance) => Ok(balance),
+        None => conn
+            .query_row(
+                r#"SELECT opening_balance_cents FROM "Account" WHERE user_id = ? AND id = ?"#,
+                params![user_id, account_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_error)?
+            .ok_or_else(|| TransactionServiceError::NotFound(account_id.to_string())),
+    }
+}
+
+/// Fills the live totals of an in-progress session; completed sessions keep the totals
+/// recorded when they were finished.
+fn summarize(
+    conn: &Connection,
+    user_id: &str,
+    mut reconciliation: ReconciliationDto,
+) -> TransactionResult<ReconciliationDto> {
+    if reconciliation.status != ReconciliationStatus::InProgress {
+        return Ok(reconciliation);
+    }
+    let (cleared_delta, count): (i64, i64) = conn
+        .query_row(
+            &format!(
+                r#"SELECT COALESCE(SUM({LEDGER_DELTA_SQL}), 0), COUNT(*) FROM "Transaction" t
+                   WHERE {CANDIDATE_WHERE} AND t.cleared = 1"#
+            ),
+            params![
+                user_id,
+                reconciliation.account_id,
+                reconciliation.statement_date
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(db_error)?;
+    reconciliation.starting_balance_cents =
+        starting_balance(conn, user_id, &reconciliation.account_id)?;
+    reconciliation.cleared_balance_cents = reconciliation.starting_balance_cents + cleared_delta;
+    reconciliation.difference_cents =
+        reconciliation.statement_balance_cents - reconciliation.cleared_balance_cents;
+    reconciliation.transaction_count = count;
+    Ok(reconciliation)
+}
+
+pub(crate) fn fetch(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+) -> TransactionResult<ReconciliationDto> {
+    let reconciliation = conn
+        .query_row(
+            &format!("{RECONCILIATION_SELECT} WHERE r.user_id = ? AND r.id = ?"),
+            params![user_id, id],
+            map_reconciliation,
+        )
+        .optional()
+        .map_err(db_error)?
+        .ok_or_else(|| TransactionServiceError::NotFound(id.to_string()))?;
+    summarize(conn, user_id, reconciliation)
+}
+
+fn fetch_open(conn: &Connection, user_id: &str, id: &str) -> TransactionResult<ReconciliationDto> {
+    let reconciliation = fetch(conn, user_id, id)?;
+    if reconciliation.status != ReconciliationStatus::InProgress {
+        return Err(TransactionServiceError::Validation(format!(
+            "Reconciliation {id} is already completed"
+        )));
+    }
+    Ok(reconciliation)
+}
+
+pub(crate) fn list(
+    conn: &Connection,
+    user_id: &str,
+    account_id: Option<&str>,
+) -> TransactionResult<Vec<ReconciliationDto>> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "{RECONCILIATION_SELECT} WHERE r.user_id = ?1 AND (?2 IS NULL OR r.account_id = ?2)
+             ORDER BY r.statement_date DESC, r.created_at DESC"
+        ))
+        .map_err(db_error)?;
+    let rows = stmt
+        .query_map(params![user_id, account_id], map_reconciliation)
+        .map_err(db_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_error)?;
+    rows.into_iter()
+        .map(|reconciliation| summarize(conn, user_id, reconciliation))
+        .collect()
+}
+
+/// Rejects statement dates before the account's latest completed reconciliation.
+fn validate_statement_date(
+    conn: &Connection,
+    user_id: &str,
+    account_id: &str,
+    statement_date: &str,
+) -> TransactionResult<String> {
+    let statement_date = parse_date(statement_date)?;
+    let latest: Option<String> = conn
+        .query_row(
+            r#"SELECT MAX(statement_date) FROM "Reconciliation"
+               WHERE user_id = ? AND account_id = ? AND status = 'completed'"#,
+            params![user_id, account_id],
+            |row| row.get(0),
+        )
+        .map_err(db_error)?;
+    if let Some(latest) = latest.filter(|latest| *latest > statement_date) {
+        return Err(TransactionServiceError::Validation(format!(
+            "Statement date must not be before the last reconciled statement ({latest})"
+        )));
+    }
+    Ok(statement_date)
+}
+
+pub(crate) fn start(
+    conn: &Connection,
+    user_id: &str,
+    input: StartReconciliationInput,
+) -> TransactionResult<String> {
+    let opening = starting_balance(conn, user_id, &input.account_id)?;
+    let statement_date =
+        validate_statement_date(conn, user_id, &input.account_id, &input.statement_date)?;
+    let open: Option<String> = conn
+        .query_row(
+            r#"SELECT id FROM "Reconciliation"
+               WHERE user_id = ? AND account_id = ? AND status = 'in_progress'"#,
+            params![user_id, input.account_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(db_error)?;
+    if let Some(open) = open {
+        return Err(TransactionServiceError::Validation(format!(
+            "Reconciliation {open} is already in progress for this account"
+        )));
+    }
+
+    let id = format!("recon_{}", Uuid::new_v4());
+    conn.execute(
+        r#"INSERT INTO "Reconciliation" (id, user_id, account_id, statement_date, statement_balance_cents, starting_balance_cents, status, updated_at)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'in_progress', CURRENT_TIMESTAMP)"#,
+        params![
+            id,
+            user_id,
+            input.account_id,
+            statement_date,
+            input.statement_balance_cents,
+            opening
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(id)
+}
+
+pub(crate) fn update(
+    conn: &Connection,
+    user_id: &str,
+    input: UpdateReconciliationInput,
+) -> TransactionResult<()> {
+    let reconciliation = fetch_open(conn, user_id, &input.id)?;
+    let statement_date = validate_statement_date(
+        conn,
+        user_id,
+        &reconciliation.account_id,
+        &input.statement_date,
+    )?;
+    conn.execute(
+        r#"UPDATE "Reconciliation"
+           SET statement_date = ?, statement_balance_cents = ?, updated_at = CURRENT_TIMESTAMP
+           WHERE id = ? AND user_id = ?"#,
+        params![
+            statement_date,
+            input.statement_balance_cents,
+            input.id,
+            user_id
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+pub(crate) fn mark(
+    tx: &rusqlite::Transaction<'_>,
+    user_id: &str,
+    input: &MarkReconciliationInput,
+) -> TransactionResult<()> {
+    let reconciliation = fetch_open(tx, user_id, &input.id)?;
+    let sql = format!(
+        r#"UPDATE "Transaction" AS t SET cleared = ?, updated_at = CURRENT_TIMESTAMP
+           WHERE t.id = ? AND {CANDIDATE_WHERE}"#
+    );
+    for transaction_id in &input.transaction_ids {
+        let affected = tx
+            .execute(
+                &sql,
+                params![
+                    input.cleared,
+                    transaction_id,
+                    user_id,
+                    reconciliation.account_id,
+                    reconciliation.statement_date
+                ],
+            )
+            .map_err(db_error)?;
+        if affected == 0 {
+            return Err(TransactionServiceError::Validation(format!(
+                "Transaction {transaction_id} is not open for this reconciliation"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Locks the session's cleared transactions and records its totals.
+pub(crate) fn finish(
+    tx: &rusqlite::Transaction<'_>,
+    user_id: &str,
+    id: &str,
+) -> TransactionResult<()> {
+    let reconciliation = fetch_open(tx, user_id, id)?;
+    if reconciliation.difference_cents != 0 {
+        return Err(TransactionServiceError::Validation(format!(
+            "Cleared balance differs from the statement by {} cents",
+            reconciliation.difference_cents
+        )));
+    }
+
+    tx.execute(
+        &format!(
+            r#"UPDATE "Transaction" AS t SET reconciliation_id = ?, updated_at = CURRENT_TIMESTAMP
+               WHERE {CANDIDATE_WHERE} AND t.cleared = 1"#
+        ),
+        params![
+            id,
+            user_id,
+            reconciliation.account_id,
+            reconciliation.statement_date
+        ],
+    )
+    .map_err(db_error)?;
+    tx.execute(
+        r#"UPDATE "Reconciliation"
+           SET status = 'completed', starting_balance_cents = ?, cleared_balance_cents = ?,
+               completed_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+           WHERE id = ? AND user_id = ?"#,
+        params![
+            reconciliation.starting_balance_cents,
+            reconciliation.cleared_balance_cents,
+            id,
+            user_id
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+pub(crate) fn cancel(conn: &Connection, user_id: &str, id: &str) -> TransactionResult<()> {
+    fetch_open(conn, user_id, id)?;
+    conn.execute(
+        r#"DELETE FROM "Reconciliation" WHERE id = ? AND user_id = ?"#,
+        params![id, user_id],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// Releases rows that an override moved to another account or un-cleared, so they no
+/// longer count as reconciled.
+pub(crate) fn release_moved(
+    tx: &rusqlite::Transaction<'_>,
+    transaction_id: &str,
+) -> TransactionResult<()> {
+    tx.execute(
+        r#"UPDATE "Transaction" AS t SET reconciliation_id = NULL
+           WHERE t.id = ? AND t.reconciliation_id IS NOT NULL
+             AND (t.cleared = 0 OR t.account_id <> (
+                 SELECT r.account_id FROM "Reconciliation" r WHERE r.id = t.reconciliation_id
+             ))"#,
+        params![transaction_id],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
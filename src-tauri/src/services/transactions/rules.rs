@@ -0,0 +1,698 @@
+use regex::{Regex, RegexBuilder};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, ToSql};
+use uuid::Uuid;
+
+use super::{
+    search::parse_date, ApplyRulesInput, ApplyRulesResult, CreatePayeeInput, PayeeDto,
+    RuleChangeDto, RuleFieldsDto, SqliteTransactionService, TransactionResult, TransactionRuleDto,
+    TransactionRuleInput, TransactionServiceError, UpdatePayeeInput,
+};
+
+const PAYEE_SELECT: &str = r#"
+    SELECT p.id, p.name, (SELECT COUNT(*) FROM "Transaction" t WHERE t.payee_id = p.id)
+    FROM "Payee" p
+"#;
+
+const RULE_SELECT: &str = r#"
+    SELECT id, name, priority, enabled, payee_contains, notes_pattern, min_amount_cents,
+           max_amount_cents, account_id, category_id, tags, goal_id
+    FROM "TransactionRule"
+"#;
+
+pub(crate) fn ensure_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS "Payee" (
+            "id" TEXT NOT NULL PRIMARY KEY,
+            "user_id" TEXT NOT NULL,
+            "name" TEXT NOT NULL,
+            "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            "updated_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            CONSTRAINT "Payee_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS "Payee_user_id_name_key" ON "Payee"("user_id", "name" COLLATE NOCASE);
+        CREATE TABLE IF NOT EXISTS "TransactionRule" (
+            "id" TEXT NOT NULL PRIMARY KEY,
+            "user_id" TEXT NOT NULL,
+            "name" TEXT NOT NULL,
+            "priority" INTEGER NOT NULL DEFAULT 0,
+            "enabled" BOOLEAN NOT NULL DEFAULT true,
+            "payee_contains" TEXT,
+            "notes_pattern" TEXT,
+            "min_amount_cents" INTEGER,
+            "max_amount_cents" INTEGER,
+            "account_id" TEXT,
+            "category_id" TEXT,
+            "tags" TEXT,
+            "goal_id" TEXT,
+            "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            "updated_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            CONSTRAINT "TransactionRule_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "User" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+            CONSTRAINT "TransactionRule_account_id_fkey" FOREIGN KEY ("account_id") REFERENCES "Account" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+            CONSTRAINT "TransactionRule_category_id_fkey" FOREIGN KEY ("category_id") REFERENCES "Category" ("id") ON DELETE SET NULL ON UPDATE CASCADE,
+            CONSTRAINT "TransactionRule_goal_id_fkey" FOREIGN KEY ("goal_id") REFERENCES "Goal" ("id") ON DELETE SET NULL ON UPDATE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS "TransactionRule_user_id_priority_idx" ON "TransactionRule"("user_id", "priority");
+        "#,
+    )
+}
+
+fn db_error(err: rusqlite::Error) -> TransactionServiceError {
+    TransactionServiceError::Database(err.to_string())
+}
+
+// ----- Payees -----
+
+fn map_payee(row: &rusqlite::Row<'_>) -> rusqlite::Result<PayeeDto> {
+    Ok(PayeeDto {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        transaction_count: row.get(2)?,
+    })
+}
+
+fn payee_name(name: &str) -> TransactionResult<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(TransactionServiceError::Validation(
+            "Payee name is required".into(),
+        ));
+    }
+    Ok(name)
+}
+
+fn find_payee_id(
+    conn: &Connection,
+    user_id: &str,
+    name: &str,
+) -> TransactionResult<Option<String>> {
+    conn.query_row(
+        r#"SELECT id FROM "Payee" WHERE user_id = ? AND name = ? COLLATE NOCASE"#,
+        params![user_id, name],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(db_error)
+}
+
+pub(crate) fn fetch_payee(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+) -> TransactionResult<PayeeDto> {
+    conn.query_row(
+        &format!("{PAYEE_SELECT} WHERE p.user_id = ? AND p.id = ?"),
+        params![user_id, id],
+        map_payee,
+    )
+    .optional()
+    .map_err(db_error)?
+    .ok_or_else(|| TransactionServiceError::NotFound(id.to_string()))
+}
+
+pub(crate) fn list_payees(conn: &Connection, user_id: &str) -> TransactionResult<Vec<PayeeDto>> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "{PAYEE_SELECT} WHERE p.user_id = ? ORDER BY p.name COLLATE NOCASE"
+        ))
+        .map_err(db_error)?;
+    let rows = stmt
+        .query_map(params![user_id], map_payee)
+        .map_err(db_error)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(db_error)
+}
+
+pub(crate) fn create_payee(
+    conn: &Connection,
+    user_id: &str,
+    input: CreatePayeeInput,
+) -> TransactionResult<PayeeDto> {
+    let name = payee_name(&input.name)?;
+    if find_payee_id(conn, user_id, name)?.is_some() {
+        return Err(TransactionServiceError::Validation(format!(
+            "Payee '{name}' already exists"
+        )));
+    }
+    let id = insert_payee(conn, user_id, name)?;
+    fetch_payee(conn, user_id, &id)
+}
+
+pub(crate) fn update_payee(
+    conn: &Connection,
+    user_id: &str,
+    input: UpdatePayeeInput,
+) -> TransactionResult<PayeeDto> {
+    let name = payee_name(&input.name)?;
+    if find_payee_id(conn, user_id, name)?.is_some_and(|existing| existing != input.id) {
+        return Err(TransactionServiceError::Validation(format!(
+            "Payee '{name}' already exists"
+        )));
+    }
+    let affected = conn
+        .execute(
+            r#"UPDATE "Payee" SET name = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ? AND user_id = ?"#,
+            params![name, input.id, user_id],
+        )
+        .map_err(db_error)?;
+    if affected == 0 {
+        return Err(TransactionServiceError::NotFound(input.id));
+    }
+    fetch_payee(conn, user_id, &input.id)
+}
+
+pub(crate) fn delete_payee(conn: &Connection, user_id: &str, id: &str) -> TransactionResult<()> {
+    conn.execute(
+        r#"UPDATE "Transaction" SET payee_id = NULL WHERE user_id = ? AND payee_id = ?"#,
+        params![user_id, id],
+    )
+    .map_err(db_error)?;
+    let affected = conn
+        .execute(
+            r#"DELETE FROM "Payee" WHERE id = ? AND user_id = ?"#,
+            params![id, user_id],
+        )
+        .map_err(db_error)?;
+    if affected == 0 {
+        return Err(TransactionServiceError::NotFound(id.to_string()));
+    }
+    Ok(())
+}
+
+fn insert_payee(conn: &Connection, user_id: &str, name: &str) -> TransactionResult<String> {
+    let id = format!("payee_{}", Uuid::new_v4());
+    conn.execute(
+        r#"INSERT INTO "Payee" (id, user_id, name, updated_at) VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)"#,
+        params![id, user_id, name],
+    )
+    .map_err(db_error)?;
+    Ok(id)
+}
+
+/// Id of the payee called `name` (case-insensitive), creating it on first use.
+pub(crate) fn resolve_payee(
+    conn: &Connection,
+    user_id: &str,
+    name: Option<&str>,
+) -> TransactionResult<Option<(String, String)>> {
+    let Some(name) = name.map(str::trim).filter(|name| !name.is_empty()) else {
+        return Ok(None);
+    };
+    let id = match find_payee_id(conn, user_id, name)? {
+        Some(id) => id,
+        None => insert_payee(conn, user_id, name)?,
+    };
+    Ok(Some((id, name.to_string())))
+}
+
+// ----- Rules -----
+
+fn map_rule(row: &rusqlite::Row<'_>) -> rusqlite::Result<TransactionRuleDto> {
+    Ok(TransactionRuleDto {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        priority: row.get(2)?,
+        enabled: row.get(3)?,
+        payee_contains: row.get(4)?,
+        notes_pattern: row.get(5)?,
+        min_amount_cents: row.get(6)?,
+        max_amount_cents: row.get(7)?,
+        account_id: row.get(8)?,
+        category_id: row.get(9)?,
+        tags: SqliteTransactionService::split_tags(row.get(10)?),
+        goal_id: row.get(11)?,
+    })
+}
+
+pub(crate) fn fetch_rule(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+) -> TransactionResult<TransactionRuleDto> {
+    conn.query_row(
+        &format!("{RULE_SELECT} WHERE user_id = ? AND id = ?"),
+        params![user_id, id],
+        map_rule,
+    )
+    .optional()
+    .map_err(db_error)?
+    .ok_or_else(|| TransactionServiceError::NotFound(id.to_string()))
+}
+
+pub(crate) fn list_rules(
+    conn: &Connection,
+    user_id: &str,
+) -> TransactionResult<Vec<TransactionRuleDto>> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "{RULE_SELECT} WHERE user_id = ? ORDER BY priority, created_at"
+        ))
+        .map_err(db_error)?;
+    let rows = stmt
+        .query_map(params![user_id], map_rule)
+        .map_err(db_error)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(db_error)
+}
+
+fn validate_rule(input: &TransactionRuleInput) -> TransactionResult<()> {
+    if input.name.trim().is_empty() {
+        return Err(TransactionServiceError::Validation(
+            "Rule name is required".into(),
+        ));
+    }
+    let has_condition = input
+        .payee_contains
+        .as_deref()
+        .is_some_and(|value| !value.trim().is_empty())
+        || input
+            .notes_pattern
+            .as_deref()
+            .is_some_and(|value| !value.is_empty())
+        || input.min_amount_cents.is_some()
+        || input.max_amount_cents.is_some()
+        || input.account_id.is_some();
+    if !has_condition {
+        return Err(TransactionServiceError::Validation(
+            "A rule needs at least one condition".into(),
+        ));
+    }
+    if input.category_id.is_none() && input.goal_id.is_none() && input.tags.is_empty() {
+        return Err(TransactionServiceError::Validation(
+            "A rule must set a category, goal or tags".into(),
+        ));
+    }
+    if let (Some(min), Some(max)) = (input.min_amount_cents, input.max_amount_cents) {
+        if min > max {
+            return Err(TransactionServiceError::Validation(
+                "Minimum amount cannot exceed the maximum".into(),
+            ));
+        }
+    }
+    if let Some(pattern) = input.notes_pattern.as_deref() {
+        compile_pattern(pattern)?;
+    }
+    Ok(())
+}
+
+fn compile_pattern(pattern: &str) -> TransactionResult<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(1 << 20)
+        .build()
+        .map_err(|err| TransactionServiceError::Validation(format!("Invalid notes pattern: {err}")))
+}
+
+fn rule_params(input: &TransactionRuleInput) -> [Box<dyn ToSql>; 11] {
+    let text = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    [
+        Box::new(input.name.trim().to_string()),
+        Box::new(input.priority),
+        Box::new(input.enabled),
+        Box::new(text(&input.payee_contains)),
+        Box::new(text(&input.notes_pattern)),
+        Box::new(input.min_amount_cents),
+        Box::new(input.max_amount_cents),
+        Box::new(input.account_id.clone()),
+        Box::new(input.category_id.clone()),
+        Box::new(SqliteTransactionService::parse_tags(Some(
+            input.tags.clone(),
+        ))),
+        Box::new(input.goal_id.clone()),
+    ]
+}
+
+pub(crate) fn create_rule(
+    conn: &Connection,
+    user_id: &str,
+    input: TransactionRuleInput,
+) -> TransactionResult<TransactionRuleDto> {
+    validate_rule(&input)?;
+    let id = format!("rule_{}", Uuid::new_v4());
+    let mut values: Vec<Box<dyn ToSql>> = vec![Box::new(id.clone()), Box::new(user_id.to_string())];
+    values.extend(rule_params(&input));
+    conn.execute(
+        r#"
+        INSERT INTO "TransactionRule" (
+            id, user_id, name, priority, enabled, payee_contains, notes_pattern,
+            min_amount_cents, max_amount_cents, account_id, category_id, tags, goal_id,
+            updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, CURRENT_TIMESTAMP)
+    "#,
+        params_from_iter(values.iter().map(|value| &**value)),
+    )
+    .map_err(db_error)?;
+    fetch_rule(conn, user_id, &id)
+}
+
+pub(crate) fn update_rule(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    input: TransactionRuleInput,
+) -> TransactionResult<TransactionRuleDto> {
+    validate_rule(&input)?;
+    let mut values: Vec<Box<dyn ToSql>> = rule_params(&input).into_iter().collect();
+    values.push(Box::new(id.to_string()));
+    values.push(Box::new(user_id.to_string()));
+    let affected = conn
+        .execute(
+            r#"
+            UPDATE "TransactionRule" SET
+                name = ?1, priority = ?2, enabled = ?3, payee_contains = ?4, notes_pattern = ?5,
+                min_amount_cents = ?6, max_amount_cents = ?7, account_id = ?8, category_id = ?9,
+                tags = ?10, goal_id = ?11, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?12 AND user_id = ?13
+        "#,
+            params_from_iter(values.iter().map(|value| &**value)),
+        )
+        .map_err(db_error)?;
+    if affected == 0 {
+        return Err(TransactionServiceError::NotFound(id.to_string()));
+    }
+    fetch_rule(conn, user_id, id)
+}
+
+pub(crate) fn delete_rule(conn: &Connection, user_id: &str, id: &str) -> TransactionResult<()> {
+    let affected = conn
+        .execute(
+            r#"DELETE FROM "TransactionRule" WHERE id = ? AND user_id = ?"#,
+            params![id, user_id],
+        )
+        .map_err(db_error)?;
+    if affected == 0 {
+        return Err(TransactionServiceError::NotFound(id.to_string()));
+    }
+    Ok(())
+}
+
+// ----- Engine -----
+
+pub(crate) struct CompiledRule {
+    id: String,
+    payee_contains: Option<String>,
+    notes_pattern: Option<Regex>,
+    min_amount_cents: Option<i64>,
+    max_amount_cents: Option<i64>,
+    account_id: Option<String>,
+    category_id: Option<String>,
+    tags: Vec<String>,
+    goal_id: Option<String>,
+}
+
+/// What a rule is matched against.
+pub(crate) struct RuleSubject<'a> {
+    pub payee_name: Option<&'a str>,
+    pub notes: Option<&'a str>,
+    pub amount_cents: i64,
+    pub account_id: &'a str,
+}
+
+impl CompiledRule {
+    fn matches(&self, subject: &RuleSubject<'_>) -> bool {
+        if let Some(needle) = &self.payee_contains {
+            let found = subject
+                .payee_name
+                .is_some_and(|payee| payee.to_lowercase().contains(needle));
+            if !found {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.notes_pattern {
+            if !subject.notes.is_some_and(|notes| pattern.is_match(notes)) {
+                return false;
+            }
+        }
+        if self
+            .min_amount_cents
+            .is_some_and(|min| subject.amount_cents < min)
+            || self
+                .max_amount_cents
+                .is_some_and(|max| subject.amount_cents > max)
+        {
+            return false;
+        }
+        self.account_id
+            .as_deref()
+            .is_none_or(|account_id| account_id == subject.account_id)
+    }
+}
+
+/// Enabled rules in evaluation order, or exactly `only` (enabled or not) when given.
+pub(crate) fn load_rules(
+    conn: &Connection,
+    user_id: &str,
+    only: &[String],
+) -> TransactionResult<Vec<CompiledRule>> {
+    let rules = list_rules(conn, user_id)?
+        .into_iter()
+        .filter(|rule| {
+            if only.is_empty() {
+                rule.enabled
+            } else {
+                only.contains(&rule.id)
+            }
+        })
+        .map(|rule| {
+            Ok(CompiledRule {
+                notes_pattern: rule
+                    .notes_pattern
+                    .as_deref()
+                    .map(compile_pattern)
+                    .transpose()?,
+                payee_contains: rule.payee_contains.map(|value| value.to_lowercase()),
+                id: rule.id,
+                min_amount_cents: rule.min_amount_cents,
+                max_amount_cents: rule.max_amount_cents,
+                account_id: rule.account_id,
+                category_id: rule.category_id,
+                tags: rule.tags,
+                goal_id: rule.goal_id,
+            })
+        })
+        .collect::<TransactionResult<Vec<_>>>()?;
+    Ok(rules)
+}
+
+/// Applies matching rules to `fields` and returns the ids of the rules that changed them.
+/// The first matching rule wins for category and goal; values already present are kept
+/// unless `overwrite` is set. Tags from every matching rule are merged in.
+pub(crate) fn apply(
+    rules: &[CompiledRule],
+    subject: &RuleSubject<'_>,
+    fields: &mut RuleFieldsDto,
+    overwrite: bool,
+    allow_category: bool,
+) -> Vec<String> {
+    let mut applied = Vec::new();
+    let mut category_locked = !overwrite && fields.category_id.is_some() || !allow_category;
+    let mut goal_locked = !overwrite && fields.goal_id.is_some();
+
+    for rule in rules.iter().filter(|rule| rule.matches(subject)) {
+        let mut changed = false;
+        if let (Some(category_id), false) = (&rule.category_id, category_locked) {
+            changed |= fields.category_id.as_ref() != Some(category_id);
+            fields.category_id = Some(category_id.clone());
+            category_locked = true;
+        }
+        if let (Some(goal_id), false) = (&rule.goal_id, goal_locked) {
+            changed |= fields.goal_id.as_ref() != Some(goal_id);
+            fields.goal_id = Some(goal_id.clone());
+            goal_locked = true;
+        }
+        for tag in &rule.tags {
+            if !fields
+                .tags
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(tag))
+            {
+                fields.tags.push(tag.clone());
+                changed = true;
+            }
+        }
+        if changed {
+            applied.push(rule.id.clone());
+        }
+    }
+
+    applied
+}
+
+/// Re-runs rules over stored income/expense transactions. Split transactions keep their
+/// per-line categories.
+pub(crate) fn apply_retroactively(
+    conn: &mut Connection,
+    user_id: &str,
+    input: ApplyRulesInput,
+) -> TransactionResult<ApplyRulesResult> {
+    let rules = load_rules(conn, user_id, &input.rule_ids)?;
+
+    let mut sql = String::from(
+        r#"
+        SELECT t.id, t.occurred_on, p.name, t.notes, t.amount_cents, t.account_id,
+               t.category_id, t.goal_id, t.tags,
+               EXISTS(SELECT 1 FROM "TransactionSplit" s WHERE s.transaction_id = t.id)
+        FROM "Transaction" t
+        LEFT JOIN "Payee" p ON p.id = t.payee_id
+        WHERE t.user_id = ? AND t.type IN ('income', 'expense')
+    "#,
+    );
+    let mut values: Vec<Box<dyn ToSql>> = vec![Box::new(user_id.to_string())];
+    if let Some(account_id) = &input.account_id {
+        sql.push_str(" AND t.account_id = ?");
+        values.push(Box::new(account_id.clone()));
+    }
+    if let Some(from) = &input.from_date {
+        sql.push_str(" AND DATE(t.occurred_on) >= ?");
+        values.push(Box::new(parse_date(from)?));
+    }
+    if let Some(to) = &input.to_date {
+        sql.push_str(" AND DATE(t.occurred_on) <= ?");
+        values.push(Box::new(parse_date(to)?));
+    }
+    sql.push_str(" ORDER BY t.occurred_on, t.created_at");
+
+    let mut scanned = 0;
+    let mut changes = Vec::new();
+    {
+        let mut stmt = conn.prepare(&sql).map_err(db_error)?;
+        let mut rows = stmt
+            .query(params_from_iter(values.iter().map(|value| &**value)))
+            .map_err(db_error)?;
+        while let Some(row) = rows.next().map_err(db_error)? {
+            scanned += 1;
+            let payee_name: Option<String> = row.get(2).map_err(db_error)?;
+            let notes: Option<String> = row.get(3).map_err(db_error)?;
+            let amount_cents: i64 = row.get(4).map_err(db_error)?;
+            let account_id: String = row.get(5).map_err(db_error)?;
+            let has_splits: bool = row.get(9).map_err(db_error)?;
+            let before = RuleFieldsDto {
+                category_id: row.get(6).map_err(db_error)?,
+                goal_id: row.get(7).map_err(db_error)?,
+                tags: SqliteTransactionService::split_tags(row.get(8).map_err(db_error)?),
+            };
+
+            let mut after = before.clone();
+            let subject = RuleSubject {
+                payee_name: payee_name.as_deref(),
+                notes: notes.as_deref(),
+                amount_cents,
+                account_id: &account_id,
+            };
+            let rule_ids = apply(&rules, &subject, &mut after, input.overwrite, !has_splits);
+            if after != before {
+                changes.push(RuleChangeDto {
+                    transaction_id: row.get(0).map_err(db_error)?,
+                    occurred_on: row.get(1).map_err(db_error)?,
+                    payee_name,
+                    notes,
+                    amount_cents,
+                    rule_ids,
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+
+    if !input.dry_run && !changes.is_empty() {
+        let tx = conn.transaction().map_err(db_error)?;
+        for change in &changes {
+            tx.execute(
+                r#"
+                UPDATE "Transaction"
+                SET category_id = ?1, goal_id = ?2, tags = ?3, updated_at = CURRENT_TIMESTAMP
+                WHERE id = ?4 AND user_id = ?5
+            "#,
+                params![
+                    change.after.category_id,
+                    change.after.goal_id,
+                    SqliteTransactionService::parse_tags(Some(change.after.tags.clone())),
+                    change.transaction_id,
+                    user_id
+                ],
+            )
+            .map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)?;
+    }
+
+    Ok(ApplyRulesResult {
+        dry_run: input.dry_run,
+        scanned,
+        changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        payee: Option<&str>,
+        notes: Option<&str>,
+        category: Option<&str>,
+        tags: &[&str],
+    ) -> CompiledRule {
+        CompiledRule {
+            id: format!("rule-{}", category.unwrap_or("tags")),
+            payee_contains: payee.map(str::to_lowercase),
+            notes_pattern: notes.map(|pattern| compile_pattern(pattern).unwrap()),
+            min_amount_cents: None,
+            max_amount_cents: Some(10_000),
+            account_id: None,
+            category_id: category.map(str::to_string),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            goal_id: None,
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins_and_tags_merge() {
+        let rules = [
+            rule(Some("STARBUCKS"), None, Some("cat-coffee"), &["coffee"]),
+            rule(
+                None,
+                Some(r"latte|espresso"),
+                Some("cat-food"),
+                &["Coffee", "treat"],
+            ),
+        ];
+        let subject = RuleSubject {
+            payee_name: Some("Starbucks #1234"),
+            notes: Some("Morning latte"),
+            amount_cents: 550,
+            account_id: "acct-default",
+        };
+
+        let mut fields = RuleFieldsDto {
+            category_id: None,
+            goal_id: None,
+            tags: Vec::new(),
+        };
+        let applied = apply(&rules, &subject, &mut fields, false, true);
+        assert_eq!(applied.len(), 2);
+        assert_eq!(fields.category_id.as_deref(), Some("cat-coffee"));
+        assert_eq!(fields.tags, vec!["coffee", "treat"]);
+
+        // Existing categories are kept unless overwriting; amounts outside the range never match
+        let mut fields = RuleFieldsDto {
+            category_id: Some("cat-rent".into()),
+            goal_id: None,
+            tags: Vec::new(),
+        };
+        apply(&rules, &subject, &mut fields, false, true);
+        assert_eq!(fields.category_id.as_deref(), Some("cat-rent"));
+        apply(&rules, &subject, &mut fields, true, true);
+        assert_eq!(fields.category_id.as_deref(), Some("cat-coffee"));
+        let large = RuleSubject {
+            amount_cents: 20_000,
+            ..subject
+        };
+        assert!(apply(&rules, &large, &mut fields, true, true).is_empty());
+    }
+}
@@ -0,0 +1,550 @@
+use chrono::NaiveDate;
+use rusqlite::{Connection, ToSql};
+
+use super::{TransactionKind, TransactionQuery, TransactionResult, TransactionServiceError};
+
+/// FTS5 index over the searchable text of every `"Transaction"` row, kept in sync by triggers.
+/// `payee` holds the linked payee's name; renaming a payee rewrites it on every row.
+pub(crate) fn ensure_search_index(conn: &Connection) -> rusqlite::Result<()> {
+    // The insert/update triggers predate payees and are recreated so upgraded databases
+    // pick up the payee column.
+    conn.execute_batch(
+        r#"
+        CREATE VIRTUAL TABLE IF NOT EXISTS "TransactionSearch" USING fts5(
+            transaction_id UNINDEXED,
+            notes,
+            payee,
+            tags,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+        DROP TRIGGER IF EXISTS "Transaction_search_insert";
+        CREATE TRIGGER "Transaction_search_insert" AFTER INSERT ON "Transaction" BEGIN
+            INSERT INTO "TransactionSearch" (transaction_id, notes, payee, tags)
+            VALUES (
+                new.id,
+                new.notes,
+                (SELECT name FROM "Payee" WHERE id = new.payee_id),
+                REPLACE(new.tags, ',', ' ')
+            );
+        END;
+        DROP TRIGGER IF EXISTS "Transaction_search_update";
+        CREATE TRIGGER "Transaction_search_update" AFTER UPDATE OF notes, tags, payee_id ON "Transaction" BEGIN
+            UPDATE "TransactionSearch"
+            SET notes = new.notes,
+                payee = (SELECT name FROM "Payee" WHERE id = new.payee_id),
+                tags = REPLACE(new.tags, ',', ' ')
+            WHERE transaction_id = new.id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS "Transaction_search_delete" AFTER DELETE ON "Transaction" BEGIN
+            DELETE FROM "TransactionSearch" WHERE transaction_id = old.id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS "Payee_search_rename" AFTER UPDATE OF name ON "Payee" BEGIN
+            UPDATE "TransactionSearch"
+            SET payee = new.name
+            WHERE transaction_id IN (SELECT id FROM "Transaction" WHERE payee_id = new.id);
+        END;
+        "#,
+    )?;
+
+    // Databases created before the index existed (or restored from a backup) are reindexed.
+    let in_sync: bool = conn.query_row(
+        r#"SELECT (SELECT COUNT(*) FROM "TransactionSearch") = (SELECT COUNT(*) FROM "Transaction")"#,
+        [],
+        |row| row.get(0),
+    )?;
+    if !in_sync {
+        conn.execute_batch(
+            r#"
+            DELETE FROM "TransactionSearch";
+            INSERT INTO "TransactionSearch" (transaction_id, notes, payee, tags)
+            SELECT t.id, t.notes, p.name, REPLACE(t.tags, ',', ' ')
+            FROM "Transaction" t
+            LEFT JOIN "Payee" p ON p.id = t.payee_id;
+            "#,
+        )?;
+    }
+    Ok(())
+}
+
+/// `WHERE` clause and parameters for a [`TransactionQuery`] over `"Transaction" t`.
+/// Structured fields and the `search` query language are combined with `AND`.
+pub(crate) struct TransactionFilter {
+    clauses: Vec<String>,
+    params: Vec<Box<dyn ToSql>>,
+}
+
+impl TransactionFilter {
+    pub(crate) fn new(user_id: &str, query: &TransactionQuery) -> TransactionResult<Self> {
+        let mut filter = Self {
+            clauses: Vec::new(),
+            params: Vec::new(),
+        };
+        filter.push("t.user_id = ?", [user_id.to_string()]);
+
+        if let Some(account_id) = &query.account_id {
+            filter.push("t.account_id = ?", [account_id.clone()]);
+        }
+        if let Some(category_id) = &query.category_id {
+            filter.push("t.category_id = ?", [category_id.clone()]);
+        }
+        if let Some(goal_id) = &query.goal_id {
+            filter.push("t.goal_id = ?", [goal_id.clone()]);
+        }
+        if let Some(kind) = &query.kind {
+            filter.push("t.type = ?", [kind.as_str().to_string()]);
+        }
+        if let Some(cleared) = query.cleared {
+            filter.push_value("t.cleared = ?", cleared);
+        }
+        if let Some(from) = &query.from_date {
+            filter.push("DATE(t.occurred_on) >= ?", [parse_date(from)?]);
+        }
+        if let Some(to) = &query.to_date {
+            filter.push("DATE(t.occurred_on) <= ?", [parse_date(to)?]);
+        }
+        if let Some(min) = query.min_amount_cents {
+            filter.push_value("t.amount_cents >= ?", min);
+        }
+        if let Some(max) = query.max_amount_cents {
+            filter.push_value("t.amount_cents <= ?", max);
+        }
+        for tag in &query.tags {
+            filter.push_tag(tag, false);
+        }
+        for tag in &query.exclude_tags {
+            filter.push_tag(tag, true);
+        }
+
+        if let Some(search) = query.search.as_deref() {
+            filter.apply_search(&parse_search(search)?);
+        }
+
+        Ok(filter)
+    }
+
+    pub(crate) fn where_sql(&self) -> String {
+        format!(" WHERE {}", self.clauses.join(" AND "))
+    }
+
+    pub(crate) fn params(&self) -> Vec<&dyn ToSql> {
+        self.params.iter().map(|value| &**value).collect()
+    }
+
+    fn push<const N: usize>(&mut self, clause: &str, values: [String; N]) {
+        self.clauses.push(clause.to_string());
+        for value in values {
+            self.params.push(Box::new(value));
+        }
+    }
+
+    fn push_value<T: ToSql + 'static>(&mut self, clause: &str, value: T) {
+        self.clauses.push(clause.to_string());
+        self.params.push(Box::new(value));
+    }
+
+    fn push_tag(&mut self, tag: &str, exclude: bool) {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() {
+            return;
+        }
+        let operator = if exclude { "=" } else { ">" };
+        self.push(
+            &format!(
+                "INSTR(',' || LOWER(REPLACE(COALESCE(t.tags, ''), ', ', ',')) || ',', ?) {operator} 0"
+            ),
+            [format!(",{tag},")],
+        );
+    }
+
+    fn apply_search(&mut self, search: &ParsedSearch) {
+        for filter in &search.filters {
+            match filter {
+                SearchFilter::Tag { tag, negated } => self.push_tag(tag, *negated),
+                SearchFilter::Amount { operator, cents } => {
+                    self.push_value(&format!("t.amount_cents {} ?", operator.as_sql()), *cents)
+                }
+                SearchFilter::Date { operator, date } => self.push(
+                    &format!("DATE(t.occurred_on) {} ?", operator.as_sql()),
+                    [date.clone()],
+                ),
+                SearchFilter::Kind(kind) => self.push("t.type = ?", [kind.as_str().to_string()]),
+                SearchFilter::Cleared(cleared) => self.push_value("t.cleared = ?", *cleared),
+                SearchFilter::Reconciled(true) => self.push("t.reconciliation_id IS NOT NULL", []),
+                SearchFilter::Reconciled(false) => self.push("t.reconciliation_id IS NULL", []),
+                SearchFilter::Account(value) => self.push(
+                    r#"t.account_id IN (SELECT id FROM "Account" WHERE user_id = t.user_id AND (id = ? OR LOWER(name) = LOWER(?)))"#,
+                    [value.clone(), value.clone()],
+                ),
+                SearchFilter::Category(value) => self.push(
+                    r#"t.category_id IN (SELECT id FROM "Category" WHERE user_id = t.user_id AND (id = ? OR LOWER(name) = LOWER(?)))"#,
+                    [value.clone(), value.clone()],
+                ),
+                SearchFilter::Goal(value) => self.push(
+                    r#"t.goal_id IN (SELECT id FROM "Goal" WHERE user_id = t.user_id AND (id = ? OR LOWER(name) = LOWER(?)))"#,
+                    [value.clone(), value.clone()],
+                ),
+            }
+        }
+
+        let (negated, included): (Vec<_>, Vec<_>) =
+            search.terms.iter().partition(|term| term.negated);
+        if !included.is_empty() {
+            self.push(
+                r#"t.id IN (SELECT transaction_id FROM "TransactionSearch" WHERE "TransactionSearch" MATCH ?)"#,
+                [fts_expression(&included, " AND ")],
+            );
+        }
+        if !negated.is_empty() {
+            self.push(
+                r#"t.id NOT IN (SELECT transaction_id FROM "TransactionSearch" WHERE "TransactionSearch" MATCH ?)"#,
+                [fts_expression(&negated, " OR ")],
+            );
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Comparison {
+    Lt,
+    Lte,
+    Eq,
+    Gte,
+    Gt,
+}
+
+impl Comparison {
+    fn as_sql(&self) -> &'static str {
+        match self {
+            Comparison::Lt => "<",
+            Comparison::Lte => "<=",
+            Comparison::Eq => "=",
+            Comparison::Gte => ">=",
+            Comparison::Gt => ">",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SearchFilter {
+    Tag { tag: String, negated: bool },
+    Amount { operator: Comparison, cents: i64 },
+    Date { operator: Comparison, date: String },
+    Kind(TransactionKind),
+    Cleared(bool),
+    Reconciled(bool),
+    Account(String),
+    Category(String),
+    Goal(String),
+}
+
+/// Free-text term matched against the FTS index, optionally limited to one column.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SearchTerm {
+    pub column: Option<&'static str>,
+    pub text: String,
+    /// Quoted phrases match exactly; bare words also match as a prefix.
+    pub phrase: bool,
+    pub negated: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ParsedSearch {
+    pub filters: Vec<SearchFilter>,
+    pub terms: Vec<SearchTerm>,
+}
+
+/// Parses the search box query language. Space-separated tokens are combined with `AND`:
+///
+/// - `tag:travel`, `-tag:work`
+/// - `amount>100`, `amount<=25.50`, `amount:40` (major currency units)
+/// - `before:2025-06-01`, `after:2025-01-01`, `on:2025-03-14`
+/// - `type:expense`, `is:cleared`, `is:uncleared`, `is:reconciled`, `is:unreconciled`
+/// - `account:`, `category:`, `goal:` (id or exact name)
+/// - `payee:`, `notes:` and bare words or `"quoted phrases"` (full-text); `-word` excludes
+pub(crate) fn parse_search(input: &str) -> TransactionResult<ParsedSearch> {
+    let mut parsed = ParsedSearch::default();
+
+    for token in tokenize(input) {
+        let (negated, body) = match token.text.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() && !token.quoted_whole => (true, rest),
+            _ => (false, token.text.as_str()),
+        };
+
+        if token.quoted_whole {
+            parsed.terms.push(SearchTerm {
+                column: None,
+                text: body.to_string(),
+                phrase: true,
+                negated,
+            });
+            continue;
+        }
+
+        if let Some(rest) = body.strip_prefix("amount") {
+            if let Some((operator, value)) = split_comparison(rest) {
+                if negated {
+                    return Err(invalid(&token.text, "amount filters cannot be negated"));
+                }
+                parsed.filters.push(SearchFilter::Amount {
+                    operator,
+                    cents: parse_amount(value)
+                        .ok_or_else(|| invalid(&token.text, "invalid amount"))?,
+                });
+                continue;
+            }
+        }
+
+        let Some((key, value)) = body.split_once(':') else {
+            parsed.terms.push(SearchTerm {
+                column: None,
+                text: body.to_string(),
+                phrase: false,
+                negated,
+            });
+            continue;
+        };
+        let key = key.to_ascii_lowercase();
+        let value = value.to_string();
+        let quoted = token.quoted_value;
+
+        let filter = match key.as_str() {
+            "tag" => SearchFilter::Tag {
+                tag: value,
+                negated,
+            },
+            "before" | "after" | "on" => {
+                if negated {
+                    return Err(invalid(&token.text, "date filters cannot be negated"));
+                }
+                let operator = match key.as_str() {
+                    "before" => Comparison::Lt,
+                    "after" => Comparison::Gt,
+                    _ => Comparison::Eq,
+                };
+                SearchFilter::Date {
+                    operator,
+                    date: parse_date(&value)?,
+                }
+            }
+            "type" | "kind" if !negated => {
+                SearchFilter::Kind(match value.to_lowercase().as_str() {
+                    "income" => TransactionKind::Income,
+                    "expense" => TransactionKind::Expense,
+                    "transfer" => TransactionKind::Transfer,
+                    _ => return Err(invalid(&token.text, "unknown transaction type")),
+                })
+            }
+            "is" => match value.to_lowercase().as_str() {
+                "cleared" => SearchFilter::Cleared(!negated),
+                "uncleared" | "pending" => SearchFilter::Cleared(negated),
+                "reconciled" => SearchFilter::Reconciled(!negated),
+                "unreconciled" => SearchFilter::Reconciled(negated),
+                _ => {
+                    return Err(invalid(
+                        &token.text,
+                        "expected is:cleared, is:uncleared, is:reconciled or is:unreconciled",
+                    ))
+                }
+            },
+            "account" if !negated => SearchFilter::Account(value),
+            "category" if !negated => SearchFilter::Category(value),
+            "goal" if !negated => SearchFilter::Goal(value),
+            "payee" | "notes" => {
+                parsed.terms.push(SearchTerm {
+                    column: Some(if key == "payee" { "payee" } else { "notes" }),
+                    text: value,
+                    phrase: quoted,
+                    negated,
+                });
+                continue;
+            }
+            "type" | "kind" | "account" | "category" | "goal" => {
+                return Err(invalid(&token.text, "this filter cannot be negated"));
+            }
+            // Not a known filter (e.g. a time like `10:30`): search it as text
+            _ => {
+                parsed.terms.push(SearchTerm {
+                    column: None,
+                    text: body.to_string(),
+                    phrase: quoted,
+                    negated,
+                });
+                continue;
+            }
+        };
+        parsed.filters.push(filter);
+    }
+
+    parsed
+        .terms
+        .retain(|term| term.text.chars().any(char::is_alphanumeric));
+    Ok(parsed)
+}
+
+struct Token {
+    text: String,
+    /// The whole token was a `"quoted phrase"` (optionally `-"negated"`).
+    quoted_whole: bool,
+    /// The value after `key:` was quoted.
+    quoted_value: bool,
+}
+
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut text = String::new();
+        let mut quoted_whole = false;
+        let mut quoted_value = false;
+        while let Some(&ch) = chars.peek() {
+            if ch.is_whitespace() {
+                break;
+            }
+            chars.next();
+            if ch == '"' {
+                if text.is_empty() || text == "-" {
+                    quoted_whole = true;
+                } else {
+                    quoted_value = true;
+                }
+                for inner in chars.by_ref() {
+                    if inner == '"' {
+                        break;
+                    }
+                    text.push(inner);
+                }
+            } else {
+                text.push(ch);
+            }
+        }
+        tokens.push(Token {
+            text,
+            quoted_whole,
+            quoted_value,
+        });
+    }
+
+    tokens
+}
+
+fn split_comparison(rest: &str) -> Option<(Comparison, &str)> {
+    [
+        (">=", Comparison::Gte),
+        ("<=", Comparison::Lte),
+        (">", Comparison::Gt),
+        ("<", Comparison::Lt),
+        ("=", Comparison::Eq),
+        (":", Comparison::Eq),
+    ]
+    .into_iter()
+    .find_map(|(prefix, operator)| rest.strip_prefix(prefix).map(|value| (operator, value)))
+}
+
+fn parse_amount(value: &str) -> Option<i64> {
+    let value = value.trim().trim_start_matches('$').replace(',', "");
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|amount| amount.is_finite() && *amount >= 0.0)
+        .map(|amount| (amount * 100.0).round() as i64)
+}
+
+pub(super) fn parse_date(value: &str) -> TransactionResult<String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .map_err(|_| TransactionServiceError::Validation(format!("Invalid date: {value}")))
+}
+
+fn invalid(token: &str, reason: &str) -> TransactionServiceError {
+    TransactionServiceError::Validation(format!("Invalid search filter `{token}`: {reason}"))
+}
+
+fn fts_expression(terms: &[&SearchTerm], separator: &str) -> String {
+    terms
+        .iter()
+        .map(|term| {
+            let quoted = format!("\"{}\"", term.text.replace('"', "\"\""));
+            let quoted = if term.phrase { quoted } else { quoted + "*" };
+            match term.column {
+                Some(column) => format!("{column} : {quoted}"),
+                None => quoted,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_filters_and_terms() {
+        let parsed = parse_search(
+            r#"tag:travel -tag:work amount>100 before:2025-06-01 is:uncleared "hotel booking" -taxi payee:"Air France""#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            parsed.filters,
+            vec![
+                SearchFilter::Tag {
+                    tag: "travel".into(),
+                    negated: false
+                },
+                SearchFilter::Tag {
+                    tag: "work".into(),
+                    negated: true
+                },
+                SearchFilter::Amount {
+                    operator: Comparison::Gt,
+                    cents: 10_000
+                },
+                SearchFilter::Date {
+                    operator: Comparison::Lt,
+                    date: "2025-06-01".into()
+                },
+                SearchFilter::Cleared(false),
+            ]
+        );
+        assert_eq!(
+            parsed.terms,
+            vec![
+                SearchTerm {
+                    column: None,
+                    text: "hotel booking".into(),
+                    phrase: true,
+                    negated: false
+                },
+                SearchTerm {
+                    column: None,
+                    text: "taxi".into(),
+                    phrase: false,
+                    negated: true
+                },
+                SearchTerm {
+                    column: Some("payee"),
+                    text: "Air France".into(),
+                    phrase: true,
+                    negated: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_filters() {
+        assert!(parse_search("amount>abc").is_err());
+        assert!(parse_search("before:June").is_err());
+        assert!(parse_search("type:refund").is_err());
+        assert!(parse_search("-amount<5").is_err());
+        // Unknown keys fall back to text search
+        assert_eq!(parse_search("at 10:30").unwrap().terms.len(), 2);
+    }
+}
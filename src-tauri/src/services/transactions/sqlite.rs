@@ -1,19 +1,101 @@
-use std::path::PathBuf;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 use chrono::{DateTime, Utc};
 use rusqlite::{params, params_from_iter, Connection};
 use uuid::Uuid;
 
-use crate::services::ServiceDescriptor;
+use crate::services::{currency, sqlcipher, ServiceDescriptor};
 
 use super::{
-    AccountDto, CategoryDto, CreateTransactionInput, TransactionDto, TransactionKind,
-    TransactionQuery, TransactionResult, TransactionService, TransactionServiceError,
-    UpdateTransactionInput,
+    batches, duplicates, reconcile,
+    rules::{self, CompiledRule, RuleSubject},
+    search::{ensure_search_index, TransactionFilter},
+    AccountDto, AccountStatus, ApplyRulesInput, ApplyRulesResult, ArchiveCategoryInput,
+    CategoryDto, CreateAccountInput, CreateCategoryInput, CreatePayeeInput, CreateTransactionInput,
+    DeleteCategoryInput, DuplicateAction, DuplicateReason, ImportBatchDto, ImportTransactionsInput,
+    ImportTransactionsResult, MarkReconciliationInput, MergeCategoriesInput, PayeeDto,
+    ReconciliationDetailDto, ReconciliationDto, ReconciliationStatus, ReorderAccountsInput,
+    RuleFieldsDto, StartReconciliationInput, SuspectedDuplicateDto, TransactionDto,
+    TransactionKind, TransactionPage, TransactionQuery, TransactionResult, TransactionRuleDto,
+    TransactionRuleInput, TransactionService, TransactionServiceError, TransactionSplitDto,
+    TransactionSplitInput, TransferDirection, UpdateAccountInput, UpdateAccountStatusInput,
+    UpdateCategoryInput, UpdatePayeeInput, UpdateReconciliationInput, UpdateTransactionInput,
+    UpdateTransactionRuleInput, CATEGORY_ANCESTRY_CTE, LEDGER_DELTA_SQL,
 };
 
 const DEFAULT_USER_ID: &str = "seed-user";
 
+const ACCOUNT_TYPES: [&str; 6] = [
+    "cash",
+    "checking",
+    "credit",
+    "savings",
+    "investment",
+    "wallet",
+];
+
+const CATEGORY_TYPES: [&str; 3] = ["income", "expense", "transfer"];
+
+const CATEGORY_SELECT: &str = r#"
+    SELECT id, name, type, sort_order, parent_id, icon, archived
+    FROM "Category"
+"#;
+
+const ACCOUNT_SELECT: &str = r#"
+    SELECT
+        id,
+        name,
+        type,
+        currency,
+        balance_cents,
+        color_token,
+        institution,
+        sync_external_id,
+        opening_balance_cents,
+        sort_order,
+        status,
+        closed_at
+    FROM "Account"
+"#;
+
+const TRANSACTION_SELECT: &str = r#"
+    SELECT 
+        t.id,
+        t.account_id,
+        a.name as account_name,
+        t.category_id,
+        c.name as category_name,
+        t.type,
+        t.amount_cents,
+        t.currency,
+        t.occurred_on,
+        t.cleared,
+        t.notes,
+        t.tags,
+        t.goal_id,
+        t.transfer_direction,
+        t.linked_transaction_id,
+        peer.account_id as transfer_account_id,
+        pa.name as transfer_account_name,
+        t.recurrence_id,
+        t.exchange_rate,
+        t.payee_id,
+        p.name as payee_name,
+        t.reconciliation_id,
+        t.external_id,
+        t.import_batch_id
+    FROM "Transaction" t
+    JOIN "Account" a ON a.id = t.account_id
+    LEFT JOIN "Category" c ON c.id = t.category_id
+    LEFT JOIN "Transaction" peer ON peer.id = t.linked_transaction_id
+    LEFT JOIN "Account" pa ON pa.id = peer.account_id
+    LEFT JOIN "Payee" p ON p.id = t.payee_id
+"#;
+
+#[derive(Clone)]
 pub struct SqliteTransactionService {
     db_path: PathBuf,
     db_key: Option<String>,
@@ -38,10 +120,153 @@ impl SqliteTransactionService {
     fn bootstrap(&self) -> TransactionResult<()> {
         let conn = self.connection()?;
         self.init_schema(&conn)?;
+        self.ensure_transfer_columns(&conn)?;
+        self.ensure_account_columns(&conn)?;
+        self.ensure_split_table(&conn)?;
+        self.ensure_payee_tables(&conn)?;
+        self.ensure_reconciliation_tables(&conn)?;
+        self.ensure_external_id_column(&conn)?;
+        self.ensure_import_batch_table(&conn)?;
+        ensure_search_index(&conn).map_err(|err| {
+            TransactionServiceError::Database(format!("Failed to create search index: {err}"))
+        })?;
+        currency::ensure_schema(&conn).map_err(|err| {
+            TransactionServiceError::Database(format!(
+                "Failed to create exchange rate table: {err}"
+            ))
+        })?;
         self.recalculate_account_balances(&conn)?;
         Ok(())
     }
 
+    fn ensure_transfer_columns(&self, conn: &Connection) -> TransactionResult<()> {
+        // Paired transfer legs (handles databases created before transfers were double-entry)
+        ensure_column(
+            conn,
+            "Transaction",
+            "transfer_direction",
+            r#"TEXT CHECK ("transfer_direction" IN ('out','in'))"#,
+        )?;
+        ensure_column(conn, "Transaction", "linked_transaction_id", "TEXT")?;
+        conn.execute(
+            r#"CREATE INDEX IF NOT EXISTS "Transaction_linked_transaction_id_idx" ON "Transaction"("linked_transaction_id")"#,
+            [],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        Ok(())
+    }
+
+    fn ensure_account_columns(&self, conn: &Connection) -> TransactionResult<()> {
+        // Account lifecycle fields (handles databases created before accounts were editable)
+        ensure_column(
+            conn,
+            "Account",
+            "opening_balance_cents",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+        ensure_column(conn, "Account", "sort_order", "INTEGER NOT NULL DEFAULT 0")?;
+        ensure_column(
+            conn,
+            "Account",
+            "status",
+            r#"TEXT NOT NULL DEFAULT 'active' CHECK ("status" IN ('active','archived','closed'))"#,
+        )?;
+        ensure_column(conn, "Account", "closed_at", "DATETIME")?;
+        Ok(())
+    }
+
+    fn ensure_split_table(&self, conn: &Connection) -> TransactionResult<()> {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS "TransactionSplit" (
+                "id" TEXT NOT NULL PRIMARY KEY,
+                "transaction_id" TEXT NOT NULL,
+                "user_id" TEXT NOT NULL,
+                "category_id" TEXT,
+                "goal_id" TEXT,
+                "amount_cents" INTEGER NOT NULL,
+                "notes" TEXT,
+                "sort_order" INTEGER NOT NULL DEFAULT 0,
+                "created_at" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                CONSTRAINT "TransactionSplit_transaction_id_fkey" FOREIGN KEY ("transaction_id") REFERENCES "Transaction" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+                CONSTRAINT "TransactionSplit_category_id_fkey" FOREIGN KEY ("category_id") REFERENCES "Category" ("id") ON DELETE SET NULL ON UPDATE CASCADE,
+                CONSTRAINT "TransactionSplit_goal_id_fkey" FOREIGN KEY ("goal_id") REFERENCES "Goal" ("id") ON DELETE SET NULL ON UPDATE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS "TransactionSplit_transaction_id_idx" ON "TransactionSplit"("transaction_id");
+            CREATE INDEX IF NOT EXISTS "TransactionSplit_category_id_idx" ON "TransactionSplit"("category_id");
+            "#,
+        )
+        .map_err(|err| {
+            TransactionServiceError::Database(format!("Failed to create split table: {err}"))
+        })
+    }
+
+    fn ensure_payee_tables(&self, conn: &Connection) -> TransactionResult<()> {
+        rules::ensure_schema(conn).map_err(|err| {
+            TransactionServiceError::Database(format!("Failed to create payee tables: {err}"))
+        })?;
+        ensure_column(
+            conn,
+            "Transaction",
+            "payee_id",
+            r#"TEXT REFERENCES "Payee" ("id") ON DELETE SET NULL ON UPDATE CASCADE"#,
+        )?;
+        conn.execute(
+            r#"CREATE INDEX IF NOT EXISTS "Transaction_payee_id_idx" ON "Transaction"("payee_id")"#,
+            [],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        Ok(())
+    }
+
+    fn ensure_reconciliation_tables(&self, conn: &Connection) -> TransactionResult<()> {
+        reconcile::ensure_schema(conn).map_err(|err| {
+            TransactionServiceError::Database(format!(
+                "Failed to create reconciliation table: {err}"
+            ))
+        })?;
+        ensure_column(
+            conn,
+            "Transaction",
+            "reconciliation_id",
+            r#"TEXT REFERENCES "Reconciliation" ("id") ON DELETE SET NULL ON UPDATE CASCADE"#,
+        )?;
+        conn.execute(
+            r#"CREATE INDEX IF NOT EXISTS "Transaction_reconciliation_id_idx" ON "Transaction"("reconciliation_id")"#,
+            [],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        Ok(())
+    }
+
+    fn ensure_external_id_column(&self, conn: &Connection) -> TransactionResult<()> {
+        ensure_column(conn, "Transaction", "external_id", "TEXT")?;
+        conn.execute(
+            r#"CREATE INDEX IF NOT EXISTS "Transaction_account_id_external_id_idx" ON "Transaction"("account_id", "external_id")"#,
+            [],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        Ok(())
+    }
+
+    fn ensure_import_batch_table(&self, conn: &Connection) -> TransactionResult<()> {
+        batches::ensure_schema(conn).map_err(|err| {
+            TransactionServiceError::Database(format!("Failed to create import batch table: {err}"))
+        })?;
+        ensure_column(
+            conn,
+            "Transaction",
+            "import_batch_id",
+            r#"TEXT REFERENCES "ImportBatch" ("id") ON DELETE SET NULL ON UPDATE CASCADE"#,
+        )?;
+        conn.execute(
+            r#"CREATE INDEX IF NOT EXISTS "Transaction_import_batch_id_idx" ON "Transaction"("import_batch_id")"#,
+            [],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        Ok(())
+    }
+
     fn init_schema(&self, conn: &Connection) -> TransactionResult<()> {
         // Check if schema already exists
         let table_exists: bool = conn
@@ -62,6 +287,11 @@ impl SqliteTransactionService {
         conn.execute_batch(include_str!(
             "../../../../prisma/migrations/20251120193838_init/migration.sql"
         ))
+        .and_then(|_| {
+            conn.execute_batch(include_str!(
+                "../../../../prisma/migrations/20250120200000_add_theme_preference/migration.sql"
+            ))
+        })
         .map_err(|err| {
             TransactionServiceError::Database(format!("Failed to initialize schema: {}", err))
         })?;
@@ -93,14 +323,14 @@ impl SqliteTransactionService {
         let conn = Connection::open(&self.db_path)
             .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
 
-        if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
-            return Err(TransactionServiceError::Database(err.to_string()));
+        if let Some(key) = &self.db_key {
+            sqlcipher::apply_key(&conn, key).map_err(|err| {
+                TransactionServiceError::Database(format!("Failed to apply SQLCipher key: {err}"))
+            })?;
         }
 
-        if let Some(key) = &self.db_key {
-            if let Err(err) = conn.pragma_update(None, "key", key) {
-                tracing::warn!(error = %err, "Failed to apply SQLCipher key; continuing without encryption");
-            }
+        if let Err(err) = conn.execute("PRAGMA foreign_keys = ON;", []) {
+            return Err(TransactionServiceError::Database(err.to_string()));
         }
 
         Ok(conn)
@@ -120,17 +350,7 @@ impl SqliteTransactionService {
         drop(stmt);
 
         for account_id in account_ids {
-            let balance: i64 = conn
-                .query_row(
-                    "SELECT COALESCE(SUM(CASE 
-                        WHEN type = 'income' THEN amount_cents 
-                        WHEN type = 'expense' THEN -amount_cents 
-                        ELSE 0 END), 0) 
-                        FROM \"Transaction\" WHERE user_id = ? AND account_id = ?",
-                    params![self.user_id, account_id],
-                    |row| row.get(0),
-                )
-                .unwrap_or(0);
+            let balance = self.ledger_balance(conn, &account_id);
 
             conn.execute(
                 "UPDATE \"Account\" SET balance_cents = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
@@ -142,6 +362,347 @@ impl SqliteTransactionService {
         Ok(())
     }
 
+    /// Opening balance plus the signed sum of every ledger row for the account.
+    fn ledger_balance(&self, conn: &Connection, account_id: &str) -> i64 {
+        let sql = format!(
+            r#"SELECT a.opening_balance_cents + COALESCE((
+                   SELECT SUM({LEDGER_DELTA_SQL}) FROM "Transaction"
+                   WHERE user_id = a.user_id AND account_id = a.id
+               ), 0)
+               FROM "Account" a WHERE a.user_id = ? AND a.id = ?"#
+        );
+        conn.query_row(&sql, params![self.user_id, account_id], |row| row.get(0))
+            .unwrap_or(0)
+    }
+
+    fn map_account_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<AccountDto> {
+        let status: String = row.get(10)?;
+        Ok(AccountDto {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            account_type: row.get(2)?,
+            currency: row.get(3)?,
+            balance_cents: row.get(4)?,
+            available_balance_cents: None,
+            color_token: row.get(5)?,
+            institution: row.get(6)?,
+            sync_external_id: row.get(7)?,
+            opening_balance_cents: row.get(8)?,
+            sort_order: row.get(9)?,
+            status: AccountStatus::from_db(&status),
+            closed_at: row.get(11)?,
+        })
+    }
+
+    fn fetch_account(&self, conn: &Connection, id: &str) -> TransactionResult<AccountDto> {
+        let sql = format!("{ACCOUNT_SELECT} WHERE user_id = ? AND id = ?");
+
+        let mut account = conn
+            .query_row(&sql, params![self.user_id, id], Self::map_account_row)
+            .map_err(|err| match err {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    TransactionServiceError::NotFound(id.to_string())
+                }
+                _ => TransactionServiceError::Database(err.to_string()),
+            })?;
+        account.available_balance_cents = Some(self.ledger_balance(conn, id));
+        Ok(account)
+    }
+
+    fn validate_account_fields(
+        name: &str,
+        account_type: &str,
+        currency: &str,
+    ) -> TransactionResult<()> {
+        if name.trim().is_empty() {
+            return Err(TransactionServiceError::Validation(
+                "Account name is required".into(),
+            ));
+        }
+        if !ACCOUNT_TYPES.contains(&account_type) {
+            return Err(TransactionServiceError::Validation(format!(
+                "Unsupported account type: {account_type}"
+            )));
+        }
+        if currency.trim().len() != 3 {
+            return Err(TransactionServiceError::Validation(
+                "Currency must be a 3-letter ISO code".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn map_category_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CategoryDto> {
+        Ok(CategoryDto {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            category_type: row.get(2)?,
+            sort_order: row.get(3)?,
+            parent_id: row.get(4)?,
+            icon: row.get(5)?,
+            archived: row.get::<_, i64>(6)? != 0,
+        })
+    }
+
+    fn fetch_category(&self, conn: &Connection, id: &str) -> TransactionResult<CategoryDto> {
+        let sql = format!("{CATEGORY_SELECT} WHERE user_id = ? AND id = ?");
+
+        conn.query_row(&sql, params![self.user_id, id], Self::map_category_row)
+            .map_err(|err| match err {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    TransactionServiceError::NotFound(id.to_string())
+                }
+                _ => TransactionServiceError::Database(err.to_string()),
+            })
+    }
+
+    /// Inserts a validated category after the user's others and returns its id.
+    fn insert_category(
+        &self,
+        conn: &Connection,
+        input: CreateCategoryInput,
+    ) -> TransactionResult<String> {
+        self.validate_category(
+            conn,
+            None,
+            &input.name,
+            &input.category_type,
+            input.parent_id.as_deref(),
+        )?;
+        let id = input
+            .id
+            .unwrap_or_else(|| format!("cat_{}", Uuid::new_v4()));
+
+        let next_sort_order: i32 = conn
+            .query_row(
+                r#"SELECT COALESCE(MAX(sort_order) + 1, 0) FROM "Category" WHERE user_id = ?"#,
+                params![self.user_id],
+                |row| row.get(0),
+            )
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        conn.execute(
+            r#"
+            INSERT INTO "Category" (id, user_id, name, type, parent_id, icon, sort_order, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP)
+        "#,
+            params![
+                id,
+                self.user_id,
+                input.name.trim(),
+                input.category_type,
+                input.parent_id,
+                input.icon,
+                next_sort_order
+            ],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        Ok(id)
+    }
+
+    /// Validates name/type and that `parent_id` is an existing category of the same type
+    /// that is not `id` itself or one of its descendants.
+    fn validate_category(
+        &self,
+        conn: &Connection,
+        id: Option<&str>,
+        name: &str,
+        category_type: &str,
+        parent_id: Option<&str>,
+    ) -> TransactionResult<()> {
+        if name.trim().is_empty() {
+            return Err(TransactionServiceError::Validation(
+                "Category name is required".into(),
+            ));
+        }
+        if !CATEGORY_TYPES.contains(&category_type) {
+            return Err(TransactionServiceError::Validation(format!(
+                "Unsupported category type: {category_type}"
+            )));
+        }
+
+        let Some(parent_id) = parent_id else {
+            return Ok(());
+        };
+
+        let parent = self.fetch_category(conn, parent_id)?;
+        if parent.category_type != category_type {
+            return Err(TransactionServiceError::Validation(
+                "Subcategories must share their parent's type".into(),
+            ));
+        }
+
+        if let Some(id) = id {
+            let creates_cycle: bool = conn
+                .query_row(
+                    &format!(
+                        "WITH RECURSIVE {CATEGORY_ANCESTRY_CTE}
+                         SELECT EXISTS(SELECT 1 FROM category_ancestry WHERE category_id = ? AND ancestor_id = ?)"
+                    ),
+                    params![parent_id, id],
+                    |row| row.get(0),
+                )
+                .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+            if creates_cycle {
+                return Err(TransactionServiceError::Validation(
+                    "A category cannot be nested under itself or one of its subcategories".into(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Budget ids whose comma-separated `category_id` list contains `category_id`.
+    fn budgets_referencing(
+        &self,
+        conn: &Connection,
+        category_id: &str,
+    ) -> TransactionResult<Vec<(String, Vec<String>)>> {
+        let mut stmt = conn
+            .prepare(
+                r#"SELECT id, category_id FROM "Budget" WHERE user_id = ? AND category_id IS NOT NULL"#,
+            )
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        let budgets = stmt
+            .query_map(params![self.user_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        Ok(budgets
+            .into_iter()
+            .map(|(id, raw)| {
+                let categories = raw
+                    .split(',')
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+                    .collect::<Vec<_>>();
+                (id, categories)
+            })
+            .filter(|(_, categories)| categories.iter().any(|value| value == category_id))
+            .collect())
+    }
+
+    fn category_in_use(&self, conn: &Connection, category_id: &str) -> TransactionResult<bool> {
+        let referenced: bool = conn
+            .query_row(
+                r#"SELECT EXISTS(SELECT 1 FROM "Transaction" WHERE user_id = ?1 AND category_id = ?2)
+                       OR EXISTS(SELECT 1 FROM "TransactionSplit" WHERE user_id = ?1 AND category_id = ?2)
+                       OR EXISTS(SELECT 1 FROM "Goal" WHERE user_id = ?1 AND category_id = ?2)
+                       OR EXISTS(SELECT 1 FROM "TransactionRule" WHERE user_id = ?1 AND category_id = ?2)"#,
+                params![self.user_id, category_id],
+                |row| row.get(0),
+            )
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        if referenced || !self.budgets_referencing(conn, category_id)?.is_empty() {
+            return Ok(true);
+        }
+
+        if !table_exists(conn, "RecurringTransaction")? {
+            return Ok(false);
+        }
+        conn.query_row(
+            r#"SELECT EXISTS(SELECT 1 FROM "RecurringTransaction" WHERE user_id = ?1 AND category_id = ?2)"#,
+            params![self.user_id, category_id],
+            |row| row.get(0),
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))
+    }
+
+    /// Points every transaction, split line, goal, rule, budget, planned expense and
+    /// recurring series at `to` instead of `from`.
+    fn reassign_category_references(
+        &self,
+        tx: &rusqlite::Transaction<'_>,
+        from: &str,
+        to: &str,
+    ) -> TransactionResult<()> {
+        for table in ["Transaction", "TransactionSplit", "Goal", "TransactionRule"] {
+            tx.execute(
+                &format!(r#"UPDATE "{table}" SET category_id = ?1 WHERE user_id = ?2 AND category_id = ?3"#),
+                params![to, self.user_id, from],
+            )
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        }
+
+        for (budget_id, categories) in self.budgets_referencing(tx, from)? {
+            let mut merged: Vec<String> = Vec::with_capacity(categories.len());
+            for category in categories {
+                let category = if category == from {
+                    to.to_string()
+                } else {
+                    category
+                };
+                if !merged.contains(&category) {
+                    merged.push(category);
+                }
+            }
+            tx.execute(
+                r#"UPDATE "Budget" SET category_id = ? WHERE id = ? AND user_id = ?"#,
+                params![merged.join(","), budget_id, self.user_id],
+            )
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        }
+
+        let has_planned_expenses: bool = tx
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type='table' AND name='PlannedExpense')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+        if has_planned_expenses {
+            tx.execute(
+                r#"UPDATE "PlannedExpense" SET category_id = ?1 WHERE user_id = ?2 AND category_id = ?3"#,
+                params![to, self.user_id, from],
+            )
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        }
+
+        // Otherwise future occurrences keep posting to the removed category
+        if table_exists(tx, "RecurringTransaction")? {
+            tx.execute(
+                r#"UPDATE "RecurringTransaction" SET category_id = ?1 WHERE user_id = ?2 AND category_id = ?3"#,
+                params![to, self.user_id, from],
+            )
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Rejects ledger writes against closed accounts.
+    fn ensure_account_open(
+        &self,
+        tx: &rusqlite::Transaction<'_>,
+        account_id: &str,
+    ) -> TransactionResult<()> {
+        let status: String = tx
+            .query_row(
+                r#"SELECT status FROM "Account" WHERE user_id = ? AND id = ?"#,
+                params![self.user_id, account_id],
+                |row| row.get(0),
+            )
+            .map_err(|err| match err {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    TransactionServiceError::NotFound(account_id.to_string())
+                }
+                _ => TransactionServiceError::Database(err.to_string()),
+            })?;
+
+        if AccountStatus::from_db(&status) == AccountStatus::Closed {
+            return Err(TransactionServiceError::Validation(format!(
+                "Account {account_id} is closed"
+            )));
+        }
+        Ok(())
+    }
+
     fn ensure_seed_data(&self, conn: &Connection) -> TransactionResult<()> {
         // Seed a default checking account if none exist
         let account_count: i64 = conn
@@ -161,7 +722,20 @@ impl SqliteTransactionService {
             .map_err(|err| TransactionServiceError::Database(format!("Failed to seed default account: {}", err)))?;
         }
 
-        // Seed baseline categories (idempotent; insert if missing)
+        // Seed baseline categories on first run only, so later edits, merges and
+        // deletions of the starter set are never undone on startup
+        let category_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM \"Category\" WHERE user_id = ?",
+                params![self.user_id],
+                |row| row.get(0),
+            )
+            .unwrap_or(0);
+
+        if category_count > 0 {
+            return Ok(());
+        }
+
         let categories = vec![
             ("cat-income", "💼 Income", "income", 0),
             ("cat-food", "🥕 Food & Groceries", "expense", 1),
@@ -183,18 +757,6 @@ impl SqliteTransactionService {
                 params![id, self.user_id, name, kind, order],
             )
             .map_err(|err| TransactionServiceError::Database(format!("Failed to seed category {name}: {err}")))?;
-
-            // Keep names/types/sort_order in sync if the row already existed under the same ID
-            conn.execute(
-                r#"UPDATE "Category" SET name = ?, type = ?, sort_order = ?
-                   WHERE id = ? AND user_id = ?"#,
-                params![name, kind, order, id, self.user_id],
-            )
-            .map_err(|err| {
-                TransactionServiceError::Database(format!(
-                    "Failed to update category {name}: {err}"
-                ))
-            })?;
         }
 
         Ok(())
@@ -206,12 +768,12 @@ impl SqliteTransactionService {
         Ok(parsed.with_timezone(&Utc).to_rfc3339())
     }
 
-    fn parse_tags(tags: Option<Vec<String>>) -> Option<String> {
+    pub(super) fn parse_tags(tags: Option<Vec<String>>) -> Option<String> {
         tags.filter(|list| !list.is_empty())
             .map(|list| list.join(","))
     }
 
-    fn split_tags(tags: Option<String>) -> Vec<String> {
+    pub(super) fn split_tags(tags: Option<String>) -> Vec<String> {
         tags.unwrap_or_default()
             .split(',')
             .filter_map(|token| {
@@ -225,69 +787,171 @@ impl SqliteTransactionService {
             .collect()
     }
 
-    fn fetch_transaction_row(
+    fn map_transaction_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<TransactionDto> {
+        let kind: String = row.get(5)?;
+        Ok(TransactionDto {
+            id: row.get(0)?,
+            account_id: row.get(1)?,
+            account_name: row.get(2)?,
+            category_id: row.get(3)?,
+            category_name: row.get(4)?,
+            kind: parse_kind(&kind),
+            amount_cents: row.get(6)?,
+            currency: row.get(7)?,
+            occurred_on: row.get(8)?,
+            cleared: row.get::<_, i64>(9)? != 0,
+            notes: row.get(10)?,
+            tags: Self::split_tags(row.get(11)?),
+            goal_id: row.get(12)?,
+            transfer_direction: TransferDirection::from_db(row.get(13)?),
+            linked_transaction_id: row.get(14)?,
+            transfer_account_id: row.get(15)?,
+            transfer_account_name: row.get(16)?,
+            recurrence_id: row.get(17)?,
+            exchange_rate: row.get(18)?,
+            payee_id: row.get(19)?,
+            payee_name: row.get(20)?,
+            reconciliation_id: row.get(21)?,
+            external_id: row.get(22)?,
+            import_batch_id: row.get(23)?,
+            splits: Vec::new(),
+        })
+    }
+
+    /// Loads split lines for the given transactions, keyed by transaction id.
+    fn load_splits(
         &self,
         conn: &Connection,
-        id: &str,
-    ) -> TransactionResult<TransactionDto> {
-        let sql = r#"
-            SELECT 
-                t.id,
-                t.account_id,
-                a.name as account_name,
-                t.category_id,
-                c.name as category_name,
-                t.type,
-                t.amount_cents,
-                t.currency,
-                t.occurred_on,
-                t.cleared,
-                t.notes,
-                t.tags,
-                t.goal_id
-            FROM "Transaction" t
-            JOIN "Account" a ON a.id = t.account_id
-            LEFT JOIN "Category" c ON c.id = t.category_id
-            WHERE t.user_id = ? AND t.id = ?
-        "#;
+        transaction_ids: &[String],
+    ) -> TransactionResult<HashMap<String, Vec<TransactionSplitDto>>> {
+        let mut splits: HashMap<String, Vec<TransactionSplitDto>> = HashMap::new();
+        if transaction_ids.is_empty() {
+            return Ok(splits);
+        }
 
-        conn.query_row(sql, params![self.user_id, id], |row| {
-            let kind: String = row.get(5)?;
-            Ok(TransactionDto {
-                id: row.get(0)?,
-                account_id: row.get(1)?,
-                account_name: row.get(2)?,
-                category_id: row.get(3)?,
-                category_name: row.get(4)?,
-                kind: match kind.as_str() {
-                    "income" => TransactionKind::Income,
-                    "expense" => TransactionKind::Expense,
-                    _ => TransactionKind::Transfer,
-                },
-                amount_cents: row.get(6)?,
-                currency: row.get(7)?,
-                occurred_on: row.get(8)?,
-                cleared: row.get::<_, i64>(9)? != 0,
-                notes: row.get(10)?,
-                tags: Self::split_tags(row.get(11)?),
-                goal_id: row.get(12)?,
+        let placeholders = vec!["?"; transaction_ids.len()].join(",");
+        let sql = format!(
+            r#"
+            SELECT s.transaction_id, s.id, s.category_id, c.name, s.amount_cents, s.notes, s.goal_id
+            FROM "TransactionSplit" s
+            LEFT JOIN "Category" c ON c.id = s.category_id
+            WHERE s.user_id = ? AND s.transaction_id IN ({placeholders})
+            ORDER BY s.transaction_id, s.sort_order
+        "#
+        );
+
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&self.user_id];
+        params.extend(transaction_ids.iter().map(|id| id as &dyn rusqlite::ToSql));
+
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        let rows = stmt
+            .query_map(params_from_iter(params), |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    TransactionSplitDto {
+                        id: row.get(1)?,
+                        category_id: row.get(2)?,
+                        category_name: row.get(3)?,
+                        amount_cents: row.get(4)?,
+                        notes: row.get(5)?,
+                        goal_id: row.get(6)?,
+                    },
+                ))
             })
-        })
-        .map_err(|err| match err {
-            rusqlite::Error::QueryReturnedNoRows => {
-                TransactionServiceError::NotFound(id.to_string())
-            }
-            _ => TransactionServiceError::Database(err.to_string()),
-        })
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        for row in rows {
+            let (transaction_id, split) =
+                row.map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+            splits.entry(transaction_id).or_default().push(split);
+        }
+
+        Ok(splits)
     }
 
-    fn apply_balance_delta(
+    fn attach_splits(
         &self,
-        tx: &rusqlite::Transaction<'_>,
-        account_id: &str,
-        delta: i64,
+        conn: &Connection,
+        transactions: &mut [TransactionDto],
     ) -> TransactionResult<()> {
-        if delta == 0 {
+        let ids = transactions
+            .iter()
+            .map(|transaction| transaction.id.clone())
+            .collect::<Vec<_>>();
+        let mut splits = self.load_splits(conn, &ids)?;
+        for transaction in transactions {
+            transaction.splits = splits.remove(&transaction.id).unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    /// Replaces the split lines stored for a transaction.
+    fn replace_splits(
+        &self,
+        tx: &rusqlite::Transaction<'_>,
+        transaction_id: &str,
+        splits: &[TransactionSplitInput],
+    ) -> TransactionResult<()> {
+        tx.execute(
+            r#"DELETE FROM "TransactionSplit" WHERE transaction_id = ? AND user_id = ?"#,
+            params![transaction_id, self.user_id],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        for (index, split) in splits.iter().enumerate() {
+            tx.execute(
+                r#"
+                INSERT INTO "TransactionSplit" (id, transaction_id, user_id, category_id, goal_id, amount_cents, notes, sort_order)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+                params![
+                    format!("split_{}", Uuid::new_v4()),
+                    transaction_id,
+                    self.user_id,
+                    split.category_id,
+                    split.goal_id,
+                    split.amount_cents,
+                    split.notes,
+                    index as i32
+                ],
+            )
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    fn fetch_transaction_row(
+        &self,
+        conn: &Connection,
+        id: &str,
+    ) -> TransactionResult<TransactionDto> {
+        let sql = format!("{TRANSACTION_SELECT} WHERE t.user_id = ? AND t.id = ?");
+
+        let transaction = conn
+            .query_row(&sql, params![self.user_id, id], Self::map_transaction_row)
+            .map_err(|err| match err {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    TransactionServiceError::NotFound(id.to_string())
+                }
+                _ => TransactionServiceError::Database(err.to_string()),
+            })?;
+
+        let mut transactions = [transaction];
+        self.attach_splits(conn, &mut transactions)?;
+        let [transaction] = transactions;
+        Ok(transaction)
+    }
+
+    fn apply_balance_delta(
+        &self,
+        tx: &rusqlite::Transaction<'_>,
+        account_id: &str,
+        delta: i64,
+    ) -> TransactionResult<()> {
+        if delta == 0 {
             return Ok(());
         }
 
@@ -313,7 +977,8 @@ impl SqliteTransactionService {
         id: &str,
     ) -> TransactionResult<TransactionLedgerRecord> {
         let sql = r#"
-            SELECT account_id, type, amount_cents
+            SELECT account_id, type, amount_cents, transfer_direction, linked_transaction_id,
+                   occurred_on, reconciliation_id, currency, exchange_rate
             FROM "Transaction"
             WHERE user_id = ? AND id = ?
         "#;
@@ -321,13 +986,16 @@ impl SqliteTransactionService {
         tx.query_row(sql, params![self.user_id, id], |row| {
             let kind: String = row.get(1)?;
             Ok(TransactionLedgerRecord {
+                id: id.to_string(),
                 account_id: row.get(0)?,
-                kind: match kind.as_str() {
-                    "income" => TransactionKind::Income,
-                    "expense" => TransactionKind::Expense,
-                    _ => TransactionKind::Transfer,
-                },
+                kind: parse_kind(&kind),
                 amount_cents: row.get(2)?,
+                transfer_direction: TransferDirection::from_db(row.get(3)?),
+                linked_transaction_id: row.get(4)?,
+                occurred_on: row.get(5)?,
+                reconciliation_id: row.get(6)?,
+                currency: row.get(7)?,
+                exchange_rate: row.get(8)?,
             })
         })
         .map_err(|err| match err {
@@ -338,10 +1006,29 @@ impl SqliteTransactionService {
         })
     }
 
-    fn insert_transaction(
+    /// Loads a row together with its transfer counterpart, if any.
+    fn ledger_records(
+        &self,
+        tx: &rusqlite::Transaction<'_>,
+        id: &str,
+    ) -> TransactionResult<(TransactionLedgerRecord, Option<TransactionLedgerRecord>)> {
+        let record = self.transaction_record(tx, id)?;
+        let peer = match &record.linked_transaction_id {
+            Some(peer_id) => match self.transaction_record(tx, peer_id) {
+                Ok(peer) => Some(peer),
+                Err(TransactionServiceError::NotFound(_)) => None,
+                Err(err) => return Err(err),
+            },
+            None => None,
+        };
+        Ok((record, peer))
+    }
+
+    fn insert_leg(
         &self,
         tx: &rusqlite::Transaction<'_>,
         payload: &TransactionPayload,
+        leg: &LedgerLeg,
     ) -> TransactionResult<()> {
         tx.execute(
             r#"
@@ -358,22 +1045,37 @@ impl SqliteTransactionService {
                 cleared,
                 notes,
                 tags,
+                transfer_direction,
+                linked_transaction_id,
+                recurrence_id,
+                exchange_rate,
+                payee_id,
+                external_id,
+                import_batch_id,
                 updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, CURRENT_TIMESTAMP)
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, CURRENT_TIMESTAMP)
         "#,
             params![
-                payload.id,
+                leg.id,
                 self.user_id,
-                payload.account_id,
+                leg.account_id,
                 payload.category_id,
                 payload.goal_id,
                 payload.kind.as_str(),
-                payload.amount_cents,
-                payload.currency,
+                leg.amount_cents,
+                leg.currency,
                 payload.occurred_on,
                 payload.cleared,
                 payload.notes,
-                Self::parse_tags(payload.tags.clone())
+                Self::parse_tags(payload.tags.clone()),
+                leg.direction.map(|direction| direction.as_str()),
+                leg.linked_transaction_id,
+                payload.recurrence_id,
+                leg.exchange_rate,
+                payload.payee_id,
+                // The bank id belongs to the statement line, i.e. the primary leg
+                payload.external_id.as_ref().filter(|_| leg.id == payload.id),
+                payload.import_batch_id,
             ],
         )
         .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
@@ -381,10 +1083,115 @@ impl SqliteTransactionService {
         Ok(())
     }
 
-    fn update_transaction_row(
+    /// Existing rows an import item may repeat: those with its external id on the account,
+    /// or else those sharing its fingerprint.
+    fn suspected_duplicates(
+        &self,
+        conn: &Connection,
+        payload: &TransactionPayload,
+    ) -> TransactionResult<Option<(DuplicateReason, Vec<TransactionDto>)>> {
+        let query = |where_sql: &str, params: &[&dyn rusqlite::ToSql]| {
+            let sql = format!(
+                "{TRANSACTION_SELECT} WHERE {where_sql} ORDER BY t.occurred_on, t.created_at"
+            );
+            let mut stmt = conn
+                .prepare(&sql)
+                .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+            let rows = stmt
+                .query_map(params, Self::map_transaction_row)
+                .map_err(|err| TransactionServiceError::Database(err.to_string()))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| TransactionServiceError::Database(err.to_string()));
+            rows
+        };
+
+        let mut found = None;
+        if let Some(external_id) = &payload.external_id {
+            let rows = query(
+                duplicates::EXTERNAL_ID_WHERE,
+                params![self.user_id, payload.account_id, external_id],
+            )?;
+            if !rows.is_empty() {
+                found = Some((DuplicateReason::ExternalId, rows));
+            }
+        }
+        if found.is_none() {
+            let notes = duplicates::normalize_notes(payload.notes.as_deref());
+            let rows: Vec<TransactionDto> = query(
+                duplicates::FINGERPRINT_WHERE,
+                params![
+                    self.user_id,
+                    payload.account_id,
+                    payload.kind.as_str(),
+                    payload.amount_cents,
+                    payload.occurred_on,
+                    duplicates::WINDOW_DAYS,
+                    payload.external_id,
+                ],
+            )?
+            .into_iter()
+            .filter(|row| duplicates::normalize_notes(row.notes.as_deref()) == notes)
+            .collect();
+            if !rows.is_empty() {
+                found = Some((DuplicateReason::Fingerprint, rows));
+            }
+        }
+
+        let Some((reason, mut rows)) = found else {
+            return Ok(None);
+        };
+        self.attach_splits(conn, &mut rows)?;
+        Ok(Some((reason, rows)))
+    }
+
+    /// Fills what the existing row lacks from the imported item. Amount, date and account
+    /// stay as they are, so merging never moves a balance.
+    fn merge_import(
+        &self,
+        tx: &rusqlite::Transaction<'_>,
+        target_id: &str,
+        payload: &TransactionPayload,
+    ) -> TransactionResult<()> {
+        let target = self.fetch_transaction_row(tx, target_id)?;
+        if target.account_id != payload.account_id {
+            return Err(TransactionServiceError::Validation(format!(
+                "Transaction {target_id} is not on the imported account"
+            )));
+        }
+        let category_id = payload
+            .category_id
+            .as_ref()
+            .filter(|_| target.splits.is_empty());
+        tx.execute(
+            r#"
+            UPDATE "Transaction" SET
+                external_id = COALESCE(external_id, ?1),
+                payee_id = COALESCE(payee_id, ?2),
+                notes = COALESCE(NULLIF(notes, ''), ?3),
+                category_id = COALESCE(category_id, ?4),
+                cleared = MAX(cleared, ?5),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE user_id = ?6 AND id = ?7
+            "#,
+            params![
+                payload.external_id,
+                payload.payee_id,
+                payload.notes,
+                category_id,
+                payload.cleared,
+                self.user_id,
+                target_id,
+            ],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        Ok(())
+    }
+
+    fn update_leg(
         &self,
         tx: &rusqlite::Transaction<'_>,
         payload: &TransactionPayload,
+        leg: &LedgerLeg,
     ) -> TransactionResult<()> {
         let affected = tx
             .execute(
@@ -400,171 +1207,78 @@ impl SqliteTransactionService {
                     cleared = ?8,
                     notes = ?9,
                     tags = ?10,
+                    transfer_direction = ?11,
+                    linked_transaction_id = ?12,
+                    exchange_rate = ?13,
+                    payee_id = ?14,
                     updated_at = CURRENT_TIMESTAMP
-                WHERE id = ?11 AND user_id = ?12
+                WHERE id = ?15 AND user_id = ?16
             "#,
                 params![
-                    payload.account_id,
+                    leg.account_id,
                     payload.category_id,
                     payload.goal_id,
                     payload.kind.as_str(),
-                    payload.amount_cents,
-                    payload.currency,
+                    leg.amount_cents,
+                    leg.currency,
                     payload.occurred_on,
                     payload.cleared,
                     payload.notes,
                     Self::parse_tags(payload.tags.clone()),
-                    payload.id,
+                    leg.direction.map(|direction| direction.as_str()),
+                    leg.linked_transaction_id,
+                    leg.exchange_rate,
+                    payload.payee_id,
+                    leg.id,
                     self.user_id
                 ],
             )
             .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
 
         if affected == 0 {
-            return Err(TransactionServiceError::NotFound(payload.id.clone()));
+            return Err(TransactionServiceError::NotFound(leg.id.clone()));
         }
 
         Ok(())
     }
-}
-
-impl TransactionService for SqliteTransactionService {
-    fn descriptor(&self) -> ServiceDescriptor {
-        ServiceDescriptor::new("TransactionService", "sqlite")
-    }
 
-    fn list_accounts(&self, include_balances: bool) -> TransactionResult<Vec<AccountDto>> {
-        let conn = self.connection()?;
-        let mut stmt = conn
-            .prepare(
-                r#"SELECT id, name, type, currency, balance_cents, color_token 
-                   FROM "Account" WHERE user_id = ? ORDER BY name ASC"#,
+    fn delete_row(&self, tx: &rusqlite::Transaction<'_>, id: &str) -> TransactionResult<()> {
+        self.replace_splits(tx, id, &[])?;
+        let affected = tx
+            .execute(
+                "DELETE FROM \"Transaction\" WHERE id = ? AND user_id = ?",
+                params![id, self.user_id],
             )
             .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
 
-        let mut accounts = Vec::new();
-        let mut rows = stmt
-            .query(params![self.user_id])
-            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
-
-        while let Some(row) = rows
-            .next()
-            .map_err(|err| TransactionServiceError::Database(err.to_string()))?
-        {
-            let id: String = row.get(0)?;
-            let available = if include_balances {
-                Some(
-                    conn.query_row(
-                        "SELECT COALESCE(SUM(CASE 
-                            WHEN type = 'income' THEN amount_cents 
-                            WHEN type = 'expense' THEN -amount_cents 
-                            ELSE 0 END), 0) 
-                            FROM \"Transaction\" WHERE user_id = ? AND account_id = ?",
-                        params![self.user_id, id.clone()],
-                        |r| r.get(0),
-                    )
-                    .unwrap_or(0),
-                )
-            } else {
-                None
-            };
-
-            accounts.push(AccountDto {
-                id,
-                name: row.get(1)?,
-                account_type: row.get(2)?,
-                currency: row.get(3)?,
-                balance_cents: row.get(4)?,
-                available_balance_cents: available,
-                color_token: row.get(5)?,
-            });
+        if affected == 0 {
+            return Err(TransactionServiceError::NotFound(id.to_string()));
         }
 
-        Ok(accounts)
-    }
-
-    fn list_categories(&self) -> TransactionResult<Vec<CategoryDto>> {
-        let conn = self.connection()?;
-        let mut stmt = conn
-            .prepare(
-                r#"SELECT id, name, type, sort_order 
-                   FROM "Category" WHERE user_id = ? AND archived = 0 
-                   ORDER BY sort_order, name"#,
-            )
-            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
-
-        let categories = stmt
-            .query_map(params![self.user_id], |row| {
-                Ok(CategoryDto {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    category_type: row.get(2)?,
-                    sort_order: row.get(3)?,
-                })
-            })
-            .map_err(|err| TransactionServiceError::Database(err.to_string()))?
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
-
-        Ok(categories)
+        Ok(())
     }
 
-    fn list_transactions(&self, query: TransactionQuery) -> TransactionResult<Vec<TransactionDto>> {
-        let conn = self.connection()?;
-        let mut sql = String::from(
-            r#"
-            SELECT 
-                t.id,
-                t.account_id,
-                a.name as account_name,
-                t.category_id,
-                c.name as category_name,
-                t.type,
-                t.amount_cents,
-                t.currency,
-                t.occurred_on,
-                t.cleared,
-                t.notes,
-                t.tags,
-                t.goal_id
-            FROM "Transaction" t
-            JOIN "Account" a ON a.id = t.account_id
-            LEFT JOIN "Category" c ON c.id = t.category_id
-            WHERE t.user_id = ?
-        "#,
+    fn query_transactions(
+        &self,
+        conn: &Connection,
+        filter: &TransactionFilter,
+        query: &TransactionQuery,
+    ) -> TransactionResult<Vec<TransactionDto>> {
+        let sql = format!(
+            "{TRANSACTION_SELECT}{} ORDER BY t.occurred_on DESC, t.created_at DESC LIMIT ? OFFSET ?",
+            filter.where_sql()
         );
+        let mut params = filter.params();
+        let limit = query.limit.unwrap_or(50);
+        let offset = query.offset.unwrap_or(0);
+        params.push(&limit);
+        params.push(&offset);
 
-        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(self.user_id.clone())];
-
-        if let Some(account_id) = &query.account_id {
-            sql.push_str(" AND t.account_id = ?");
-            params.push(Box::new(account_id.clone()));
-        }
-
-        if let Some(category_id) = &query.category_id {
-            sql.push_str(" AND t.category_id = ?");
-            params.push(Box::new(category_id.clone()));
-        }
-
-        if let Some(search) = &query.search {
-            sql.push_str(" AND (LOWER(t.notes) LIKE ? OR LOWER(t.tags) LIKE ?)");
-            let needle = format!("%{}%", search.to_lowercase());
-            params.push(Box::new(needle.clone()));
-            params.push(Box::new(needle));
-        }
-
-        sql.push_str(" ORDER BY t.occurred_on DESC, t.created_at DESC");
-        sql.push_str(" LIMIT ? OFFSET ?");
-
-        params.push(Box::new(query.limit.unwrap_or(50)));
-        params.push(Box::new(query.offset.unwrap_or(0)));
-
-        let sqlite_params = params_from_iter(params.iter().map(|value| &**value));
         let mut stmt = conn
             .prepare(&sql)
             .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
         let mut rows = stmt
-            .query(sqlite_params)
+            .query(params_from_iter(params))
             .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
 
         let mut transactions = Vec::new();
@@ -572,153 +1286,995 @@ impl TransactionService for SqliteTransactionService {
             .next()
             .map_err(|err| TransactionServiceError::Database(err.to_string()))?
         {
-            let kind: String = row.get(5)?;
-            let occurred_on: String = row.get(8)?;
-            transactions.push(TransactionDto {
-                id: row.get(0)?,
-                account_id: row.get(1)?,
-                account_name: row.get(2)?,
-                category_id: row.get(3)?,
-                category_name: row.get(4)?,
-                kind: match kind.as_str() {
-                    "income" => TransactionKind::Income,
-                    "expense" => TransactionKind::Expense,
-                    _ => TransactionKind::Transfer,
-                },
-                amount_cents: row.get(6)?,
-                currency: row.get(7)?,
-                occurred_on,
-                cleared: row.get::<_, i64>(9)? != 0,
-                notes: row.get(10)?,
-                tags: Self::split_tags(row.get(11)?),
-                goal_id: row.get(12)?,
-            });
+            transactions.push(Self::map_transaction_row(row)?);
         }
+        drop(rows);
+        drop(stmt);
 
+        self.attach_splits(conn, &mut transactions)?;
         Ok(transactions)
     }
 
-    fn create_transaction(
+    /// Amount and currency credited to the transfer destination. Cross-currency transfers
+    /// use the payload's `exchange_rate`, or the stored rate for the transfer date.
+    fn transfer_quote(
         &self,
-        input: CreateTransactionInput,
-    ) -> TransactionResult<TransactionDto> {
-        let payload = TransactionPayload::from_create(input)?;
-        let mut conn = self.connection()?;
-        // Ensure seed data exists before writes in case DB was created without upfront seeding
-        self.ensure_seed_data(&conn)?;
-        let tx = conn
-            .transaction()
-            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        tx: &rusqlite::Transaction<'_>,
+        payload: &TransactionPayload,
+    ) -> TransactionResult<Option<TransferQuote>> {
+        let Some(destination) = &payload.transfer_account_id else {
+            return Ok(None);
+        };
 
-        self.insert_transaction(&tx, &payload)?;
-        self.apply_balance_delta(
-            &tx,
-            &payload.account_id,
-            payload.kind.balance_delta(payload.amount_cents),
-        )?;
+        let currency: String = tx
+            .query_row(
+                r#"SELECT currency FROM "Account" WHERE user_id = ? AND id = ?"#,
+                params![self.user_id, destination],
+                |row| row.get(0),
+            )
+            .map_err(|err| match err {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    TransactionServiceError::NotFound(destination.clone())
+                }
+                _ => TransactionServiceError::Database(err.to_string()),
+            })?;
+        let currency = currency.to_uppercase();
 
-        tx.commit()
-            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        if currency == payload.currency {
+            return Ok(Some(TransferQuote {
+                amount_cents: payload.amount_cents,
+                currency,
+                exchange_rate: None,
+            }));
+        }
 
-        self.fetch_transaction_row(&conn, &payload.id)
+        let rate = match payload.exchange_rate {
+            Some(rate) => rate,
+            None => {
+                let on = payload
+                    .occurred_on
+                    .get(..10)
+                    .unwrap_or(&payload.occurred_on);
+                currency::lookup_rate(tx, &payload.currency, &currency, on)
+                    .map_err(|err| TransactionServiceError::Database(err.to_string()))?
+                    .ok_or_else(|| {
+                        TransactionServiceError::Validation(format!(
+                            "No exchange rate from {} to {currency}; enter the rate for this transfer",
+                            payload.currency
+                        ))
+                    })?
+            }
+        };
+
+        let amount_cents = (payload.amount_cents as f64 * rate).round() as i64;
+        if amount_cents <= 0 {
+            return Err(TransactionServiceError::Validation(
+                "Converted transfer amount must be greater than zero".into(),
+            ));
+        }
+
+        Ok(Some(TransferQuote {
+            amount_cents,
+            currency,
+            exchange_rate: Some(rate),
+        }))
     }
 
-    fn update_transaction(
+    /// Links the payload to its `Payee` row, creating the payee on first use.
+    fn resolve_payee(
         &self,
-        input: UpdateTransactionInput,
-    ) -> TransactionResult<TransactionDto> {
-        let payload = TransactionPayload::from_update(input)?;
-        let mut conn = self.connection()?;
-        let tx = conn
-            .transaction()
-            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
-
-        let existing = self.transaction_record(&tx, &payload.id)?;
+        conn: &Connection,
+        payload: &mut TransactionPayload,
+    ) -> TransactionResult<()> {
+        let resolved = rules::resolve_payee(conn, &self.user_id, payload.payee_name.as_deref())?;
+        (payload.payee_id, payload.payee_name) = resolved.unzip();
+        Ok(())
+    }
 
-        self.apply_balance_delta(
-            &tx,
-            &existing.account_id,
-            -existing.kind.balance_delta(existing.amount_cents),
-        )?;
-        self.update_transaction_row(&tx, &payload)?;
-        self.apply_balance_delta(
-            &tx,
-            &payload.account_id,
-            payload.kind.balance_delta(payload.amount_cents),
-        )?;
+    /// Fills category, goal and tags from matching rules. Values entered on the
+    /// transaction itself are kept; transfers are never categorized by rules.
+    fn categorize(&self, rules: &[CompiledRule], payload: &mut TransactionPayload) {
+        if rules.is_empty() || payload.kind == TransactionKind::Transfer {
+            return;
+        }
 
-        tx.commit()
-            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        let mut fields = RuleFieldsDto {
+            category_id: payload.category_id.take(),
+            goal_id: payload.goal_id.take(),
+            tags: payload.tags.take().unwrap_or_default(),
+        };
+        let subject = RuleSubject {
+            payee_name: payload.payee_name.as_deref(),
+            notes: payload.notes.as_deref(),
+            amount_cents: payload.amount_cents,
+            account_id: &payload.account_id,
+        };
+        let allow_category = payload.splits.is_empty();
+        rules::apply(rules, &subject, &mut fields, false, allow_category);
 
-        self.fetch_transaction_row(&conn, &payload.id)
+        payload.category_id = fields.category_id;
+        payload.goal_id = fields.goal_id;
+        payload.tags = Some(fields.tags).filter(|tags| !tags.is_empty());
     }
 
-    fn delete_transaction(&self, id: &str) -> TransactionResult<()> {
-        let mut conn = self.connection()?;
-        let tx = conn
-            .transaction()
-            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+    /// Inserts the ledger rows for a new payload and applies their balance deltas.
+    fn write_new_transaction(
+        &self,
+        tx: &rusqlite::Transaction<'_>,
+        payload: &TransactionPayload,
+    ) -> TransactionResult<()> {
+        let quote = self.transfer_quote(tx, payload)?;
+        for leg in payload.legs(None, None, quote) {
+            self.ensure_account_open(tx, &leg.account_id)?;
+            self.insert_leg(tx, payload, &leg)?;
+            self.apply_balance_delta(tx, &leg.account_id, leg.balance_delta(payload))?;
+        }
+        if !payload.splits.is_empty() {
+            self.replace_splits(tx, &payload.id, &payload.splits)?;
+        }
+        Ok(())
+    }
 
-        let existing = self.transaction_record(&tx, id)?;
-        self.apply_balance_delta(
-            &tx,
-            &existing.account_id,
-            -existing.kind.balance_delta(existing.amount_cents),
-        )?;
+    /// A reconciliation with the transactions it covers: the open candidates while in
+    /// progress, or the rows it locked once completed.
+    fn reconciliation_detail(
+        &self,
+        conn: &Connection,
+        id: &str,
+    ) -> TransactionResult<ReconciliationDetailDto> {
+        let reconciliation = reconcile::fetch(conn, &self.user_id, id)?;
+        let (where_sql, params) = match reconciliation.status {
+            ReconciliationStatus::InProgress => (
+                reconcile::CANDIDATE_WHERE,
+                vec![
+                    self.user_id.clone(),
+                    reconciliation.account_id.clone(),
+                    reconciliation.statement_date.clone(),
+                ],
+            ),
+            ReconciliationStatus::Completed => (
+                "t.user_id = ? AND t.reconciliation_id = ?",
+                vec![self.user_id.clone(), reconciliation.id.clone()],
+            ),
+        };
 
-        let affected = tx
-            .execute(
-                "DELETE FROM \"Transaction\" WHERE id = ? AND user_id = ?",
-                params![id, self.user_id],
-            )
+        let mut stmt = conn
+            .prepare(&format!(
+                "{TRANSACTION_SELECT} WHERE {where_sql} ORDER BY t.occurred_on, t.created_at"
+            ))
             .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
-
-        if affected == 0 {
-            return Err(TransactionServiceError::NotFound(id.to_string()));
-        }
-
-        tx.commit()
+        let mut transactions = stmt
+            .query_map(params_from_iter(params), Self::map_transaction_row)
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
             .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        drop(stmt);
+        self.attach_splits(conn, &mut transactions)?;
 
-        Ok(())
+        Ok(ReconciliationDetailDto {
+            reconciliation,
+            transactions,
+        })
     }
+}
 
-    fn import_transactions(
+impl TransactionService for SqliteTransactionService {
+    fn descriptor(&self) -> ServiceDescriptor {
+        ServiceDescriptor::new("TransactionService", "sqlite")
+    }
+
+    fn list_accounts(
         &self,
-        inputs: Vec<CreateTransactionInput>,
-    ) -> TransactionResult<Vec<TransactionDto>> {
-        if inputs.is_empty() {
-            return Ok(Vec::new());
+        include_balances: bool,
+        include_archived: bool,
+    ) -> TransactionResult<Vec<AccountDto>> {
+        let conn = self.connection()?;
+        let mut sql = format!("{ACCOUNT_SELECT} WHERE user_id = ?");
+        if !include_archived {
+            sql.push_str(" AND status = 'active'");
         }
+        sql.push_str(" ORDER BY sort_order ASC, name ASC");
 
-        let payloads = inputs
-            .into_iter()
-            .map(TransactionPayload::from_create)
-            .collect::<TransactionResult<Vec<_>>>()?;
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        let mut accounts = stmt
+            .query_map(params![self.user_id], Self::map_account_row)
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        if include_balances {
+            for account in &mut accounts {
+                account.available_balance_cents = Some(self.ledger_balance(&conn, &account.id));
+            }
+        }
+
+        Ok(accounts)
+    }
+
+    fn create_account(&self, input: CreateAccountInput) -> TransactionResult<AccountDto> {
+        Self::validate_account_fields(&input.name, &input.account_type, &input.currency)?;
+        let conn = self.connection()?;
+        let id = input
+            .id
+            .unwrap_or_else(|| format!("acct_{}", Uuid::new_v4()));
+
+        let next_sort_order: i32 = conn
+            .query_row(
+                r#"SELECT COALESCE(MAX(sort_order) + 1, 0) FROM "Account" WHERE user_id = ?"#,
+                params![self.user_id],
+                |row| row.get(0),
+            )
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        conn.execute(
+            r#"
+            INSERT INTO "Account" (
+                id,
+                user_id,
+                name,
+                type,
+                currency,
+                balance_cents,
+                opening_balance_cents,
+                institution,
+                color_token,
+                sync_external_id,
+                sort_order,
+                status,
+                updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, ?7, ?8, ?9, ?10, 'active', CURRENT_TIMESTAMP)
+        "#,
+            params![
+                id,
+                self.user_id,
+                input.name.trim(),
+                input.account_type,
+                input.currency.to_uppercase(),
+                input.opening_balance_cents,
+                input.institution,
+                input.color_token,
+                input.sync_external_id,
+                next_sort_order
+            ],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        self.fetch_account(&conn, &id)
+    }
+
+    fn update_account(&self, input: UpdateAccountInput) -> TransactionResult<AccountDto> {
+        Self::validate_account_fields(&input.name, &input.account_type, &input.currency)?;
+        let conn = self.connection()?;
+        let existing = self.fetch_account(&conn, &input.id)?;
+        let opening_balance_cents = input
+            .opening_balance_cents
+            .unwrap_or(existing.opening_balance_cents);
+        let opening_delta = opening_balance_cents - existing.opening_balance_cents;
+
+        conn.execute(
+            r#"
+            UPDATE "Account" SET
+                name = ?1,
+                type = ?2,
+                currency = ?3,
+                institution = ?4,
+                color_token = ?5,
+                sync_external_id = ?6,
+                opening_balance_cents = ?7,
+                balance_cents = balance_cents + ?8,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?9 AND user_id = ?10
+        "#,
+            params![
+                input.name.trim(),
+                input.account_type,
+                input.currency.to_uppercase(),
+                input.institution,
+                input.color_token,
+                input.sync_external_id,
+                opening_balance_cents,
+                opening_delta,
+                input.id,
+                self.user_id
+            ],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        self.fetch_account(&conn, &input.id)
+    }
+
+    fn update_account_status(
+        &self,
+        input: UpdateAccountStatusInput,
+    ) -> TransactionResult<AccountDto> {
+        let conn = self.connection()?;
+        let existing = self.fetch_account(&conn, &input.id)?;
+
+        if input.status == AccountStatus::Closed {
+            let balance = self.ledger_balance(&conn, &input.id);
+            if balance != 0 {
+                return Err(TransactionServiceError::Validation(format!(
+                    "Account {} still has a balance of {} cents; settle it before closing",
+                    existing.name, balance
+                )));
+            }
+        }
+
+        conn.execute(
+            r#"
+            UPDATE "Account" SET
+                status = ?1,
+                closed_at = CASE WHEN ?1 = 'closed' THEN COALESCE(closed_at, CURRENT_TIMESTAMP) ELSE NULL END,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?2 AND user_id = ?3
+        "#,
+            params![input.status.as_str(), input.id, self.user_id],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        self.fetch_account(&conn, &input.id)
+    }
+
+    fn reorder_accounts(&self, input: ReorderAccountsInput) -> TransactionResult<()> {
+        let mut conn = self.connection()?;
+        let tx = conn
+            .transaction()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        for (index, account_id) in input.account_ids.iter().enumerate() {
+            let affected = tx
+                .execute(
+                    r#"UPDATE "Account" SET sort_order = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ? AND user_id = ?"#,
+                    params![index as i32, account_id, self.user_id],
+                )
+                .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+            if affected == 0 {
+                return Err(TransactionServiceError::NotFound(account_id.clone()));
+            }
+        }
+
+        tx.commit()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        Ok(())
+    }
+
+    fn list_categories(&self, include_archived: bool) -> TransactionResult<Vec<CategoryDto>> {
+        let conn = self.connection()?;
+        let mut sql = format!("{CATEGORY_SELECT} WHERE user_id = ?");
+        if !include_archived {
+            sql.push_str(" AND archived = 0");
+        }
+        sql.push_str(" ORDER BY sort_order, name");
+
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        let categories = stmt
+            .query_map(params![self.user_id], Self::map_category_row)
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        Ok(categories)
+    }
+
+    fn create_category(&self, input: CreateCategoryInput) -> TransactionResult<CategoryDto> {
+        let conn = self.connection()?;
+        let id = self.insert_category(&conn, input)?;
+        self.fetch_category(&conn, &id)
+    }
+
+    fn update_category(&self, input: UpdateCategoryInput) -> TransactionResult<CategoryDto> {
+        let conn = self.connection()?;
+        let existing = self.fetch_category(&conn, &input.id)?;
+        self.validate_category(
+            &conn,
+            Some(&input.id),
+            &input.name,
+            &input.category_type,
+            input.parent_id.as_deref(),
+        )?;
+
+        if existing.category_type != input.category_type {
+            let has_children: bool = conn
+                .query_row(
+                    r#"SELECT EXISTS(SELECT 1 FROM "Category" WHERE user_id = ? AND parent_id = ?)"#,
+                    params![self.user_id, input.id],
+                    |row| row.get(0),
+                )
+                .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+            if has_children {
+                return Err(TransactionServiceError::Validation(
+                    "Cannot change the type of a category that has subcategories".into(),
+                ));
+            }
+        }
+
+        conn.execute(
+            r#"
+            UPDATE "Category" SET name = ?1, type = ?2, parent_id = ?3, icon = ?4
+            WHERE id = ?5 AND user_id = ?6
+        "#,
+            params![
+                input.name.trim(),
+                input.category_type,
+                input.parent_id,
+                input.icon,
+                input.id,
+                self.user_id
+            ],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        self.fetch_category(&conn, &input.id)
+    }
+
+    fn archive_category(&self, input: ArchiveCategoryInput) -> TransactionResult<CategoryDto> {
+        let conn = self.connection()?;
+        self.fetch_category(&conn, &input.id)?;
+
+        // Archiving cascades to subcategories so no orphaned child stays selectable
+        conn.execute(
+            &format!(
+                r#"WITH RECURSIVE {CATEGORY_ANCESTRY_CTE}
+                   UPDATE "Category" SET archived = ?1
+                   WHERE user_id = ?2
+                     AND id IN (SELECT category_id FROM category_ancestry WHERE ancestor_id = ?3)"#
+            ),
+            params![input.archived, self.user_id, input.id],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        self.fetch_category(&conn, &input.id)
+    }
+
+    fn merge_categories(&self, input: MergeCategoriesInput) -> TransactionResult<CategoryDto> {
+        if input.source_id == input.target_id {
+            return Err(TransactionServiceError::Validation(
+                "Cannot merge a category into itself".into(),
+            ));
+        }
+
+        let mut conn = self.connection()?;
+        let source = self.fetch_category(&conn, &input.source_id)?;
+        let target = self.fetch_category(&conn, &input.target_id)?;
+        if source.category_type != target.category_type {
+            return Err(TransactionServiceError::Validation(
+                "Only categories of the same type can be merged".into(),
+            ));
+        }
+
+        let target_is_descendant: bool = conn
+            .query_row(
+                &format!(
+                    "WITH RECURSIVE {CATEGORY_ANCESTRY_CTE}
+                     SELECT EXISTS(SELECT 1 FROM category_ancestry WHERE category_id = ? AND ancestor_id = ?)"
+                ),
+                params![target.id, source.id],
+                |row| row.get(0),
+            )
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        if target_is_descendant {
+            return Err(TransactionServiceError::Validation(
+                "Cannot merge a category into one of its own subcategories".into(),
+            ));
+        }
+
+        let tx = conn
+            .transaction()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        self.reassign_category_references(&tx, &source.id, &target.id)?;
+        tx.execute(
+            r#"UPDATE "Category" SET parent_id = ? WHERE user_id = ? AND parent_id = ?"#,
+            params![target.id, self.user_id, source.id],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        tx.execute(
+            r#"DELETE FROM "Category" WHERE id = ? AND user_id = ?"#,
+            params![source.id, self.user_id],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        tx.commit()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        self.fetch_category(&conn, &target.id)
+    }
+
+    fn delete_category(&self, input: DeleteCategoryInput) -> TransactionResult<()> {
+        let mut conn = self.connection()?;
+        let existing = self.fetch_category(&conn, &input.id)?;
 
+        if let Some(reassign_to) = input.reassign_to {
+            return self
+                .merge_categories(MergeCategoriesInput {
+                    source_id: existing.id,
+                    target_id: reassign_to,
+                })
+                .map(|_| ());
+        }
+
+        if self.category_in_use(&conn, &existing.id)? {
+            return Err(TransactionServiceError::Validation(format!(
+                "Category {} is still in use; pick a category to reassign it to or archive it instead",
+                existing.name
+            )));
+        }
+
+        let tx = conn
+            .transaction()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        // Subcategories move up one level instead of becoming top-level orphans
+        tx.execute(
+            r#"UPDATE "Category" SET parent_id = ? WHERE user_id = ? AND parent_id = ?"#,
+            params![existing.parent_id, self.user_id, existing.id],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        tx.execute(
+            r#"DELETE FROM "Category" WHERE id = ? AND user_id = ?"#,
+            params![existing.id, self.user_id],
+        )
+        .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        tx.commit()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        Ok(())
+    }
+
+    fn list_transactions(&self, query: TransactionQuery) -> TransactionResult<Vec<TransactionDto>> {
+        let conn = self.connection()?;
+        let filter = TransactionFilter::new(&self.user_id, &query)?;
+        self.query_transactions(&conn, &filter, &query)
+    }
+
+    fn search_transactions(&self, query: TransactionQuery) -> TransactionResult<TransactionPage> {
+        let conn = self.connection()?;
+        let filter = TransactionFilter::new(&self.user_id, &query)?;
+        let items = self.query_transactions(&conn, &filter, &query)?;
+        let total: i64 = conn
+            .query_row(
+                &format!(
+                    r#"SELECT COUNT(*) FROM "Transaction" t{}"#,
+                    filter.where_sql()
+                ),
+                params_from_iter(filter.params()),
+                |row| row.get(0),
+            )
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        Ok(TransactionPage {
+            items,
+            total,
+            limit: query.limit.unwrap_or(50),
+            offset: query.offset.unwrap_or(0),
+        })
+    }
+
+    fn create_transaction(
+        &self,
+        input: CreateTransactionInput,
+    ) -> TransactionResult<TransactionDto> {
+        let mut payload = TransactionPayload::from_create(input)?;
         let mut conn = self.connection()?;
+        // Ensure seed data exists before writes in case DB was created without upfront seeding
+        self.ensure_seed_data(&conn)?;
         let tx = conn
             .transaction()
             .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
 
+        self.resolve_payee(&tx, &mut payload)?;
+        let rules = rules::load_rules(&tx, &self.user_id, &[])?;
+        self.categorize(&rules, &mut payload);
+        self.write_new_transaction(&tx, &payload)?;
+
+        tx.commit()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        self.fetch_transaction_row(&conn, &payload.id)
+    }
+
+    fn update_transaction(
+        &self,
+        input: UpdateTransactionInput,
+    ) -> TransactionResult<TransactionDto> {
+        let override_reconciled = input.override_reconciled;
+        let mut payload = TransactionPayload::from_update(input)?;
+        let mut conn = self.connection()?;
+        let tx = conn
+            .transaction()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        self.resolve_payee(&tx, &mut payload)?;
+
+        let (existing, peer) = self.ledger_records(&tx, &payload.id)?;
+
+        // Keep the rate already on the transfer (possibly entered by hand) unless the
+        // update sets one or converts between different accounts now
+        if payload.exchange_rate.is_none()
+            && existing.currency == payload.currency
+            && peer.as_ref().map(|peer| &peer.account_id) == payload.transfer_account_id.as_ref()
+        {
+            payload.exchange_rate = existing.exchange_rate;
+        }
+
+        self.apply_balance_delta(&tx, &existing.account_id, -existing.balance_delta())?;
+        if let Some(peer) = &peer {
+            self.apply_balance_delta(&tx, &peer.account_id, -peer.balance_delta())?;
+        }
+
+        // An edited leg keeps its side of the transfer; `account_id` always refers to the
+        // edited row and `transfer_account_id` to its counterpart.
+        let quote = self.transfer_quote(&tx, &payload)?;
+        let legs = payload.legs(
+            existing.transfer_direction,
+            peer.as_ref().map(|peer| peer.id.clone()),
+            quote,
+        );
+        if !override_reconciled {
+            for record in std::iter::once(&existing).chain(peer.as_ref()) {
+                let leg = legs.iter().find(|leg| leg.id == record.id);
+                if record.breaks_reconciliation(&payload, leg) {
+                    return Err(reconciled_error(&payload.id));
+                }
+            }
+        }
+        for leg in &legs {
+            self.ensure_account_open(&tx, &leg.account_id)?;
+            if leg.id == payload.id || peer.as_ref().is_some_and(|peer| peer.id == leg.id) {
+                self.update_leg(&tx, &payload, leg)?;
+            } else {
+                self.insert_leg(&tx, &payload, leg)?;
+            }
+            reconcile::release_moved(&tx, &leg.id)?;
+            self.apply_balance_delta(&tx, &leg.account_id, leg.balance_delta(&payload))?;
+        }
+
+        if let Some(peer) = &peer {
+            if !legs.iter().any(|leg| leg.id == peer.id) {
+                self.delete_row(&tx, &peer.id)?;
+            }
+        }
+        self.replace_splits(&tx, &payload.id, &payload.splits)?;
+
+        tx.commit()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        self.fetch_transaction_row(&conn, &payload.id)
+    }
+
+    fn delete_transaction(&self, id: &str, override_reconciled: bool) -> TransactionResult<()> {
+        let mut conn = self.connection()?;
+        let tx = conn
+            .transaction()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        let (existing, peer) = self.ledger_records(&tx, id)?;
+        if !override_reconciled
+            && std::iter::once(&existing)
+                .chain(peer.as_ref())
+                .any(|record| record.reconciliation_id.is_some())
+        {
+            return Err(reconciled_error(id));
+        }
+        for record in std::iter::once(&existing).chain(peer.as_ref()) {
+            self.apply_balance_delta(&tx, &record.account_id, -record.balance_delta())?;
+            self.delete_row(&tx, &record.id)?;
+        }
+
+        tx.commit()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        Ok(())
+    }
+
+    fn find_import_duplicates(
+        &self,
+        items: &[CreateTransactionInput],
+    ) -> TransactionResult<Vec<SuspectedDuplicateDto>> {
+        let conn = self.connection()?;
+        let mut suspected = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            let payload = TransactionPayload::from_create(item.clone())?;
+            if let Some((reason, matches)) = self.suspected_duplicates(&conn, &payload)? {
+                suspected.push(SuspectedDuplicateDto {
+                    index,
+                    reason,
+                    fingerprint: payload.fingerprint(reason),
+                    matches,
+                });
+            }
+        }
+        Ok(suspected)
+    }
+
+    fn import_transactions(
+        &self,
+        input: ImportTransactionsInput,
+    ) -> TransactionResult<ImportTransactionsResult> {
+        let ImportTransactionsInput {
+            items,
+            categories,
+            decisions: decision_inputs,
+            batch,
+        } = input;
+        let count = items.len();
+        let mut decisions = HashMap::new();
+        for decision in decision_inputs {
+            if decision.index >= count {
+                return Err(TransactionServiceError::Validation(format!(
+                    "Decision for item {} but the import has {count} items",
+                    decision.index
+                )));
+            }
+            decisions.insert(decision.index, decision);
+        }
+
+        let payloads = items
+            .into_iter()
+            .map(TransactionPayload::from_create)
+            .collect::<TransactionResult<Vec<_>>>()?;
+
+        let mut conn = self.connection()?;
+        let tx = conn
+            .transaction()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        for category in categories {
+            self.insert_category(&tx, category)?;
+        }
+        let batch_id = batches::insert(&tx, &self.user_id, &batch, count)?;
+
+        // Checked before writing anything so items only match rows that existed before
+        // the import, not each other
+        let mut suspected = Vec::with_capacity(count);
         for payload in &payloads {
-            self.insert_transaction(&tx, payload)?;
-            self.apply_balance_delta(
-                &tx,
-                &payload.account_id,
-                payload.kind.balance_delta(payload.amount_cents),
-            )?;
+            suspected.push(self.suspected_duplicates(&tx, payload)?);
         }
 
+        let rules = rules::load_rules(&tx, &self.user_id, &[])?;
+        let mut batch_external_ids = HashSet::new();
+        let mut written = Vec::new();
+        let mut merged = Vec::new();
+        let mut skipped = Vec::new();
+        let mut flagged = Vec::new();
+        for (index, (mut payload, matches)) in payloads.into_iter().zip(suspected).enumerate() {
+            let decision = decisions.get(&index);
+            let repeated = payload.external_id.as_ref().is_some_and(|external_id| {
+                !batch_external_ids.insert((payload.account_id.clone(), external_id.clone()))
+            });
+            let action = match (&matches, decision) {
+                (Some(_), Some(decision)) => decision.action,
+                (Some(_), None) => DuplicateAction::Skip,
+                // An external id listed twice in one file is the same bank line
+                (None, _) if repeated => DuplicateAction::Skip,
+                (None, _) => DuplicateAction::Import,
+            };
+
+            match action {
+                DuplicateAction::Skip => {
+                    if let (Some((DuplicateReason::Fingerprint, rows)), None) = (matches, decision)
+                    {
+                        flagged.push(SuspectedDuplicateDto {
+                            index,
+                            reason: DuplicateReason::Fingerprint,
+                            fingerprint: payload.fingerprint(DuplicateReason::Fingerprint),
+                            matches: rows,
+                        });
+                    }
+                    skipped.push(index);
+                }
+                DuplicateAction::Import => {
+                    payload.import_batch_id = Some(batch_id.clone());
+                    self.resolve_payee(&tx, &mut payload)?;
+                    self.categorize(&rules, &mut payload);
+                    self.write_new_transaction(&tx, &payload)?;
+                    written.push(payload.id);
+                }
+                DuplicateAction::Merge => {
+                    let target_id = decision
+                        .and_then(|decision| decision.target_id.clone())
+                        .or_else(|| {
+                            let (_, rows) = matches?;
+                            rows.into_iter().next().map(|row| row.id)
+                        })
+                        .ok_or_else(|| {
+                            TransactionServiceError::Validation(format!(
+                                "Item {index} has no transaction to merge into"
+                            ))
+                        })?;
+                    self.resolve_payee(&tx, &mut payload)?;
+                    self.merge_import(&tx, &target_id, &payload)?;
+                    merged.push(target_id);
+                }
+            }
+        }
+        batches::record_counts(&tx, &batch_id, written.len(), merged.len(), skipped.len())?;
+
         tx.commit()
             .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
 
-        let mut created = Vec::with_capacity(payloads.len());
-        for payload in payloads {
-            created.push(self.fetch_transaction_row(&conn, &payload.id)?);
+        let mut created = Vec::with_capacity(written.len());
+        for id in written {
+            created.push(self.fetch_transaction_row(&conn, &id)?);
         }
+        let merged = merged
+            .iter()
+            .map(|id| self.fetch_transaction_row(&conn, id))
+            .collect::<TransactionResult<Vec<_>>>()?;
 
-        Ok(created)
+        Ok(ImportTransactionsResult {
+            batch_id,
+            created,
+            merged,
+            skipped,
+            flagged,
+        })
+    }
+
+    fn list_import_batches(&self) -> TransactionResult<Vec<ImportBatchDto>> {
+        let conn = self.connection()?;
+        batches::list(&conn, &self.user_id)
+    }
+
+    fn rollback_import_batch(&self, id: &str) -> TransactionResult<ImportBatchDto> {
+        let mut conn = self.connection()?;
+        let tx = conn
+            .transaction()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        let batch = batches::fetch(&tx, &self.user_id, id)?;
+        if batch.rolled_back_at.is_some() {
+            return Err(TransactionServiceError::Validation(format!(
+                "Import batch {id} was already rolled back"
+            )));
+        }
+
+        let mut removed = HashSet::new();
+        for transaction_id in batches::transaction_ids(&tx, &self.user_id, id)? {
+            if removed.contains(&transaction_id) {
+                continue;
+            }
+            let (existing, peer) = self.ledger_records(&tx, &transaction_id)?;
+            for record in std::iter::once(&existing).chain(peer.as_ref()) {
+                if record.reconciliation_id.is_some() {
+                    return Err(TransactionServiceError::Reconciled(format!(
+                        "Transaction {} from this import belongs to a completed reconciliation; \
+                         delete it separately before rolling back the batch",
+                        record.id
+                    )));
+                }
+            }
+            for record in std::iter::once(existing).chain(peer) {
+                self.apply_balance_delta(&tx, &record.account_id, -record.balance_delta())?;
+                self.delete_row(&tx, &record.id)?;
+                removed.insert(record.id);
+            }
+        }
+        batches::mark_rolled_back(&tx, id)?;
+        let batch = batches::fetch(&tx, &self.user_id, id)?;
+
+        tx.commit()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+
+        Ok(batch)
+    }
+
+    fn list_payees(&self) -> TransactionResult<Vec<PayeeDto>> {
+        let conn = self.connection()?;
+        rules::list_payees(&conn, &self.user_id)
+    }
+
+    fn create_payee(&self, input: CreatePayeeInput) -> TransactionResult<PayeeDto> {
+        let conn = self.connection()?;
+        rules::create_payee(&conn, &self.user_id, input)
+    }
+
+    fn update_payee(&self, input: UpdatePayeeInput) -> TransactionResult<PayeeDto> {
+        let conn = self.connection()?;
+        rules::update_payee(&conn, &self.user_id, input)
+    }
+
+    fn delete_payee(&self, id: &str) -> TransactionResult<()> {
+        let conn = self.connection()?;
+        rules::delete_payee(&conn, &self.user_id, id)
+    }
+
+    fn list_rules(&self) -> TransactionResult<Vec<TransactionRuleDto>> {
+        let conn = self.connection()?;
+        rules::list_rules(&conn, &self.user_id)
+    }
+
+    fn create_rule(&self, input: TransactionRuleInput) -> TransactionResult<TransactionRuleDto> {
+        let conn = self.connection()?;
+        rules::create_rule(&conn, &self.user_id, input)
+    }
+
+    fn update_rule(
+        &self,
+        input: UpdateTransactionRuleInput,
+    ) -> TransactionResult<TransactionRuleDto> {
+        let conn = self.connection()?;
+        rules::update_rule(&conn, &self.user_id, &input.id, input.rule)
+    }
+
+    fn delete_rule(&self, id: &str) -> TransactionResult<()> {
+        let conn = self.connection()?;
+        rules::delete_rule(&conn, &self.user_id, id)
+    }
+
+    fn apply_rules(&self, input: ApplyRulesInput) -> TransactionResult<ApplyRulesResult> {
+        let mut conn = self.connection()?;
+        rules::apply_retroactively(&mut conn, &self.user_id, input)
+    }
+
+    fn list_reconciliations(
+        &self,
+        account_id: Option<&str>,
+    ) -> TransactionResult<Vec<ReconciliationDto>> {
+        let conn = self.connection()?;
+        reconcile::list(&conn, &self.user_id, account_id)
+    }
+
+    fn get_reconciliation(&self, id: &str) -> TransactionResult<ReconciliationDetailDto> {
+        let conn = self.connection()?;
+        self.reconciliation_detail(&conn, id)
+    }
+
+    fn start_reconciliation(
+        &self,
+        input: StartReconciliationInput,
+    ) -> TransactionResult<ReconciliationDetailDto> {
+        let conn = self.connection()?;
+        let id = reconcile::start(&conn, &self.user_id, input)?;
+        self.reconciliation_detail(&conn, &id)
+    }
+
+    fn update_reconciliation(
+        &self,
+        input: UpdateReconciliationInput,
+    ) -> TransactionResult<ReconciliationDetailDto> {
+        let conn = self.connection()?;
+        let id = input.id.clone();
+        reconcile::update(&conn, &self.user_id, input)?;
+        self.reconciliation_detail(&conn, &id)
+    }
+
+    fn mark_reconciliation_transactions(
+        &self,
+        input: MarkReconciliationInput,
+    ) -> TransactionResult<ReconciliationDetailDto> {
+        let mut conn = self.connection()?;
+        let tx = conn
+            .transaction()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        reconcile::mark(&tx, &self.user_id, &input)?;
+        tx.commit()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        self.reconciliation_detail(&conn, &input.id)
+    }
+
+    fn finish_reconciliation(&self, id: &str) -> TransactionResult<ReconciliationDto> {
+        let mut conn = self.connection()?;
+        let tx = conn
+            .transaction()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        reconcile::finish(&tx, &self.user_id, id)?;
+        tx.commit()
+            .map_err(|err| TransactionServiceError::Database(err.to_string()))?;
+        reconcile::fetch(&conn, &self.user_id, id)
+    }
+
+    fn cancel_reconciliation(&self, id: &str) -> TransactionResult<()> {
+        let conn = self.connection()?;
+        reconcile::cancel(&conn, &self.user_id, id)
     }
 }
 
@@ -734,9 +2290,32 @@ struct TransactionPayload {
     tags: Option<Vec<String>>,
     cleared: bool,
     goal_id: Option<String>,
+    transfer_account_id: Option<String>,
+    splits: Vec<TransactionSplitInput>,
+    recurrence_id: Option<String>,
+    exchange_rate: Option<f64>,
+    payee_name: Option<String>,
+    payee_id: Option<String>,
+    external_id: Option<String>,
+    import_batch_id: Option<String>,
 }
 
 impl TransactionPayload {
+    fn fingerprint(&self, reason: DuplicateReason) -> String {
+        let external_id = self
+            .external_id
+            .as_deref()
+            .filter(|_| reason == DuplicateReason::ExternalId);
+        duplicates::fingerprint(
+            &self.account_id,
+            &self.kind,
+            self.amount_cents,
+            &self.occurred_on,
+            self.notes.as_deref(),
+            external_id,
+        )
+    }
+
     fn validate_amount(amount: i64) -> TransactionResult<i64> {
         if amount <= 0 {
             return Err(TransactionServiceError::Validation(
@@ -746,44 +2325,309 @@ impl TransactionPayload {
         Ok(amount)
     }
 
+    fn validate_transfer(
+        kind: &TransactionKind,
+        account_id: &str,
+        transfer_account_id: Option<String>,
+    ) -> TransactionResult<Option<String>> {
+        if *kind != TransactionKind::Transfer {
+            return Ok(None);
+        }
+
+        match transfer_account_id {
+            Some(destination) if destination == account_id => {
+                Err(TransactionServiceError::Validation(
+                    "Transfer destination must differ from the source account".into(),
+                ))
+            }
+            Some(destination) => Ok(Some(destination)),
+            None => Err(TransactionServiceError::Validation(
+                "Transfers require a destination account".into(),
+            )),
+        }
+    }
+
+    fn validate_exchange_rate(
+        kind: &TransactionKind,
+        exchange_rate: Option<f64>,
+    ) -> TransactionResult<Option<f64>> {
+        if *kind != TransactionKind::Transfer {
+            return Ok(None);
+        }
+        match exchange_rate {
+            Some(rate) if !rate.is_finite() || rate <= 0.0 => {
+                Err(TransactionServiceError::Validation(
+                    "Exchange rate must be greater than zero".into(),
+                ))
+            }
+            rate => Ok(rate),
+        }
+    }
+
+    fn validate_splits(
+        kind: &TransactionKind,
+        amount_cents: i64,
+        splits: Vec<TransactionSplitInput>,
+    ) -> TransactionResult<Vec<TransactionSplitInput>> {
+        if splits.is_empty() {
+            return Ok(splits);
+        }
+        if *kind == TransactionKind::Transfer {
+            return Err(TransactionServiceError::Validation(
+                "Transfers cannot be split across categories".into(),
+            ));
+        }
+        if splits.len() < 2 {
+            return Err(TransactionServiceError::Validation(
+                "A split transaction needs at least two lines".into(),
+            ));
+        }
+        if splits.iter().any(|split| split.amount_cents <= 0) {
+            return Err(TransactionServiceError::Validation(
+                "Split amounts must be greater than zero".into(),
+            ));
+        }
+
+        let total: i64 = splits.iter().map(|split| split.amount_cents).sum();
+        if total != amount_cents {
+            return Err(TransactionServiceError::Validation(format!(
+                "Split lines add up to {total} cents but the transaction is {amount_cents} cents"
+            )));
+        }
+
+        Ok(splits)
+    }
+
     fn from_create(input: CreateTransactionInput) -> TransactionResult<Self> {
-        let id = input.id.unwrap_or_else(|| format!("tx_{}", Uuid::new_v4()));
+        let id = input.id.unwrap_or_else(new_transaction_id);
+        let transfer_account_id =
+            Self::validate_transfer(&input.kind, &input.account_id, input.transfer_account_id)?;
+        let amount_cents = Self::validate_amount(input.amount_cents)?;
+        let splits = Self::validate_splits(&input.kind, amount_cents, input.splits)?;
+        let exchange_rate = Self::validate_exchange_rate(&input.kind, input.exchange_rate)?;
         Ok(Self {
             id,
             account_id: input.account_id,
             category_id: input.category_id,
             kind: input.kind,
-            amount_cents: Self::validate_amount(input.amount_cents)?,
+            amount_cents,
             currency: input.currency.to_uppercase(),
             occurred_on: SqliteTransactionService::normalize_datetime(&input.occurred_on)?,
             notes: input.notes,
             tags: input.tags,
             cleared: input.cleared,
             goal_id: input.goal_id,
+            transfer_account_id,
+            splits,
+            recurrence_id: input.recurrence_id,
+            exchange_rate,
+            payee_name: input.payee,
+            payee_id: None,
+            external_id: input
+                .external_id
+                .map(|id| id.trim().to_string())
+                .filter(|id| !id.is_empty()),
+            import_batch_id: None,
         })
     }
 
     fn from_update(input: UpdateTransactionInput) -> TransactionResult<Self> {
+        let transfer_account_id =
+            Self::validate_transfer(&input.kind, &input.account_id, input.transfer_account_id)?;
+        let amount_cents = Self::validate_amount(input.amount_cents)?;
+        let splits = Self::validate_splits(&input.kind, amount_cents, input.splits)?;
+        let exchange_rate = Self::validate_exchange_rate(&input.kind, input.exchange_rate)?;
         Ok(Self {
             id: input.id,
             account_id: input.account_id,
             category_id: input.category_id,
             kind: input.kind,
-            amount_cents: Self::validate_amount(input.amount_cents)?,
+            amount_cents,
             currency: input.currency.to_uppercase(),
             occurred_on: SqliteTransactionService::normalize_datetime(&input.occurred_on)?,
             notes: input.notes,
             tags: input.tags,
             cleared: input.cleared,
             goal_id: input.goal_id,
+            transfer_account_id,
+            splits,
+            recurrence_id: None,
+            exchange_rate,
+            payee_name: input.payee,
+            payee_id: None,
+            external_id: None,
+            import_batch_id: None,
         })
     }
+
+    /// Expands the payload into the ledger rows it is stored as: a single row for
+    /// income/expense, or two linked legs for a transfer. `direction` and `peer_id`
+    /// let an update keep the edited row's side and reuse the existing counterpart, and
+    /// `quote` carries the counterpart's amount for cross-currency transfers.
+    fn legs(
+        &self,
+        direction: Option<TransferDirection>,
+        peer_id: Option<String>,
+        quote: Option<TransferQuote>,
+    ) -> Vec<LedgerLeg> {
+        let (Some(destination), Some(quote)) = (&self.transfer_account_id, quote) else {
+            return vec![LedgerLeg {
+                id: self.id.clone(),
+                account_id: self.account_id.clone(),
+                amount_cents: self.amount_cents,
+                currency: self.currency.clone(),
+                exchange_rate: None,
+                direction: None,
+                linked_transaction_id: None,
+            }];
+        };
+
+        let direction = direction.unwrap_or(TransferDirection::Out);
+        let peer_id = peer_id.unwrap_or_else(new_transaction_id);
+        vec![
+            LedgerLeg {
+                id: self.id.clone(),
+                account_id: self.account_id.clone(),
+                amount_cents: self.amount_cents,
+                currency: self.currency.clone(),
+                exchange_rate: quote.exchange_rate,
+                direction: Some(direction),
+                linked_transaction_id: Some(peer_id.clone()),
+            },
+            LedgerLeg {
+                id: peer_id,
+                account_id: destination.clone(),
+                amount_cents: quote.amount_cents,
+                currency: quote.currency,
+                exchange_rate: quote.exchange_rate.map(|rate| 1.0 / rate),
+                direction: Some(direction.opposite()),
+                linked_transaction_id: Some(self.id.clone()),
+            },
+        ]
+    }
+}
+
+/// Counterpart side of a transfer, in the destination account's currency.
+struct TransferQuote {
+    amount_cents: i64,
+    currency: String,
+    exchange_rate: Option<f64>,
+}
+
+struct LedgerLeg {
+    id: String,
+    account_id: String,
+    amount_cents: i64,
+    currency: String,
+    exchange_rate: Option<f64>,
+    direction: Option<TransferDirection>,
+    linked_transaction_id: Option<String>,
+}
+
+impl LedgerLeg {
+    fn balance_delta(&self, payload: &TransactionPayload) -> i64 {
+        match self.direction {
+            Some(direction) => direction.balance_delta(self.amount_cents),
+            None => payload.kind.balance_delta(self.amount_cents),
+        }
+    }
 }
 
 struct TransactionLedgerRecord {
+    id: String,
     account_id: String,
     kind: TransactionKind,
     amount_cents: i64,
+    transfer_direction: Option<TransferDirection>,
+    linked_transaction_id: Option<String>,
+    occurred_on: String,
+    reconciliation_id: Option<String>,
+    currency: String,
+    exchange_rate: Option<f64>,
+}
+
+impl TransactionLedgerRecord {
+    fn balance_delta(&self) -> i64 {
+        match (&self.kind, self.transfer_direction) {
+            (TransactionKind::Transfer, Some(direction)) => {
+                direction.balance_delta(self.amount_cents)
+            }
+            (kind, _) => kind.balance_delta(self.amount_cents),
+        }
+    }
+
+    /// Whether writing `leg` (or removing the row when `None`) would change what a
+    /// completed reconciliation of this row agreed with the statement.
+    fn breaks_reconciliation(&self, payload: &TransactionPayload, leg: Option<&LedgerLeg>) -> bool {
+        let Some(leg) = leg else {
+            return self.reconciliation_id.is_some();
+        };
+        let same_day = |value: &str| value.get(..10) == self.occurred_on.get(..10);
+        self.reconciliation_id.is_some()
+            && (leg.account_id != self.account_id
+                || leg.amount_cents != self.amount_cents
+                || leg.direction != self.transfer_direction
+                || payload.kind != self.kind
+                || !payload.cleared
+                || !same_day(&payload.occurred_on))
+    }
+}
+
+fn reconciled_error(id: &str) -> TransactionServiceError {
+    TransactionServiceError::Reconciled(format!(
+        "Transaction {id} belongs to a completed reconciliation; set overrideReconciled to change its amount, account, date or cleared state"
+    ))
+}
+
+fn new_transaction_id() -> String {
+    format!("tx_{}", Uuid::new_v4())
+}
+
+fn parse_kind(value: &str) -> TransactionKind {
+    match value {
+        "income" => TransactionKind::Income,
+        "expense" => TransactionKind::Expense,
+        _ => TransactionKind::Transfer,
+    }
+}
+
+/// Tables other services create when they start may not exist yet.
+fn table_exists(conn: &Connection, table: &str) -> TransactionResult<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type='table' AND name = ?)",
+        [table],
+        |row| row.get(0),
+    )
+    .map_err(|err| TransactionServiceError::Database(err.to_string()))
+}
+
+fn ensure_column(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> TransactionResult<()> {
+    let has_column: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM pragma_table_info(?) WHERE name = ?)",
+            params![table, column],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+
+    if !has_column {
+        conn.execute(
+            &format!(r#"ALTER TABLE "{table}" ADD COLUMN "{column}" {definition}"#),
+            [],
+        )
+        .map_err(|err| {
+            TransactionServiceError::Database(format!(
+                "Failed to add {table}.{column} column: {err}"
+            ))
+        })?;
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -791,6 +2635,7 @@ mod tests {
     use chrono::Utc;
 
     use super::*;
+    use crate::services::transactions::{ImportBatchInput, ImportDecisionInput, ImportSource};
 
     fn setup_in_memory() -> SqliteTransactionService {
         let tmp = tempfile::NamedTempFile::new().unwrap();
@@ -805,7 +2650,7 @@ mod tests {
     #[test]
     fn creates_transaction_and_updates_balance() {
         let service = setup_in_memory();
-        let account_before = service.list_accounts(true).unwrap();
+        let account_before = service.list_accounts(true, false).unwrap();
         let target = account_before
             .iter()
             .find(|acct| acct.id == "acct-checking")
@@ -826,11 +2671,17 @@ mod tests {
                 tags: Some(vec!["test".into()]),
                 cleared: true,
                 goal_id: None,
+                transfer_account_id: None,
+                splits: Vec::new(),
+                recurrence_id: None,
+                exchange_rate: None,
+                payee: None,
+                external_id: None,
             })
             .unwrap();
 
         assert_eq!(created.amount_cents, 1200);
-        let updated_accounts = service.list_accounts(true).unwrap();
+        let updated_accounts = service.list_accounts(true, false).unwrap();
         let new_balance = updated_accounts
             .iter()
             .find(|acct| acct.id == "acct-checking")
@@ -840,6 +2691,891 @@ mod tests {
         assert_eq!(target - 1200, new_balance);
     }
 
+    #[test]
+    fn transfer_moves_balance_between_accounts() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let service = SqliteTransactionService::new(path, None, Some("seed-user".into())).unwrap();
+        let conn = service.connection().unwrap();
+        conn.execute(
+            r#"INSERT INTO "Account" (id, user_id, name, type, currency, balance_cents, updated_at)
+               VALUES ('acct-savings', 'seed-user', 'Savings', 'savings', 'USD', 0, CURRENT_TIMESTAMP)"#,
+            [],
+        )
+        .unwrap();
+
+        let transfer = service
+            .create_transaction(CreateTransactionInput {
+                id: None,
+                account_id: "acct-default".into(),
+                category_id: None,
+                kind: TransactionKind::Transfer,
+                amount_cents: 5000,
+                currency: "USD".into(),
+                occurred_on: Utc::now().to_rfc3339(),
+                notes: None,
+                tags: None,
+                cleared: false,
+                goal_id: None,
+                transfer_account_id: Some("acct-savings".into()),
+                splits: Vec::new(),
+                recurrence_id: None,
+                exchange_rate: None,
+                payee: None,
+                external_id: None,
+            })
+            .unwrap();
+
+        assert_eq!(transfer.transfer_direction, Some(TransferDirection::Out));
+        assert_eq!(
+            transfer.transfer_account_id.as_deref(),
+            Some("acct-savings")
+        );
+        let accounts = service.list_accounts(true, false).unwrap();
+        assert_eq!(acct_before_balance(&accounts, "acct-default"), -5000);
+        assert_eq!(acct_before_balance(&accounts, "acct-savings"), 5000);
+
+        service.delete_transaction(&transfer.id, false).unwrap();
+        let accounts = service.list_accounts(true, false).unwrap();
+        assert_eq!(acct_before_balance(&accounts, "acct-default"), 0);
+        assert_eq!(acct_before_balance(&accounts, "acct-savings"), 0);
+        assert!(service
+            .list_transactions(TransactionQuery::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn import_flags_duplicates_and_applies_decisions() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let service = SqliteTransactionService::new(path, None, Some("seed-user".into())).unwrap();
+        let item = |kind: TransactionKind, amount_cents: i64, day: &str, notes: &str, id: &str| {
+            CreateTransactionInput {
+                id: None,
+                account_id: "acct-default".into(),
+                category_id: None,
+                kind,
+                amount_cents,
+                currency: "USD".into(),
+                occurred_on: format!("2025-03-{day}T12:00:00Z"),
+                notes: Some(notes.into()),
+                tags: None,
+                cleared: true,
+                goal_id: None,
+                transfer_account_id: None,
+                splits: Vec::new(),
+                recurrence_id: None,
+                exchange_rate: None,
+                payee: Some("Bean Bar".into()),
+                external_id: Some(id.into()).filter(|id: &String| !id.is_empty()),
+            }
+        };
+        let mut manual = item(TransactionKind::Expense, 450, "02", "Coffee shop", "");
+        manual.cleared = false;
+        manual.payee = None;
+        let manual = service.create_transaction(manual).unwrap();
+
+        let coffee = item(TransactionKind::Expense, 450, "01", "COFFEE-SHOP ", "F1");
+        let later = item(TransactionKind::Expense, 450, "10", "Coffee shop", "F2");
+        let refund = item(TransactionKind::Income, 2000, "03", "Refund", "F3");
+        let items = vec![coffee.clone(), later, refund.clone()];
+
+        let suspected = service.find_import_duplicates(&items).unwrap();
+        assert_eq!(suspected.len(), 1);
+        assert_eq!(suspected[0].index, 0);
+        assert_eq!(suspected[0].reason, DuplicateReason::Fingerprint);
+        assert_eq!(
+            suspected[0].fingerprint,
+            "acct-default|expense|450|2025-03-01|coffee shop"
+        );
+        assert_eq!(suspected[0].matches[0].id, manual.id);
+
+        let first = service
+            .import_transactions(ImportTransactionsInput {
+                items,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(first.created.len(), 2);
+        assert_eq!(first.skipped, [0]);
+
+        let out_of_range = ImportDecisionInput {
+            index: 5,
+            action: DuplicateAction::Import,
+            target_id: None,
+        };
+        assert!(matches!(
+            service.import_transactions(ImportTransactionsInput {
+                items: vec![coffee.clone()],
+                decisions: vec![out_of_range],
+                ..Default::default()
+            }),
+            Err(TransactionServiceError::Validation(_))
+        ));
+
+        let second = service
+            .import_transactions(ImportTransactionsInput {
+                items: vec![coffee.clone(), refund],
+                decisions: vec![ImportDecisionInput {
+                    index: 0,
+                    action: DuplicateAction::Merge,
+                    target_id: None,
+                }],
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(second.created.is_empty());
+        assert_eq!(second.skipped, [1]);
+        let merged = &second.merged[0];
+        assert_eq!(merged.id, manual.id);
+        assert_eq!(merged.external_id.as_deref(), Some("F1"));
+        assert_eq!(merged.payee_name.as_deref(), Some("Bean Bar"));
+        assert_eq!(merged.notes.as_deref(), Some("Coffee shop"));
+        assert!(merged.cleared);
+
+        let suspected = service.find_import_duplicates(&[coffee]).unwrap();
+        assert_eq!(suspected[0].reason, DuplicateReason::ExternalId);
+        assert_eq!(suspected[0].fingerprint, "acct-default|ext|F1");
+    }
+
+    #[test]
+    fn rollback_removes_batch_transactions_and_restores_balances() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let service = SqliteTransactionService::new(path, None, Some("seed-user".into())).unwrap();
+        let conn = service.connection().unwrap();
+        conn.execute(
+            r#"INSERT INTO "Account" (id, user_id, name, type, currency, balance_cents, updated_at)
+               VALUES ('acct-savings', 'seed-user', 'Savings', 'savings', 'USD', 0, CURRENT_TIMESTAMP)"#,
+            [],
+        )
+        .unwrap();
+        let item = |kind: TransactionKind, amount_cents: i64, transfer: Option<&str>| {
+            CreateTransactionInput {
+                id: None,
+                account_id: "acct-default".into(),
+                category_id: None,
+                kind,
+                amount_cents,
+                currency: "USD".into(),
+                occurred_on: "2025-04-01T12:00:00Z".into(),
+                notes: Some(format!("Line {amount_cents}")),
+                tags: None,
+                cleared: false,
+                goal_id: None,
+                transfer_account_id: transfer.map(str::to_string),
+                splits: Vec::new(),
+                recurrence_id: None,
+                exchange_rate: None,
+                payee: None,
+                external_id: None,
+            }
+        };
+        let kept = service
+            .create_transaction(item(TransactionKind::Income, 10_000, None))
+            .unwrap();
+
+        let groceries = CreateCategoryInput {
+            id: Some("cat-import-groceries".into()),
+            name: "Groceries".into(),
+            category_type: "expense".into(),
+            parent_id: None,
+            icon: None,
+        };
+        let mut shopping = item(TransactionKind::Expense, 2_500, None);
+        shopping.category_id = groceries.id.clone();
+        assert!(service
+            .import_transactions(ImportTransactionsInput {
+                items: vec![
+                    shopping.clone(),
+                    item(TransactionKind::Transfer, 1_000, Some("acct-missing")),
+                ],
+                categories: vec![groceries.clone()],
+                ..Default::default()
+            })
+            .is_err());
+        let category_ids = |service: &SqliteTransactionService| {
+            service
+                .list_categories(true)
+                .unwrap()
+                .into_iter()
+                .map(|category| category.id)
+                .collect::<Vec<_>>()
+        };
+        assert!(!category_ids(&service).contains(&"cat-import-groceries".to_string()));
+
+        let result = service
+            .import_transactions(ImportTransactionsInput {
+                items: vec![
+                    shopping,
+                    item(TransactionKind::Transfer, 1_000, Some("acct-savings")),
+                ],
+                categories: vec![groceries],
+                batch: ImportBatchInput {
+                    source: ImportSource::Csv,
+                    file_name: Some("april.csv".into()),
+                    file_hash: Some("abc123".into()),
+                    profile_id: None,
+                },
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            result.created[0].import_batch_id.as_deref(),
+            Some(result.batch_id.as_str())
+        );
+        assert!(category_ids(&service).contains(&"cat-import-groceries".to_string()));
+        let accounts = service.list_accounts(true, false).unwrap();
+        assert_eq!(acct_before_balance(&accounts, "acct-default"), 6_500);
+        assert_eq!(acct_before_balance(&accounts, "acct-savings"), 1_000);
+
+        let batches = service.list_import_batches().unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].source, ImportSource::Csv);
+        assert_eq!(batches[0].file_name.as_deref(), Some("april.csv"));
+        assert_eq!((batches[0].item_count, batches[0].created_count), (2, 2));
+
+        let rolled_back = service.rollback_import_batch(&result.batch_id).unwrap();
+        assert!(rolled_back.rolled_back_at.is_some());
+        let accounts = service.list_accounts(true, false).unwrap();
+        assert_eq!(acct_before_balance(&accounts, "acct-default"), 10_000);
+        assert_eq!(acct_before_balance(&accounts, "acct-savings"), 0);
+        let remaining = service
+            .list_transactions(TransactionQuery::default())
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, kept.id);
+        assert!(matches!(
+            service.rollback_import_batch(&result.batch_id),
+            Err(TransactionServiceError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn account_lifecycle_respects_opening_balance_and_close_check() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let service = SqliteTransactionService::new(path, None, Some("seed-user".into())).unwrap();
+
+        let account = service
+            .create_account(CreateAccountInput {
+                id: None,
+                name: "Wallet".into(),
+                account_type: "cash".into(),
+                currency: "usd".into(),
+                institution: None,
+                color_token: Some("emerald".into()),
+                sync_external_id: None,
+                opening_balance_cents: 2500,
+            })
+            .unwrap();
+        assert_eq!(account.currency, "USD");
+        assert_eq!(account.balance_cents, 2500);
+
+        let closing = service.update_account_status(UpdateAccountStatusInput {
+            id: account.id.clone(),
+            status: AccountStatus::Closed,
+        });
+        assert!(matches!(
+            closing,
+            Err(TransactionServiceError::Validation(_))
+        ));
+
+        let updated = service
+            .update_account(UpdateAccountInput {
+                id: account.id.clone(),
+                name: "Pocket cash".into(),
+                account_type: "cash".into(),
+                currency: "USD".into(),
+                institution: Some("Self".into()),
+                color_token: None,
+                sync_external_id: None,
+                opening_balance_cents: Some(0),
+            })
+            .unwrap();
+        assert_eq!(updated.balance_cents, 0);
+
+        let closed = service
+            .update_account_status(UpdateAccountStatusInput {
+                id: account.id.clone(),
+                status: AccountStatus::Closed,
+            })
+            .unwrap();
+        assert_eq!(closed.status, AccountStatus::Closed);
+        assert!(closed.closed_at.is_some());
+        assert!(service
+            .list_accounts(false, false)
+            .unwrap()
+            .iter()
+            .all(|acct| acct.id != account.id));
+
+        service
+            .reorder_accounts(ReorderAccountsInput {
+                account_ids: vec![account.id.clone(), "acct-default".into()],
+            })
+            .unwrap();
+        let all = service.list_accounts(false, true).unwrap();
+        assert_eq!(all[0].id, account.id);
+    }
+
+    #[test]
+    fn rules_keep_their_category_through_merge() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let service = SqliteTransactionService::new(path, None, Some("seed-user".into())).unwrap();
+        let category = |name: &str| {
+            service
+                .create_category(CreateCategoryInput {
+                    id: None,
+                    name: name.into(),
+                    category_type: "expense".into(),
+                    parent_id: None,
+                    icon: None,
+                })
+                .unwrap()
+        };
+        let coffee = category("Coffee");
+        let cafes = category("Cafes");
+        let rule = service
+            .create_rule(TransactionRuleInput {
+                name: "Coffee shops".into(),
+                priority: 0,
+                enabled: true,
+                payee_contains: Some("roastery".into()),
+                notes_pattern: None,
+                min_amount_cents: None,
+                max_amount_cents: None,
+                account_id: None,
+                category_id: Some(coffee.id.clone()),
+                tags: Vec::new(),
+                goal_id: None,
+            })
+            .unwrap();
+
+        let in_use = service.delete_category(DeleteCategoryInput {
+            id: coffee.id.clone(),
+            reassign_to: None,
+        });
+        assert!(matches!(
+            in_use,
+            Err(TransactionServiceError::Validation(_))
+        ));
+
+        service
+            .merge_categories(MergeCategoriesInput {
+                source_id: coffee.id,
+                target_id: cafes.id.clone(),
+            })
+            .unwrap();
+        let rules = service.list_rules().unwrap();
+        let merged = rules.iter().find(|item| item.id == rule.id).unwrap();
+        assert_eq!(merged.category_id.as_deref(), Some(cafes.id.as_str()));
+    }
+
+    #[test]
+    fn categories_support_hierarchy_merge_and_keep_user_edits() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let service =
+            SqliteTransactionService::new(path.clone(), None, Some("seed-user".into())).unwrap();
+
+        let child = service
+            .create_category(CreateCategoryInput {
+                id: None,
+                name: "Coffee".into(),
+                category_type: "expense".into(),
+                parent_id: Some("cat-food".into()),
+                icon: Some("☕".into()),
+            })
+            .unwrap();
+        assert_eq!(child.parent_id.as_deref(), Some("cat-food"));
+
+        let cycle = service.update_category(UpdateCategoryInput {
+            id: "cat-food".into(),
+            name: "Food".into(),
+            category_type: "expense".into(),
+            parent_id: Some(child.id.clone()),
+            icon: None,
+        });
+        assert!(matches!(cycle, Err(TransactionServiceError::Validation(_))));
+
+        service
+            .update_category(UpdateCategoryInput {
+                id: "cat-food".into(),
+                name: "Groceries".into(),
+                category_type: "expense".into(),
+                parent_id: None,
+                icon: None,
+            })
+            .unwrap();
+
+        let spent = service
+            .create_transaction(CreateTransactionInput {
+                id: None,
+                account_id: "acct-default".into(),
+                category_id: Some("cat-entertainment".into()),
+                kind: TransactionKind::Expense,
+                amount_cents: 900,
+                currency: "USD".into(),
+                occurred_on: Utc::now().to_rfc3339(),
+                notes: None,
+                tags: None,
+                cleared: false,
+                goal_id: None,
+                transfer_account_id: None,
+                splits: Vec::new(),
+                recurrence_id: None,
+                exchange_rate: None,
+                payee: None,
+                external_id: None,
+            })
+            .unwrap();
+
+        let in_use = service.delete_category(DeleteCategoryInput {
+            id: "cat-entertainment".into(),
+            reassign_to: None,
+        });
+        assert!(matches!(
+            in_use,
+            Err(TransactionServiceError::Validation(_))
+        ));
+
+        service
+            .merge_categories(MergeCategoriesInput {
+                source_id: "cat-entertainment".into(),
+                target_id: child.id.clone(),
+            })
+            .unwrap();
+        let moved = service
+            .list_transactions(TransactionQuery::default())
+            .unwrap()
+            .into_iter()
+            .find(|tx| tx.id == spent.id)
+            .unwrap();
+        assert_eq!(moved.category_id.as_deref(), Some(child.id.as_str()));
+
+        // Re-running bootstrap must not restore merged seeds or overwrite renames
+        let reopened = SqliteTransactionService::new(path, None, Some("seed-user".into())).unwrap();
+        let categories = reopened.list_categories(true).unwrap();
+        assert!(categories.iter().all(|cat| cat.id != "cat-entertainment"));
+        assert_eq!(
+            categories
+                .iter()
+                .find(|cat| cat.id == "cat-food")
+                .map(|cat| cat.name.as_str()),
+            Some("Groceries")
+        );
+    }
+
+    #[test]
+    fn split_lines_must_match_amount_and_round_trip() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let service = SqliteTransactionService::new(path, None, Some("seed-user".into())).unwrap();
+
+        let line = |category: &str, amount_cents: i64| TransactionSplitInput {
+            category_id: Some(category.into()),
+            amount_cents,
+            notes: None,
+            goal_id: None,
+        };
+        let input = |splits: Vec<TransactionSplitInput>| CreateTransactionInput {
+            id: None,
+            account_id: "acct-default".into(),
+            category_id: None,
+            kind: TransactionKind::Expense,
+            amount_cents: 4500,
+            currency: "USD".into(),
+            occurred_on: Utc::now().to_rfc3339(),
+            notes: Some("Supermarket".into()),
+            tags: None,
+            cleared: false,
+            goal_id: None,
+            transfer_account_id: None,
+            splits,
+            recurrence_id: None,
+            exchange_rate: None,
+            payee: None,
+            external_id: None,
+        };
+
+        let mismatch =
+            service.create_transaction(input(vec![line("cat-food", 3000), line("cat-home", 1000)]));
+        assert!(matches!(
+            mismatch,
+            Err(TransactionServiceError::Validation(_))
+        ));
+
+        let created = service
+            .create_transaction(input(vec![
+                line("cat-food", 3000),
+                line("cat-home", 1000),
+                line("cat-kids", 500),
+            ]))
+            .unwrap();
+        assert_eq!(created.splits.len(), 3);
+        assert_eq!(created.splits[0].category_id.as_deref(), Some("cat-food"));
+
+        let listed = service
+            .list_transactions(TransactionQuery::default())
+            .unwrap();
+        assert_eq!(listed[0].splits.len(), 3);
+
+        service.delete_transaction(&created.id, false).unwrap();
+        let remaining: i64 = service
+            .connection()
+            .unwrap()
+            .query_row(r#"SELECT COUNT(*) FROM "TransactionSplit""#, [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(remaining, 0);
+    }
+
+    #[test]
+    fn search_applies_query_language_and_counts_all_pages() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let service = SqliteTransactionService::new(path, None, Some("seed-user".into())).unwrap();
+
+        let expense = |amount_cents: i64, day: &str, notes: &str, tags: &[&str]| {
+            service
+                .create_transaction(CreateTransactionInput {
+                    id: None,
+                    account_id: "acct-default".into(),
+                    category_id: Some("cat-food".into()),
+                    kind: TransactionKind::Expense,
+                    amount_cents,
+                    currency: "USD".into(),
+                    occurred_on: format!("{day}T12:00:00Z"),
+                    notes: Some(notes.into()),
+                    tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+                    cleared: false,
+                    goal_id: None,
+                    transfer_account_id: None,
+                    splits: Vec::new(),
+                    recurrence_id: None,
+                    exchange_rate: None,
+                    payee: None,
+                    external_id: None,
+                })
+                .unwrap()
+        };
+        expense(25_000, "2025-05-02", "Hotel in Lisbon", &["travel"]);
+        expense(4_000, "2025-05-03", "Airport taxi", &["travel", "work"]);
+        expense(18_000, "2025-07-01", "Hotel in Porto", &["travel"]);
+        let edited = expense(900, "2025-05-04", "Coffee", &[]);
+
+        let search = |search: &str, limit: i64| {
+            service
+                .search_transactions(TransactionQuery {
+                    search: Some(search.into()),
+                    limit: Some(limit),
+                    ..TransactionQuery::default()
+                })
+                .unwrap()
+        };
+
+        let page = search("tag:travel amount>100 before:2025-06-01", 50);
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].notes.as_deref(), Some("Hotel in Lisbon"));
+
+        let page = search("hot -porto", 50);
+        assert_eq!(page.total, 1);
+        assert_eq!(search("tag:travel -tag:work", 1).total, 2);
+        assert_eq!(search("tag:travel -tag:work", 1).items.len(), 1);
+
+        // The index follows edits
+        let mut input = UpdateTransactionInput {
+            id: edited.id.clone(),
+            account_id: edited.account_id.clone(),
+            category_id: edited.category_id.clone(),
+            kind: TransactionKind::Expense,
+            amount_cents: 900,
+            currency: "USD".into(),
+            occurred_on: edited.occurred_on.clone(),
+            notes: Some("Espresso at the airport".into()),
+            tags: None,
+            cleared: true,
+            goal_id: None,
+            transfer_account_id: None,
+            splits: Vec::new(),
+            exchange_rate: None,
+            payee: None,
+            override_reconciled: false,
+        };
+        service.update_transaction(input.clone()).unwrap();
+        assert_eq!(search("airport is:cleared", 50).total, 1);
+        input.notes = None;
+        service.update_transaction(input).unwrap();
+        assert_eq!(search("espresso", 50).total, 0);
+    }
+
+    #[test]
+    fn rules_categorize_new_and_historical_transactions_by_payee() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let service = SqliteTransactionService::new(path, None, Some("seed-user".into())).unwrap();
+
+        let expense = |payee: &str, category_id: Option<&str>| {
+            service
+                .create_transaction(CreateTransactionInput {
+                    id: None,
+                    account_id: "acct-default".into(),
+                    category_id: category_id.map(str::to_string),
+                    kind: TransactionKind::Expense,
+                    amount_cents: 4_200,
+                    currency: "USD".into(),
+                    occurred_on: "2025-05-02T12:00:00Z".into(),
+                    notes: None,
+                    tags: None,
+                    cleared: false,
+                    goal_id: None,
+                    transfer_account_id: None,
+                    splits: Vec::new(),
+                    recurrence_id: None,
+                    exchange_rate: None,
+                    payee: Some(payee.into()),
+                    external_id: None,
+                })
+                .unwrap()
+        };
+        let before_rule = expense("Shell Station 42", None);
+        let manual = expense("shell station 42", Some("cat-transport"));
+        assert_eq!(before_rule.payee_id, manual.payee_id);
+        assert_eq!(service.list_payees().unwrap()[0].transaction_count, 2);
+
+        let rule = service
+            .create_rule(TransactionRuleInput {
+                name: "Fuel".into(),
+                priority: 0,
+                enabled: true,
+                payee_contains: Some("shell".into()),
+                notes_pattern: None,
+                min_amount_cents: None,
+                max_amount_cents: None,
+                account_id: None,
+                category_id: Some("cat-fuel".into()),
+                tags: vec!["car".into()],
+                goal_id: None,
+            })
+            .unwrap();
+
+        // New transactions are categorized on create; an explicit category wins
+        let created = expense("SHELL Station 7", None);
+        assert_eq!(created.category_id.as_deref(), Some("cat-fuel"));
+        assert_eq!(created.tags, vec!["car"]);
+
+        let preview = service
+            .apply_rules(ApplyRulesInput {
+                dry_run: true,
+                ..ApplyRulesInput::default()
+            })
+            .unwrap();
+        assert_eq!(preview.scanned, 3);
+        assert_eq!(preview.changes.len(), 2);
+        let change = preview
+            .changes
+            .iter()
+            .find(|change| change.transaction_id == before_rule.id)
+            .unwrap();
+        assert_eq!(change.rule_ids, vec![rule.id.clone()]);
+        assert_eq!(change.after.category_id.as_deref(), Some("cat-fuel"));
+        assert_eq!(
+            service
+                .fetch_transaction_row(&service.connection().unwrap(), &before_rule.id)
+                .unwrap()
+                .category_id,
+            None
+        );
+
+        service
+            .apply_rules(ApplyRulesInput {
+                overwrite: true,
+                ..ApplyRulesInput::default()
+            })
+            .unwrap();
+        let search = service
+            .search_transactions(TransactionQuery {
+                search: Some("payee:shell tag:car".into()),
+                category_id: Some("cat-fuel".into()),
+                ..TransactionQuery::default()
+            })
+            .unwrap();
+        assert_eq!(search.total, 3);
+
+        // Renaming a payee is reflected in the search index
+        service
+            .update_payee(UpdatePayeeInput {
+                id: manual.payee_id.clone().unwrap(),
+                name: "Shell Recharge".into(),
+            })
+            .unwrap();
+        let renamed = service
+            .search_transactions(TransactionQuery {
+                search: Some("payee:recharge".into()),
+                ..TransactionQuery::default()
+            })
+            .unwrap();
+        assert_eq!(renamed.total, 2);
+    }
+
+    #[test]
+    fn reconciliation_balances_statement_and_locks_transactions() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let path = tmp.path().to_path_buf();
+        std::mem::forget(tmp);
+        let service = SqliteTransactionService::new(path, None, Some("seed-user".into())).unwrap();
+
+        let create = |kind: TransactionKind, amount_cents: i64, day: &str, cleared| {
+            service
+                .create_transaction(CreateTransactionInput {
+                    id: None,
+                    account_id: "acct-default".into(),
+                    category_id: None,
+                    kind,
+                    amount_cents,
+                    currency: "USD".into(),
+                    occurred_on: format!("{day}T09:00:00Z"),
+                    notes: None,
+                    tags: None,
+                    cleared,
+                    goal_id: None,
+                    transfer_account_id: None,
+                    splits: Vec::new(),
+                    recurrence_id: None,
+                    exchange_rate: None,
+                    payee: None,
+                    external_id: None,
+                })
+                .unwrap()
+        };
+        let salary = create(TransactionKind::Income, 100_000, "2025-05-01", true);
+        let rent = create(TransactionKind::Expense, 2_500, "2025-05-10", false);
+        create(TransactionKind::Expense, 1_000, "2025-06-02", true);
+
+        let opening = service
+            .list_accounts(false, false)
+            .unwrap()
+            .into_iter()
+            .find(|account| account.id == "acct-default")
+            .unwrap()
+            .opening_balance_cents;
+        let detail = service
+            .start_reconciliation(StartReconciliationInput {
+                account_id: "acct-default".into(),
+                statement_date: "2025-05-31".into(),
+                statement_balance_cents: opening + 97_500,
+            })
+            .unwrap();
+        let id = detail.reconciliation.id.clone();
+        assert_eq!(detail.transactions.len(), 2);
+        assert_eq!(detail.reconciliation.difference_cents, -2_500);
+        assert!(service.finish_reconciliation(&id).is_err());
+        assert!(service
+            .start_reconciliation(StartReconciliationInput {
+                account_id: "acct-default".into(),
+                statement_date: "2025-05-31".into(),
+                statement_balance_cents: 0,
+            })
+            .is_err());
+
+        let detail = service
+            .mark_reconciliation_transactions(MarkReconciliationInput {
+                id: id.clone(),
+                transaction_ids: vec![rent.id.clone()],
+                cleared: true,
+            })
+            .unwrap();
+        assert_eq!(detail.reconciliation.difference_cents, 0);
+        let finished = service.finish_reconciliation(&id).unwrap();
+        assert_eq!(finished.status, ReconciliationStatus::Completed);
+        assert_eq!(finished.transaction_count, 2);
+        assert_eq!(finished.cleared_balance_cents, opening + 97_500);
+
+        // Balance-affecting edits and deletes need an explicit override
+        let mut edit = UpdateTransactionInput {
+            id: rent.id.clone(),
+            account_id: rent.account_id.clone(),
+            category_id: Some("cat-rent".into()),
+            kind: TransactionKind::Expense,
+            amount_cents: 2_500,
+            currency: "USD".into(),
+            occurred_on: rent.occurred_on.clone(),
+            notes: Some("May rent".into()),
+            tags: None,
+            cleared: true,
+            goal_id: None,
+            transfer_account_id: None,
+            splits: Vec::new(),
+            exchange_rate: None,
+            payee: None,
+            override_reconciled: false,
+        };
+        let recategorized = service.update_transaction(edit.clone()).unwrap();
+        assert_eq!(
+            recategorized.reconciliation_id.as_deref(),
+            Some(id.as_str())
+        );
+        edit.amount_cents = 2_600;
+        assert!(matches!(
+            service.update_transaction(edit.clone()),
+            Err(TransactionServiceError::Reconciled(_))
+        ));
+        assert!(matches!(
+            service.delete_transaction(&salary.id, false),
+            Err(TransactionServiceError::Reconciled(_))
+        ));
+        edit.override_reconciled = true;
+        service.update_transaction(edit).unwrap();
+
+        let reconciled = service
+            .search_transactions(TransactionQuery {
+                search: Some("is:reconciled".into()),
+                ..TransactionQuery::default()
+            })
+            .unwrap();
+        assert_eq!(reconciled.total, 2);
+        let history = service.list_reconciliations(Some("acct-default")).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            service.get_reconciliation(&id).unwrap().transactions.len(),
+            2
+        );
+
+        // The next statement starts from this one and cannot predate it
+        assert!(service
+            .start_reconciliation(StartReconciliationInput {
+                account_id: "acct-default".into(),
+                statement_date: "2025-05-30".into(),
+                statement_balance_cents: 0,
+            })
+            .is_err());
+        let next = service
+            .start_reconciliation(StartReconciliationInput {
+                account_id: "acct-default".into(),
+                statement_date: "2025-06-30".into(),
+                statement_balance_cents: opening + 96_500,
+            })
+            .unwrap();
+        assert_eq!(next.reconciliation.starting_balance_cents, opening + 97_500);
+        assert_eq!(next.reconciliation.difference_cents, 0);
+        service
+            .cancel_reconciliation(&next.reconciliation.id)
+            .unwrap();
+        assert_eq!(service.list_reconciliations(None).unwrap().len(), 1);
+    }
+
     fn acct_before_balance(accounts: &[AccountDto], id: &str) -> i64 {
         accounts
             .iter()
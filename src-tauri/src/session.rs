@@ -0,0 +1,262 @@
+use std::{env, sync::Arc};
+
+use crate::{
+    secrets::{self, AppSecrets},
+    services::{
+        self, key_rotation, AppLockSettingsDto, ServiceRegistry, SqliteAttachmentService,
+        SqliteBudgetService, SqliteCurrencyService, SqliteDashboardService, SqliteGoalService,
+        SqliteImportService, SqliteRecurringService, SqliteReminderService, SqliteReportService,
+        SqliteSettingsService, SqliteSyncService, SqliteTransactionService,
+    },
+    state::{AppState, PathState},
+};
+
+/// Opens the encrypted database with `secrets` and installs the services on `state`.
+/// A rotation left pending by a crash is finished first; `passphrase` re-wraps the
+/// promoted key when the secrets are passphrase protected.
+pub fn open(state: &AppState, secrets: AppSecrets, passphrase: Option<&str>) -> Result<(), String> {
+    let opened = prepare(state, secrets, passphrase)?;
+    env::set_var("DATABASE_URL", &opened.database_url);
+    state.set_app_lock_settings(opened.app_lock);
+    state.open(opened.secrets, opened.services, opened.database_url);
+    Ok(())
+}
+
+/// A database opened with its key, ready to become the session.
+struct Opened {
+    secrets: AppSecrets,
+    services: ServiceRegistry,
+    database_url: String,
+    app_lock: AppLockSettingsDto,
+}
+
+/// Finishes a pending rotation, checks the key against the database and builds the
+/// services, without touching the state's session.
+fn prepare(
+    state: &AppState,
+    secrets: AppSecrets,
+    passphrase: Option<&str>,
+) -> Result<Opened, String> {
+    let paths = state.paths();
+    let secrets = finish_rotation(state, secrets, passphrase)?;
+
+    // Every service opens the database with this key; refusing to start beats silently
+    // writing finances to disk unencrypted.
+    let db_key = secrets.sqlcipher_key().to_string();
+    services::sqlcipher::prepare_database(paths.db_path(), &db_key).map_err(|err| {
+        tracing::error!(error = %err, "Failed to open the encrypted database");
+        err.to_string()
+    })?;
+
+    let database_url = paths.database_url(&db_key).map_err(|err| err.to_string())?;
+    let services = build_services(paths, &db_key)?;
+    let app_lock = services
+        .settings()
+        .get_app_lock_settings()
+        .map_err(|err| err.to_string())?;
+    Ok(Opened {
+        secrets,
+        services,
+        database_url,
+        app_lock,
+    })
+}
+
+/// Unwraps the stored key with the master passphrase and opens the database with it.
+pub fn unlock(state: &AppState, passphrase: &str) -> Result<(), String> {
+    let secrets =
+        secrets::unlock(state.paths().secrets_file(), passphrase).map_err(|err| err.to_string())?;
+    open(state, secrets, Some(passphrase))
+}
+
+/// Re-keys the database and attachments with a freshly generated key. The new key is
+/// stored as pending before anything is touched, so a crash mid-way is finished at the
+/// next start. The current session stays installed until the new key has opened the
+/// database and every service, and is kept if that fails.
+pub fn rotate_key(state: &AppState, passphrase: Option<&str>) -> Result<(), String> {
+    let current = state.secrets()?;
+    let passphrase = verified_passphrase(state, &current, passphrase)?;
+    let current_key = current.sqlcipher_key().to_string();
+    let rotating = secrets::save(
+        state.secret_store(),
+        state.paths().secrets_file(),
+        &current.with_new_pending_key(),
+        passphrase,
+    )
+    .map_err(|err| err.to_string())?;
+
+    // The app lock settings live in the database and do not change with its key
+    let rotated = state.reopen(|| {
+        let opened = prepare(state, rotating, passphrase)?;
+        env::set_var("DATABASE_URL", &opened.database_url);
+        Ok((opened.secrets, opened.services, opened.database_url))
+    });
+    if rotated.is_err()
+        && services::sqlcipher::prepare_database(state.paths().db_path(), &current_key).is_err()
+    {
+        // Re-keyed already, so only the pending key opens the database; the next start
+        // finishes with it
+        return rotated.map_err(|err| format!("{err}. Restart the app to finish the key rotation"));
+    }
+    rotated
+}
+
+/// Sets, changes or (with `new_passphrase` of `None`) removes the master passphrase that
+/// wraps the stored key. Returns whether the key is passphrase protected afterwards.
+pub fn set_passphrase(
+    state: &AppState,
+    current_passphrase: Option<&str>,
+    new_passphrase: Option<&str>,
+) -> Result<bool, String> {
+    let current = state.secrets()?;
+    verified_passphrase(state, &current, current_passphrase)?;
+    let stored = secrets::save(
+        state.secret_store(),
+        state.paths().secrets_file(),
+        &current,
+        new_passphrase.filter(|passphrase| !passphrase.is_empty()),
+    )
+    .map_err(|err| err.to_string())?;
+
+    let protected = stored.is_passphrase_protected();
+    state.replace_secrets(stored);
+    Ok(protected)
+}
+
+/// Checks `passphrase` against the stored key when one is set and returns it for
+/// re-wrapping; unprotected secrets need none.
+fn verified_passphrase<'a>(
+    state: &AppState,
+    secrets: &AppSecrets,
+    passphrase: Option<&'a str>,
+) -> Result<Option<&'a str>, String> {
+    if !secrets.is_passphrase_protected() {
+        return Ok(None);
+    }
+    let passphrase = passphrase
+        .filter(|passphrase| !passphrase.is_empty())
+        .ok_or_else(|| "Enter the current master passphrase".to_string())?;
+    secrets::unlock(state.paths().secrets_file(), passphrase).map_err(|err| err.to_string())?;
+    Ok(Some(passphrase))
+}
+
+fn finish_rotation(
+    state: &AppState,
+    secrets: AppSecrets,
+    passphrase: Option<&str>,
+) -> Result<AppSecrets, String> {
+    let Some(new_key) = secrets.pending_key() else {
+        return Ok(secrets);
+    };
+    let paths = state.paths();
+    let summary = key_rotation::rotate_key(
+        paths.db_path(),
+        paths.attachments_dir(),
+        secrets.sqlcipher_key(),
+        new_key,
+    )
+    .map_err(|err| {
+        tracing::error!(error = %err, "Failed to rotate the database key");
+        err.to_string()
+    })?;
+    tracing::info!(
+        attachments = summary.attachments_rekeyed,
+        "Rotated the database key"
+    );
+
+    let passphrase = if secrets.is_passphrase_protected() {
+        Some(passphrase.ok_or_else(|| "The master passphrase is required".to_string())?)
+    } else {
+        None
+    };
+    secrets::save(
+        state.secret_store(),
+        paths.secrets_file(),
+        &secrets.promote_pending_key(),
+        passphrase,
+    )
+    .map_err(|err| err.to_string())
+}
+
+fn build_services(paths: &PathState, db_key: &str) -> Result<ServiceRegistry, String> {
+    let db_path = paths.db_path().to_path_buf();
+    let key = Some(db_key.to_string());
+
+    let transaction_service = SqliteTransactionService::new(db_path.clone(), key.clone(), None)
+        .map_err(|err| err.to_string())?;
+
+    let recurring_service = SqliteRecurringService::new(
+        db_path.clone(),
+        key.clone(),
+        None,
+        Arc::new(transaction_service.clone()),
+    )
+    .map_err(|err| err.to_string())?;
+
+    let import_service = SqliteImportService::new(
+        db_path.clone(),
+        key.clone(),
+        None,
+        Arc::new(transaction_service.clone()),
+    )
+    .map_err(|err| err.to_string())?;
+
+    let currency_service = SqliteCurrencyService::new(db_path.clone(), key.clone(), None)
+        .map_err(|err| err.to_string())?;
+
+    // Also removes blobs only the previous key could address after a rotation
+    let attachment_service = SqliteAttachmentService::new(
+        db_path.clone(),
+        key.clone(),
+        None,
+        paths.attachments_dir().to_path_buf(),
+        db_key,
+    )
+    .map_err(|err| err.to_string())?;
+
+    let budget_service = SqliteBudgetService::new(db_path.clone(), key.clone(), None)
+        .map_err(|err| err.to_string())?;
+
+    let dashboard_service = SqliteDashboardService::new(
+        db_path.clone(),
+        key.clone(),
+        None,
+        Arc::new(budget_service.clone()),
+    )
+    .map_err(|err| err.to_string())?;
+
+    let goal_service = SqliteGoalService::new(db_path.clone(), key.clone(), None)
+        .map_err(|err| err.to_string())?;
+
+    let reminder_service = SqliteReminderService::new(db_path.clone(), key.clone(), None)
+        .map_err(|err| err.to_string())?;
+
+    let report_service = SqliteReportService::new(
+        db_path.clone(),
+        key.clone(),
+        None,
+        Arc::new(budget_service.clone()),
+    )
+    .map_err(|err| err.to_string())?;
+
+    let settings_service = SqliteSettingsService::new(db_path.clone(), key.clone(), None)
+        .map_err(|err| err.to_string())?;
+
+    let sync_service = SqliteSyncService::new(db_path, key, env::var("PF_SYNC_ENDPOINT").ok())
+        .map_err(|err| err.to_string())?;
+
+    Ok(ServiceRegistry::builder()
+        .with_transaction(transaction_service)
+        .with_dashboard(dashboard_service)
+        .with_budget(budget_service)
+        .with_goal(goal_service)
+        .with_reminder(reminder_service)
+        .with_report(report_service)
+        .with_settings(settings_service)
+        .with_sync(sync_service)
+        .with_recurring(recurring_service)
+        .with_currency(currency_service)
+        .with_attachment(attachment_service)
+        .with_import(import_service)
+        .build())
+}
@@ -1,14 +1,21 @@
 use std::{
     io,
     path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    time::{Duration, Instant, SystemTime},
 };
 
 use serde::Serialize;
 use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
 use url::Url;
 use urlencoding::encode;
 
-use crate::{secrets::AppSecrets, services::ServiceRegistry};
+use crate::{
+    secret_store::SecretStore,
+    secrets::AppSecrets,
+    services::{AppLockSettingsDto, ServiceRegistry},
+};
 
 #[derive(Debug, Clone)]
 pub struct PathState {
@@ -19,6 +26,7 @@ pub struct PathState {
     exports_dir: PathBuf,
     db_path: PathBuf,
     secrets_file: PathBuf,
+    secret_store_file: PathBuf,
 }
 
 impl PathState {
@@ -35,6 +43,7 @@ impl PathState {
         let exports_dir = storage_dir.join("exports");
         let db_path = storage_dir.join("app.db");
         let secrets_file = data_dir.join("config").join("secrets.json");
+        let secret_store_file = data_dir.join("config").join("secret-store.json");
 
         std::fs::create_dir_all(&storage_dir)?;
         std::fs::create_dir_all(&attachments_dir)?;
@@ -52,6 +61,7 @@ impl PathState {
             exports_dir,
             db_path,
             secrets_file,
+            secret_store_file,
         })
     }
 
@@ -59,6 +69,10 @@ impl PathState {
         &self.db_path
     }
 
+    pub fn attachments_dir(&self) -> &Path {
+        &self.attachments_dir
+    }
+
     pub fn logs_dir(&self) -> &Path {
         &self.logs_dir
     }
@@ -71,6 +85,11 @@ impl PathState {
         &self.secrets_file
     }
 
+    /// Encrypted file used for secrets when the platform keyring is unavailable.
+    pub fn secret_store_file(&self) -> &Path {
+        &self.secret_store_file
+    }
+
     pub fn database_url(&self, key: &str) -> tauri::Result<String> {
         let mut url = Url::from_file_path(&self.db_path).map_err(|_| {
             tauri::Error::Io(io::Error::other("failed to build SQLCipher database URL"))
@@ -78,7 +97,7 @@ impl PathState {
 
         let encoded_key = encode(key);
         let query = format!(
-            "cipher=sqlcipher&kdf_iter=256000&cipher_page_size=1024&mode=rwc&cache=shared&key={}",
+            "cipher=sqlcipher&kdf_iter=256000&cipher_page_size=4096&mode=rwc&cache=shared&key={}",
             encoded_key
         );
         url.set_query(Some(&query));
@@ -106,27 +125,60 @@ pub struct PathSummary {
     pub exports: String,
 }
 
+/// The database key and the services opened with it. Empty while the app waits for the
+/// master passphrase or re-keys the database.
+#[derive(Default)]
+struct Session {
+    secrets: Option<AppSecrets>,
+    services: ServiceRegistry,
+    database_url: Option<String>,
+}
+
+/// The app lock. Separate from the session: the database stays open while locked, only
+/// commands from the window are refused (see `app_lock::guard`).
+struct AppLock {
+    locked: bool,
+    settings: AppLockSettingsDto,
+    /// Wall clock, so time spent asleep counts as idle.
+    last_activity: SystemTime,
+    /// Wrong PINs in a row; each one doubles the wait before the next check.
+    failed_attempts: u32,
+    /// No PIN is checked before this, nor while another check runs.
+    retry_after: Instant,
+    checking_pin: bool,
+}
+
 pub struct AppState {
     paths: PathState,
-    #[allow(dead_code)]
-    secrets: AppSecrets,
-    services: ServiceRegistry,
-    #[allow(dead_code)]
-    database_url: String,
+    secret_store: Box<dyn SecretStore>,
+    /// Why the fallback store is in use although the platform keyring also holds a key.
+    secret_store_conflict: Option<String>,
+    session: RwLock<Session>,
+    app_lock: Mutex<AppLock>,
+    budget_check: Notify,
 }
 
 impl AppState {
+    /// Starts sealed; [`AppState::open`] installs the services once the key is known.
     pub fn new(
         paths: PathState,
-        secrets: AppSecrets,
-        services: ServiceRegistry,
-        database_url: String,
+        secret_store: Box<dyn SecretStore>,
+        secret_store_conflict: Option<String>,
     ) -> Self {
         Self {
             paths,
-            secrets,
-            services,
-            database_url,
+            secret_store,
+            secret_store_conflict,
+            session: RwLock::default(),
+            app_lock: Mutex::new(AppLock {
+                locked: false,
+                settings: AppLockSettingsDto::default(),
+                last_activity: SystemTime::now(),
+                failed_attempts: 0,
+                retry_after: Instant::now(),
+                checking_pin: false,
+            }),
+            budget_check: Notify::new(),
         }
     }
 
@@ -134,17 +186,161 @@ impl AppState {
         &self.paths
     }
 
-    #[allow(dead_code)]
-    pub fn secrets(&self) -> &AppSecrets {
-        &self.secrets
+    /// Where the database key is kept while no master passphrase wraps it.
+    pub fn secret_store(&self) -> &dyn SecretStore {
+        self.secret_store.as_ref()
+    }
+
+    pub fn secret_store_conflict(&self) -> Option<&str> {
+        self.secret_store_conflict.as_deref()
+    }
+
+    fn session(&self) -> RwLockReadGuard<'_, Session> {
+        self.session.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn session_mut(&self) -> RwLockWriteGuard<'_, Session> {
+        self.session.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The current services; every one of them reports "not configured" while sealed.
+    pub fn services(&self) -> ServiceRegistry {
+        self.session().services.clone()
+    }
+
+    pub fn secrets(&self) -> Result<AppSecrets, String> {
+        self.session()
+            .secrets
+            .clone()
+            .ok_or_else(|| "The database is locked".to_string())
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        self.session().secrets.is_none()
+    }
+
+    pub fn open(&self, secrets: AppSecrets, services: ServiceRegistry, database_url: String) {
+        *self.session_mut() = Session {
+            secrets: Some(secrets),
+            services,
+            database_url: Some(database_url),
+        };
     }
 
-    pub fn services(&self) -> &ServiceRegistry {
-        &self.services
+    /// Holds the session while `open` builds its replacement, so no command starts on the
+    /// current services meanwhile. The session is only replaced if `open` succeeds.
+    pub fn reopen(
+        &self,
+        open: impl FnOnce() -> Result<(AppSecrets, ServiceRegistry, String), String>,
+    ) -> Result<(), String> {
+        let mut session = self.session_mut();
+        let (secrets, services, database_url) = open()?;
+        *session = Session {
+            secrets: Some(secrets),
+            services,
+            database_url: Some(database_url),
+        };
+        Ok(())
+    }
+
+    /// Swaps in re-stored secrets for the same database key, e.g. after the master
+    /// passphrase changed.
+    pub fn replace_secrets(&self, secrets: AppSecrets) {
+        self.session_mut().secrets = Some(secrets);
+    }
+
+    /// Drops the services so nothing new opens the database; returns the secrets they used.
+    pub fn seal(&self) -> Option<AppSecrets> {
+        std::mem::take(&mut *self.session_mut()).secrets
     }
 
     #[allow(dead_code)]
-    pub fn database_url(&self) -> &str {
-        &self.database_url
+    pub fn database_url(&self) -> Option<String> {
+        self.session().database_url.clone()
+    }
+
+    fn app_lock(&self) -> MutexGuard<'_, AppLock> {
+        self.app_lock.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.app_lock().locked
+    }
+
+    /// Locks the app if a PIN is set; returns whether it was unlocked before.
+    pub fn lock(&self) -> bool {
+        let mut app_lock = self.app_lock();
+        let changed = app_lock.settings.pin_set && !app_lock.locked;
+        app_lock.locked |= changed;
+        changed
+    }
+
+    /// Claims the one PIN check allowed at a time. Refused while another check runs or
+    /// while the wait after a wrong PIN lasts, so parallel calls guess no faster.
+    pub fn start_pin_check(&self) -> Result<(), String> {
+        let mut app_lock = self.app_lock();
+        if app_lock.checking_pin {
+            return Err("Another unlock attempt is in progress".to_string());
+        }
+        let wait = app_lock
+            .retry_after
+            .saturating_duration_since(Instant::now());
+        if !wait.is_zero() {
+            return Err(format!(
+                "Too many incorrect PINs; try again in {} s",
+                wait.as_secs().max(1)
+            ));
+        }
+        app_lock.checking_pin = true;
+        Ok(())
+    }
+
+    /// Ends the check claimed by [`AppState::start_pin_check`], lifting the lock if the
+    /// PIN was right. A wrong PIN (or a failed check) waits 1 s, doubling per failure in a
+    /// row up to about four minutes.
+    pub fn finish_pin_check(&self, valid: bool) {
+        let mut app_lock = self.app_lock();
+        app_lock.checking_pin = false;
+        if valid {
+            app_lock.locked = false;
+            app_lock.failed_attempts = 0;
+            app_lock.last_activity = SystemTime::now();
+        } else {
+            app_lock.failed_attempts = app_lock.failed_attempts.saturating_add(1);
+            let backoff = Duration::from_secs(1 << (app_lock.failed_attempts - 1).min(8));
+            app_lock.retry_after = Instant::now() + backoff;
+        }
+    }
+
+    pub fn record_activity(&self) {
+        self.app_lock().last_activity = SystemTime::now();
+    }
+
+    pub fn idle_for(&self) -> Duration {
+        let last_activity = self.app_lock().last_activity;
+        SystemTime::now()
+            .duration_since(last_activity)
+            .unwrap_or_default()
+    }
+
+    pub fn app_lock_settings(&self) -> AppLockSettingsDto {
+        self.app_lock().settings.clone()
+    }
+
+    /// Asks the scheduler to evaluate budget alerts now, after a write that changes
+    /// spending. Requests made while a check is pending collapse into one.
+    pub fn request_budget_check(&self) {
+        self.budget_check.notify_one();
+    }
+
+    pub async fn budget_check_requested(&self) {
+        self.budget_check.notified().await;
+    }
+
+    /// Caches the stored app lock settings; removing the PIN also lifts the lock.
+    pub fn set_app_lock_settings(&self, settings: AppLockSettingsDto) {
+        let mut app_lock = self.app_lock();
+        app_lock.locked &= settings.pin_set;
+        app_lock.settings = settings;
     }
 }
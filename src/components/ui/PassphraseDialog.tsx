@@ -0,0 +1,116 @@
+import { useEffect, useState, type FormEvent } from 'react';
+
+import * as Dialog from '@radix-ui/react-dialog';
+
+const MIN_LENGTH = 8;
+
+interface PassphraseDialogProps {
+  open: boolean;
+  title: string;
+  description?: string;
+  /** Ask for the passphrase twice and enforce the minimum length, for new passphrases. */
+  confirm?: boolean;
+  submitLabel?: string;
+  onSubmit: (passphrase: string) => void;
+  onCancel: () => void;
+}
+
+export function PassphraseDialog({
+  open,
+  title,
+  description,
+  confirm = false,
+  submitLabel = 'Continue',
+  onSubmit,
+  onCancel
+}: PassphraseDialogProps) {
+  const [passphrase, setPassphrase] = useState('');
+  const [confirmation, setConfirmation] = useState('');
+
+  // Never keep a passphrase around between openings
+  useEffect(() => {
+    if (!open) {
+      setPassphrase('');
+      setConfirmation('');
+    }
+  }, [open]);
+
+  const tooShort = confirm && passphrase.length > 0 && passphrase.length < MIN_LENGTH;
+  const mismatch = confirm && confirmation.length > 0 && confirmation !== passphrase;
+  const canSubmit =
+    passphrase.length > 0 &&
+    (!confirm || (passphrase.length >= MIN_LENGTH && confirmation === passphrase));
+
+  const handleSubmit = (e: FormEvent) => {
+    e.preventDefault();
+    if (canSubmit) {
+      onSubmit(passphrase);
+    }
+  };
+
+  return (
+    <Dialog.Root open={open} onOpenChange={(next) => !next && onCancel()}>
+      <Dialog.Portal>
+        <Dialog.Overlay className="fixed inset-0 z-40 bg-black/50 backdrop-blur-sm" />
+        <Dialog.Content className="fixed left-1/2 top-1/2 z-50 w-full max-w-md -translate-x-1/2 -translate-y-1/2 rounded-2xl border border-slate-200 bg-white p-6 shadow-xl dark:border-slate-700 dark:bg-slate-800">
+          <Dialog.Title className="text-lg font-semibold text-slate-900 dark:text-slate-100">
+            {title}
+          </Dialog.Title>
+          {description ? (
+            <Dialog.Description className="mt-1 text-sm text-slate-500 dark:text-slate-400">
+              {description}
+            </Dialog.Description>
+          ) : null}
+
+          <form onSubmit={handleSubmit} className="mt-4 space-y-3">
+            <input
+              type="password"
+              autoFocus
+              autoComplete={confirm ? 'new-password' : 'current-password'}
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+              placeholder={confirm ? `Passphrase (at least ${MIN_LENGTH} characters)` : 'Passphrase'}
+              className="w-full rounded-lg border border-slate-300 px-3 py-2 text-sm dark:border-slate-600 dark:bg-slate-700 dark:text-white"
+            />
+            {confirm ? (
+              <input
+                type="password"
+                autoComplete="new-password"
+                value={confirmation}
+                onChange={(e) => setConfirmation(e.target.value)}
+                placeholder="Confirm passphrase"
+                className="w-full rounded-lg border border-slate-300 px-3 py-2 text-sm dark:border-slate-600 dark:bg-slate-700 dark:text-white"
+              />
+            ) : null}
+            {tooShort ? (
+              <p className="text-xs text-red-600 dark:text-red-400">
+                Use at least {MIN_LENGTH} characters.
+              </p>
+            ) : null}
+            {mismatch ? (
+              <p className="text-xs text-red-600 dark:text-red-400">The passphrases do not match.</p>
+            ) : null}
+
+            <div className="flex justify-end gap-2 pt-2">
+              <Dialog.Close asChild>
+                <button
+                  type="button"
+                  className="rounded-lg border border-slate-300 px-4 py-2 text-sm font-medium text-slate-700 hover:bg-slate-50 dark:border-slate-600 dark:text-slate-300 dark:hover:bg-slate-700"
+                >
+                  Cancel
+                </button>
+              </Dialog.Close>
+              <button
+                type="submit"
+                disabled={!canSubmit}
+                className="rounded-lg bg-primary px-4 py-2 text-sm font-medium text-white disabled:opacity-50"
+              >
+                {submitLabel}
+              </button>
+            </div>
+          </form>
+        </Dialog.Content>
+      </Dialog.Portal>
+    </Dialog.Root>
+  );
+}
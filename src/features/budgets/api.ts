@@ -2,23 +2,39 @@ import { invoke } from '@tauri-apps/api/core';
 import { z } from 'zod';
 
 import {
+  assignToEnvelopeFormSchema,
+  budgetAlertSchema,
   budgetEntrySchema,
+  budgetPeriodSummarySchema,
   budgetSchema,
   createBudgetFormSchema,
+  moveEnvelopeFundsFormSchema,
   updateBudgetFormSchema,
+  zeroBasedMonthSchema,
+  type AssignToEnvelopeForm,
   type Budget,
+  type BudgetAlert,
   type BudgetEntry,
+  type BudgetPeriodSummary,
   type CreateBudgetForm,
-  type UpdateBudgetForm
+  type MoveEnvelopeFundsForm,
+  type UpdateBudgetForm,
+  type ZeroBasedMonth
 } from './schema';
 
 const budgetListSchema = z.array(budgetSchema);
 
-export async function fetchBudgets(): Promise<Budget[]> {
-  const payload = await invoke<Budget[]>('list_budgets');
+/** `asOf` (YYYY-MM-DD) shows the budgets as they stood in the period containing it. */
+export async function fetchBudgets(asOf?: string): Promise<Budget[]> {
+  const payload = await invoke<Budget[]>('list_budgets', { asOf: asOf ?? null });
   return budgetListSchema.parse(payload);
 }
 
+export async function fetchBudgetAlerts(limit?: number): Promise<BudgetAlert[]> {
+  const payload = await invoke<BudgetAlert[]>('list_budget_alerts', { limit: limit ?? null });
+  return z.array(budgetAlertSchema).parse(payload);
+}
+
 export async function fetchBudget(id: string): Promise<Budget> {
   const payload = await invoke<Budget>('get_budget', { id });
   return budgetSchema.parse(payload);
@@ -58,3 +74,33 @@ export async function recordSnapshot(
   return budgetEntrySchema.parse(result);
 }
 
+/** The period of a budget containing `asOf` (default today), with its burn-down. */
+export async function fetchBudgetPeriodSummary(
+  id: string,
+  asOf?: string
+): Promise<BudgetPeriodSummary> {
+  const result = await invoke<BudgetPeriodSummary>('get_budget_period_summary', {
+    id,
+    asOf: asOf ?? null
+  });
+  return budgetPeriodSummarySchema.parse(result);
+}
+
+/** Zero-based budgeting for the month containing `month` (YYYY-MM-DD, default today). */
+export async function fetchZeroBasedMonth(month?: string): Promise<ZeroBasedMonth> {
+  const result = await invoke<ZeroBasedMonth>('get_zero_based_month', { month: month ?? null });
+  return zeroBasedMonthSchema.parse(result);
+}
+
+/** Moves money from the month's "to be assigned" pool into a category envelope. */
+export async function assignToEnvelope(data: AssignToEnvelopeForm): Promise<ZeroBasedMonth> {
+  const payload = assignToEnvelopeFormSchema.parse(data);
+  const result = await invoke<ZeroBasedMonth>('assign_to_envelope', { payload });
+  return zeroBasedMonthSchema.parse(result);
+}
+
+export async function moveEnvelopeFunds(data: MoveEnvelopeFundsForm): Promise<ZeroBasedMonth> {
+  const payload = moveEnvelopeFundsFormSchema.parse(data);
+  const result = await invoke<ZeroBasedMonth>('move_envelope_funds', { payload });
+  return zeroBasedMonthSchema.parse(result);
+}
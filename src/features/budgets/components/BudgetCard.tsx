@@ -35,9 +35,16 @@ export function BudgetCard({ budget, currency = 'USD', onClick }: BudgetCardProp
               {formatCurrency(budget.spentCents, currency)}
             </span>
             <span className="text-sm text-slate-500 dark:text-slate-400">
-              / {formatCurrency(budget.amountCents, currency)}
+              / {formatCurrency(budget.availableCents, currency)}
             </span>
           </div>
+          {budget.carriedOverCents !== 0 && (
+            <p className="mt-1 text-xs text-slate-500 dark:text-slate-400">
+              {formatCurrency(budget.amountCents, currency)}{' '}
+              {budget.carriedOverCents > 0 ? '+' : '−'}{' '}
+              {formatCurrency(Math.abs(budget.carriedOverCents), currency)} carried over
+            </p>
+          )}
           <div className="mt-2 flex items-center gap-2">
             <span className={`text-sm font-medium ${getBudgetStatusColor(budget.status)}`}>
               {budget.status === 'over' ? 'Over budget' : `${progress.toFixed(0)}% used`}
@@ -29,7 +29,9 @@ export function BudgetForm({ open, onOpenChange, budget, currency = 'USD' }: Bud
     amountCents: budget?.amountCents ?? 0,
     startDate: budget?.startDate ?? new Date().toISOString(),
     endDate: budget?.endDate ?? new Date().toISOString(),
+    recurring: budget?.recurring ?? false,
     rollover: budget?.rollover ?? false,
+    rolloverCapCents: budget?.rolloverCapCents ?? null,
     alertThreshold: budget?.alertThreshold ?? 0.8
   });
 
@@ -50,6 +52,9 @@ export function BudgetForm({ open, onOpenChange, budget, currency = 'USD' }: Bud
     }
   };
 
+  // Zero-based envelopes cover one month and hold money assigned from that month's income
+  const zeroBased = formData.budgetType === 'zero_based';
+
   const expenseCategories =
     categoriesQuery.data?.filter((c) => c.type === 'expense') ?? [];
 
@@ -83,6 +88,7 @@ export function BudgetForm({ open, onOpenChange, budget, currency = 'USD' }: Bud
                 </label>
                 <select
                   value={formData.period}
+                  disabled={zeroBased}
                   onChange={(e) =>
                     setFormData({ ...formData, period: e.target.value as BudgetPeriod })
                   }
@@ -101,22 +107,29 @@ export function BudgetForm({ open, onOpenChange, budget, currency = 'USD' }: Bud
                 </label>
                 <select
                   value={formData.budgetType}
-                  onChange={(e) =>
-                    setFormData({ ...formData, budgetType: e.target.value as BudgetType })
-                  }
+                  onChange={(e) => {
+                    const budgetType = e.target.value as BudgetType;
+                    setFormData(
+                      budgetType === 'zero_based'
+                        ? { ...formData, budgetType, period: 'monthly', recurring: false }
+                        : { ...formData, budgetType }
+                    );
+                  }}
                   className="mt-1 w-full rounded-lg border border-slate-300 px-3 py-2 dark:border-slate-600 dark:bg-slate-700 dark:text-slate-100"
                 >
                   <option value="envelope">Envelope</option>
                   <option value="overall">Overall</option>
+                  <option value="zero_based">Zero-based</option>
                 </select>
               </div>
             </div>
 
             <div>
               <label className="block text-sm font-medium text-slate-700 dark:text-slate-300">
-                Category (optional)
+                Category {zeroBased ? '' : '(optional)'}
               </label>
               <select
+                required={zeroBased}
                 value={formData.categoryId ?? ''}
                 onChange={(e) =>
                   setFormData({
@@ -180,7 +193,8 @@ export function BudgetForm({ open, onOpenChange, budget, currency = 'USD' }: Bud
                 </label>
                 <input
                   type="datetime-local"
-                  required
+                  required={!formData.recurring && !zeroBased}
+                  disabled={formData.recurring || zeroBased}
                   value={formData.endDate.slice(0, 16)}
                   onChange={(e) =>
                     setFormData({
@@ -193,6 +207,26 @@ export function BudgetForm({ open, onOpenChange, budget, currency = 'USD' }: Bud
               </div>
             </div>
 
+            {zeroBased ? (
+              <p className="text-xs text-slate-500 dark:text-slate-400">
+                Covers the month of the start date. The amount comes out of the income for that
+                month and cannot exceed what is left to assign.
+              </p>
+            ) : null}
+
+            <label className="flex items-center gap-2">
+              <input
+                type="checkbox"
+                checked={formData.recurring}
+                disabled={zeroBased}
+                onChange={(e) => setFormData({ ...formData, recurring: e.target.checked })}
+                className="rounded border-slate-300 dark:border-slate-600"
+              />
+              <span className="text-sm text-slate-700 dark:text-slate-300">
+                Renew every period (the end date follows the period)
+              </span>
+            </label>
+
             <div className="flex items-center gap-4">
               <label className="flex items-center gap-2">
                 <input
@@ -225,6 +259,31 @@ export function BudgetForm({ open, onOpenChange, budget, currency = 'USD' }: Bud
               </div>
             </div>
 
+            {formData.rollover ? (
+              <div>
+                <label className="block text-sm font-medium text-slate-700 dark:text-slate-300">
+                  Rollover cap ({currency}, optional)
+                </label>
+                <input
+                  type="number"
+                  step="0.01"
+                  min="0"
+                  value={formData.rolloverCapCents == null ? '' : formData.rolloverCapCents / 100}
+                  onChange={(e) =>
+                    setFormData({
+                      ...formData,
+                      rolloverCapCents: e.target.value ? parseInputAmount(e.target.value) : null
+                    })
+                  }
+                  placeholder="No cap"
+                  className="mt-1 w-full rounded-lg border border-slate-300 px-3 py-2 dark:border-slate-600 dark:bg-slate-700 dark:text-slate-100"
+                />
+                <p className="mt-1 text-xs text-slate-500 dark:text-slate-400">
+                  Limits how much is carried from the previous period, in either direction.
+                </p>
+              </div>
+            ) : null}
+
             <div className="flex justify-end gap-3 pt-4">
               <Dialog.Close asChild>
                 <button
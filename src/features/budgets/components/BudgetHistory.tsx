@@ -0,0 +1,169 @@
+import { useMemo } from 'react';
+
+import * as Dialog from '@radix-ui/react-dialog';
+import type { EChartsCoreOption } from 'echarts/core';
+
+import { Chart } from '@/features/reports/components/Chart';
+import { formatCurrency } from '@/features/transactions/utils/money';
+
+import { useBudgetPeriodSummaryQuery } from '../hooks';
+import type { Budget } from '../schema';
+
+interface BudgetHistoryProps {
+  budget?: Budget;
+  asOf?: string;
+  currency?: string;
+  onOpenChange: (open: boolean) => void;
+}
+
+/** Burn-down of one budget period from its daily snapshots. */
+export function BudgetHistory({
+  budget,
+  asOf,
+  currency = 'USD',
+  onOpenChange
+}: BudgetHistoryProps) {
+  const summaryQuery = useBudgetPeriodSummaryQuery(budget?.id, asOf);
+  const summary = summaryQuery.data;
+
+  const burnDownOption = useMemo<EChartsCoreOption>(() => {
+    if (!summary || summary.snapshots.length === 0) {
+      return {};
+    }
+
+    const available = summary.budget.availableCents;
+    const days = summary.snapshots.map((s) => s.snapshotDate.slice(0, 10));
+    const remaining = summary.snapshots.map((s) => available - s.actualCents);
+    const projected = summary.snapshots.map((s) => available - s.projectedCents);
+    // Even spending leaves this much at the end of each day
+    const ideal = days.map((_, index) =>
+      Math.round(available * (1 - (index + 1) / summary.daysTotal))
+    );
+
+    return {
+      tooltip: {
+        trigger: 'axis',
+        valueFormatter: (value: number) => formatCurrency(value, currency)
+      },
+      legend: {
+        data: ['Remaining', 'Ideal', 'Projected at period end']
+      },
+      grid: {
+        left: '3%',
+        right: '4%',
+        bottom: '3%',
+        containLabel: true
+      },
+      xAxis: {
+        type: 'category',
+        boundaryGap: false,
+        data: days
+      },
+      yAxis: {
+        type: 'value',
+        axisLabel: {
+          formatter: (value: number) => formatCurrency(value, currency)
+        }
+      },
+      series: [
+        {
+          name: 'Remaining',
+          type: 'line',
+          data: remaining,
+          lineStyle: { color: '#3b82f6' }
+        },
+        {
+          name: 'Ideal',
+          type: 'line',
+          data: ideal,
+          symbol: 'none',
+          lineStyle: { color: '#94a3b8', type: 'dashed' }
+        },
+        {
+          name: 'Projected at period end',
+          type: 'line',
+          data: projected,
+          symbol: 'none',
+          lineStyle: { color: '#f59e0b', type: 'dotted' }
+        }
+      ]
+    };
+  }, [summary, currency]);
+
+  return (
+    <Dialog.Root open={!!budget} onOpenChange={onOpenChange}>
+      <Dialog.Portal>
+        <Dialog.Overlay className="fixed inset-0 z-40 bg-black/50 backdrop-blur-sm" />
+        <Dialog.Content className="fixed left-1/2 top-1/2 z-50 w-full max-w-2xl -translate-x-1/2 -translate-y-1/2 rounded-2xl border border-slate-200 bg-white p-6 shadow-xl dark:border-slate-700 dark:bg-slate-800">
+          <Dialog.Title className="text-2xl font-semibold text-slate-900 dark:text-slate-100">
+            {budget?.name ?? 'Budget'} history
+          </Dialog.Title>
+
+          {summaryQuery.isError ? (
+            <p className="mt-4 text-sm text-red-600 dark:text-red-400">
+              Error loading the budget history. Please try again.
+            </p>
+          ) : summary ? (
+            <div className="mt-4 space-y-4">
+              <p className="text-sm text-slate-500 dark:text-slate-400">
+                {summary.budget.startDate.slice(0, 10)} to {summary.budget.endDate.slice(0, 10)} ·
+                day {summary.daysElapsed} of {summary.daysTotal}
+                {summary.complete ? ' (ended)' : ''}
+              </p>
+              <dl className="grid grid-cols-3 gap-4 text-sm">
+                <div>
+                  <dt className="text-slate-500 dark:text-slate-400">Average per day</dt>
+                  <dd className="font-semibold text-slate-900 dark:text-slate-100">
+                    {formatCurrency(summary.averageDailyCents, currency)}
+                  </dd>
+                </div>
+                <div>
+                  <dt className="text-slate-500 dark:text-slate-400">
+                    {summary.complete ? 'Spent' : 'Projected spend'}
+                  </dt>
+                  <dd className="font-semibold text-slate-900 dark:text-slate-100">
+                    {formatCurrency(summary.projectedCents, currency)}
+                  </dd>
+                </div>
+                <div>
+                  <dt className="text-slate-500 dark:text-slate-400">
+                    {summary.varianceCents < 0 ? 'Over by' : 'Left over'}
+                  </dt>
+                  <dd
+                    className={`font-semibold ${
+                      summary.varianceCents < 0
+                        ? 'text-red-600 dark:text-red-400'
+                        : 'text-emerald-600 dark:text-emerald-400'
+                    }`}
+                  >
+                    {formatCurrency(Math.abs(summary.varianceCents), currency)}
+                  </dd>
+                </div>
+              </dl>
+              {summary.snapshots.length === 0 ? (
+                <p className="text-sm text-slate-500 dark:text-slate-400">
+                  No snapshots for this period yet. They are recorded once a day.
+                </p>
+              ) : (
+                <Chart option={burnDownOption} height={280} />
+              )}
+            </div>
+          ) : (
+            <p className="mt-4 text-sm text-slate-500 dark:text-slate-400">Loading history...</p>
+          )}
+
+          <div className="flex justify-end pt-4">
+            <Dialog.Close asChild>
+              <button
+                type="button"
+                className="rounded-lg border border-slate-300 px-4 py-2 text-sm font-medium text-slate-700 hover:bg-slate-50 dark:border-slate-600 dark:text-slate-300 dark:hover:bg-slate-700"
+              >
+                Close
+              </button>
+            </Dialog.Close>
+          </div>
+        </Dialog.Content>
+      </Dialog.Portal>
+    </Dialog.Root>
+  );
+}
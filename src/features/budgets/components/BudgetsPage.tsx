@@ -1,15 +1,32 @@
 import { useState } from 'react';
 
-import { useBudgetsQuery, useDeleteBudgetMutation } from '../hooks';
+import { useUserSettingsQuery } from '@/features/settings/hooks';
+import { formatCurrency } from '@/features/transactions/utils/money';
+
+import {
+  useBudgetAlertEvents,
+  useBudgetAlertsQuery,
+  useBudgetsQuery,
+  useDeleteBudgetMutation
+} from '../hooks';
 import { BudgetCard } from './BudgetCard';
 import { BudgetForm } from './BudgetForm';
+import { BudgetHistory } from './BudgetHistory';
+import { ZeroBasedPanel } from './ZeroBasedPanel';
 import type { Budget } from '../schema';
 
 export function BudgetsPage() {
-  const budgetsQuery = useBudgetsQuery();
+  const [view, setView] = useState<'budgets' | 'zeroBased'>('budgets');
+  const [asOf, setAsOf] = useState('');
+  const budgetsQuery = useBudgetsQuery(asOf || undefined);
+  const alertsQuery = useBudgetAlertsQuery(5);
+  useBudgetAlertEvents();
+  const { data: settings } = useUserSettingsQuery();
+  const currency = settings?.defaultCurrency ?? 'USD';
   const deleteMutation = useDeleteBudgetMutation();
   const [isFormOpen, setFormOpen] = useState(false);
   const [editingBudget, setEditingBudget] = useState<Budget | undefined>();
+  const [historyBudget, setHistoryBudget] = useState<Budget | undefined>();
 
   const handleEdit = (budget: Budget) => {
     setEditingBudget(budget);
@@ -48,6 +65,7 @@ export function BudgetsPage() {
   }
 
   const budgets = budgetsQuery.data ?? [];
+  const alerts = alertsQuery.data ?? [];
 
   return (
     <div className="space-y-6">
@@ -58,15 +76,58 @@ export function BudgetsPage() {
             Track your spending and stay on budget
           </p>
         </div>
-        <button
-          onClick={() => setFormOpen(true)}
-          className="rounded-lg bg-primary px-4 py-2 text-sm font-medium text-white hover:bg-primary/90"
-        >
-          + New Budget
-        </button>
+        <div className="flex items-center gap-3">
+          <div className="flex rounded-lg border border-slate-300 p-0.5 text-sm dark:border-slate-600">
+            {(
+              [
+                ['budgets', 'Budgets'],
+                ['zeroBased', 'Zero-based']
+              ] as const
+            ).map(([value, label]) => (
+              <button
+                key={value}
+                onClick={() => setView(value)}
+                className={`rounded-md px-3 py-1 font-medium ${
+                  view === value
+                    ? 'bg-primary text-white'
+                    : 'text-slate-600 hover:bg-slate-100 dark:text-slate-300 dark:hover:bg-slate-700'
+                }`}
+              >
+                {label}
+              </button>
+            ))}
+          </div>
+          {view === 'budgets' ? (
+            <label className="flex items-center gap-2 text-sm text-slate-500 dark:text-slate-400">
+              Period of
+              <input
+                type="date"
+                value={asOf}
+                onChange={(e) => setAsOf(e.target.value)}
+                className="rounded-lg border border-slate-300 px-2 py-1 text-sm dark:border-slate-600 dark:bg-slate-700 dark:text-slate-100"
+              />
+            </label>
+          ) : null}
+          {view === 'budgets' && asOf ? (
+            <button
+              onClick={() => setAsOf('')}
+              className="text-sm font-medium text-primary hover:underline"
+            >
+              Current
+            </button>
+          ) : null}
+          <button
+            onClick={() => setFormOpen(true)}
+            className="rounded-lg bg-primary px-4 py-2 text-sm font-medium text-white hover:bg-primary/90"
+          >
+            + New Budget
+          </button>
+        </div>
       </div>
 
-      {budgets.length === 0 ? (
+      {view === 'zeroBased' ? (
+        <ZeroBasedPanel />
+      ) : budgets.length === 0 ? (
         <div className="rounded-2xl border border-dashed border-slate-300/70 p-12 text-center dark:border-slate-700/70">
           <p className="text-slate-500 dark:text-slate-400">
             No budgets yet. Create your first budget to start tracking your spending.
@@ -84,6 +145,15 @@ export function BudgetsPage() {
             <div key={budget.id} className="group relative">
               <BudgetCard budget={budget} onClick={() => handleEdit(budget)} />
               <div className="absolute right-2 top-2 flex gap-2 opacity-0 transition-opacity group-hover:opacity-100">
+                <button
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    setHistoryBudget(budget);
+                  }}
+                  className="rounded-lg bg-white/90 px-2 py-1 text-xs font-medium text-slate-700 shadow-sm hover:bg-white dark:bg-slate-800/90 dark:text-slate-300 dark:hover:bg-slate-800"
+                >
+                  History
+                </button>
                 <button
                   onClick={(e) => {
                     e.stopPropagation();
@@ -108,7 +178,34 @@ export function BudgetsPage() {
         </div>
       )}
 
+      {alerts.length > 0 ? (
+        <div className="rounded-xl border border-slate-200 bg-white p-4 dark:border-slate-700 dark:bg-slate-800">
+          <h2 className="text-sm font-semibold text-slate-900 dark:text-slate-100">Recent alerts</h2>
+          <ul className="mt-2 space-y-1 text-sm text-slate-600 dark:text-slate-300">
+            {alerts.map((alert) => (
+              <li key={alert.id} className="flex justify-between gap-4">
+                <span>
+                  {alert.kind === 'over'
+                    ? `${alert.budgetName} went over budget`
+                    : `${alert.budgetName} reached ${alert.progressPercent.toFixed(0)}%`}
+                </span>
+                <span className="text-slate-500 dark:text-slate-400">
+                  {formatCurrency(alert.spentCents, currency)} of{' '}
+                  {formatCurrency(alert.availableCents, currency)} ·{' '}
+                  {new Date(alert.createdAt).toLocaleDateString()}
+                </span>
+              </li>
+            ))}
+          </ul>
+        </div>
+      ) : null}
+
       <BudgetForm open={isFormOpen} onOpenChange={handleFormClose} budget={editingBudget} />
+      <BudgetHistory
+        budget={historyBudget}
+        asOf={asOf || undefined}
+        onOpenChange={(open) => !open && setHistoryBudget(undefined)}
+      />
     </div>
   );
 }
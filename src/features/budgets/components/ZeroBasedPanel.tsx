@@ -0,0 +1,256 @@
+import { useState } from 'react';
+
+import { useToast } from '@/components/ui/Toast';
+import { useCategoriesQuery } from '@/features/transactions/hooks';
+import { formatCurrency, parseInputAmount } from '@/features/transactions/utils/money';
+
+import {
+  useAssignToEnvelopeMutation,
+  useMoveEnvelopeFundsMutation,
+  useZeroBasedMonthQuery
+} from '../hooks';
+import { getBudgetStatusColor } from '../utils';
+
+interface ZeroBasedPanelProps {
+  currency?: string;
+}
+
+const inputClassName =
+  'rounded-lg border border-slate-300 px-3 py-2 text-sm dark:border-slate-600 dark:bg-slate-700 dark:text-slate-100';
+
+/** Zero-based budgeting: assign the month's income to envelopes until nothing is left. */
+export function ZeroBasedPanel({ currency = 'USD' }: ZeroBasedPanelProps) {
+  const { showError } = useToast();
+  // `YYYY-MM` from the month picker; empty means the current month
+  const [month, setMonth] = useState('');
+  const monthQuery = useZeroBasedMonthQuery(month ? `${month}-01` : undefined);
+  const categoriesQuery = useCategoriesQuery();
+  const assignMutation = useAssignToEnvelopeMutation();
+  const moveMutation = useMoveEnvelopeFundsMutation();
+
+  const [assignCategoryId, setAssignCategoryId] = useState('');
+  const [assignAmount, setAssignAmount] = useState('');
+  const [moveFromId, setMoveFromId] = useState('');
+  const [moveToId, setMoveToId] = useState('');
+  const [moveAmount, setMoveAmount] = useState('');
+
+  const data = monthQuery.data;
+  const envelopes = data?.envelopes ?? [];
+  const expenseCategories =
+    categoriesQuery.data?.filter((c) => c.type === 'expense') ?? [];
+
+  const handleAssign = async (e: React.FormEvent) => {
+    e.preventDefault();
+    try {
+      await assignMutation.mutateAsync({
+        categoryId: assignCategoryId,
+        month: data?.monthStart ?? null,
+        amountCents: parseInputAmount(assignAmount)
+      });
+      setAssignAmount('');
+    } catch (err) {
+      showError('Assignment failed', String(err));
+    }
+  };
+
+  const handleMove = async (e: React.FormEvent) => {
+    e.preventDefault();
+    try {
+      await moveMutation.mutateAsync({
+        fromBudgetId: moveFromId,
+        toBudgetId: moveToId,
+        amountCents: parseInputAmount(moveAmount)
+      });
+      setMoveAmount('');
+    } catch (err) {
+      showError('Move failed', String(err));
+    }
+  };
+
+  if (monthQuery.isError) {
+    return (
+      <div className="rounded-2xl border border-red-200 bg-red-50 p-6 text-sm text-red-700 dark:border-red-800 dark:bg-red-900/20 dark:text-red-300">
+        Error loading the zero-based budget. Please try again.
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-6">
+      <div className="flex flex-wrap items-end justify-between gap-4">
+        <label className="flex items-center gap-2 text-sm text-slate-500 dark:text-slate-400">
+          Month
+          <input
+            type="month"
+            value={month}
+            onChange={(e) => setMonth(e.target.value)}
+            className="rounded-lg border border-slate-300 px-2 py-1 text-sm dark:border-slate-600 dark:bg-slate-700 dark:text-slate-100"
+          />
+        </label>
+        <dl className="grid grid-cols-3 gap-6 text-sm">
+          <div>
+            <dt className="text-slate-500 dark:text-slate-400">Income</dt>
+            <dd className="text-lg font-semibold text-slate-900 dark:text-slate-100">
+              {formatCurrency(data?.incomeCents ?? 0, currency)}
+            </dd>
+          </div>
+          <div>
+            <dt className="text-slate-500 dark:text-slate-400">Assigned</dt>
+            <dd className="text-lg font-semibold text-slate-900 dark:text-slate-100">
+              {formatCurrency(data?.assignedCents ?? 0, currency)}
+            </dd>
+          </div>
+          <div>
+            <dt className="text-slate-500 dark:text-slate-400">To be assigned</dt>
+            <dd
+              className={`text-lg font-semibold ${
+                (data?.toBeAssignedCents ?? 0) < 0
+                  ? 'text-red-600 dark:text-red-400'
+                  : 'text-emerald-600 dark:text-emerald-400'
+              }`}
+            >
+              {formatCurrency(data?.toBeAssignedCents ?? 0, currency)}
+            </dd>
+          </div>
+        </dl>
+      </div>
+
+      <div className="grid grid-cols-1 gap-4 md:grid-cols-2">
+        <form
+          onSubmit={handleAssign}
+          className="space-y-3 rounded-xl border border-slate-200 bg-white p-4 dark:border-slate-700 dark:bg-slate-800"
+        >
+          <h2 className="text-sm font-semibold text-slate-900 dark:text-slate-100">Assign</h2>
+          <div className="flex gap-2">
+            <select
+              required
+              value={assignCategoryId}
+              onChange={(e) => setAssignCategoryId(e.target.value)}
+              className={`flex-1 ${inputClassName}`}
+            >
+              <option value="">Category…</option>
+              {expenseCategories.map((cat) => (
+                <option key={cat.id} value={cat.id}>
+                  {cat.name}
+                </option>
+              ))}
+            </select>
+            <input
+              type="number"
+              step="0.01"
+              required
+              value={assignAmount}
+              onChange={(e) => setAssignAmount(e.target.value)}
+              placeholder={`Amount (${currency})`}
+              className={`w-32 ${inputClassName}`}
+            />
+          </div>
+          <p className="text-xs text-slate-500 dark:text-slate-400">
+            A negative amount returns money to the pool.
+          </p>
+          <button
+            type="submit"
+            disabled={assignMutation.isPending}
+            className="rounded-lg bg-primary px-4 py-2 text-sm font-medium text-white hover:bg-primary/90 disabled:opacity-50"
+          >
+            {assignMutation.isPending ? 'Assigning...' : 'Assign'}
+          </button>
+        </form>
+
+        <form
+          onSubmit={handleMove}
+          className="space-y-3 rounded-xl border border-slate-200 bg-white p-4 dark:border-slate-700 dark:bg-slate-800"
+        >
+          <h2 className="text-sm font-semibold text-slate-900 dark:text-slate-100">
+            Move between envelopes
+          </h2>
+          <div className="flex gap-2">
+            <select
+              required
+              value={moveFromId}
+              onChange={(e) => setMoveFromId(e.target.value)}
+              className={`flex-1 ${inputClassName}`}
+            >
+              <option value="">From…</option>
+              {envelopes.map((envelope) => (
+                <option key={envelope.id} value={envelope.id}>
+                  {envelope.name}
+                </option>
+              ))}
+            </select>
+            <select
+              required
+              value={moveToId}
+              onChange={(e) => setMoveToId(e.target.value)}
+              className={`flex-1 ${inputClassName}`}
+            >
+              <option value="">To…</option>
+              {envelopes
+                .filter((envelope) => envelope.id !== moveFromId)
+                .map((envelope) => (
+                  <option key={envelope.id} value={envelope.id}>
+                    {envelope.name}
+                  </option>
+                ))}
+            </select>
+          </div>
+          <div className="flex gap-2">
+            <input
+              type="number"
+              step="0.01"
+              min="0.01"
+              required
+              value={moveAmount}
+              onChange={(e) => setMoveAmount(e.target.value)}
+              placeholder={`Amount (${currency})`}
+              className={`w-32 ${inputClassName}`}
+            />
+            <button
+              type="submit"
+              disabled={moveMutation.isPending || envelopes.length < 2}
+              className="rounded-lg bg-primary px-4 py-2 text-sm font-medium text-white hover:bg-primary/90 disabled:opacity-50"
+            >
+              {moveMutation.isPending ? 'Moving...' : 'Move'}
+            </button>
+          </div>
+        </form>
+      </div>
+
+      {envelopes.length === 0 ? (
+        <div className="rounded-2xl border border-dashed border-slate-300/70 p-12 text-center dark:border-slate-700/70">
+          <p className="text-slate-500 dark:text-slate-400">
+            Nothing assigned this month yet. Give every dollar of income a job by assigning it to a
+            category.
+          </p>
+        </div>
+      ) : (
+        <table className="w-full text-sm">
+          <thead>
+            <tr className="text-left text-slate-500 dark:text-slate-400">
+              <th className="py-2 font-medium">Envelope</th>
+              <th className="py-2 text-right font-medium">Assigned</th>
+              <th className="py-2 text-right font-medium">Spent</th>
+              <th className="py-2 text-right font-medium">Available</th>
+            </tr>
+          </thead>
+          <tbody className="divide-y divide-slate-200 dark:divide-slate-700">
+            {envelopes.map((envelope) => (
+              <tr key={envelope.id} className="text-slate-700 dark:text-slate-300">
+                <td className="py-2">{envelope.name}</td>
+                <td className="py-2 text-right">
+                  {formatCurrency(envelope.amountCents, currency)}
+                </td>
+                <td className="py-2 text-right">{formatCurrency(envelope.spentCents, currency)}</td>
+                <td
+                  className={`py-2 text-right font-medium ${getBudgetStatusColor(envelope.status)}`}
+                >
+                  {formatCurrency(envelope.availableCents - envelope.spentCents, currency)}
+                </td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      )}
+    </div>
+  );
+}
@@ -1,27 +1,58 @@
-import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { useEffect } from 'react';
+
+import { keepPreviousData, useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
 
 import { AppEvents } from '@/utils/events';
 
 import {
+  assignToEnvelope,
   createBudget,
   deleteBudget,
   fetchBudget,
+  fetchBudgetAlerts,
+  fetchBudgetPeriodSummary,
   fetchBudgets,
+  fetchZeroBasedMonth,
+  moveEnvelopeFunds,
   recordSnapshot,
   updateBudget
 } from './api';
 
 const budgetsKey = ['budgets'] as const;
 const budgetKey = (id: string) => ['budgets', id] as const;
+const budgetAlertsKey = [...budgetsKey, 'alerts'] as const;
+
+export function useBudgetsQuery(asOf?: string) {
+  return useQuery({
+    queryKey: [...budgetsKey, 'list', asOf ?? 'current'],
+    queryFn: () => fetchBudgets(asOf),
+    placeholderData: keepPreviousData,
+    staleTime: 1000 * 30
+  });
+}
 
-export function useBudgetsQuery() {
+export function useBudgetAlertsQuery(limit?: number) {
   return useQuery({
-    queryKey: budgetsKey,
-    queryFn: fetchBudgets,
+    queryKey: [...budgetAlertsKey, limit ?? 'default'],
+    queryFn: () => fetchBudgetAlerts(limit),
     staleTime: 1000 * 30
   });
 }
 
+/** Refreshes budgets when the backend raises a `budget:alert`. */
+export function useBudgetAlertEvents() {
+  const queryClient = useQueryClient();
+  useEffect(() => {
+    const unlisten = listen('budget:alert', () => {
+      void queryClient.invalidateQueries({ queryKey: budgetsKey });
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+}
+
 export function useBudgetQuery(id: string) {
   return useQuery({
     queryKey: budgetKey(id),
@@ -31,6 +62,46 @@ export function useBudgetQuery(id: string) {
   });
 }
 
+export function useBudgetPeriodSummaryQuery(id: string | undefined, asOf?: string) {
+  return useQuery({
+    queryKey: [...budgetsKey, 'summary', id, asOf ?? 'current'],
+    queryFn: () => fetchBudgetPeriodSummary(id!, asOf),
+    enabled: !!id,
+    staleTime: 1000 * 30
+  });
+}
+
+export function useZeroBasedMonthQuery(month?: string) {
+  return useQuery({
+    queryKey: [...budgetsKey, 'zero-based', month ?? 'current'],
+    queryFn: () => fetchZeroBasedMonth(month),
+    placeholderData: keepPreviousData,
+    staleTime: 1000 * 30
+  });
+}
+
+export function useAssignToEnvelopeMutation() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: assignToEnvelope,
+    onSuccess: async () => {
+      await queryClient.invalidateQueries({ queryKey: budgetsKey });
+      window.dispatchEvent(new CustomEvent(AppEvents.transactionsChanged));
+    }
+  });
+}
+
+export function useMoveEnvelopeFundsMutation() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: moveEnvelopeFunds,
+    onSuccess: async () => {
+      await queryClient.invalidateQueries({ queryKey: budgetsKey });
+      window.dispatchEvent(new CustomEvent(AppEvents.transactionsChanged));
+    }
+  });
+}
+
 export function useCreateBudgetMutation() {
   const queryClient = useQueryClient();
   return useMutation({
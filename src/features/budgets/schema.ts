@@ -1,7 +1,7 @@
 import { z } from 'zod';
 
 export const budgetPeriodSchema = z.enum(['weekly', 'monthly', 'quarterly', 'yearly']);
-export const budgetTypeSchema = z.enum(['envelope', 'overall']);
+export const budgetTypeSchema = z.enum(['envelope', 'overall', 'zero_based']);
 export const budgetStatusSchema = z.enum(['normal', 'atRisk', 'over']);
 
 export const budgetSchema = z.object({
@@ -15,8 +15,12 @@ export const budgetSchema = z.object({
   amountCents: z.number(),
   startDate: z.string(),
   endDate: z.string(),
+  recurring: z.boolean(),
   rollover: z.boolean(),
+  rolloverCapCents: z.number().nullable().optional(),
   alertThreshold: z.number(),
+  carriedOverCents: z.number(),
+  availableCents: z.number(),
   spentCents: z.number(),
   remainingCents: z.number(),
   progressPercent: z.number(),
@@ -37,7 +41,9 @@ export const createBudgetFormSchema = z.object({
   amountCents: z.number().int().nonnegative('Amount must be non-negative'),
   startDate: z.string(),
   endDate: z.string(),
+  recurring: z.boolean().optional(),
   rollover: z.boolean().optional(),
+  rolloverCapCents: z.number().int().nonnegative('Cap must be non-negative').nullable().optional(),
   alertThreshold: z.number().min(0).max(1).optional()
 });
 
@@ -60,3 +66,58 @@ export const budgetEntrySchema = z.object({
 
 export type BudgetEntry = z.infer<typeof budgetEntrySchema>;
 
+export const budgetPeriodSummarySchema = z.object({
+  budget: budgetSchema,
+  daysTotal: z.number(),
+  daysElapsed: z.number(),
+  complete: z.boolean(),
+  averageDailyCents: z.number(),
+  projectedCents: z.number(),
+  varianceCents: z.number(),
+  snapshots: z.array(budgetEntrySchema)
+});
+
+export type BudgetPeriodSummary = z.infer<typeof budgetPeriodSummarySchema>;
+
+export const budgetAlertKindSchema = z.enum(['threshold', 'over']);
+
+export const budgetAlertSchema = z.object({
+  id: z.string(),
+  budgetId: z.string(),
+  budgetName: z.string(),
+  kind: budgetAlertKindSchema,
+  periodStart: z.string(),
+  spentCents: z.number(),
+  availableCents: z.number(),
+  progressPercent: z.number(),
+  createdAt: z.string()
+});
+
+export type BudgetAlert = z.infer<typeof budgetAlertSchema>;
+
+export const zeroBasedMonthSchema = z.object({
+  monthStart: z.string(),
+  monthEnd: z.string(),
+  incomeCents: z.number(),
+  assignedCents: z.number(),
+  toBeAssignedCents: z.number(),
+  envelopes: z.array(budgetSchema)
+});
+
+export type ZeroBasedMonth = z.infer<typeof zeroBasedMonthSchema>;
+
+export const assignToEnvelopeFormSchema = z.object({
+  categoryId: z.string().min(1, 'Category is required'),
+  month: z.string().nullable().optional(),
+  amountCents: z.number().int().refine((value) => value !== 0, 'Amount must not be zero')
+});
+
+export type AssignToEnvelopeForm = z.infer<typeof assignToEnvelopeFormSchema>;
+
+export const moveEnvelopeFundsFormSchema = z.object({
+  fromBudgetId: z.string().min(1),
+  toBudgetId: z.string().min(1),
+  amountCents: z.number().int().positive('Amount must be positive')
+});
+
+export type MoveEnvelopeFundsForm = z.infer<typeof moveEnvelopeFundsFormSchema>;
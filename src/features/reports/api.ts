@@ -74,11 +74,13 @@ export async function exportReportJson(
 
 export async function exportReportEncryptedJson(
   month: string,
-  report: MonthlyReport
+  report: MonthlyReport,
+  passphrase: string
 ): Promise<ExportResult> {
   const payload = await invoke<ExportResult>('export_report_encrypted_json', {
     month,
-    report
+    report,
+    passphrase
   });
   return exportResultSchema.parse(payload);
 }
@@ -2,6 +2,7 @@ import { useState } from 'react';
 
 import type * as echarts from 'echarts/core';
 
+import { PassphraseDialog } from '@/components/ui/PassphraseDialog';
 import {
   exportChartPng,
   exportReportCsv,
@@ -28,6 +29,7 @@ export function ExportButton({
 }: ExportButtonProps) {
   const [isExporting, setIsExporting] = useState(false);
   const [showMenu, setShowMenu] = useState(false);
+  const [askPassphrase, setAskPassphrase] = useState(false);
 
   const handleExportCsv = async () => {
     if (!spendingByCategory || spendingByCategory.length === 0) {
@@ -65,15 +67,24 @@ export function ExportButton({
     }
   };
 
-  const handleExportEncryptedJson = async () => {
+  const handleExportEncryptedJson = () => {
     if (!report) {
       alert('No report data available to export');
       return;
     }
+    setShowMenu(false);
+    setAskPassphrase(true);
+  };
+
+  const exportEncryptedJson = async (passphrase: string) => {
+    setAskPassphrase(false);
+    if (!report) {
+      return;
+    }
 
     setIsExporting(true);
     try {
-      const result = await exportReportEncryptedJson(month, report);
+      const result = await exportReportEncryptedJson(month, report, passphrase);
       alert(`Encrypted report exported successfully!\nFile: ${result.fileName}\nPath: ${result.filePath}`);
     } catch (error) {
       alert(`Export failed: ${error instanceof Error ? error.message : String(error)}`);
@@ -167,6 +178,16 @@ export function ExportButton({
           </div>
         </>
       )}
+
+      <PassphraseDialog
+        open={askPassphrase}
+        title="Encrypt report"
+        description="The file can only be opened with this passphrase. It cannot be recovered if you forget it."
+        confirm
+        submitLabel="Export"
+        onSubmit={(passphrase) => void exportEncryptedJson(passphrase)}
+        onCancel={() => setAskPassphrase(false)}
+      />
     </div>
   );
 }
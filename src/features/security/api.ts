@@ -0,0 +1,75 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface EncryptionStatus {
+  /** Waiting for the master passphrase; data commands fail until unlocked. */
+  locked: boolean;
+  passphraseProtected: boolean;
+  /** The key comes from PF_APP_DB_KEY and cannot be rotated or wrapped by the app. */
+  keyFromEnvironment: boolean;
+  /** The system keyring and the encrypted file hold different keys; says which one is used. */
+  secretStoreConflict: string | null;
+}
+
+export async function getEncryptionStatus(): Promise<EncryptionStatus> {
+  return invoke<EncryptionStatus>('get_encryption_status');
+}
+
+export async function unlockWithPassphrase(passphrase: string): Promise<void> {
+  await invoke('unlock_with_passphrase', { payload: { passphrase } });
+}
+
+/** Sets, changes or (with an empty `newPassphrase`) removes the master passphrase. */
+export async function setMasterPassphrase(
+  currentPassphrase: string | undefined,
+  newPassphrase: string | undefined
+): Promise<boolean> {
+  return invoke<boolean>('set_master_passphrase', {
+    payload: { currentPassphrase, newPassphrase }
+  });
+}
+
+/** Re-keys the database and attachments; needs the passphrase when one is set. */
+export async function rotateEncryptionKey(passphrase?: string): Promise<void> {
+  await invoke('rotate_encryption_key', { payload: { passphrase } });
+}
+
+export interface AppLockStatus {
+  /** Every command but the unlock ones is refused by the backend while locked. */
+  locked: boolean;
+  pinSet: boolean;
+  /** 0 turns the idle lock off. */
+  idleMinutes: number;
+  lockOnSleep: boolean;
+}
+
+export async function getAppLockStatus(): Promise<AppLockStatus> {
+  return invoke<AppLockStatus>('get_app_lock_status');
+}
+
+export async function unlockApp(pin: string): Promise<void> {
+  await invoke('unlock_app', { payload: { pin } });
+}
+
+export async function lockApp(): Promise<void> {
+  await invoke('lock_app');
+}
+
+/** Resets the idle timer; the caller throttles it. */
+export async function reportActivity(): Promise<void> {
+  await invoke('report_activity');
+}
+
+/** Sets, changes or (with an empty `newPin`) removes the app lock PIN. */
+export async function setAppLockPin(
+  currentPin: string | undefined,
+  newPin: string | undefined
+): Promise<AppLockStatus> {
+  return invoke<AppLockStatus>('set_app_lock_pin', { input: { currentPin, newPin } });
+}
+
+export async function updateAppLockSettings(input: {
+  idleMinutes?: number;
+  lockOnSleep?: boolean;
+}): Promise<AppLockStatus> {
+  return invoke<AppLockStatus>('update_app_lock_settings', { input });
+}
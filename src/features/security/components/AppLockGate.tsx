@@ -0,0 +1,106 @@
+import { useEffect, useRef, useState, type FormEvent, type ReactNode } from 'react';
+import { listen } from '@tauri-apps/api/event';
+
+import { getAppLockStatus, reportActivity, unlockApp } from '../api';
+
+interface Props {
+  children: ReactNode;
+}
+
+const ACTIVITY_EVENTS = ['pointerdown', 'pointermove', 'keydown', 'wheel'] as const;
+const ACTIVITY_THROTTLE_MS = 30_000;
+
+/**
+ * Replaces the app with a PIN prompt while the app lock is engaged. The backend refuses
+ * every data command in that state; this only keeps the UI in step and reports activity
+ * for the idle timer.
+ */
+export function AppLockGate({ children }: Props) {
+  const [locked, setLocked] = useState<boolean | null>(null);
+  const [pin, setPin] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [isUnlocking, setIsUnlocking] = useState(false);
+  const lastReport = useRef(0);
+
+  useEffect(() => {
+    getAppLockStatus()
+      .then((status) => setLocked(status.locked))
+      .catch(() => setLocked(false));
+
+    const unlisten = listen<string>('app:locked', () => setLocked(true));
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    if (locked !== false) {
+      return;
+    }
+    const handler = () => {
+      const now = Date.now();
+      if (now - lastReport.current < ACTIVITY_THROTTLE_MS) {
+        return;
+      }
+      lastReport.current = now;
+      void reportActivity().catch(() => undefined);
+    };
+    ACTIVITY_EVENTS.forEach((name) => window.addEventListener(name, handler, { passive: true }));
+    return () => ACTIVITY_EVENTS.forEach((name) => window.removeEventListener(name, handler));
+  }, [locked]);
+
+  const handleSubmit = async (event: FormEvent) => {
+    event.preventDefault();
+    setIsUnlocking(true);
+    setError(null);
+    try {
+      await unlockApp(pin);
+      setPin('');
+      setLocked(false);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsUnlocking(false);
+    }
+  };
+
+  if (locked === null) {
+    return null;
+  }
+  if (!locked) {
+    return <>{children}</>;
+  }
+
+  return (
+    <div className="flex min-h-screen items-center justify-center bg-bg px-6 dark:bg-bg-dark">
+      <form
+        onSubmit={handleSubmit}
+        className="w-full max-w-sm space-y-4 rounded-xl border border-slate-200 bg-white p-6 shadow-sm dark:border-slate-700 dark:bg-slate-800"
+      >
+        <div>
+          <p className="text-xs uppercase tracking-wide text-slate-500">Finance OS</p>
+          <h1 className="text-xl font-semibold text-slate-900 dark:text-white">App locked</h1>
+          <p className="text-sm text-slate-500 dark:text-slate-400">
+            Enter your PIN to continue.
+          </p>
+        </div>
+        <input
+          type="password"
+          autoFocus
+          value={pin}
+          onChange={(e) => setPin(e.target.value)}
+          className="w-full rounded-lg border border-slate-300 px-3 py-2 text-sm dark:border-slate-600 dark:bg-slate-700 dark:text-white"
+          placeholder="PIN or passphrase"
+        />
+        {error ? <p className="text-sm text-red-600 dark:text-red-400">{error}</p> : null}
+        <button
+          type="submit"
+          disabled={isUnlocking || pin.length === 0}
+          className="w-full rounded-lg bg-primary px-4 py-2 text-sm font-medium text-white disabled:opacity-50"
+        >
+          {isUnlocking ? 'Unlocking…' : 'Unlock'}
+        </button>
+      </form>
+    </div>
+  );
+}
@@ -0,0 +1,107 @@
+import { useEffect, useState, type FormEvent, type ReactNode } from 'react';
+
+import { getEncryptionStatus, unlockWithPassphrase } from '../api';
+
+interface Props {
+  children: ReactNode;
+}
+
+/** Asks for the master passphrase before rendering the app while the database is locked. */
+export function UnlockGate({ children }: Props) {
+  const [locked, setLocked] = useState<boolean | null>(null);
+  const [conflict, setConflict] = useState<string | null>(null);
+  const [passphrase, setPassphrase] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [isUnlocking, setIsUnlocking] = useState(false);
+
+  useEffect(() => {
+    getEncryptionStatus()
+      .then((status) => {
+        setLocked(status.locked);
+        setConflict(status.secretStoreConflict);
+      })
+      .catch(() => setLocked(false));
+  }, []);
+
+  const handleSubmit = async (event: FormEvent) => {
+    event.preventDefault();
+    setIsUnlocking(true);
+    setError(null);
+    try {
+      await unlockWithPassphrase(passphrase);
+      setPassphrase('');
+      setLocked(false);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsUnlocking(false);
+    }
+  };
+
+  if (locked === null) {
+    return null;
+  }
+
+  const conflictBanner = conflict ? (
+    <div
+      role="alert"
+      className="flex items-start justify-between gap-4 border-b border-red-200 bg-red-50 px-4 py-3 text-sm text-red-700 dark:border-red-500/30 dark:bg-red-500/10 dark:text-red-300"
+    >
+      <p>
+        <span className="font-semibold">The database key is stored twice.</span> {conflict}
+      </p>
+      <button
+        type="button"
+        onClick={() => setConflict(null)}
+        className="shrink-0 text-xs font-medium underline"
+      >
+        Dismiss
+      </button>
+    </div>
+  ) : null;
+
+  if (!locked) {
+    return (
+      <>
+        {conflictBanner}
+        {children}
+      </>
+    );
+  }
+
+  return (
+    <div className="flex min-h-screen flex-col bg-bg dark:bg-bg-dark">
+      {conflictBanner}
+      <div className="flex flex-1 items-center justify-center px-6">
+        <form
+          onSubmit={handleSubmit}
+          className="w-full max-w-sm space-y-4 rounded-xl border border-slate-200 bg-white p-6 shadow-sm dark:border-slate-700 dark:bg-slate-800"
+        >
+          <div>
+            <p className="text-xs uppercase tracking-wide text-slate-500">Finance OS</p>
+            <h1 className="text-xl font-semibold text-slate-900 dark:text-white">Unlock your data</h1>
+            <p className="text-sm text-slate-500 dark:text-slate-400">
+              Enter the master passphrase that protects the database key.
+            </p>
+          </div>
+          <input
+            type="password"
+            autoFocus
+            value={passphrase}
+            onChange={(e) => setPassphrase(e.target.value)}
+            className="w-full rounded-lg border border-slate-300 px-3 py-2 text-sm dark:border-slate-600 dark:bg-slate-700 dark:text-white"
+            placeholder="Master passphrase"
+          />
+          {error ? <p className="text-sm text-red-600 dark:text-red-400">{error}</p> : null}
+          <button
+            type="submit"
+            disabled={isUnlocking || passphrase.length === 0}
+            className="w-full rounded-lg bg-primary px-4 py-2 text-sm font-medium text-white disabled:opacity-50"
+          >
+            {isUnlocking ? 'Unlocking…' : 'Unlock'}
+          </button>
+        </form>
+      </div>
+    </div>
+  );
+}
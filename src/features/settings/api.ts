@@ -1,7 +1,7 @@
 import { invoke } from '@tauri-apps/api/core';
 
 import { importTransactions } from '@/features/transactions/api';
-import type { TransactionForm } from '@/features/transactions/schema';
+import type { ImportTransactionsResult, TransactionForm } from '@/features/transactions/schema';
 import { transactionFormSchema } from '@/features/transactions/schema';
 
 import {
@@ -48,12 +48,21 @@ export async function readImportFile(filePath: string): Promise<ImportFileResult
   });
 }
 
+export interface DecryptedJsonResult {
+  contents: string;
+  warning: string | null;
+}
+
 /**
- * Decrypt encrypted JSON file
+ * Decrypt encrypted JSON file. Files in the legacy format need no passphrase and come
+ * back with a warning.
  */
-export async function decryptEncryptedJson(filePath: string): Promise<string> {
-  return await invoke<string>('decrypt_encrypted_json', {
-    payload: { filePath }
+export async function decryptEncryptedJson(
+  filePath: string,
+  passphrase?: string
+): Promise<DecryptedJsonResult> {
+  return await invoke<DecryptedJsonResult>('decrypt_encrypted_json', {
+    payload: { filePath, passphrase }
   });
 }
 
@@ -174,25 +183,39 @@ export function parseAndValidateJson(jsonContent: string): ImportValidationResul
 export async function importTransactionsFromFile(
   filePath: string,
   onProgress?: (progress: { processed: number; total: number; errors: number }) => void
-): Promise<{ success: number; errors: number; errorDetails: Array<{ row: number; message: string }> }> {
+): Promise<{
+  success: number;
+  skipped: number;
+  errors: number;
+  errorDetails: Array<{ row: number; message: string }>;
+  /** For the user, e.g. that a legacy "encrypted" file was not actually encrypted. */
+  warning?: string;
+}> {
   // Read file
   const fileResult = await readImportFile(filePath);
 
   // Parse and validate based on format
   let validationResult: ImportValidationResult;
+  let warning: string | undefined;
   if (fileResult.format === 'json') {
     // Check if encrypted
+    let wrapper: { encrypted?: unknown; version?: unknown } | null = null;
     try {
-      const wrapper = JSON.parse(fileResult.contents);
-      if (wrapper.encrypted) {
-        // Decrypt first
-        const decrypted = await decryptEncryptedJson(filePath);
-        validationResult = parseAndValidateJson(decrypted);
-      } else {
-        validationResult = parseAndValidateJson(fileResult.contents);
-      }
+      wrapper = JSON.parse(fileResult.contents);
     } catch {
-      // Not encrypted, parse as regular JSON
+      // Not valid JSON; validation reports the error
+    }
+    if (wrapper?.encrypted) {
+      // Decrypt first; legacy files carry no version 2 envelope and need no passphrase.
+      // Decryption errors (wrong passphrase, tampering) propagate to the caller.
+      const passphrase =
+        wrapper.version === 2
+          ? window.prompt('Passphrase for the encrypted file') ?? undefined
+          : undefined;
+      const decrypted = await decryptEncryptedJson(filePath, passphrase);
+      warning = decrypted.warning ?? undefined;
+      validationResult = parseAndValidateJson(decrypted.contents);
+    } else {
       validationResult = parseAndValidateJson(fileResult.contents);
     }
   } else {
@@ -209,12 +232,14 @@ export async function importTransactionsFromFile(
   }
 
   // Import valid transactions
+  let result: ImportTransactionsResult | null = null;
   if (validationResult.valid.length > 0) {
     try {
-      await importTransactions(validationResult.valid);
+      result = await importTransactions(validationResult.valid);
     } catch (error) {
       return {
         success: 0,
+        skipped: 0,
         errors: validationResult.errors.length + validationResult.valid.length,
         errorDetails: [
           ...validationResult.errors,
@@ -222,14 +247,17 @@ export async function importTransactionsFromFile(
             row: validationResult.errors.length + index + 1,
             message: `Import failed: ${error instanceof Error ? error.message : 'Unknown error'}`
           }))
-        ]
+        ],
+        warning
       };
     }
   }
 
   return {
-    success: validationResult.valid.length,
+    success: result?.created.length ?? 0,
+    skipped: result?.skipped.length ?? 0,
     errors: validationResult.errors.length,
-    errorDetails: validationResult.errors
+    errorDetails: validationResult.errors,
+    warning
   };
 }
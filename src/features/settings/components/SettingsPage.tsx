@@ -4,6 +4,7 @@ import { CategoriesSection } from './sections/CategoriesSection';
 import { DataSection } from './sections/DataSection';
 import { GeneralSection } from './sections/GeneralSection';
 import { PersonalizationSection } from './sections/PersonalizationSection';
+import { SecuritySection } from './sections/SecuritySection';
 import { SyncSection } from './sections/SyncSection';
 import { AccountsSection } from './sections/AccountsSection';
 import { NotificationsSection } from './sections/NotificationsSection';
@@ -15,6 +16,7 @@ type SectionId =
   | 'sync'
   | 'notifications'
   | 'data'
+  | 'security'
   | 'personalization';
 
 interface Section {
@@ -30,6 +32,7 @@ const SECTIONS: Section[] = [
   { id: 'sync', label: 'Sync' },
   { id: 'notifications', label: 'Notifications' },
   { id: 'data', label: 'Data' },
+  { id: 'security', label: 'Security' },
   { id: 'personalization', label: 'Personalization' }
 ];
 
@@ -44,6 +47,8 @@ export function SettingsPage() {
         return <CategoriesSection />;
       case 'data':
         return <DataSection />;
+      case 'security':
+        return <SecuritySection />;
       case 'personalization':
         return <PersonalizationSection />;
       case 'accounts':
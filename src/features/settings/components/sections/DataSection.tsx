@@ -19,6 +19,7 @@ export function DataSection() {
   } | null>(null);
   const [importErrors, setImportErrors] = useState<ImportError[]>([]);
   const [importSuccess, setImportSuccess] = useState<number | null>(null);
+  const [importSkipped, setImportSkipped] = useState(0);
   const queryClient = useQueryClient();
 
   const handleFileSelect = async (e: React.ChangeEvent<HTMLInputElement>) => {
@@ -29,6 +30,7 @@ export function DataSection() {
     setImportProgress(null);
     setImportErrors([]);
     setImportSuccess(null);
+    setImportSkipped(0);
 
     try {
       // Read file as text (since we can't access file path directly in browser)
@@ -86,8 +88,9 @@ export function DataSection() {
       // Import valid transactions
       if (validationResult.valid.length > 0) {
         try {
-          await importTransactions(validationResult.valid);
-          setImportSuccess(validationResult.valid.length);
+          const result = await importTransactions(validationResult.valid);
+          setImportSuccess(result?.created.length ?? 0);
+          setImportSkipped(result?.skipped.length ?? 0);
           setImportErrors(validationResult.errors);
 
           // Invalidate queries to refresh data
@@ -181,6 +184,8 @@ export function DataSection() {
             {importSuccess !== null && (
               <div className="rounded-lg border border-green-200 bg-green-50 p-3 text-xs text-green-700 dark:border-green-800 dark:bg-green-900/20 dark:text-green-300">
                 Successfully imported {importSuccess} transaction{importSuccess !== 1 ? 's' : ''}
+                {importSkipped > 0 &&
+                  `, skipped ${importSkipped} that look${importSkipped === 1 ? 's' : ''} like existing transactions`}
                 {importErrors.length > 0 &&
                   ` with ${importErrors.length} error${importErrors.length !== 1 ? 's' : ''}`}
               </div>
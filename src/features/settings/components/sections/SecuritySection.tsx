@@ -0,0 +1,270 @@
+import { useEffect, useState } from 'react';
+
+import { useToast } from '@/components/ui/Toast';
+import {
+  getAppLockStatus,
+  getEncryptionStatus,
+  lockApp,
+  rotateEncryptionKey,
+  setAppLockPin,
+  setMasterPassphrase,
+  updateAppLockSettings,
+  type AppLockStatus,
+  type EncryptionStatus
+} from '@/features/security/api';
+
+export function SecuritySection() {
+  const { showError, showSuccess } = useToast();
+  const [status, setStatus] = useState<EncryptionStatus | null>(null);
+  const [currentPassphrase, setCurrentPassphrase] = useState('');
+  const [newPassphrase, setNewPassphrase] = useState('');
+  const [isBusy, setIsBusy] = useState(false);
+
+  useEffect(() => {
+    void getEncryptionStatus().then(setStatus);
+  }, []);
+
+  const run = async (action: () => Promise<unknown>, success: string) => {
+    setIsBusy(true);
+    try {
+      await action();
+      setCurrentPassphrase('');
+      setNewPassphrase('');
+      setStatus(await getEncryptionStatus());
+      showSuccess(success);
+    } catch (err) {
+      showError('Security update failed', String(err));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const protectedKey = status?.passphraseProtected ?? false;
+  const managedExternally = status?.keyFromEnvironment ?? false;
+
+  return (
+    <div className="space-y-4">
+      <div>
+        <p className="text-xs uppercase tracking-wide text-slate-500">Security</p>
+        <h3 className="text-xl font-semibold text-slate-900 dark:text-white">Locking and encryption</h3>
+        <p className="text-sm text-slate-500 dark:text-slate-400">
+          Lock the app with a PIN, protect the database key with a master passphrase and rotate
+          the key when needed.
+        </p>
+      </div>
+
+      <AppLockCard />
+
+      {managedExternally ? (
+        <p className="rounded-xl border border-yellow-200 bg-yellow-50 p-4 text-sm text-yellow-800 dark:border-yellow-500/30 dark:bg-yellow-500/10 dark:text-yellow-300">
+          The database key is set by the PF_APP_DB_KEY environment variable and is managed outside
+          the app.
+        </p>
+      ) : null}
+
+      <div className="space-y-3 rounded-xl border border-slate-200 bg-white p-4 shadow-sm dark:border-slate-700 dark:bg-slate-800">
+        <h4 className="text-sm font-semibold text-slate-900 dark:text-white">Master passphrase</h4>
+        <p className="text-xs text-slate-500 dark:text-slate-400">
+          {protectedKey
+            ? 'The key is wrapped with your passphrase; it is asked for at every start.'
+            : 'Without a passphrase the key is kept in the system keyring, or an encrypted file when there is none.'}
+        </p>
+        {protectedKey ? (
+          <input
+            type="password"
+            value={currentPassphrase}
+            onChange={(e) => setCurrentPassphrase(e.target.value)}
+            placeholder="Current passphrase"
+            className="w-full rounded-lg border border-slate-300 px-3 py-2 text-sm dark:border-slate-600 dark:bg-slate-700 dark:text-white"
+          />
+        ) : null}
+        <input
+          type="password"
+          value={newPassphrase}
+          onChange={(e) => setNewPassphrase(e.target.value)}
+          placeholder={protectedKey ? 'New passphrase' : 'Passphrase (at least 8 characters)'}
+          className="w-full rounded-lg border border-slate-300 px-3 py-2 text-sm dark:border-slate-600 dark:bg-slate-700 dark:text-white"
+        />
+        <div className="flex flex-wrap gap-2">
+          <button
+            type="button"
+            disabled={isBusy || managedExternally || newPassphrase.length === 0}
+            onClick={() =>
+              void run(
+                () => setMasterPassphrase(currentPassphrase || undefined, newPassphrase),
+                protectedKey ? 'Passphrase changed' : 'Passphrase set'
+              )
+            }
+            className="rounded-lg bg-primary px-4 py-2 text-sm font-medium text-white disabled:opacity-50"
+          >
+            {protectedKey ? 'Change passphrase' : 'Set passphrase'}
+          </button>
+          {protectedKey ? (
+            <button
+              type="button"
+              disabled={isBusy || currentPassphrase.length === 0}
+              onClick={() =>
+                void run(
+                  () => setMasterPassphrase(currentPassphrase, undefined),
+                  'Passphrase removed'
+                )
+              }
+              className="rounded-lg border border-slate-300 px-4 py-2 text-sm font-medium text-slate-700 disabled:opacity-50 dark:border-slate-600 dark:text-slate-300"
+            >
+              Remove passphrase
+            </button>
+          ) : null}
+        </div>
+      </div>
+
+      <div className="space-y-3 rounded-xl border border-slate-200 bg-white p-4 shadow-sm dark:border-slate-700 dark:bg-slate-800">
+        <h4 className="text-sm font-semibold text-slate-900 dark:text-white">Rotate database key</h4>
+        <p className="text-xs text-slate-500 dark:text-slate-400">
+          Re-encrypts the database and attachments with a new random key. Backups made before
+          the rotation only open with the old key, so create a new backup afterwards.{protectedKey ? ' Enter your current passphrase above first.' : ''}
+        </p>
+        <button
+          type="button"
+          disabled={isBusy || managedExternally || (protectedKey && currentPassphrase.length === 0)}
+          onClick={() =>
+            void run(() => rotateEncryptionKey(currentPassphrase || undefined), 'Database key rotated')
+          }
+          className="rounded-lg border border-slate-300 px-4 py-2 text-sm font-medium text-slate-700 disabled:opacity-50 dark:border-slate-600 dark:text-slate-300"
+        >
+          {isBusy ? 'Working…' : 'Rotate key'}
+        </button>
+      </div>
+    </div>
+  );
+}
+
+function AppLockCard() {
+  const { showError, showSuccess } = useToast();
+  const [status, setStatus] = useState<AppLockStatus | null>(null);
+  const [currentPin, setCurrentPin] = useState('');
+  const [newPin, setNewPin] = useState('');
+  const [isBusy, setIsBusy] = useState(false);
+
+  useEffect(() => {
+    void getAppLockStatus().then(setStatus);
+  }, []);
+
+  const run = async (action: () => Promise<AppLockStatus | void>, success: string) => {
+    setIsBusy(true);
+    try {
+      const next = await action();
+      setCurrentPin('');
+      setNewPin('');
+      if (next) {
+        setStatus(next);
+      }
+      showSuccess(success);
+    } catch (err) {
+      showError('App lock update failed', String(err));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const pinSet = status?.pinSet ?? false;
+
+  return (
+    <div className="space-y-3 rounded-xl border border-slate-200 bg-white p-4 shadow-sm dark:border-slate-700 dark:bg-slate-800">
+      <h4 className="text-sm font-semibold text-slate-900 dark:text-white">App lock</h4>
+      <p className="text-xs text-slate-500 dark:text-slate-400">
+        {pinSet
+          ? 'The app asks for your PIN at start, after being idle and when the computer wakes up.'
+          : 'Set a PIN to lock the app while you are away.'}
+      </p>
+      {pinSet ? (
+        <input
+          type="password"
+          value={currentPin}
+          onChange={(e) => setCurrentPin(e.target.value)}
+          placeholder="Current PIN"
+          className="w-full rounded-lg border border-slate-300 px-3 py-2 text-sm dark:border-slate-600 dark:bg-slate-700 dark:text-white"
+        />
+      ) : null}
+      <input
+        type="password"
+        value={newPin}
+        onChange={(e) => setNewPin(e.target.value)}
+        placeholder={pinSet ? 'New PIN' : 'PIN or passphrase (at least 4 characters)'}
+        className="w-full rounded-lg border border-slate-300 px-3 py-2 text-sm dark:border-slate-600 dark:bg-slate-700 dark:text-white"
+      />
+      <div className="flex flex-wrap gap-2">
+        <button
+          type="button"
+          disabled={isBusy || newPin.length === 0}
+          onClick={() =>
+            void run(
+              () => setAppLockPin(currentPin || undefined, newPin),
+              pinSet ? 'PIN changed' : 'PIN set'
+            )
+          }
+          className="rounded-lg bg-primary px-4 py-2 text-sm font-medium text-white disabled:opacity-50"
+        >
+          {pinSet ? 'Change PIN' : 'Set PIN'}
+        </button>
+        {pinSet ? (
+          <>
+            <button
+              type="button"
+              disabled={isBusy || currentPin.length === 0}
+              onClick={() => void run(() => setAppLockPin(currentPin, undefined), 'PIN removed')}
+              className="rounded-lg border border-slate-300 px-4 py-2 text-sm font-medium text-slate-700 disabled:opacity-50 dark:border-slate-600 dark:text-slate-300"
+            >
+              Remove PIN
+            </button>
+            <button
+              type="button"
+              disabled={isBusy}
+              onClick={() => void run(lockApp, 'App locked')}
+              className="rounded-lg border border-slate-300 px-4 py-2 text-sm font-medium text-slate-700 disabled:opacity-50 dark:border-slate-600 dark:text-slate-300"
+            >
+              Lock now
+            </button>
+          </>
+        ) : null}
+      </div>
+      {pinSet && status ? (
+        <div className="flex flex-wrap items-center gap-4 text-sm text-slate-700 dark:text-slate-300">
+          <label className="flex items-center gap-2">
+            Lock after
+            <select
+              value={status.idleMinutes}
+              disabled={isBusy}
+              onChange={(e) =>
+                void run(
+                  () => updateAppLockSettings({ idleMinutes: Number(e.target.value) }),
+                  'Idle timeout updated'
+                )
+              }
+              className="rounded-lg border border-slate-300 px-2 py-1 text-sm dark:border-slate-600 dark:bg-slate-700 dark:text-white"
+            >
+              {[0, 1, 5, 10, 15, 30, 60].map((minutes) => (
+                <option key={minutes} value={minutes}>
+                  {minutes === 0 ? 'Never' : `${minutes} min idle`}
+                </option>
+              ))}
+            </select>
+          </label>
+          <label className="flex items-center gap-2">
+            <input
+              type="checkbox"
+              checked={status.lockOnSleep}
+              disabled={isBusy}
+              onChange={(e) =>
+                void run(
+                  () => updateAppLockSettings({ lockOnSleep: e.target.checked }),
+                  'Sleep lock updated'
+                )
+              }
+            />
+            Lock when the computer sleeps
+          </label>
+        </div>
+      ) : null}
+    </div>
+  );
+}
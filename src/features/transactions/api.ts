@@ -4,11 +4,13 @@ import { z } from 'zod';
 import {
   accountSchema,
   categorySchema,
+  importTransactionsResultSchema,
   transactionFiltersSchema,
   transactionFormSchema,
   transactionSchema,
   type Account,
   type Category,
+  type ImportTransactionsResult,
   type Transaction,
   type TransactionFilters,
   type TransactionForm
@@ -52,14 +54,16 @@ export async function deleteTransaction(id: string): Promise<void> {
   await invoke('delete_transaction', { payload: { id } });
 }
 
-export async function importTransactions(items: TransactionForm[]): Promise<Transaction[]> {
+export async function importTransactions(
+  items: TransactionForm[]
+): Promise<ImportTransactionsResult | null> {
   if (!items.length) {
-    return [];
+    return null;
   }
   const parsedItems = z.array(transactionFormSchema).parse(items);
-  const result = await invoke<Transaction[]>('import_transactions', {
+  const result = await invoke<ImportTransactionsResult>('import_transactions', {
     payload: { items: parsedItems }
   });
-  return transactionListSchema.parse(result);
+  return importTransactionsResultSchema.parse(result);
 }
 
@@ -19,6 +19,7 @@ import {
   transactionFiltersSchema,
   type Account,
   type Category,
+  type ImportTransactionsResult,
   type Transaction,
   type TransactionFilters,
   type TransactionForm
@@ -144,7 +145,7 @@ export function useDeleteTransactionMutation(
 
 export function useImportTransactionsMutation(
   filters: TransactionFilters
-): UseMutationResult<Transaction[], unknown, TransactionForm[]> {
+): UseMutationResult<ImportTransactionsResult | null, unknown, TransactionForm[]> {
   const queryClient = useQueryClient();
   const key = transactionsKeys.list(transactionFiltersSchema.parse(filters));
 
@@ -41,6 +41,23 @@ export const transactionSchema = z.object({
 
 export type Transaction = z.infer<typeof transactionSchema>;
 
+export const importTransactionsResultSchema = z.object({
+  batchId: z.string(),
+  created: z.array(transactionSchema),
+  merged: z.array(transactionSchema),
+  skipped: z.array(z.number().int()),
+  flagged: z.array(
+    z.object({
+      index: z.number().int(),
+      reason: z.enum(['externalId', 'fingerprint']),
+      fingerprint: z.string(),
+      matches: z.array(transactionSchema)
+    })
+  )
+});
+
+export type ImportTransactionsResult = z.infer<typeof importTransactionsResultSchema>;
+
 export const transactionFormSchema = z.object({
   id: z.string().optional(),
   accountId: z.string().min(1, 'Account is required'),
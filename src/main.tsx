@@ -4,6 +4,8 @@ import { HashRouter } from 'react-router-dom';
 
 import App from './app/App';
 import { AppProviders } from './app/providers';
+import { AppLockGate } from './features/security/components/AppLockGate';
+import { UnlockGate } from './features/security/components/UnlockGate';
 import './styles/global.css';
 
 const rootEl = document.getElementById('root');
@@ -16,7 +18,11 @@ ReactDOM.createRoot(rootEl).render(
   <React.StrictMode>
     <HashRouter>
       <AppProviders>
-        <App />
+        <UnlockGate>
+          <AppLockGate>
+            <App />
+          </AppLockGate>
+        </UnlockGate>
       </AppProviders>
     </HashRouter>
   </React.StrictMode>
@@ -0,0 +1,65 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+
+import { invoke } from '@tauri-apps/api/core';
+
+import { importTransactions } from '@/features/transactions/api';
+
+vi.mock('@tauri-apps/api/core', () => ({ invoke: vi.fn() }));
+
+const item = {
+  accountId: 'acct-1',
+  type: 'expense' as const,
+  amountCents: 450,
+  currency: 'USD',
+  occurredOn: '2025-03-01T12:00:00Z'
+};
+
+const row = {
+  id: 'tx-1',
+  accountId: 'acct-1',
+  accountName: 'Checking',
+  type: 'expense',
+  amountCents: 450,
+  currency: 'USD',
+  occurredOn: '2025-03-01T12:00:00Z',
+  cleared: false
+};
+
+describe('importTransactions', () => {
+  beforeEach(() => {
+    vi.mocked(invoke).mockReset();
+  });
+
+  it('parses the import summary', async () => {
+    vi.mocked(invoke).mockResolvedValue({
+      batchId: 'batch-1',
+      created: [row],
+      merged: [],
+      skipped: [1],
+      flagged: [
+        { index: 1, reason: 'fingerprint', fingerprint: 'acct-1|expense|450', matches: [row] }
+      ]
+    });
+
+    const result = await importTransactions([item, item]);
+
+    expect(invoke).toHaveBeenCalledWith('import_transactions', {
+      payload: { items: [item, item] }
+    });
+    expect(result?.batchId).toBe('batch-1');
+    expect(result?.created.map((transaction) => transaction.id)).toEqual(['tx-1']);
+    expect(result?.skipped).toEqual([1]);
+    expect(result?.flagged[0]?.matches[0]?.id).toBe('tx-1');
+  });
+
+  it('rejects a bare transaction list', async () => {
+    vi.mocked(invoke).mockResolvedValue([row]);
+
+    await expect(importTransactions([item])).rejects.toThrow();
+  });
+
+  it('skips the command when there is nothing to import', async () => {
+    expect(await importTransactions([])).toBeNull();
+    expect(invoke).not.toHaveBeenCalled();
+  });
+});